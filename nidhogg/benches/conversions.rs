@@ -0,0 +1,159 @@
+//! Benchmarks for the conversions and serialization that nidhogg performs every `LoLA` cycle.
+//!
+//! `LoLA` sends a frame every 12 ms, and the whole read-convert-write path of nidhogg should take
+//! a negligible part of that. The `cycle/read_convert_write` benchmark measures this path without
+//! the socket IO, and should stay below [`CYCLE_BUDGET`] on a desktop CPU.
+//!
+//! Run the benchmarks using `cargo bench -p nidhogg`.
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use nidhogg::{
+    backend::{LolaControlMsg, LolaNaoState},
+    safety::SafeBackend,
+    testing::fixtures,
+    types::{color, FillExt, JointArray, LeftEar, RightEye, Skull},
+    NaoBackend, NaoControlMessage, NaoState, Result,
+};
+
+/// Budget for decoding a state frame, converting it, and converting and encoding a control message.
+const CYCLE_BUDGET: Duration = Duration::from_micros(10);
+
+/// Backend that ignores everything, used to benchmark the [`SafeBackend`] checks by themselves.
+#[derive(Debug)]
+struct NullBackend;
+
+impl NaoBackend for NullBackend {
+    fn connect() -> Result<Self> {
+        Ok(NullBackend)
+    }
+
+    fn send_control_msg(&mut self, _update: NaoControlMessage) -> Result<()> {
+        Ok(())
+    }
+
+    fn read_nao_state(&mut self) -> Result<NaoState> {
+        Ok(NaoState::default())
+    }
+}
+
+fn frame() -> Vec<u8> {
+    let mut state = NaoState {
+        position: JointArray::fill(0.25),
+        stiffness: JointArray::fill(0.8),
+        temperature: JointArray::fill(40.0),
+        ..Default::default()
+    };
+    state.battery.charge = 0.75;
+
+    fixtures::lola_frame_bytes(&state, &fixtures::hardware_info())
+}
+
+fn control_msg() -> NaoControlMessage {
+    NaoControlMessage::builder()
+        .position(JointArray::fill(0.1))
+        .stiffness(JointArray::fill(0.9))
+        .chest(color::f32::MAGENTA)
+        .left_ear(LeftEar::from_fraction(0.6))
+        .right_eye(RightEye::fill(color::f32::CYAN))
+        .skull(Skull::sweep(0.3))
+        .build()
+}
+
+fn decode(frame: &[u8]) -> NaoState {
+    rmp_serde::from_slice::<LolaNaoState<'_>>(frame)
+        .unwrap()
+        .into()
+}
+
+fn encode(msg: NaoControlMessage, buf: &mut Vec<u8>) {
+    buf.clear();
+    rmp_serde::encode::write_named(buf, &LolaControlMsg::from(msg)).unwrap();
+}
+
+fn state(c: &mut Criterion) {
+    let frame = frame();
+    assert_eq!(frame.len(), 896);
+
+    let mut group = c.benchmark_group("state");
+    group.bench_function("decode_frame", |b| {
+        b.iter(|| rmp_serde::from_slice::<LolaNaoState<'_>>(black_box(&frame)).unwrap())
+    });
+    group.bench_function("decode_and_convert", |b| {
+        b.iter(|| decode(black_box(&frame)))
+    });
+    group.finish();
+}
+
+fn control(c: &mut Criterion) {
+    let msg = control_msg();
+    let mut buf = Vec::new();
+
+    let mut group = c.benchmark_group("control");
+    group.bench_function("convert", |b| {
+        b.iter_batched(
+            || msg.clone(),
+            |msg| LolaControlMsg::from(black_box(msg)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("convert_and_encode", |b| {
+        b.iter_batched(
+            || msg.clone(),
+            |msg| encode(black_box(msg), &mut buf),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let mut safe = SafeBackend::new(NullBackend);
+    group.bench_function("safety", |b| {
+        b.iter_batched(
+            || msg.clone(),
+            |msg| safe.enforce(black_box(msg)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn joint_array(c: &mut Criterion) {
+    let position: JointArray<f32> = JointArray::fill(0.25);
+    let target = JointArray::fill(0.5);
+
+    c.bench_function("joint_array/map_zip", |b| {
+        b.iter(|| {
+            black_box(&position)
+                .clone()
+                .zip(black_box(&target).clone())
+                .map(|(position, target)| position + 0.1 * (target - position))
+                .map(|position| position.clamp(-1.0, 1.0))
+        })
+    });
+}
+
+fn cycle(c: &mut Criterion) {
+    let frame = frame();
+    let msg = control_msg();
+    let mut buf = Vec::new();
+
+    let mut group = c.benchmark_group("cycle");
+    group.bench_function("read_convert_write", |b| {
+        b.iter_batched(
+            || msg.clone(),
+            |mut msg| {
+                let state = decode(black_box(&frame));
+                msg.position = state.position;
+                encode(msg, &mut buf);
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+
+    println!("cycle budget: {CYCLE_BUDGET:?} per read_convert_write");
+}
+
+criterion_group!(benches, state, control, joint_array, cycle);
+criterion_main!(benches);
@@ -0,0 +1,123 @@
+//! Compares the owned and borrowed state reads of the `LoLA` backend.
+//!
+//! Frames are served from a local socket, so this measures decoding and conversion rather than `LoLA` itself.
+//! Run with `cargo bench --bench lola_read`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    io::Write,
+    os::unix::net::UnixListener,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Instant,
+};
+
+use nidhogg::{backend::LolaBackend, NaoBackend};
+use serde::Serialize;
+
+const FRAME_SIZE: usize = 896;
+const ITERATIONS: usize = 20_000;
+
+/// Counts every allocation, to show which read path allocates.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct Frame {
+    stiffness: [f32; 25],
+    position: [f32; 25],
+    temperature: [f32; 25],
+    current: [f32; 25],
+    battery: [f32; 4],
+    accelerometer: [f32; 3],
+    gyroscope: [f32; 3],
+    angles: [f32; 2],
+    sonar: [f32; 2],
+    f_s_r: [f32; 8],
+    touch: [f32; 14],
+    status: [i32; 25],
+    robot_config: [&'static str; 4],
+}
+
+fn frame() -> Vec<u8> {
+    let frame = Frame {
+        stiffness: [0.5; 25],
+        position: std::array::from_fn(|i| i as f32 / 10.0),
+        temperature: [30.0; 25],
+        current: [0.1; 25],
+        battery: [0.9, 1.0, 0.0, 25.0],
+        accelerometer: [0.0, 0.0, 9.81],
+        gyroscope: [0.0; 3],
+        angles: [0.0; 2],
+        sonar: [1.0, 2.0],
+        f_s_r: [0.1; 8],
+        touch: [0.0; 14],
+        status: [0; 25],
+        robot_config: ["body", "6.0.0", "head", "6.0.0"],
+    };
+
+    let mut frame = rmp_serde::to_vec_named(&frame).unwrap();
+    frame.resize(FRAME_SIZE, 0);
+    frame
+}
+
+fn bench(name: &str, nao: &mut LolaBackend, mut read: impl FnMut(&mut LolaBackend)) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        read(nao);
+    }
+
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "{name:<10} {:>8.2?} per read, {:.2} allocations per read",
+        elapsed / ITERATIONS as u32,
+        allocations as f64 / ITERATIONS as f64
+    );
+}
+
+fn main() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("robocup");
+    let listener = UnixListener::bind(&path).unwrap();
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let frame = frame();
+        // one frame for each read of both benchmarks
+        for _ in 0..2 * ITERATIONS {
+            stream.write_all(&frame).unwrap();
+        }
+    });
+
+    let mut nao = LolaBackend::connect_with_path(&path).unwrap();
+
+    bench("owned", &mut nao, |nao| {
+        black_box(nao.read_nao_state().unwrap());
+    });
+    bench("borrowed", &mut nao, |nao| {
+        let state = nao.read_lola_state_ref().unwrap();
+        black_box(state.position()[0]);
+    });
+
+    server.join().unwrap();
+}
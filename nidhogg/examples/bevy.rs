@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use bevy_ecs::{prelude::*, schedule::ScheduleLabel};
+use nidhogg::{
+    bevy::{NaoConnectionStatus, NidhoggPlugin},
+    prelude::*,
+};
+
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct PreUpdate;
+
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct Update;
+
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct PostUpdate;
+
+/// Turns the chest red while the chest button is pressed.
+fn chest_button(state: Res<'_, NaoState>, mut control: ResMut<'_, NaoControlMessage>) {
+    control.chest = if state.touch.chest_board > 0.5 {
+        color::f32::RED
+    } else {
+        color::f32::EMPTY
+    };
+}
+
+fn report_connection(status: Res<'_, NaoConnectionStatus>) {
+    if status.is_changed() {
+        println!("connection status: {:?}", *status);
+    }
+}
+
+fn main() {
+    tracing_subscriber::fmt::init();
+
+    let mut world = World::new();
+    NidhoggPlugin::<LolaBackend>::new(10, Duration::from_millis(500))
+        .build(&mut world, PreUpdate, PostUpdate);
+
+    let mut update = Schedule::new(Update);
+    update.add_systems((chest_button, report_connection));
+    world.add_schedule(update);
+
+    // LoLA blocks until the next state is available, so this loop runs at the rate of the robot
+    loop {
+        world.run_schedule(PreUpdate);
+        world.run_schedule(Update);
+        world.run_schedule(PostUpdate);
+    }
+}
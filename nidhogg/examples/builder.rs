@@ -1,6 +1,6 @@
 use miette::Result;
 
-use nidhogg::{types::*, NaoControlMessage};
+use nidhogg::prelude::*;
 
 fn main() -> Result<()> {
     let cool_float = 1337.0;
@@ -88,63 +88,58 @@ fn main() -> Result<()> {
                 .right_hand(cool_float)
                 .build(),
         )
-        .sonar(
-            SonarEnabled::builder()
-                .left(cool_bool)
-                .right(cool_bool)
-                .build(),
-        )
+        .sonar(SonarMode::from_emitters(cool_bool, cool_bool))
         .left_ear(
             LeftEar::builder()
-                .l9(cool_float)
-                .l8(cool_float)
-                .l7(cool_float)
-                .l6(cool_float)
-                .l5(cool_float)
-                .l4(cool_float)
-                .l3(cool_float)
-                .l3(cool_float)
-                .l2(cool_float)
-                .l1(cool_float)
+                .led9(cool_float)
+                .led8(cool_float)
+                .led7(cool_float)
+                .led6(cool_float)
+                .led5(cool_float)
+                .led4(cool_float)
+                .led3(cool_float)
+                .led3(cool_float)
+                .led2(cool_float)
+                .led1(cool_float)
                 .build(),
         )
         .right_ear(
             RightEar::builder()
-                .r0(cool_float)
-                .r1(cool_float)
-                .r2(cool_float)
-                .r3(cool_float)
-                .r4(cool_float)
-                .r5(cool_float)
-                .r6(cool_float)
-                .r7(cool_float)
-                .r8(cool_float)
-                .r9(cool_float)
+                .led0(cool_float)
+                .led1(cool_float)
+                .led2(cool_float)
+                .led3(cool_float)
+                .led4(cool_float)
+                .led5(cool_float)
+                .led6(cool_float)
+                .led7(cool_float)
+                .led8(cool_float)
+                .led9(cool_float)
                 .build(),
         )
         .chest(cool_color)
         .left_eye(
             LeftEye::builder()
-                .l0(cool_color)
-                .l7(cool_color)
-                .l6(cool_color)
-                .l5(cool_color)
-                .l4(cool_color)
-                .l3(cool_color)
-                .l2(cool_color)
-                .l1(cool_color)
+                .led0(cool_color)
+                .led7(cool_color)
+                .led6(cool_color)
+                .led5(cool_color)
+                .led4(cool_color)
+                .led3(cool_color)
+                .led2(cool_color)
+                .led1(cool_color)
                 .build(),
         )
         .right_eye(
             RightEye::builder()
-                .r0(cool_color)
-                .r1(cool_color)
-                .r2(cool_color)
-                .r3(cool_color)
-                .r4(cool_color)
-                .r5(cool_color)
-                .r6(cool_color)
-                .r7(cool_color)
+                .led0(cool_color)
+                .led1(cool_color)
+                .led2(cool_color)
+                .led3(cool_color)
+                .led4(cool_color)
+                .led5(cool_color)
+                .led6(cool_color)
+                .led7(cool_color)
                 .build(),
         )
         .left_foot(cool_color)
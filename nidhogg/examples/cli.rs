@@ -0,0 +1,390 @@
+//! Small tools for the bring-up of new robots.
+//!
+//! ```sh
+//! # print a summary of the state once, or at 2 Hz
+//! cargo run --example cli -- state
+//! cargo run --example cli -- state --rate 2
+//!
+//! # light up every LED group in red, green and blue, one after another
+//! cargo run --example cli -- led-test --step-ms 500
+//!
+//! # move a single joint back and forth at low stiffness
+//! cargo run --example cli -- wiggle HeadYaw --amplitude 0.3 --frequency 0.5 --duration 10
+//! ```
+
+use std::{
+    env,
+    f32::consts::TAU,
+    time::{Duration, Instant},
+};
+
+use nidhogg::{
+    leds::LedState,
+    prelude::*,
+    safety::{SafeBackend, JOINT_LIMITS},
+};
+
+use miette::{miette, Result};
+
+const USAGE: &str = "usage:
+    cli state [--rate <hz>]
+    cli led-test [--step-ms <ms>]
+    cli wiggle <joint> [--amplitude <rad>] [--frequency <hz>] [--duration <s>]";
+
+/// Stiffness of the wiggled joint, low enough to not hurt anyone holding the robot.
+const WIGGLE_STIFFNESS: f32 = 0.3;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Command {
+    /// Print the state once, or at `rate` Hz.
+    State { rate: Option<f32> },
+    /// Light every LED group in red, green and blue, for `step` each.
+    LedTest { step: Duration },
+    /// Drive `joint` along a sine around its current position.
+    Wiggle {
+        joint: JointName,
+        amplitude: f32,
+        frequency: f32,
+        duration: Duration,
+    },
+}
+
+/// Parse the arguments, without the name of the binary.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let command = args.next().ok_or("missing command")?;
+
+    let (positional, options) = parse_options(args)?;
+    let option = |name: &str, default: f32| -> Result<f32, String> {
+        match options.iter().find(|(option, _)| option == name) {
+            Some((_, value)) => value
+                .parse::<f32>()
+                .ok()
+                .filter(|value| value.is_finite() && *value > 0.0)
+                .ok_or_else(|| format!("`--{name}` must be a positive number, got `{value}`")),
+            None => Ok(default),
+        }
+    };
+    let allow = |allowed: &[&str], positional_count: usize| -> Result<(), String> {
+        if let Some((name, _)) = options
+            .iter()
+            .find(|(name, _)| !allowed.contains(&name.as_str()))
+        {
+            return Err(format!("unknown option `--{name}` for `{command}`"));
+        }
+        if positional.len() != positional_count {
+            return Err(format!(
+                "`{command}` expects {positional_count} argument(s), got {}",
+                positional.len()
+            ));
+        }
+        Ok(())
+    };
+
+    match command.as_str() {
+        "state" => {
+            allow(&["rate"], 0)?;
+            let rate = options
+                .iter()
+                .any(|(name, _)| name == "rate")
+                .then(|| option("rate", 1.0))
+                .transpose()?;
+            Ok(Command::State { rate })
+        }
+        "led-test" => {
+            allow(&["step-ms"], 0)?;
+            Ok(Command::LedTest {
+                step: Duration::from_secs_f32(option("step-ms", 500.0)? / 1000.0),
+            })
+        }
+        "wiggle" => {
+            allow(&["amplitude", "frequency", "duration"], 1)?;
+            let joint = positional[0].parse().map_err(|error| format!("{error}"))?;
+            Ok(Command::Wiggle {
+                joint,
+                amplitude: option("amplitude", 0.2)?,
+                frequency: option("frequency", 0.5)?,
+                duration: Duration::from_secs_f32(option("duration", 10.0)?),
+            })
+        }
+        command => Err(format!("unknown command `{command}`")),
+    }
+}
+
+/// Options passed as `--name value`.
+type Options = Vec<(String, String)>;
+
+/// Split the arguments into positional arguments, and `--name value` options.
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<(Vec<String>, Options), String> {
+    let mut positional = Vec::new();
+    let mut options = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some(name) => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for `--{name}`"))?;
+                options.push((name.to_owned(), value));
+            }
+            None => positional.push(arg),
+        }
+    }
+
+    Ok((positional, options))
+}
+
+/// The LED states shown by the LED test, each lighting a single LED group in a single color.
+///
+/// The ears and skull only have a single color, so they are shown once at full brightness.
+fn led_test_steps() -> Vec<(&'static str, LedState)> {
+    let mut steps = vec![
+        (
+            "left_ear",
+            LedState::builder().left_ear(LeftEar::fill(1.0)).build(),
+        ),
+        (
+            "right_ear",
+            LedState::builder().right_ear(RightEar::fill(1.0)).build(),
+        ),
+        ("skull", LedState::builder().skull(Skull::fill(1.0)).build()),
+    ];
+
+    for color in [color::f32::RED, color::f32::GREEN, color::f32::BLUE] {
+        steps.extend([
+            ("chest", LedState::builder().chest(color).build()),
+            (
+                "left_eye",
+                LedState::builder().left_eye(LeftEye::fill(color)).build(),
+            ),
+            (
+                "right_eye",
+                LedState::builder().right_eye(RightEye::fill(color)).build(),
+            ),
+            ("left_foot", LedState::builder().left_foot(color).build()),
+            ("right_foot", LedState::builder().right_foot(color).build()),
+        ]);
+    }
+
+    steps
+}
+
+/// The message that wiggles `joint` at time `t`, holding all other joints at `start` without stiffness.
+///
+/// The joint moves along a sine around its position in `start`, which is moved away from the
+/// joint limits far enough to stay within them.
+fn wiggle_message(
+    start: &JointArray<f32>,
+    joint: JointName,
+    amplitude: f32,
+    frequency: f32,
+    t: Duration,
+) -> NaoControlMessage {
+    let limit = JOINT_LIMITS[joint];
+    let amplitude = amplitude.min((limit.max - limit.min) / 2.0);
+    // not `clamp`, which panics if rounding puts the bounds in the wrong order
+    let center = start[joint]
+        .max(limit.min + amplitude)
+        .min(limit.max - amplitude);
+
+    let mut position = start.clone();
+    position[joint] = limit.clamp(center + amplitude * (TAU * frequency * t.as_secs_f32()).sin());
+
+    let mut stiffness = JointArray::fill(0.0);
+    stiffness[joint] = WIGGLE_STIFFNESS;
+
+    NaoControlMessage::builder()
+        .position(position)
+        .stiffness(stiffness)
+        .build()
+}
+
+fn state(nao: &mut LolaBackend, rate: Option<f32>) -> Result<()> {
+    let Some(rate) = rate else {
+        println!("{}", nao.read_nao_state()?);
+        return Ok(());
+    };
+
+    let period = Duration::from_secs_f32(1.0 / rate);
+    let mut last_print: Option<Instant> = None;
+    loop {
+        let state = nao.read_nao_state()?;
+        if last_print.is_none_or(|last_print| last_print.elapsed() >= period) {
+            println!("{state}");
+            last_print = Some(Instant::now());
+        }
+    }
+}
+
+fn led_test(nao: &mut LolaBackend, step: Duration) -> Result<()> {
+    for (group, leds) in led_test_steps() {
+        println!("{group}");
+
+        let start = Instant::now();
+        while start.elapsed() < step {
+            let state = nao.read_nao_state()?;
+            let msg = NaoControlMessage::builder()
+                .position(state.position)
+                .leds(leds.clone())
+                .build();
+            nao.send_control_msg(msg)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn wiggle(
+    nao: LolaBackend,
+    joint: JointName,
+    amplitude: f32,
+    frequency: f32,
+    duration: Duration,
+) -> Result<()> {
+    let mut nao = SafeBackend::new(nao);
+    let start_position = nao.read_nao_state()?.position;
+    println!("wiggling {joint} from {:.3} rad", start_position[joint]);
+
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        nao.read_nao_state()?;
+        let msg = wiggle_message(
+            &start_position,
+            joint,
+            amplitude,
+            frequency,
+            start.elapsed(),
+        );
+        nao.send_control_msg(msg)?;
+    }
+
+    nao.send_control_msg(NaoControlMessage::unstiff())?;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let command = parse_args(env::args().skip(1)).map_err(|error| miette!("{error}\n\n{USAGE}"))?;
+    let mut nao = LolaBackend::connect_with_retry(10, Duration::from_millis(500))?;
+
+    match command {
+        Command::State { rate } => state(&mut nao, rate),
+        Command::LedTest { step } => led_test(&mut nao, step),
+        Command::Wiggle {
+            joint,
+            amplitude,
+            frequency,
+            duration,
+        } => wiggle(nao, joint, amplitude, frequency, duration),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(str::to_owned))
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse("state"), Ok(Command::State { rate: None }));
+        assert_eq!(
+            parse("state --rate 2"),
+            Ok(Command::State { rate: Some(2.0) })
+        );
+        assert_eq!(
+            parse("led-test --step-ms 250"),
+            Ok(Command::LedTest {
+                step: Duration::from_millis(250)
+            })
+        );
+        assert_eq!(
+            parse("wiggle HeadYaw --amplitude 0.3"),
+            Ok(Command::Wiggle {
+                joint: JointName::HeadYaw,
+                amplitude: 0.3,
+                frequency: 0.5,
+                duration: Duration::from_secs(10),
+            })
+        );
+        assert!(matches!(
+            parse("wiggle left_knee_pitch"),
+            Ok(Command::Wiggle {
+                joint: JointName::LeftKneePitch,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse(""), Err("missing command".to_owned()));
+        assert_eq!(parse("dance"), Err("unknown command `dance`".to_owned()));
+        assert_eq!(
+            parse("wiggle Tail"),
+            Err("unknown joint name `Tail`".to_owned())
+        );
+        assert_eq!(
+            parse("wiggle"),
+            Err("`wiggle` expects 1 argument(s), got 0".to_owned())
+        );
+        assert_eq!(
+            parse("state --rate"),
+            Err("missing value for `--rate`".to_owned())
+        );
+        assert_eq!(
+            parse("state --rate fast"),
+            Err("`--rate` must be a positive number, got `fast`".to_owned())
+        );
+        assert_eq!(
+            parse("led-test --rate 2"),
+            Err("unknown option `--rate` for `led-test`".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_led_test_lights_one_group_per_step() {
+        let steps = led_test_steps();
+        assert_eq!(steps.len(), 3 + 3 * 5);
+
+        let default = NaoControlMessage::default();
+        for (group, leds) in steps {
+            let mut msg = NaoControlMessage::default();
+            msg.set_leds(leds);
+            assert_eq!(default.diff(&msg).leds, [group]);
+        }
+    }
+
+    #[test]
+    fn test_wiggle_stays_within_limits() {
+        let start = nidhogg::poses::stand();
+
+        for joint in JointName::ALL {
+            for ms in (0..2000).step_by(20) {
+                let msg = wiggle_message(&start, joint, 1.0, 1.0, Duration::from_millis(ms));
+                assert!(JOINT_LIMITS[joint].contains(msg.position[joint]), "{joint}");
+
+                assert!(msg
+                    .position
+                    .iter_named()
+                    .all(|(name, position)| name == joint || *position == start[name]));
+                assert_eq!(msg.stiffness.sum(), WIGGLE_STIFFNESS);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wiggle_around_start() {
+        let start = nidhogg::poses::stand();
+        let joint = JointName::HeadYaw;
+
+        let at =
+            |ms| wiggle_message(&start, joint, 0.5, 1.0, Duration::from_millis(ms)).position[joint];
+        assert_eq!(at(0), 0.0);
+        assert!((at(250) - 0.5).abs() < 1e-5);
+        assert!((at(750) + 0.5).abs() < 1e-5);
+    }
+}
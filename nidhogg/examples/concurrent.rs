@@ -0,0 +1,45 @@
+use std::{thread, time::Duration};
+
+use nidhogg::{concurrent::spawn_state_reader, prelude::*};
+
+use miette::Result;
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let nao = LolaBackend::connect_with_retry(10, Duration::from_millis(500))?;
+    let (states, control, io) = spawn_state_reader(nao);
+
+    // A slow "perception" thread, that always works on the freshest state
+    let perception = {
+        let states = states.clone();
+        thread::spawn(move || {
+            let mut sequence = 0;
+            while let Some((state, latest)) = states.wait_for_newer(sequence) {
+                println!(
+                    "skipped {} states, head yaw is {:.3}",
+                    latest - sequence - 1,
+                    state.position.head_yaw
+                );
+                sequence = latest;
+
+                thread::sleep(Duration::from_millis(50));
+            }
+        })
+    };
+
+    // Keep the head where it is, with a bit of stiffness
+    let (state, _) = states.wait_for_newer(0).ok_or(Error::ConnectionClosed)?;
+    let mut msg = NaoControlMessage::holding(&state);
+    msg.stiffness.head_yaw = 0.2;
+    msg.stiffness.head_pitch = 0.2;
+    control.send(msg)?;
+
+    thread::sleep(Duration::from_secs(5));
+
+    control.stop();
+    perception.join().expect("perception thread panicked");
+    io.join().expect("IO thread panicked")?;
+
+    Ok(())
+}
@@ -1,10 +1,6 @@
 use std::time::Duration;
 
-use nidhogg::{
-    backend::{ConnectWithRetry, LolaBackend, ReadHardwareInfo},
-    types::{LeftEye, RgbF32},
-    NaoBackend, NaoControlMessage,
-};
+use nidhogg::prelude::*;
 
 use miette::Result;
 
@@ -22,7 +18,7 @@ fn main() -> Result<()> {
     let update = NaoControlMessage::builder()
         .left_eye(
             LeftEye::builder()
-                .l0(RgbF32::builder().red(1.0).green(1.0).build())
+                .led0(RgbF32::builder().red(1.0).green(1.0).build())
                 .build(),
         )
         .build();
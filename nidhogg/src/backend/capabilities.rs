@@ -0,0 +1,120 @@
+//! Implements [`BackendCapabilities`], which describes the data a backend actually provides.
+
+/// The sensor data and timing guarantees a backend provides.
+///
+/// Backends fill the fields of a [`NaoState`](crate::NaoState) they do not measure with default values.
+/// Generic code can use [`NaoBackend::capabilities`](crate::NaoBackend::capabilities) to find out
+/// which values are real, for example to skip the FSR checks of the [`FallDetector`](crate::safety::FallDetector).
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{backend::BackendCapabilities, NaoBackend};
+///
+/// fn describe(backend: &impl NaoBackend) -> &'static str {
+///     if backend.capabilities().has_fsr {
+///         "with foot contact"
+///     } else {
+///         "without foot contact"
+///     }
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BackendCapabilities {
+    /// The force sensitive resistors in the feet are measured.
+    pub has_fsr: bool,
+    /// The touch sensors in the head, hands and feet are measured.
+    pub has_touch: bool,
+    /// The sonar distances are measured.
+    pub has_sonar: bool,
+    /// The battery state is measured.
+    pub has_battery: bool,
+    /// The joint temperatures are measured.
+    pub has_temperature: bool,
+    /// States arrive at a fixed rate in real time, such as the 83 Hz of `LoLA`.
+    pub realtime: bool,
+}
+
+impl BackendCapabilities {
+    /// Capabilities of a backend that provides nothing but the joint positions and stiffness.
+    ///
+    /// This is the default, so backends that do not report their capabilities are treated conservatively.
+    pub const NONE: Self = Self {
+        has_fsr: false,
+        has_touch: false,
+        has_sonar: false,
+        has_battery: false,
+        has_temperature: false,
+        realtime: false,
+    };
+
+    /// Capabilities of a real robot, which measures everything in real time.
+    pub const ALL: Self = Self {
+        has_fsr: true,
+        has_touch: true,
+        has_sonar: true,
+        has_battery: true,
+        has_temperature: true,
+        realtime: true,
+    };
+
+    /// Returns `true` if every capability of `other` is also provided by `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::backend::BackendCapabilities;
+    ///
+    /// let sensors = BackendCapabilities {
+    ///     has_fsr: true,
+    ///     has_touch: true,
+    ///     ..BackendCapabilities::NONE
+    /// };
+    ///
+    /// assert!(BackendCapabilities::ALL.contains(sensors));
+    /// assert!(!sensors.contains(BackendCapabilities::ALL));
+    /// ```
+    pub fn contains(self, other: Self) -> bool {
+        (!other.has_fsr || self.has_fsr)
+            && (!other.has_touch || self.has_touch)
+            && (!other.has_sonar || self.has_sonar)
+            && (!other.has_battery || self.has_battery)
+            && (!other.has_temperature || self.has_temperature)
+            && (!other.realtime || self.realtime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NaoBackend, NaoControlMessage, NaoState, Result};
+
+    /// Backend that does not report its capabilities.
+    #[derive(Debug)]
+    struct Unknown;
+
+    impl NaoBackend for Unknown {
+        fn connect() -> Result<Self> {
+            Ok(Unknown)
+        }
+
+        fn send_control_msg(&mut self, _update: NaoControlMessage) -> Result<()> {
+            Ok(())
+        }
+
+        fn read_nao_state(&mut self) -> Result<NaoState> {
+            Ok(NaoState::default())
+        }
+    }
+
+    #[test]
+    fn test_default_is_conservative() {
+        assert_eq!(Unknown.capabilities(), BackendCapabilities::NONE);
+        assert_eq!(BackendCapabilities::default(), BackendCapabilities::NONE);
+        assert!(BackendCapabilities::NONE.contains(BackendCapabilities::NONE));
+        assert!(!BackendCapabilities::NONE.contains(BackendCapabilities {
+            realtime: true,
+            ..BackendCapabilities::NONE
+        }));
+    }
+}
@@ -0,0 +1,446 @@
+//! Implements the [`FrameReader`], which splits the stream of state frames sent by `LoLA`.
+
+use std::io::{ErrorKind, Read};
+
+use tracing::warn;
+
+use crate::{Error, Result};
+
+/// Size of the frames sent by `LoLA` on a NAO V6.
+pub(super) const LOLA_BUFFER_SIZE: usize = 896;
+/// Frames that are larger than this are rejected, instead of growing the buffer indefinitely.
+const MAX_FRAME_SIZE: usize = 64 * 1024;
+/// Maximum number of bytes requested by a single read, when the size of the frames is not known.
+const READ_CHUNK_SIZE: usize = 4 * 1024;
+
+/// How the [`FrameReader`] finds the end of a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    /// The first frame is read length-aware, and decides between the other modes.
+    Detect,
+    /// Frames are [`LOLA_BUFFER_SIZE`] bytes, and are read in one go.
+    Fixed,
+    /// Frames are read until the `MessagePack` map they contain is complete.
+    LengthAware,
+}
+
+/// Splits the stream sent by `LoLA` into frames.
+///
+/// A frame is a single `MessagePack` map, which may be padded with zeros. As long as frames are
+/// [`LOLA_BUFFER_SIZE`] bytes they are read in one go, otherwise the headers in the frame are parsed
+/// to find where it ends. This way a firmware update that changes the size of the frames does not
+/// result in a hang or misaligned frames.
+///
+/// In strict mode, every frame has to be [`LOLA_BUFFER_SIZE`] bytes, and a frame of another size
+/// results in [`Error::UnexpectedFrameSize`].
+#[derive(Debug)]
+pub(super) struct FrameReader {
+    buf: Vec<u8>,
+    /// Number of bytes at the start of `buf` that have been read from the stream.
+    filled: usize,
+    /// Number of bytes at the start of `buf` that belong to the current frame.
+    frame_len: usize,
+    mode: Mode,
+    strict: bool,
+}
+
+impl Default for FrameReader {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl FrameReader {
+    pub(super) fn new(strict: bool) -> Self {
+        Self {
+            buf: vec![0; LOLA_BUFFER_SIZE],
+            filled: 0,
+            frame_len: 0,
+            mode: if strict { Mode::Fixed } else { Mode::Detect },
+            strict,
+        }
+    }
+
+    /// Forget the buffered bytes and the detected frame size, for instance after reconnecting.
+    pub(super) fn reset(&mut self) {
+        let buf = std::mem::take(&mut self.buf);
+        *self = Self {
+            buf,
+            ..Self::new(self.strict)
+        };
+    }
+
+    /// The frame that was read last.
+    pub(super) fn frame(&self) -> &[u8] {
+        &self.buf[..self.frame_len]
+    }
+
+    /// Read the next frame from `stream`.
+    ///
+    /// Bytes that are read past the end of the frame are kept for the next read.
+    /// If the read fails, the bytes read so far are kept as well.
+    pub(super) fn read(&mut self, stream: &mut impl Read) -> Result<()> {
+        self.consume(self.frame_len);
+        self.frame_len = 0;
+
+        if self.mode == Mode::Fixed {
+            self.fill(stream, LOLA_BUFFER_SIZE, LOLA_BUFFER_SIZE)?;
+            if !self.fixed_size_mismatch() {
+                self.frame_len = LOLA_BUFFER_SIZE;
+                return Ok(());
+            }
+
+            if self.strict {
+                // read the complete frame, to report its size and keep the following frames aligned
+                let skipped = self.read_length_aware(stream)?;
+                let got = if skipped > 0 {
+                    // the previous frame had more padding than fits in a fixed size frame
+                    LOLA_BUFFER_SIZE + skipped
+                } else {
+                    self.frame_len
+                };
+                return Err(Error::UnexpectedFrameSize {
+                    expected: LOLA_BUFFER_SIZE,
+                    got,
+                });
+            }
+
+            warn!(
+                expected = LOLA_BUFFER_SIZE,
+                "The size of LoLA frames changed, reading frames length-aware"
+            );
+            self.mode = Mode::LengthAware;
+        }
+
+        self.read_length_aware(stream)?;
+
+        if self.mode == Mode::Detect {
+            // the padding of a fixed size frame is only included if the next frame starts right after it
+            let end = LOLA_BUFFER_SIZE;
+            let padded = self.frame_len <= end
+                && self.filled >= end
+                && self.buf[self.frame_len..end].iter().all(|byte| *byte == 0)
+                && (self.filled == end || self.buf[end] != 0);
+
+            if padded {
+                self.frame_len = end;
+                self.mode = Mode::Fixed;
+            } else {
+                self.mode = Mode::LengthAware;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the next frame by parsing its headers, and returns the number of zeros skipped before it.
+    fn read_length_aware(&mut self, stream: &mut impl Read) -> Result<usize> {
+        let mut skipped = 0;
+        loop {
+            // skip the padding of the previous frame
+            let zeros = self.buf[..self.filled]
+                .iter()
+                .take_while(|byte| **byte == 0)
+                .count();
+            self.consume(zeros);
+            skipped += zeros;
+
+            if self.filled > 0 {
+                if !is_map_marker(self.buf[0]) {
+                    // not the start of a frame, everything that was read is returned so it fails to decode
+                    self.frame_len = self.filled;
+                    return Ok(skipped);
+                }
+                if let Some(len) = msgpack_len(&self.buf[..self.filled]) {
+                    self.frame_len = len;
+                    return Ok(skipped);
+                }
+                if self.filled >= MAX_FRAME_SIZE {
+                    let got = std::mem::take(&mut self.filled);
+                    return Err(Error::UnexpectedFrameSize {
+                        expected: LOLA_BUFFER_SIZE,
+                        got,
+                    });
+                }
+            }
+
+            let filled = self.filled;
+            self.fill(stream, filled + 1, filled + READ_CHUNK_SIZE)?;
+        }
+    }
+
+    /// Returns `true` if the fixed size frame in the buffer shows that frames changed size.
+    fn fixed_size_mismatch(&self) -> bool {
+        let frame = &self.buf[..LOLA_BUFFER_SIZE];
+        if frame[0] == 0 {
+            // the remaining padding of a larger frame
+            return true;
+        }
+        if !is_map_marker(frame[0]) {
+            // not a frame at all, which is reported when it is decoded
+            return false;
+        }
+
+        match msgpack_len(frame) {
+            Some(len) => frame[len..].iter().any(|byte| *byte != 0),
+            None => true,
+        }
+    }
+
+    /// Read from `stream` until at least `min` bytes are buffered, reading no further than `max` bytes.
+    fn fill(&mut self, stream: &mut impl Read, min: usize, max: usize) -> Result<()> {
+        if self.buf.len() < max {
+            self.buf.resize(max, 0);
+        }
+
+        while self.filled < min {
+            match stream.read(&mut self.buf[self.filled..max]) {
+                Ok(0) => return Err(Error::ConnectionClosed),
+                Ok(read) => self.filled += read,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(Error::from_transfer(error)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove the first `len` buffered bytes.
+    fn consume(&mut self, len: usize) {
+        self.buf.copy_within(len..self.filled, 0);
+        self.filled -= len;
+    }
+}
+
+fn is_map_marker(byte: u8) -> bool {
+    matches!(byte, 0x80..=0x8f | 0xde | 0xdf)
+}
+
+/// Returns the length of the `MessagePack` value at the start of `buf`, or [`None`] if `buf` ends before the value does.
+fn msgpack_len(buf: &[u8]) -> Option<usize> {
+    let mut pos = 0;
+    // the number of values that still have to be skipped, a map entry counts as two values
+    let mut remaining: usize = 1;
+
+    while remaining > 0 {
+        let marker = *buf.get(pos)?;
+        pos += 1;
+        remaining -= 1;
+
+        let uint = |bytes: usize| {
+            let bytes = buf.get(pos..pos + bytes)?;
+            Some(
+                bytes
+                    .iter()
+                    .fold(0, |value, byte| value << 8 | usize::from(*byte)),
+            )
+        };
+
+        // the size of the header after the marker, the size of the data, and the number of nested values
+        let (header, data, values) = match marker {
+            0x00..=0x7f | 0xc0..=0xc3 | 0xe0..=0xff => (0, 0, 0),
+            0x80..=0x8f => (0, 0, 2 * usize::from(marker & 0x0f)),
+            0x90..=0x9f => (0, 0, usize::from(marker & 0x0f)),
+            0xa0..=0xbf => (0, usize::from(marker & 0x1f), 0),
+            0xc4 | 0xd9 => (1, uint(1)?, 0),
+            0xc5 | 0xda => (2, uint(2)?, 0),
+            0xc6 | 0xdb => (4, uint(4)?, 0),
+            // extension types have a type byte after the length
+            0xc7 => (2, uint(1)?, 0),
+            0xc8 => (3, uint(2)?, 0),
+            0xc9 => (5, uint(4)?, 0),
+            0xcc | 0xd0 => (0, 1, 0),
+            0xcd | 0xd1 => (0, 2, 0),
+            0xca | 0xce | 0xd2 => (0, 4, 0),
+            0xcb | 0xcf | 0xd3 => (0, 8, 0),
+            0xd4..=0xd8 => (1, 1 << (marker - 0xd4), 0),
+            0xdc => (2, 0, uint(2)?),
+            0xdd => (4, 0, uint(4)?),
+            0xde => (2, 0, 2 * uint(2)?),
+            0xdf => (4, 0, 2 * uint(4)?),
+        };
+
+        pos = pos.checked_add(header + data)?;
+        remaining = remaining.checked_add(values)?;
+    }
+
+    (pos <= buf.len()).then_some(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Frame {
+        position: [f32; 25],
+        status: [i32; 4],
+        large: [i64; 3],
+        nested: Vec<Vec<u16>>,
+        bytes: Bytes,
+        robot_config: [String; 2],
+    }
+
+    /// Serializes as `MessagePack` binary data.
+    struct Bytes(Vec<u8>);
+
+    impl Serialize for Bytes {
+        fn serialize<S: serde::Serializer>(
+            &self,
+            serializer: S,
+        ) -> std::result::Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+
+    /// Encodes a frame of which the `MessagePack` map is `extra` bytes longer than the minimum.
+    fn frame(extra: usize) -> Vec<u8> {
+        let frame = Frame {
+            position: std::array::from_fn(|i| i as f32 / 10.0),
+            status: [0, -1, 300, -70_000],
+            large: [i64::MAX, i64::MIN, 1 << 40],
+            nested: vec![vec![1, 2, 300], vec![], vec![65_535; 20]],
+            bytes: Bytes(vec![0xc1; 300]),
+            robot_config: ["body".repeat(extra), "head".to_string()],
+        };
+
+        rmp_serde::to_vec_named(&frame).unwrap()
+    }
+
+    fn padded(mut frame: Vec<u8>, size: usize) -> Vec<u8> {
+        frame.resize(size, 0);
+        frame
+    }
+
+    /// Stream that returns at most `chunk` bytes per read.
+    struct Chunked<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.chunk);
+            (&mut self.data).take(len as u64).read(buf)
+        }
+    }
+
+    #[test]
+    fn test_msgpack_len() {
+        let frame = frame(0);
+        assert_eq!(msgpack_len(&frame), Some(frame.len()));
+        for len in 0..frame.len() {
+            assert_eq!(msgpack_len(&frame[..len]), None, "prefix of {len} bytes");
+        }
+
+        let mut padded = padded(frame.clone(), 1024);
+        assert_eq!(msgpack_len(&padded), Some(frame.len()));
+        padded[0] = 0xdf;
+        assert_eq!(msgpack_len(&padded[..5]), None);
+    }
+
+    #[test]
+    fn test_fixed_size_frames() {
+        let frame = padded(frame(0), LOLA_BUFFER_SIZE);
+        let stream = frame.repeat(3);
+        let mut stream = stream.as_slice();
+
+        let mut reader = FrameReader::default();
+        for _ in 0..3 {
+            reader.read(&mut stream).unwrap();
+            assert_eq!(reader.frame(), frame);
+            assert_eq!(reader.mode, Mode::Fixed);
+        }
+        assert!(matches!(
+            reader.read(&mut stream),
+            Err(Error::ConnectionClosed)
+        ));
+    }
+
+    #[test]
+    fn test_frames_of_other_sizes() {
+        let small = frame(0);
+        let large = frame(200);
+        assert!(small.len() < LOLA_BUFFER_SIZE);
+        assert!(large.len() > LOLA_BUFFER_SIZE);
+
+        for frames in [
+            vec![small.clone(); 3],
+            vec![padded(small.clone(), 1000); 3],
+            vec![large.clone(); 3],
+        ] {
+            let stream = frames.concat();
+            let mut stream = Chunked {
+                data: &stream,
+                chunk: 100,
+            };
+
+            let mut reader = FrameReader::default();
+            for frame in &frames {
+                reader.read(&mut stream).unwrap();
+                assert_eq!(msgpack_len(frame), Some(reader.frame().len()));
+                assert_eq!(reader.mode, Mode::LengthAware);
+            }
+        }
+    }
+
+    #[test]
+    fn test_size_change_falls_back() {
+        let fixed = padded(frame(0), LOLA_BUFFER_SIZE);
+        let large = frame(200);
+        let stream = [fixed.clone(), fixed.clone(), large.clone(), fixed.clone()].concat();
+        let mut stream = stream.as_slice();
+
+        let mut reader = FrameReader::default();
+        reader.read(&mut stream).unwrap();
+        reader.read(&mut stream).unwrap();
+        assert_eq!(reader.mode, Mode::Fixed);
+
+        reader.read(&mut stream).unwrap();
+        assert_eq!(reader.frame(), large);
+        assert_eq!(reader.mode, Mode::LengthAware);
+
+        reader.read(&mut stream).unwrap();
+        assert_eq!(reader.frame(), frame(0));
+    }
+
+    #[test]
+    fn test_strict() {
+        let fixed = padded(frame(0), LOLA_BUFFER_SIZE);
+        let small = frame(0);
+        let large = frame(200);
+        let stream = [fixed.clone(), small.clone(), large.clone(), fixed.clone()].concat();
+        let mut stream = stream.as_slice();
+
+        let mut reader = FrameReader::new(true);
+        reader.read(&mut stream).unwrap();
+        assert_eq!(reader.frame(), fixed);
+
+        for frame in [&small, &large] {
+            match reader.read(&mut stream) {
+                Err(Error::UnexpectedFrameSize { expected, got }) => {
+                    assert_eq!(expected, LOLA_BUFFER_SIZE);
+                    assert_eq!(got, frame.len());
+                }
+                result => panic!("expected an unexpected frame size, got {result:?}"),
+            }
+        }
+
+        // the frames stay aligned after a frame of the wrong size
+        reader.read(&mut stream).unwrap();
+        assert_eq!(reader.frame(), fixed);
+    }
+
+    #[test]
+    fn test_garbage_is_returned_as_frame() {
+        let garbage = vec![0xc1; LOLA_BUFFER_SIZE];
+        let mut stream = garbage.as_slice();
+
+        let mut reader = FrameReader::new(true);
+        reader.read(&mut stream).unwrap();
+        assert_eq!(reader.frame(), garbage);
+    }
+}
@@ -1,10 +1,12 @@
-//! `LoLA` backend that communicates through the socket at `/tmp/robocup`.
+//! `LoLA` backend that communicates through the socket at `/tmp/robocup`, or over TCP.
 //!
+//! The wire types [`LolaNaoState`] and [`LolaControlMsg`] can be serialized and deserialized in both
+//! directions, for tools that inspect or convert the traffic between `LoLA` and a client.
 
 use crate::{
     types::{
-        Battery, Fsr, FsrFoot, JointArray, LeftEar, LeftEye, Rgb, RgbF32, RightEar, RightEye,
-        Skull, SonarEnabled, SonarValues, Touch,
+        for_each_joint, Battery, Ear, Eye, FillExt, Fsr, FsrFoot, JointArray, JointName, Rgb,
+        RgbF32, Side, Skull, SonarMode, SonarValues, Touch,
     },
     DisconnectExt, Error, HardwareInfo, NaoBackend, NaoControlMessage, NaoState, Result,
 };
@@ -14,60 +16,701 @@ use nalgebra::{Vector2, Vector3};
 use rmp_serde::{encode, from_slice};
 use serde::{Deserialize, Serialize};
 use std::{
-    io::{BufWriter, Read, Write},
+    env,
+    io::{Read, Write},
+    net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs},
     os::unix::net::UnixStream,
-    time::Duration,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 
-use super::{ConnectWithRetry, ReadHardwareInfo};
+use super::{
+    frame::{FrameReader, LOLA_BUFFER_SIZE},
+    BackendCapabilities, ConnectWithPath, ConnectWithRetry, FrameMeta, FrameStats,
+    FrameStatsCollector, FrameTracker, ReadHardwareInfo, RetryPolicy,
+};
 use std::any::type_name;
-use std::thread;
-use tracing::info;
+use tracing::{debug_span, field, info_span, trace, warn};
 
 const ROBOCUP_SOCKET_PATH: &str = "/tmp/robocup";
-const LOLA_BUFFER_SIZE: usize = 896;
+/// Environment variable that overrides the socket path used by [`LolaBackend::connect`].
+pub const LOLA_SOCKET_ENV: &str = "NIDHOGG_LOLA_SOCKET";
+/// Environment variable that makes new [`LolaBackend`]s log every frame at the `TRACE` level,
+/// when set to anything other than `0`. See [`LolaBackend::set_trace_frames`].
+pub const TRACE_FRAMES_ENV: &str = "NIDHOGG_TRACE_FRAMES";
+/// Number of bytes of a frame included in the warning logged when it cannot be decoded.
+const DECODE_ERROR_DUMP_LEN: usize = 32;
 
 /// `LoLA` backend that communicates with a real NAO V6 through the socket at `/tmp/robocup`
+///
+/// The backend can also connect to a `LoLA` compatible TCP server using [`LolaBackend::connect_tcp`],
+/// which is exposed by several simulators and remote bridges.
+///
+/// Frames are expected to be 896 bytes, but frames of other sizes are read as well,
+/// see [`LolaBackend::with_strict_frame_size`].
+///
+/// # Tracing
+///
+/// Every read and write is wrapped in a `lola.read` or `lola.write` span at the `DEBUG` level, with the
+/// sequence number of the frame and the number of bytes transferred. Connection attempts are wrapped in
+/// a `connect` span with the attempt number. Frames that cannot be decoded are logged at the `WARN` level.
+/// When the `tracing` crate is compiled with a maximum level such as `max_level_info`, the spans compile to nothing.
+#[derive(Debug)]
+pub struct LolaBackend {
+    stream: LolaStream,
+    endpoint: Endpoint,
+    reconnect: Option<RetryPolicy>,
+    reconnected: bool,
+    stats: Option<FrameStatsCollector>,
+    tracker: Option<FrameTracker>,
+    /// Metadata of the last frame that was read, only recorded if frames are tracked.
+    frame_meta: FrameMeta,
+    /// Hardware info of the robot, parsed from the first state read on the current connection.
+    hardware_info: Option<HardwareInfo>,
+    /// Joint positions of the last state read on the current connection, which hold the joints a control message does not command.
+    measured_position: Option<JointArray<f32>>,
+    /// Reads frames from `LoLA`, and holds the last frame that was read.
+    frames: FrameReader,
+    /// Buffer that holds the last encoded control message, reused for every write.
+    write_buf: Vec<u8>,
+    read_timeout: Option<Duration>,
+    /// Skips writing control messages that are equal to the previous one, if enabled.
+    dedup: Option<ControlDedup>,
+    /// Number of control messages sent or skipped as duplicates, used to alternate the sonar emitters.
+    sent_frames: u64,
+    /// Number of frames read, used as the sequence number of the `lola.read` span.
+    read_frames: u64,
+    /// Log every frame at the `TRACE` level.
+    trace_frames: bool,
+    /// `true` if the frame in `buf` was read for the hardware info, and has not been returned as a state yet.
+    peeked: bool,
+}
+
+/// Configuration for skipping duplicate control messages, see [`LolaBackend::with_dedup_config`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DedupConfig {
+    /// Maximum difference between two values that are considered equal.
+    ///
+    /// The LED intensities are always rounded to the 256 levels of the LEDs before comparing.
+    pub epsilon: f32,
+    /// Compare the joint positions and stiffness within `epsilon` as well.
+    ///
+    /// By default joints are compared exactly, so any change to a joint is written.
+    pub include_joints: bool,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            epsilon: 0.0,
+            include_joints: false,
+        }
+    }
+}
+
+/// Remembers the last written control message, to skip writing duplicates.
+#[derive(Debug)]
+struct ControlDedup {
+    config: DedupConfig,
+    last: Option<LolaControlMsg>,
+    skipped: u64,
+}
+
+/// The result of [`LolaBackend::read_nao_state_with_outcome`].
+#[derive(Clone, Debug)]
+pub struct ReadOutcome {
+    /// The state of the robot.
+    pub state: NaoState,
+    /// `true` if the backend reconnected to `LoLA` since the previous read.
+    ///
+    /// Controllers should reset any accumulated state, such as integrators, when this is set.
+    pub reconnected: bool,
+}
+
+/// The address a [`LolaBackend`] is connected to.
+#[derive(Clone, Debug)]
+enum Endpoint {
+    Unix(PathBuf),
+    Tcp(SocketAddr),
+}
+
+impl Endpoint {
+    fn connect(&self) -> std::io::Result<LolaStream> {
+        match self {
+            Endpoint::Unix(path) => UnixStream::connect(path).map(LolaStream::Unix),
+            Endpoint::Tcp(addr) => {
+                let stream = TcpStream::connect(addr)?;
+                // control messages are small and latency sensitive.
+                stream.set_nodelay(true)?;
+                Ok(LolaStream::Tcp(stream))
+            }
+        }
+    }
+}
+
+/// The transport used to communicate with `LoLA`.
 #[derive(Debug)]
-pub struct LolaBackend(UnixStream);
+enum LolaStream {
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl LolaStream {
+    fn shutdown(&self) -> std::io::Result<()> {
+        match self {
+            LolaStream::Unix(stream) => stream.shutdown(Shutdown::Both),
+            LolaStream::Tcp(stream) => stream.shutdown(Shutdown::Both),
+        }
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        match self {
+            LolaStream::Unix(stream) => stream.set_read_timeout(timeout),
+            LolaStream::Tcp(stream) => stream.set_read_timeout(timeout),
+        }
+    }
+}
+
+impl Read for LolaStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            LolaStream::Unix(stream) => stream.read(buf),
+            LolaStream::Tcp(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for LolaStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            LolaStream::Unix(stream) => stream.write(buf),
+            LolaStream::Tcp(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            LolaStream::Unix(stream) => stream.flush(),
+            LolaStream::Tcp(stream) => stream.flush(),
+        }
+    }
+}
 
 impl LolaBackend {
-    fn connect_with_path(socket_path: &str) -> Result<Self> {
-        let stream = UnixStream::connect(socket_path).map_err(Error::NoLoLAConnection)?;
+    /// The path of the `LoLA` socket used by [`LolaBackend::connect`].
+    ///
+    /// This is the value of the `NIDHOGG_LOLA_SOCKET` environment variable if it is set,
+    /// and `/tmp/robocup` otherwise.
+    pub fn socket_path() -> PathBuf {
+        env::var_os(LOLA_SOCKET_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(ROBOCUP_SOCKET_PATH))
+    }
+
+    /// Connects to the `LoLA` socket at the provided path.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nidhogg::backend::LolaBackend;
+    ///
+    /// let mut nao = LolaBackend::connect_with_path("/tmp/robocup").expect("Could not connect to the NAO! 😪");
+    /// ```
+    pub fn connect_with_path(socket_path: impl AsRef<Path>) -> Result<Self> {
+        let endpoint = Endpoint::Unix(socket_path.as_ref().to_path_buf());
+        let stream = endpoint.connect().map_err(Error::NoLoLAConnection)?;
 
-        Ok(LolaBackend(stream))
+        Ok(LolaBackend::new(stream, endpoint))
     }
 
+    /// Connects to the `LoLA` socket at the provided path, by trying multiple times with an interval in between.
+    ///
+    /// `retry_count` is the number of retries after the first attempt, so `0` tries once.
+    /// The error of the last attempt is returned without waiting another interval.
+    ///
+    /// Use [`ConnectWithRetry::connect_with_policy_path`] for more control over the retries.
     pub fn connect_with_path_with_retry(
         retry_count: u32,
         retry_interval: Duration,
-        socket_path: &str,
+        socket_path: impl AsRef<Path>,
     ) -> Result<Self> {
-        for i in 0..=retry_count {
-            info!(
-                "[{}/{}] Connecting to {}",
-                i,
-                retry_count,
-                type_name::<Self>()
-            );
+        Self::connect_with_retry_path(retry_count, retry_interval, socket_path)
+    }
+
+    /// Connects to a `LoLA` compatible server over TCP.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nidhogg::backend::LolaBackend;
+    ///
+    /// // Connect to a simulator exposing LoLA on port 5555
+    /// let mut nao = LolaBackend::connect_tcp("127.0.0.1:5555").expect("Could not connect to the simulator!");
+    /// ```
+    pub fn connect_tcp(addr: impl ToSocketAddrs) -> Result<Self> {
+        let stream = TcpStream::connect(addr).map_err(Error::NoLoLAConnection)?;
+        // reconnect to the same address, even if `addr` resolves to multiple addresses.
+        let endpoint = Endpoint::Tcp(stream.peer_addr()?);
+        // control messages are small and latency sensitive.
+        stream.set_nodelay(true)?;
+
+        Ok(LolaBackend::new(LolaStream::Tcp(stream), endpoint))
+    }
+
+    /// Connects to a `LoLA` compatible server over TCP, by trying multiple times with an interval in between.
+    pub fn connect_tcp_with_retry(
+        retry_count: u32,
+        retry_interval: Duration,
+        addr: impl ToSocketAddrs,
+    ) -> Result<Self> {
+        Self::connect_tcp_with_policy(&RetryPolicy::fixed(retry_count, retry_interval), addr)
+    }
+
+    /// Connects to a `LoLA` compatible server over TCP, retrying according to the provided [`RetryPolicy`].
+    pub fn connect_tcp_with_policy(policy: &RetryPolicy, addr: impl ToSocketAddrs) -> Result<Self> {
+        policy.retry(type_name::<Self>(), || Self::connect_tcp(&addr))
+    }
+
+    fn new(stream: LolaStream, endpoint: Endpoint) -> Self {
+        Self {
+            stream,
+            endpoint,
+            reconnect: None,
+            reconnected: false,
+            stats: None,
+            tracker: None,
+            frame_meta: FrameMeta::default(),
+            hardware_info: None,
+            measured_position: None,
+            frames: FrameReader::default(),
+            write_buf: Vec::new(),
+            read_timeout: None,
+            dedup: None,
+            sent_frames: 0,
+            read_frames: 0,
+            trace_frames: env::var_os(TRACE_FRAMES_ENV).is_some_and(|value| value != "0"),
+            peeked: false,
+        }
+    }
+
+    /// Log the full contents of every frame read and written at the `TRACE` level.
+    ///
+    /// This is disabled by default, unless the `NIDHOGG_TRACE_FRAMES` environment variable is set.
+    pub fn set_trace_frames(&mut self, enabled: bool) {
+        self.trace_frames = enabled;
+    }
+
+    /// Sets the maximum time a read waits for `LoLA` to send a state, [`None`] waits indefinitely.
+    ///
+    /// A read that times out returns [`Error::ReadTimeout`].
+    /// The timeout is also applied to the connections made when reconnecting.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `timeout` is zero.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        self.stream.set_read_timeout(timeout)?;
+        self.read_timeout = timeout;
+        Ok(())
+    }
+
+    /// Collect cycle time statistics, which can be retrieved using [`LolaBackend::stats`].
+    ///
+    /// A warning is logged for every cycle that exceeds the [`CYCLE_BUDGET`](super::CYCLE_BUDGET).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nidhogg::{NaoBackend, NaoControlMessage, backend::LolaBackend};
+    ///
+    /// let mut nao = LolaBackend::connect().unwrap().with_stats();
+    ///
+    /// let state = nao.read_nao_state().unwrap();
+    /// nao.send_control_msg(NaoControlMessage::holding(&state)).unwrap();
+    ///
+    /// let stats = nao.stats().unwrap();
+    /// println!("max cycle time: {:?}", stats.max_cycle);
+    /// ```
+    #[must_use]
+    pub fn with_stats(mut self) -> Self {
+        self.stats = Some(FrameStatsCollector::default());
+        self
+    }
+
+    /// Require every frame sent by `LoLA` to be exactly 896 bytes, the size of a frame on a NAO V6.
+    ///
+    /// By default, the size of the frames is detected from the first frame of every connection.
+    /// Frames of 896 bytes are read in one go, and frames of any other size are read by parsing
+    /// the `MessagePack` headers, so that a firmware update that changes the frame layout does not
+    /// result in a hang or misaligned frames. In strict mode, reading a frame of another size
+    /// returns [`Error::UnexpectedFrameSize`] instead.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nidhogg::{NaoBackend, Error, backend::LolaBackend};
+    ///
+    /// let mut nao = LolaBackend::connect().unwrap().with_strict_frame_size();
+    ///
+    /// if let Err(Error::UnexpectedFrameSize { got, .. }) = nao.read_nao_state() {
+    ///     eprintln!("LoLA sent a frame of {got} bytes, is the firmware up to date?");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn with_strict_frame_size(mut self) -> Self {
+        self.frames = FrameReader::new(true);
+        self
+    }
+
+    /// Writes a control message without validating it.
+    ///
+    /// Unlike [`NaoBackend::send_control_msg`], values are passed to `LoLA` as is, including NaN
+    /// and infinite values, and LED intensities outside of `0.0..=1.0`.
+    pub fn send_control_msg_unchecked(&mut self, control_msg: NaoControlMessage) -> Result<()> {
+        let raw = self.lola_control_msg(control_msg);
+        self.write_control_msg(raw)
+    }
+
+    /// Converts `control_msg`, resolving the sonar emitters for the next frame.
+    ///
+    /// Joints that are not commanded hold the position of the last state that was read.
+    /// If no state has been read on the current connection, their position is sent unchanged.
+    fn lola_control_msg(&self, control_msg: NaoControlMessage) -> LolaControlMsg {
+        let control_msg = match &self.measured_position {
+            Some(measured) => control_msg.hold_uncommanded(measured),
+            None => control_msg,
+        };
+        let sonar = control_msg.sonar.emitters(self.sent_frames);
+
+        LolaControlMsg {
+            sonar,
+            ..LolaControlMsg::from(control_msg)
+        }
+    }
+
+    fn write_control_msg(&mut self, mut raw: LolaControlMsg) -> Result<()> {
+        if let Some(dedup) = &mut self.dedup {
+            raw.quantize_leds();
+            if dedup
+                .last
+                .as_ref()
+                .is_some_and(|last| raw.approx_eq(last, &dedup.config))
+            {
+                trace!("Skipping duplicate control message");
+                dedup.skipped += 1;
+                self.sent_frames = self.sent_frames.wrapping_add(1);
+                return Ok(());
+            }
+        }
+
+        // convert to MessagePack and write it to the socket in one go, reusing the buffer of the previous message
+        let mut buf = std::mem::take(&mut self.write_buf);
+        buf.clear();
+        let result = encode::write_named(&mut buf, &raw).map_err(Error::MsgPackEncodeError);
+        let result = result.and_then(|()| self.write_frame(&buf));
+        self.write_buf = buf;
+
+        if let (Ok(()), Some(dedup)) = (&result, &mut self.dedup) {
+            dedup.last = Some(raw);
+        }
+        result
+    }
+
+    fn write_frame(&mut self, buf: &[u8]) -> Result<()> {
+        let _span =
+            debug_span!("lola.write", sequence = self.sent_frames, bytes = buf.len()).entered();
+        if self.trace_frames {
+            trace!(frame = %Hex(buf), "Writing frame");
+        }
+        self.with_reconnect_retry(|stream| stream.write_all(buf))?;
+        self.sent_frames = self.sent_frames.wrapping_add(1);
+
+        if let Some(stats) = &mut self.stats {
+            stats.record_send(Instant::now());
+        }
+
+        Ok(())
+    }
+
+    /// Returns a snapshot of the cycle time statistics, or `None` if they are not collected.
+    pub fn stats(&self) -> Option<FrameStats> {
+        self.stats.as_ref().map(FrameStatsCollector::snapshot)
+    }
+
+    /// Reset the collected cycle time statistics.
+    pub fn reset_stats(&mut self) {
+        if let Some(stats) = &mut self.stats {
+            stats.reset();
+        }
+    }
+
+    /// Automatically reconnect using the provided policy when the connection to `LoLA` is lost,
+    /// for example because the `LoLA` service restarted.
+    ///
+    /// A read or write that fails because the connection was closed reconnects and is retried once.
+    /// An error is only returned if reconnecting fails. Reconnecting reports every attempt like connecting
+    /// does, see [`RetryPolicy::on_attempt`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use nidhogg::{NaoBackend, backend::{LolaBackend, RetryPolicy}};
+    ///
+    /// // wait 100ms, 200ms, 400ms, ... but never more than 2 seconds between attempts
+    /// let policy = RetryPolicy::exponential(10, Duration::from_millis(100), 2.0)
+    ///     .with_max_interval(Duration::from_secs(2));
+    /// let mut nao = LolaBackend::connect().unwrap().with_reconnect(policy);
+    ///
+    /// let outcome = nao.read_nao_state_with_outcome().unwrap();
+    /// if outcome.reconnected {
+    ///     // reset controllers
+    /// }
+    /// ```
+    #[must_use]
+    pub fn with_reconnect(mut self, policy: RetryPolicy) -> Self {
+        self.reconnect = Some(policy);
+        self
+    }
+
+    /// Skip writing control messages that are equal to the previously written message, within `epsilon`.
+    ///
+    /// The LED intensities are rounded to the 256 levels of the LEDs, so float noise from multiple
+    /// subsystems writing the LEDs does not make the LEDs flicker. Joints are compared exactly,
+    /// use [`LolaBackend::with_dedup_config`] to compare them within `epsilon` as well.
+    ///
+    /// `LoLA` keeps the previous values when no control message arrives. Skipped messages are
+    /// counted by [`LolaBackend::skipped_writes`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nidhogg::{NaoBackend, NaoControlMessage, backend::LolaBackend, types::color};
+    ///
+    /// let mut nao = LolaBackend::connect().unwrap().with_dedup(0.01);
+    ///
+    /// let msg = NaoControlMessage::builder().chest(color::f32::CYAN).build();
+    /// nao.send_control_msg(msg.clone()).unwrap();
+    /// nao.send_control_msg(msg).unwrap();
+    ///
+    /// assert_eq!(nao.skipped_writes(), 1);
+    /// ```
+    #[must_use]
+    pub fn with_dedup(self, epsilon: f32) -> Self {
+        self.with_dedup_config(DedupConfig {
+            epsilon,
+            ..DedupConfig::default()
+        })
+    }
+
+    /// Skip writing duplicate control messages, using the provided configuration.
+    ///
+    /// See [`LolaBackend::with_dedup`].
+    #[must_use]
+    pub fn with_dedup_config(mut self, config: DedupConfig) -> Self {
+        self.dedup = Some(ControlDedup {
+            config,
+            last: None,
+            skipped: 0,
+        });
+        self
+    }
+
+    /// The number of control messages that were not written because they were duplicates.
+    ///
+    /// This is always zero if deduplication is not enabled.
+    pub fn skipped_writes(&self) -> u64 {
+        self.dedup.as_ref().map_or(0, |dedup| dedup.skipped)
+    }
+
+    /// Track the arrival of frames using the provided tracker, to detect frames that were missed.
+    ///
+    /// The [`FrameMeta`] of every frame is returned by [`LolaBackend::read_nao_state_tracked`],
+    /// and missed frames are counted in [`FrameStats::missed_frames`] if statistics are collected.
+    #[must_use]
+    pub fn with_frame_tracker(mut self, tracker: FrameTracker) -> Self {
+        self.tracker = Some(tracker);
+        self
+    }
+
+    /// Reads the current sensor data, together with the [`FrameMeta`] of the frame it was read from.
+    ///
+    /// Frames are tracked using a default [`FrameTracker`] from the first call onwards,
+    /// unless a tracker was provided using [`LolaBackend::with_frame_tracker`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use nidhogg::backend::LolaBackend;
+    /// use nidhogg::NaoBackend;
+    ///
+    /// let mut nao = LolaBackend::connect().unwrap();
+    ///
+    /// let (state, meta) = nao.read_nao_state_tracked().unwrap();
+    /// if meta.missed > 0 {
+    ///     println!("missed {} frames before frame {}", meta.missed, meta.seq);
+    /// }
+    /// ```
+    pub fn read_nao_state_tracked(&mut self) -> Result<(NaoState, FrameMeta)> {
+        self.tracker.get_or_insert_with(FrameTracker::default);
+        let state = self.read_nao_state()?;
+
+        Ok((state, self.frame_meta))
+    }
+
+    /// Reads the current sensor data, and whether the backend reconnected since the previous read.
+    pub fn read_nao_state_with_outcome(&mut self) -> Result<ReadOutcome> {
+        let state = self.read_lola_state_ref()?.to_nao_state();
+
+        Ok(ReadOutcome {
+            state,
+            reconnected: std::mem::take(&mut self.reconnected),
+        })
+    }
+
+    /// Reads the current sensor data, and returns a view that borrows from the read buffer of the backend.
+    ///
+    /// Unlike [`NaoBackend::read_nao_state`], this does not build an owned [`NaoState`],
+    /// and the strings in the robot config are not copied.
+    /// The numeric values are still decoded from `MessagePack`, as `LoLA` sends them big-endian.
+    ///
+    /// # Aliasing
+    ///
+    /// The returned [`LolaStateRef`] mutably borrows the backend, so no control message can be
+    /// sent and no other state can be read until it is dropped.
+    /// Every read overwrites the same buffer, copy out whatever has to outlive the view
+    /// using [`LolaStateRef::to_nao_state`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nidhogg::{NaoBackend, NaoControlMessage, backend::LolaBackend};
+    ///
+    /// let mut nao = LolaBackend::connect().unwrap();
+    ///
+    /// let state = nao.read_lola_state_ref().unwrap();
+    /// let charge = state.battery()[0];
+    /// // the view has to be dropped before the backend can be used again
+    /// drop(state);
+    ///
+    /// if charge < 0.2 {
+    ///     nao.send_control_msg(NaoControlMessage::unstiff()).unwrap();
+    /// }
+    /// ```
+    pub fn read_lola_state_ref(&mut self) -> Result<LolaStateRef<'_>> {
+        self.read_frame()?;
+
+        let state = decode_frame(self.frames.frame())?;
+        if self.hardware_info.is_none() {
+            self.hardware_info = Some(HardwareInfo::from(&state));
+        }
+        self.measured_position = Some(JointArray::from_lola(state.position));
 
-            let maybe_backend = Self::connect_with_path(socket_path);
+        Ok(LolaStateRef { state })
+    }
+
+    /// Reads the current sensor data, together with the hardware info sent in the same frame.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use nidhogg::backend::LolaBackend;
+    ///
+    /// let mut nao = LolaBackend::connect_with_path("/tmp/robocup").unwrap();
+    ///
+    /// let (state, info) = nao.read_state_and_hardware_info().unwrap();
+    /// println!("{} has {:.0}% battery", info.body_id, state.battery.charge * 100.0);
+    /// ```
+    pub fn read_state_and_hardware_info(&mut self) -> Result<(NaoState, HardwareInfo)> {
+        let state = self.read_lola_state_ref()?;
+        let info = HardwareInfo::from(&state.state);
 
-            // We connected or this was the last try
-            if maybe_backend.is_ok() || i == retry_count {
-                return maybe_backend;
+        Ok((state.to_nao_state(), info))
+    }
+
+    /// Reads a single frame from `LoLA` into the read buffer, unless a frame was peeked by
+    /// [`ReadHardwareInfo::read_hardware_info`].
+    fn read_frame(&mut self) -> Result<()> {
+        if std::mem::take(&mut self.peeked) {
+            return Ok(());
+        }
+
+        let span = debug_span!(
+            "lola.read",
+            sequence = self.read_frames,
+            bytes = field::Empty
+        );
+        let _span = span.enter();
+
+        match self.frames.read(&mut self.stream) {
+            Err(Error::ConnectionClosed) if self.reconnect.is_some() => {
+                self.reconnect()?;
+                self.frames.read(&mut self.stream)
             }
+            result => result,
+        }?;
+
+        let frame = self.frames.frame();
+        self.read_frames = self.read_frames.wrapping_add(1);
+        span.record("bytes", frame.len());
+        if self.trace_frames {
+            trace!(frame = %Hex(frame), "Read frame");
+        }
+
+        let now = Instant::now();
+        if let Some(tracker) = &mut self.tracker {
+            self.frame_meta = tracker.record(now);
+        }
+        if let Some(stats) = &mut self.stats {
+            stats.record_read(now);
+            stats.record_missed(self.frame_meta.missed);
+        }
 
-            thread::sleep(retry_interval);
+        Ok(())
+    }
+
+    /// Run `transfer` on the stream, reconnecting and retrying once if the connection was closed.
+    fn with_reconnect_retry(
+        &mut self,
+        mut transfer: impl FnMut(&mut LolaStream) -> std::io::Result<()>,
+    ) -> Result<()> {
+        match transfer(&mut self.stream).map_err(Error::from_transfer) {
+            Err(Error::ConnectionClosed) if self.reconnect.is_some() => {
+                self.reconnect()?;
+                transfer(&mut self.stream).map_err(Error::from_transfer)
+            }
+            result => result,
         }
+    }
 
-        unreachable!()
+    fn reconnect(&mut self) -> Result<()> {
+        let Some(policy) = &self.reconnect else {
+            return Err(Error::ConnectionClosed);
+        };
+
+        let _span = info_span!("reconnect").entered();
+        let target = format!("{} at {:?}", type_name::<Self>(), self.endpoint);
+        let stream = policy.retry(&target, || {
+            self.endpoint.connect().map_err(Error::NoLoLAConnection)
+        })?;
+
+        stream.set_read_timeout(self.read_timeout)?;
+        self.stream = stream;
+        self.reconnected = true;
+        self.hardware_info = None;
+        self.measured_position = None;
+        self.peeked = false;
+        self.frames.reset();
+        if let Some(tracker) = &mut self.tracker {
+            tracker.restart();
+        }
+        // the new connection has not received any control message yet
+        if let Some(dedup) = &mut self.dedup {
+            dedup.last = None;
+        }
+        Ok(())
     }
 }
 
 impl NaoBackend for LolaBackend {
     /// Connects to a NAO backend
     ///
+    /// The socket path can be overridden using the `NIDHOGG_LOLA_SOCKET` environment variable,
+    /// see [`LolaBackend::socket_path`].
+    ///
     /// # Examples
     /// ```no_run
     /// use nidhogg::{NaoBackend, backend::LolaBackend};
@@ -76,7 +719,7 @@ impl NaoBackend for LolaBackend {
     /// let mut nao = LolaBackend::connect().expect("Could not connect to the NAO! 😪");
     /// ```
     fn connect() -> Result<Self> {
-        Self::connect_with_path(ROBOCUP_SOCKET_PATH)
+        Self::connect_with_path(Self::socket_path())
     }
 
     /// Converts a control message to the format required by the backend and writes it to that backend.
@@ -93,12 +736,16 @@ impl NaoBackend for LolaBackend {
     /// // Now we send it to the NAO!
     /// nao.send_control_msg(msg).expect("Failed to write control message to backend!");
     /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidControlValue`] if any position, stiffness or LED value is NaN or infinite,
+    /// use [`LolaBackend::send_control_msg_unchecked`] to skip this check.
+    /// LED intensities are clamped to the range `0.0..=1.0`.
     fn send_control_msg(&mut self, control_msg: NaoControlMessage) -> Result<()> {
-        let raw: LolaControlMsg = control_msg.into();
+        let raw = self.lola_control_msg(control_msg).validated()?;
 
-        // convert to MessagePack and write to the socket in a buffer
-        let mut buf = BufWriter::new(&mut self.0);
-        encode::write_named(&mut buf, &raw).map_err(Error::MsgPackEncodeError)
+        self.write_control_msg(raw)
     }
 
     /// Reads the current sensor data from the chosen backend
@@ -113,9 +760,13 @@ impl NaoBackend for LolaBackend {
     /// let state = nao.read_nao_state().expect("Failed to retrieve sensor data!");
     /// ```
     fn read_nao_state(&mut self) -> Result<NaoState> {
-        let mut buf = [0; LOLA_BUFFER_SIZE];
+        self.read_nao_state_with_outcome()
+            .map(|outcome| outcome.state)
+    }
 
-        Ok(self.read_lola_nao_state(&mut buf)?.into())
+    /// `LoLA` runs on a real robot, which measures everything at 83 Hz.
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::ALL
     }
 }
 
@@ -133,17 +784,57 @@ impl DisconnectExt for LolaBackend {
     /// nao.disconnect().expect("Failed to shutdown connection!");
     /// ```
     fn disconnect(self) -> Result<()> {
-        Ok(self.0.shutdown(std::net::Shutdown::Both)?)
+        Ok(self.stream.shutdown()?)
     }
 }
 
 impl ConnectWithRetry for LolaBackend {}
 
+impl ConnectWithPath for LolaBackend {
+    fn connect_with_path(path: &Path) -> Result<Self> {
+        LolaBackend::connect_with_path(path)
+    }
+}
+
 impl ReadHardwareInfo for LolaBackend {
+    /// Returns the [`HardwareInfo`] of the NAO.
+    ///
+    /// The hardware info is parsed once per connection, from the first state that is read.
+    /// Only if no state has been read yet, this reads a state from `LoLA`.
+    /// That state is not lost, it is returned by the next read of the state.
     fn read_hardware_info(&mut self) -> Result<HardwareInfo> {
-        let mut buf = [0; LOLA_BUFFER_SIZE];
+        if let Some(info) = &self.hardware_info {
+            return Ok(info.clone());
+        }
 
-        self.read_lola_nao_state(&mut buf).map(LolaNaoState::into)
+        self.read_frame()?;
+        let info = HardwareInfo::from(&decode_frame(self.frames.frame())?);
+        self.hardware_info = Some(info.clone());
+        self.peeked = true;
+
+        Ok(info)
+    }
+}
+
+/// Decodes a frame, logging the start of the frame if it is not a valid state.
+fn decode_frame(buf: &[u8]) -> Result<LolaNaoState<'_>> {
+    from_slice::<LolaNaoState<'_>>(buf).map_err(|error| {
+        let dump = &buf[..buf.len().min(DECODE_ERROR_DUMP_LEN)];
+        warn!(%error, frame = %Hex(dump), "Failed to decode LoLA frame");
+
+        // decode the frame again using a reader that tracks its position, to find where it failed
+        let mut deserializer = rmp_serde::Deserializer::new(std::io::Cursor::new(buf));
+        let _ = LolaNaoStateOwned::deserialize(&mut deserializer);
+        Error::from_frame_decode(error, buf, deserializer.position() as usize)
+    })
+}
+
+/// Formats bytes as lowercase hexadecimal, only when it is actually logged.
+struct Hex<'a>(&'a [u8]);
+
+impl std::fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
     }
 }
 
@@ -152,30 +843,46 @@ impl LolaBackend {
     ///
     /// # Note
     ///
-    /// This reads from the underlying `LoLA` unix socket, which consumes the message
+    /// This reads from the underlying `LoLA` socket, which consumes the message
     /// sent by `LoLA`.
+    /// Use [`LolaBackend::read_lola_state_ref`] to decode from the buffer of the backend instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedFrameSize`] if the frame does not fit in `buf`.
     pub fn read_lola_nao_state<'a>(
         &mut self,
         buf: &'a mut [u8; LOLA_BUFFER_SIZE],
     ) -> Result<LolaNaoState<'a>> {
-        self.0.read_exact(buf)?;
-        from_slice::<LolaNaoState<'_>>(buf).map_err(Error::MsgPackDecodeError)
+        self.read_frame()?;
+
+        let frame = self.frames.frame();
+        if frame.len() > buf.len() {
+            return Err(Error::UnexpectedFrameSize {
+                expected: LOLA_BUFFER_SIZE,
+                got: frame.len(),
+            });
+        }
+        buf[..frame.len()].copy_from_slice(frame);
+        buf[frame.len()..].fill(0);
+
+        decode_frame(buf)
     }
 }
 
 impl Read for LolaBackend {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.0.read(buf)
+        self.stream.read(buf)
     }
 }
 
 impl Write for LolaBackend {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.0.write(buf)
+        self.stream.write(buf)
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
-        self.0.flush()
+        self.stream.flush()
     }
 }
 
@@ -227,93 +934,29 @@ impl<L, N: FromLoLA<L>> IntoNidhogg<N> for L {
     }
 }
 
-impl FromNidhogg<LeftEar> for [f32; 10] {
-    fn from_nidhogg(value: LeftEar) -> Self {
-        [
-            value.l0, value.l1, value.l2, value.l3, value.l4, value.l5, value.l6, value.l7,
-            value.l8, value.l9,
-        ]
-    }
-}
-
-impl FromNidhogg<RightEar> for [f32; 10] {
-    fn from_nidhogg(value: RightEar) -> Self {
-        [
-            value.r9, value.r8, value.r7, value.r6, value.r5, value.r4, value.r3, value.r2,
-            value.r1, value.r0,
-        ]
+impl<S: Side> FromNidhogg<Ear<S>> for [f32; 10] {
+    fn from_nidhogg(value: Ear<S>) -> Self {
+        let leds = value.as_array();
+        S::EAR_LOLA_ORDER.map(|led| leds[led])
     }
 }
 
 impl FromNidhogg<RgbF32> for [f32; 3] {
     fn from_nidhogg(value: RgbF32) -> Self {
+        let value = value.clamped();
         [value.red, value.green, value.blue]
     }
 }
 
-impl FromNidhogg<LeftEye> for [f32; 24] {
-    fn from_nidhogg(value: LeftEye) -> Self {
-        [
-            value.l7.red,
-            value.l0.red,
-            value.l1.red,
-            value.l2.red,
-            value.l3.red,
-            value.l4.red,
-            value.l5.red,
-            value.l6.red,
-            // bad rustfmt
-            value.l7.green,
-            value.l0.green,
-            value.l1.green,
-            value.l2.green,
-            value.l3.green,
-            value.l4.green,
-            value.l5.green,
-            value.l6.green,
-            // bad rustfmt
-            value.l7.blue,
-            value.l0.blue,
-            value.l1.blue,
-            value.l2.blue,
-            value.l3.blue,
-            value.l4.blue,
-            value.l5.blue,
-            value.l6.blue,
-        ]
-    }
-}
+impl<S: Side> FromNidhogg<Eye<S>> for [f32; 24] {
+    fn from_nidhogg(value: Eye<S>) -> Self {
+        let leds = value.as_array();
 
-impl FromNidhogg<RightEye> for [f32; 24] {
-    fn from_nidhogg(value: RightEye) -> Self {
-        [
-            value.r0.red,
-            value.r7.red,
-            value.r6.red,
-            value.r5.red,
-            value.r4.red,
-            value.r3.red,
-            value.r2.red,
-            value.r1.red,
-            // bad rustfmt
-            value.r0.green,
-            value.r7.green,
-            value.r6.green,
-            value.r5.green,
-            value.r4.green,
-            value.r3.green,
-            value.r2.green,
-            value.r1.green,
-            // bad rustfmt
-            value.r0.blue,
-            value.r7.blue,
-            value.r6.blue,
-            value.r5.blue,
-            value.r4.blue,
-            value.r3.blue,
-            value.r2.blue,
-            value.r1.blue,
-        ]
+        // all red values first, then all green values, then all blue values
+        std::array::from_fn(|i| {
+            let color = leds[S::EYE_LOLA_ORDER[i % 8]];
+            [color.red, color.green, color.blue][i / 8]
+        })
     }
 }
 
@@ -338,76 +981,30 @@ impl FromNidhogg<Skull> for [f32; 12] {
 
 impl<T> FromLoLA<[T; 25]> for JointArray<T> {
     fn from_lola(value: [T; 25]) -> Self {
-        let [head_yaw, head_pitch, left_shoulder_pitch, left_shoulder_roll, left_elbow_yaw, // bad rustfmt
-             left_elbow_roll, left_wrist_yaw, left_hip_yaw_pitch, left_hip_roll, left_hip_pitch,
-             left_knee_pitch, left_ankle_pitch, left_ankle_roll, right_hip_roll, right_hip_pitch,
-             right_knee_pitch, right_ankle_pitch, right_ankle_roll, right_shoulder_pitch, right_shoulder_roll,
-             right_elbow_yaw, right_elbow_roll, right_wrist_yaw, left_hand, right_hand] = value;
-
-        Self {
-            head_yaw,
-            head_pitch,
-
-            left_shoulder_pitch,
-            left_shoulder_roll,
-            left_elbow_yaw,
-            left_elbow_roll,
-            left_wrist_yaw,
-
-            left_hip_yaw_pitch,
-            left_hip_roll,
-            left_hip_pitch,
-            left_knee_pitch,
-            left_ankle_pitch,
-            left_ankle_roll,
-
-            right_shoulder_pitch,
-            right_shoulder_roll,
-            right_elbow_yaw,
-            right_elbow_roll,
-            right_wrist_yaw,
-
-            right_hip_roll,
-            right_hip_pitch,
-            right_knee_pitch,
-            right_ankle_pitch,
-            right_ankle_roll,
-
-            left_hand,
-            right_hand,
+        macro_rules! from_lola {
+            (($value:ident) $($field:ident $i:literal $lola:literal),*) => {
+                JointArray {
+                    $($field: $value[$lola].take().expect("every LoLA index is used once")),*
+                }
+            };
         }
+
+        let mut value = value.map(Some);
+        for_each_joint!(from_lola!(value))
     }
 }
 
 impl<T> FromNidhogg<JointArray<T>> for [T; 25] {
     fn from_nidhogg(value: JointArray<T>) -> Self {
-        [
-            value.head_yaw,
-            value.head_pitch,
-            value.left_shoulder_pitch,
-            value.left_shoulder_roll,
-            value.left_elbow_yaw,
-            value.left_elbow_roll,
-            value.left_wrist_yaw,
-            value.left_hip_yaw_pitch,
-            value.left_hip_roll,
-            value.left_hip_pitch,
-            value.left_knee_pitch,
-            value.left_ankle_pitch,
-            value.left_ankle_roll,
-            value.right_hip_roll,
-            value.right_hip_pitch,
-            value.right_knee_pitch,
-            value.right_ankle_pitch,
-            value.right_ankle_roll,
-            value.right_shoulder_pitch,
-            value.right_shoulder_roll,
-            value.right_elbow_yaw,
-            value.right_elbow_roll,
-            value.right_wrist_yaw,
-            value.left_hand,
-            value.right_hand,
-        ]
+        macro_rules! from_nidhogg {
+            (($value:ident, $lola_values:ident) $($field:ident $i:literal $lola:literal),*) => {
+                $($lola_values[$lola] = Some($value.$field);)*
+            };
+        }
+
+        let mut lola_values: [Option<T>; 25] = std::array::from_fn(|_| None);
+        for_each_joint!(from_nidhogg!(value, lola_values));
+        lola_values.map(|value| value.expect("every LoLA index is used once"))
     }
 }
 
@@ -458,16 +1055,17 @@ impl FromNidhogg<SonarValues> for [f32; 2] {
     }
 }
 
-impl FromLoLA<[bool; 2]> for SonarEnabled {
+impl FromLoLA<[bool; 2]> for SonarMode {
     fn from_lola(value: [bool; 2]) -> Self {
         let [left, right] = value;
-        SonarEnabled { left, right }
+        SonarMode::from_emitters(left, right)
     }
 }
 
-impl FromNidhogg<SonarEnabled> for [bool; 2] {
-    fn from_nidhogg(value: SonarEnabled) -> Self {
-        [value.left, value.right]
+/// Uses the emitters of the first frame, see [`SonarMode::emitters`].
+impl FromNidhogg<SonarMode> for [bool; 2] {
+    fn from_nidhogg(value: SonarMode) -> Self {
+        value.emitters(0)
     }
 }
 
@@ -504,7 +1102,24 @@ impl FromLoLA<[f32; 3]> for Vector3<f32> {
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// State of the robot as it is sent by `LoLA`, borrowing the robot config from the frame it was decoded from.
+///
+/// The joint arrays are in `LoLA` order, convert this into a [`NaoState`] to use them.
+/// Use [`LolaNaoStateOwned`] to keep the state after the frame is gone.
+///
+/// # Example
+///
+#[cfg_attr(feature = "testing", doc = "```")]
+#[cfg_attr(not(feature = "testing"), doc = "```ignore")]
+/// use nidhogg::{backend::lola::LolaNaoState, testing::fixtures, HardwareInfo, NaoState};
+///
+/// let frame = fixtures::lola_frame_bytes(&fixtures::standing_state(), &fixtures::hardware_info());
+///
+/// let lola: LolaNaoState<'_> = rmp_serde::from_slice(&frame).unwrap();
+/// assert_eq!(HardwareInfo::from(&lola), fixtures::hardware_info());
+/// assert_eq!(NaoState::from(lola), fixtures::standing_state());
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct LolaNaoState<'a> {
     stiffness: [f32; 25],
@@ -523,6 +1138,160 @@ pub struct LolaNaoState<'a> {
     robot_config: [&'a str; 4],
 }
 
+impl<'a> LolaNaoState<'a> {
+    /// Convert a [`NaoState`] into the state as it is sent by `LoLA`, borrowing the robot config from `info`.
+    pub fn new(state: &NaoState, info: &'a HardwareInfo) -> Self {
+        let fsr_foot = |foot: &FsrFoot| {
+            [
+                foot.front_left,
+                foot.front_right,
+                foot.rear_left,
+                foot.rear_right,
+            ]
+        };
+        let [left_fsr, right_fsr] = [
+            fsr_foot(&state.fsr.left_foot),
+            fsr_foot(&state.fsr.right_foot),
+        ];
+        let touch = &state.touch;
+
+        LolaNaoState {
+            stiffness: state.stiffness.clone().into_lola(),
+            position: state.position.clone().into_lola(),
+            temperature: state.temperature.clone().into_lola(),
+            current: state.current.clone().into_lola(),
+            battery: [
+                state.battery.charge,
+                state.battery.current,
+                state.battery.status,
+                state.battery.temperature,
+            ],
+            accelerometer: state.accelerometer.into(),
+            gyroscope: state.gyroscope.into(),
+            angles: state.angles.into(),
+            sonar: state.sonar.clone().into_lola(),
+            f_s_r: std::array::from_fn(|i| if i < 4 { left_fsr[i] } else { right_fsr[i - 4] }),
+            touch: [
+                touch.chest_board,
+                touch.head_front,
+                touch.head_middle,
+                touch.head_rear,
+                touch.left_foot_left,
+                touch.left_foot_right,
+                touch.left_hand_back,
+                touch.left_hand_left,
+                touch.left_hand_right,
+                touch.right_foot_left,
+                touch.right_foot_right,
+                touch.right_hand_back,
+                touch.right_hand_left,
+                touch.right_hand_right,
+            ],
+            status: state.status.clone().into_lola(),
+            robot_config: [
+                &info.body_id,
+                &info.body_version,
+                &info.head_id,
+                &info.head_version,
+            ],
+        }
+    }
+
+    /// Copy the robot config, so the state no longer borrows from the frame.
+    pub fn into_owned(self) -> LolaNaoStateOwned {
+        LolaNaoStateOwned {
+            stiffness: self.stiffness,
+            position: self.position,
+            temperature: self.temperature,
+            current: self.current,
+            battery: self.battery,
+            accelerometer: self.accelerometer,
+            gyroscope: self.gyroscope,
+            angles: self.angles,
+            sonar: self.sonar,
+            f_s_r: self.f_s_r,
+            touch: self.touch,
+            status: self.status,
+            robot_config: self.robot_config.map(str::to_string),
+        }
+    }
+}
+
+/// Owned version of [`LolaNaoState`], for tools that keep states after the frames they were decoded from.
+///
+/// # Example
+///
+#[cfg_attr(feature = "testing", doc = "```")]
+#[cfg_attr(not(feature = "testing"), doc = "```ignore")]
+/// use nidhogg::{backend::lola::LolaNaoStateOwned, testing::fixtures, NaoState};
+///
+/// let frame = fixtures::lola_frame_bytes(&fixtures::low_battery_state(), &fixtures::hardware_info());
+/// let states: Vec<LolaNaoStateOwned> = vec![rmp_serde::from_read(frame.as_slice()).unwrap()];
+///
+/// assert_eq!(NaoState::from(states[0].clone()).battery.charge, 0.05);
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LolaNaoStateOwned {
+    stiffness: [f32; 25],
+    position: [f32; 25],
+    temperature: [f32; 25],
+    current: [f32; 25],
+    battery: [f32; 4],
+    accelerometer: [f32; 3],
+    gyroscope: [f32; 3],
+    angles: [f32; 2],
+    sonar: [f32; 2],
+    f_s_r: [f32; 8],
+    touch: [f32; 14],
+    status: [i32; 25],
+    robot_config: [String; 4],
+}
+
+impl LolaNaoStateOwned {
+    /// Convert a [`NaoState`] into the state as it is sent by `LoLA`.
+    pub fn new(state: &NaoState, info: &HardwareInfo) -> Self {
+        LolaNaoState::new(state, info).into_owned()
+    }
+
+    /// Borrow the robot config of this state, as a [`LolaNaoState`].
+    pub fn as_borrowed(&self) -> LolaNaoState<'_> {
+        LolaNaoState {
+            stiffness: self.stiffness,
+            position: self.position,
+            temperature: self.temperature,
+            current: self.current,
+            battery: self.battery,
+            accelerometer: self.accelerometer,
+            gyroscope: self.gyroscope,
+            angles: self.angles,
+            sonar: self.sonar,
+            f_s_r: self.f_s_r,
+            touch: self.touch,
+            status: self.status,
+            robot_config: self.robot_config.each_ref().map(String::as_str),
+        }
+    }
+}
+
+impl From<LolaNaoState<'_>> for LolaNaoStateOwned {
+    fn from(value: LolaNaoState<'_>) -> Self {
+        value.into_owned()
+    }
+}
+
+impl From<LolaNaoStateOwned> for NaoState {
+    fn from(value: LolaNaoStateOwned) -> Self {
+        value.as_borrowed().into()
+    }
+}
+
+impl From<&LolaNaoStateOwned> for HardwareInfo {
+    fn from(value: &LolaNaoStateOwned) -> Self {
+        Self::from(&value.as_borrowed())
+    }
+}
+
 impl From<LolaNaoState<'_>> for NaoState {
     fn from(value: LolaNaoState<'_>) -> Self {
         Self {
@@ -542,8 +1311,130 @@ impl From<LolaNaoState<'_>> for NaoState {
     }
 }
 
+/// Borrowed view of a state read by [`LolaBackend::read_lola_state_ref`].
+///
+/// The joint arrays are in `LoLA` order, which differs from the order of the fields of [`JointArray`].
+/// Use [`LolaStateRef::to_nao_state`] to convert them.
+#[derive(Debug)]
+pub struct LolaStateRef<'a> {
+    state: LolaNaoState<'a>,
+}
+
+impl<'a> LolaStateRef<'a> {
+    /// Stiffness of the joints, in `LoLA` order.
+    pub fn stiffness(&self) -> &[f32; 25] {
+        &self.state.stiffness
+    }
+
+    /// Position of the joints in radians, in `LoLA` order.
+    pub fn position(&self) -> &[f32; 25] {
+        &self.state.position
+    }
+
+    /// Temperature of the joints in degrees Celsius, in `LoLA` order.
+    pub fn temperature(&self) -> &[f32; 25] {
+        &self.state.temperature
+    }
+
+    /// Current of the joints in ampere, in `LoLA` order.
+    pub fn current(&self) -> &[f32; 25] {
+        &self.state.current
+    }
+
+    /// Status of the joints, in `LoLA` order.
+    pub fn status(&self) -> &[i32; 25] {
+        &self.state.status
+    }
+
+    /// Battery charge, status, current and temperature.
+    pub fn battery(&self) -> &[f32; 4] {
+        &self.state.battery
+    }
+
+    /// Accelerometer values.
+    pub fn accelerometer(&self) -> &[f32; 3] {
+        &self.state.accelerometer
+    }
+
+    /// Gyroscope values.
+    pub fn gyroscope(&self) -> &[f32; 3] {
+        &self.state.gyroscope
+    }
+
+    /// Torso angles around the x and y axis.
+    pub fn angles(&self) -> &[f32; 2] {
+        &self.state.angles
+    }
+
+    /// Left and right sonar distance.
+    pub fn sonar(&self) -> &[f32; 2] {
+        &self.state.sonar
+    }
+
+    /// Force sensitive resistors, the left foot followed by the right foot.
+    pub fn fsr(&self) -> &[f32; 8] {
+        &self.state.f_s_r
+    }
+
+    /// Touch sensors.
+    pub fn touch(&self) -> &[f32; 14] {
+        &self.state.touch
+    }
+
+    /// Body id, body version, head id and head version, borrowed from the read buffer.
+    pub fn robot_config(&self) -> &[&'a str; 4] {
+        &self.state.robot_config
+    }
+
+    /// Copies the view into an owned [`NaoState`].
+    pub fn to_nao_state(&self) -> NaoState {
+        let state = &self.state;
+
+        NaoState {
+            stiffness: state.stiffness.into_nidhogg(),
+            position: state.position.into_nidhogg(),
+            temperature: state.temperature.into_nidhogg(),
+            current: state.current.into_nidhogg(),
+            battery: state.battery.into_nidhogg(),
+            accelerometer: state.accelerometer.into_nidhogg(),
+            gyroscope: state.gyroscope.into_nidhogg(),
+            angles: state.angles.into_nidhogg(),
+            sonar: state.sonar.into_nidhogg(),
+            fsr: state.f_s_r.into_nidhogg(),
+            touch: state.touch.into_nidhogg(),
+            status: state.status.into_nidhogg(),
+        }
+    }
+}
+
+impl From<LolaStateRef<'_>> for NaoState {
+    fn from(value: LolaStateRef<'_>) -> Self {
+        value.state.into()
+    }
+}
+
+/// Encodes `state` into a frame as it is sent by `LoLA`, padded with zeros to `frame_size` bytes.
+///
+/// Frames that are larger than `frame_size` are not truncated.
+#[cfg(feature = "testing")]
+pub(crate) fn encode_state_frame(
+    state: &NaoState,
+    info: &HardwareInfo,
+    frame_size: usize,
+) -> Result<Vec<u8>> {
+    let mut buf = encode::to_vec_named(&LolaNaoState::new(state, info))?;
+    buf.resize(buf.len().max(frame_size), 0);
+    Ok(buf)
+}
+
 impl From<LolaNaoState<'_>> for HardwareInfo {
     fn from(value: LolaNaoState<'_>) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<&LolaNaoState<'_>> for HardwareInfo {
+    fn from(value: &LolaNaoState<'_>) -> Self {
         Self {
             body_id: value.robot_config[0].to_string(),
             body_version: value.robot_config[1].to_string(),
@@ -553,7 +1444,25 @@ impl From<LolaNaoState<'_>> for HardwareInfo {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Control message as it is sent to `LoLA`.
+///
+/// The joint arrays and LEDs are in `LoLA` order, convert this from and into a [`NaoControlMessage`] to use them.
+///
+/// # Example
+///
+/// Decoding a captured control message:
+///
+/// ```
+/// use nidhogg::{backend::lola::LolaControlMsg, types::color, NaoControlMessage};
+///
+/// # let msg = NaoControlMessage::builder().chest(color::f32::MAGENTA).build();
+/// # let captured = rmp_serde::to_vec_named(&LolaControlMsg::from(msg)).unwrap();
+/// let lola: LolaControlMsg = rmp_serde::from_slice(&captured).unwrap();
+/// let msg = NaoControlMessage::from(lola);
+///
+/// assert_eq!(msg.chest, color::f32::MAGENTA);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct LolaControlMsg {
     position: [f32; 25],
@@ -569,6 +1478,90 @@ pub struct LolaControlMsg {
     sonar: [bool; 2],
 }
 
+impl LolaControlMsg {
+    /// Check that all values are finite, and clamp the LED intensities to `0.0..=1.0`.
+    fn validated(mut self) -> Result<Self> {
+        for (name, (position, stiffness)) in JointName::ALL
+            .into_iter()
+            .zip(self.position.iter().zip(&self.stiffness))
+        {
+            check_finite(|| format!("position.{name}"), *position)?;
+            check_finite(|| format!("stiffness.{name}"), *stiffness)?;
+        }
+
+        for (field, values) in self.leds_mut() {
+            for (index, value) in values.iter_mut().enumerate() {
+                check_finite(|| format!("{field}[{index}]"), *value)?;
+                *value = value.clamp(0.0, 1.0);
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// The LED intensities, together with the name of their field in [`NaoControlMessage`].
+    fn leds_mut(&mut self) -> [(&'static str, &mut [f32]); 8] {
+        [
+            ("right_ear", &mut self.r_ear),
+            ("left_ear", &mut self.l_ear),
+            ("chest", &mut self.chest),
+            ("left_eye", &mut self.l_eye),
+            ("right_eye", &mut self.r_eye),
+            ("left_foot", &mut self.l_foot),
+            ("right_foot", &mut self.r_foot),
+            ("skull", &mut self.skull),
+        ]
+    }
+
+    /// Round the LED intensities to the 256 levels the LEDs can display.
+    fn quantize_leds(&mut self) {
+        for (_, values) in self.leds_mut() {
+            for value in values {
+                *value = (*value * 255.0).round() / 255.0;
+            }
+        }
+    }
+
+    /// Returns `true` if all values are equal within the epsilon of `config`.
+    fn approx_eq(&self, other: &Self, config: &DedupConfig) -> bool {
+        let close = |a: &[f32], b: &[f32]| {
+            a.iter()
+                .zip(b)
+                .all(|(a, b)| (a - b).abs() <= config.epsilon)
+        };
+
+        let joints = if config.include_joints {
+            close(&self.position, &other.position) && close(&self.stiffness, &other.stiffness)
+        } else {
+            self.position == other.position && self.stiffness == other.stiffness
+        };
+
+        joints
+            && self.sonar == other.sonar
+            && close(&self.r_ear, &other.r_ear)
+            && close(&self.l_ear, &other.l_ear)
+            && close(&self.chest, &other.chest)
+            && close(&self.l_eye, &other.l_eye)
+            && close(&self.r_eye, &other.r_eye)
+            && close(&self.l_foot, &other.l_foot)
+            && close(&self.r_foot, &other.r_foot)
+            && close(&self.skull, &other.skull)
+    }
+}
+
+fn check_finite(field: impl FnOnce() -> String, value: f32) -> Result<()> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::InvalidControlValue {
+            field: field(),
+            value,
+        })
+    }
+}
+
+/// The sonar emitters are those of the first frame, a [`LolaBackend`] keeps track of the frames
+/// to alternate the emitters for [`SonarMode::Alternating`].
 impl From<NaoControlMessage> for LolaControlMsg {
     fn from(value: NaoControlMessage) -> Self {
         Self {
@@ -591,6 +1584,7 @@ impl From<LolaControlMsg> for NaoControlMessage {
     fn from(value: LolaControlMsg) -> Self {
         Self {
             position: value.position.into_nidhogg(),
+            position_mask: JointArray::fill(true),
             stiffness: value.stiffness.into_nidhogg(),
             right_ear: value.r_ear.into_nidhogg(),
             left_ear: value.l_ear.into_nidhogg(),
@@ -605,37 +1599,13 @@ impl From<LolaControlMsg> for NaoControlMessage {
     }
 }
 
-impl FromLoLA<[f32; 10]> for LeftEar {
-    fn from_lola(value: [f32; 10]) -> LeftEar {
-        LeftEar {
-            l0: value[0],
-            l1: value[1],
-            l2: value[2],
-            l3: value[3],
-            l4: value[4],
-            l5: value[5],
-            l6: value[6],
-            l7: value[7],
-            l8: value[8],
-            l9: value[9],
-        }
-    }
-}
-
-impl FromLoLA<[f32; 10]> for RightEar {
-    fn from_lola(value: [f32; 10]) -> RightEar {
-        RightEar {
-            r0: value[9],
-            r1: value[8],
-            r2: value[7],
-            r3: value[6],
-            r4: value[5],
-            r5: value[4],
-            r6: value[3],
-            r7: value[2],
-            r8: value[1],
-            r9: value[0],
+impl<S: Side> FromLoLA<[f32; 10]> for Ear<S> {
+    fn from_lola(value: [f32; 10]) -> Ear<S> {
+        let mut leds = [0.0; 10];
+        for (&led, value) in S::EAR_LOLA_ORDER.iter().zip(value) {
+            leds[led] = value;
         }
+        Ear::from_array(leds)
     }
 }
 
@@ -649,187 +1619,871 @@ impl FromLoLA<[f32; 3]> for Rgb<f32> {
     }
 }
 
-impl FromLoLA<[f32; 24]> for LeftEye {
-    fn from_lola(value: [f32; 24]) -> LeftEye {
-        let [
-            l7_r,
-            l0_r,
-            l1_r,
-            l2_r,
-            l3_r,
-            l4_r,
-            l5_r,
-            l6_r,
-            l7_g,
-            l0_g,
-            l1_g,
-            l2_g,
-            l3_g,
-            l4_g,
-            l5_g,
-            l6_g,
-            l7_b,
-            l0_b,
-            l1_b,
-            l2_b,
-            l3_b,
-            l4_b,
-            l5_b,
-            l6_b,
-            // bad rustfmt
-        ] = value;
-
-        LeftEye {
-            l0: Rgb {
-                red: l0_r,
-                green: l0_g,
-                blue: l0_b,
-            },
-            l1: Rgb {
-                red: l1_r,
-                green: l1_g,
-                blue: l1_b,
-            },
-            l2: Rgb {
-                red: l2_r,
-                green: l2_g,
-                blue: l2_b,
-            },
-            l3: Rgb {
-                red: l3_r,
-                green: l3_g,
-                blue: l3_b,
-            },
-            l4: Rgb {
-                red: l4_r,
-                green: l4_g,
-                blue: l4_b,
-            },
-            l5: Rgb {
-                red: l5_r,
-                green: l5_g,
-                blue: l5_b,
-            },
-            l6: Rgb {
-                red: l6_r,
-                green: l6_g,
-                blue: l6_b,
-            },
-            l7: Rgb {
-                red: l7_r,
-                green: l7_g,
-                blue: l7_b,
-            },
+impl<S: Side> FromLoLA<[f32; 24]> for Eye<S> {
+    fn from_lola(value: [f32; 24]) -> Eye<S> {
+        let mut leds = [RgbF32::default(); 8];
+        for (i, &led) in S::EYE_LOLA_ORDER.iter().enumerate() {
+            leds[led] = Rgb {
+                red: value[i],
+                green: value[8 + i],
+                blue: value[16 + i],
+            };
         }
+        Eye::from_array(leds)
     }
 }
 
-impl FromLoLA<[f32; 24]> for RightEye {
-    fn from_lola(value: [f32; 24]) -> RightEye {
-        let [
-            r7_r,
-            r6_r,
-            r5_r,
-            r4_r,
-            r3_r,
-            r2_r,
-            r1_r,
-            r0_r,
-            r7_g,
-            r6_g,
-            r5_g,
-            r4_g,
-            r3_g,
-            r2_g,
-            r1_g,
-            r0_g,
-            r7_b,
-            r6_b,
-            r5_b,
-            r4_b,
-            r3_b,
-            r2_b,
-            r1_b,
-            r0_b
-            // bad rustfmt
-        ] = value;
-
-        RightEye {
-            r0: Rgb {
-                red: r0_r,
-                green: r0_g,
-                blue: r0_b,
-            },
-            r1: Rgb {
-                red: r1_r,
-                green: r1_g,
-                blue: r1_b,
-            },
-            r2: Rgb {
-                red: r2_r,
-                green: r2_g,
-                blue: r2_b,
-            },
-            r3: Rgb {
-                red: r3_r,
-                green: r3_g,
-                blue: r3_b,
-            },
-            r4: Rgb {
-                red: r4_r,
-                green: r4_g,
-                blue: r4_b,
-            },
-            r5: Rgb {
-                red: r5_r,
-                green: r5_g,
-                blue: r5_b,
+impl FromLoLA<[f32; 12]> for Skull {
+    fn from_lola(value: [f32; 12]) -> Skull {
+        Skull::from_array(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+    use crate::types::{color, JointMask, JointRegion, LeftEar, LeftEye, RightEar, RightEye};
+
+    fn canned_frame() -> Vec<u8> {
+        let state = LolaNaoState {
+            stiffness: [0.5; 25],
+            position: std::array::from_fn(|i| i as f32 / 10.0),
+            temperature: [30.0; 25],
+            current: [0.1; 25],
+            battery: [0.9, 1.0, 0.0, 25.0],
+            accelerometer: [0.0, 0.0, 9.81],
+            gyroscope: [0.0; 3],
+            angles: [0.0; 2],
+            sonar: [1.0, 2.0],
+            f_s_r: [0.1; 8],
+            touch: [0.0; 14],
+            status: [0; 25],
+            robot_config: ["body", "6", "head", "6"],
+        };
+
+        let mut frame = rmp_serde::to_vec_named(&state).unwrap();
+        assert!(frame.len() <= LOLA_BUFFER_SIZE);
+        frame.resize(LOLA_BUFFER_SIZE, 0);
+        frame
+    }
+
+    #[test]
+    fn test_tcp_read_write_cycle() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(&canned_frame()).unwrap();
+
+            rmp_serde::from_read::<_, LolaControlMsg>(&mut stream).unwrap()
+        });
+
+        let mut nao = LolaBackend::connect_tcp(addr).unwrap();
+
+        let state = nao.read_nao_state().unwrap();
+        assert_eq!(state.position.head_yaw, 0.0);
+        assert_eq!(state.position.head_pitch, 0.1);
+        assert_eq!(state.battery.charge, 0.9);
+
+        let msg = NaoControlMessage::builder()
+            .chest(color::f32::MAGENTA)
+            .build();
+        nao.send_control_msg(msg).unwrap();
+
+        let received = server.join().unwrap();
+        assert_eq!(received.chest, [1.0, 0.0, 1.0]);
+
+        nao.disconnect().unwrap();
+    }
+
+    #[test]
+    fn test_hardware_info_is_cached() {
+        let (stream, mut other) = UnixStream::pair().unwrap();
+        let mut nao = LolaBackend::new(LolaStream::Unix(stream), Endpoint::Unix(PathBuf::new()));
+
+        other.write_all(&canned_frame()).unwrap();
+        drop(other);
+
+        nao.read_nao_state().unwrap();
+
+        // the connection is closed, so this only succeeds without reading another state
+        let info = nao.read_hardware_info().unwrap();
+        assert_eq!(info.body_id, "body");
+        assert_eq!(info.head_version_parsed(), Some((6, 0)));
+    }
+
+    #[test]
+    fn test_state_ref_matches_owned_state() {
+        let (stream, mut other) = UnixStream::pair().unwrap();
+        let mut nao = LolaBackend::new(LolaStream::Unix(stream), Endpoint::Unix(PathBuf::new()));
+
+        other.write_all(&canned_frame()).unwrap();
+        other.write_all(&canned_frame()).unwrap();
+
+        let owned = nao.read_nao_state().unwrap();
+        let view = nao.read_lola_state_ref().unwrap();
+
+        assert_eq!(owned.stiffness, JointArray::from_lola(*view.stiffness()));
+        assert_eq!(owned.position, JointArray::from_lola(*view.position()));
+        assert_eq!(
+            owned.temperature,
+            JointArray::from_lola(*view.temperature())
+        );
+        assert_eq!(owned.current, JointArray::from_lola(*view.current()));
+        assert_eq!(owned.status, JointArray::from_lola(*view.status()));
+        assert_eq!(owned.battery, Battery::from_lola(*view.battery()));
+        assert_eq!(
+            owned.accelerometer,
+            Vector3::from_lola(*view.accelerometer())
+        );
+        assert_eq!(owned.gyroscope, Vector3::from_lola(*view.gyroscope()));
+        assert_eq!(owned.angles, Vector2::from_lola(*view.angles()));
+        assert_eq!(owned.sonar, SonarValues::from_lola(*view.sonar()));
+        assert_eq!(owned.fsr, Fsr::from_lola(*view.fsr()));
+        assert_eq!(owned.touch, Touch::from_lola(*view.touch()));
+        assert_eq!(view.robot_config(), &["body", "6", "head", "6"]);
+        assert_eq!(owned, view.to_nao_state());
+    }
+
+    #[test]
+    fn test_socket_path_env_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("robocup");
+        let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+
+        env::set_var(LOLA_SOCKET_ENV, &socket_path);
+        assert_eq!(LolaBackend::socket_path(), socket_path);
+        let nao = LolaBackend::connect();
+        let retried =
+            LolaBackend::connect_with_retry_path(0, Duration::ZERO, socket_path.as_path());
+        env::remove_var(LOLA_SOCKET_ENV);
+
+        assert!(nao.is_ok());
+        assert!(retried.is_ok());
+        assert!(listener.accept().is_ok());
+        assert_eq!(
+            LolaBackend::socket_path(),
+            PathBuf::from(ROBOCUP_SOCKET_PATH)
+        );
+    }
+
+    #[test]
+    fn test_eof_is_connection_closed() {
+        let (stream, other) = UnixStream::pair().unwrap();
+        let mut nao = LolaBackend::new(LolaStream::Unix(stream), Endpoint::Unix(PathBuf::new()));
+
+        drop(other);
+        let error = nao.read_nao_state().unwrap_err();
+        assert!(matches!(error, Error::ConnectionClosed));
+        assert!(error.is_connection_error());
+
+        let error = nao
+            .send_control_msg(NaoControlMessage::default())
+            .unwrap_err();
+        assert!(matches!(error, Error::ConnectionClosed));
+    }
+
+    fn fast_reconnect() -> RetryPolicy {
+        RetryPolicy::exponential(5, Duration::from_millis(1), 2.0)
+            .with_max_interval(Duration::from_millis(10))
+    }
+
+    #[test]
+    fn test_reconnect_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("robocup");
+        let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+
+        let server = thread::spawn(move || {
+            // the first connection is dropped after a single frame, like a restarting LoLA
+            let (mut first, _) = listener.accept().unwrap();
+            first.write_all(&canned_frame()).unwrap();
+            drop(first);
+
+            let (mut second, _) = listener.accept().unwrap();
+            second.write_all(&canned_frame()).unwrap();
+            second.write_all(&canned_frame()).unwrap();
+            rmp_serde::from_read::<_, LolaControlMsg>(&mut second).unwrap()
+        });
+
+        let mut nao = LolaBackend::connect_with_path(&socket_path)
+            .unwrap()
+            .with_reconnect(fast_reconnect());
+
+        assert!(!nao.read_nao_state_with_outcome().unwrap().reconnected);
+
+        let outcome = nao.read_nao_state_with_outcome().unwrap();
+        assert!(outcome.reconnected);
+        assert_eq!(outcome.state.position.head_pitch, 0.1);
+
+        assert!(!nao.read_nao_state_with_outcome().unwrap().reconnected);
+
+        nao.send_control_msg(NaoControlMessage::default()).unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_reconnect_fails_without_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("robocup");
+        let listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+
+        let attempts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let policy = fast_reconnect().on_attempt({
+            let attempts = attempts.clone();
+            move |progress| attempts.lock().unwrap().push(progress.attempt)
+        });
+        let mut nao = LolaBackend::connect_with_path(&socket_path)
+            .unwrap()
+            .with_reconnect(policy);
+
+        // LoLA goes away and does not come back
+        drop(listener.accept().unwrap());
+        drop(listener);
+        std::fs::remove_file(&socket_path).unwrap();
+
+        let error = nao.read_nao_state().unwrap_err();
+        assert!(matches!(error, Error::NoLoLAConnection(_)));
+        assert_eq!(*attempts.lock().unwrap(), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_frame_stats() {
+        let (stream, mut other) = UnixStream::pair().unwrap();
+        let mut nao =
+            LolaBackend::new(LolaStream::Unix(stream), Endpoint::Unix(PathBuf::new())).with_stats();
+
+        for _ in 0..3 {
+            other.write_all(&canned_frame()).unwrap();
+        }
+
+        // a slow cycle
+        let state = nao.read_nao_state().unwrap();
+        thread::sleep(Duration::from_millis(15));
+        nao.send_control_msg(NaoControlMessage::holding(&state))
+            .unwrap();
+
+        // the remaining frames are already queued, so they arrive back to back
+        nao.read_nao_state().unwrap();
+        nao.send_control_msg(NaoControlMessage::holding(&state))
+            .unwrap();
+        nao.read_nao_state().unwrap();
+
+        let stats = nao.stats().unwrap();
+        assert_eq!(stats.cycles, 2);
+        assert_eq!(stats.over_budget, 1);
+        assert_eq!(stats.dropped_frames, 1);
+        assert!(stats.max_cycle >= Duration::from_millis(15));
+
+        nao.reset_stats();
+        assert_eq!(nao.stats(), Some(FrameStats::default()));
+    }
+
+    /// Records the spans and events emitted while it is the default subscriber.
+    #[derive(Clone, Default)]
+    struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl Recorder {
+        fn push(
+            &self,
+            kind: &str,
+            name: &str,
+            fields: impl FnOnce(&mut dyn tracing::field::Visit),
+        ) {
+            let mut line = format!("{kind} {name}");
+            fields(
+                &mut |field: &tracing::field::Field, value: &dyn std::fmt::Debug| {
+                    line.push_str(&format!(" {field}={value:?}"));
+                },
+            );
+            self.0.lock().unwrap().push(line);
+        }
+
+        fn lines(&self) -> Vec<String> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Recorder {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.push("span", attrs.metadata().name(), |visitor| {
+                attrs.record(visitor)
+            });
+        }
+
+        fn on_record(
+            &self,
+            _id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.push("record", "", |visitor| values.record(visitor));
+        }
+
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.push(event.metadata().level().as_str(), "", |visitor| {
+                event.record(visitor)
+            });
+        }
+    }
+
+    fn record_spans(f: impl FnOnce()) -> Vec<String> {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let recorder = Recorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        tracing::subscriber::with_default(subscriber, f);
+        recorder.lines()
+    }
+
+    #[test]
+    fn test_io_spans() {
+        let (mut nao, mut other) = control_msg_pair();
+        other.write_all(&canned_frame()).unwrap();
+        other.write_all(&canned_frame()).unwrap();
+
+        let lines = record_spans(|| {
+            for _ in 0..2 {
+                let state = nao.read_nao_state().unwrap();
+                nao.send_control_msg(NaoControlMessage::holding(&state))
+                    .unwrap();
+            }
+        });
+
+        let spans: Vec<_> = lines
+            .iter()
+            .filter(|line| line.starts_with("span"))
+            .collect();
+        assert_eq!(spans.len(), 4);
+        assert_eq!(spans[0], "span lola.read sequence=0");
+        assert!(spans[1].starts_with("span lola.write sequence=0 bytes="));
+        assert_eq!(spans[2], "span lola.read sequence=1");
+        assert!(spans[3].starts_with("span lola.write sequence=1 bytes="));
+        assert!(lines.contains(&format!("record  bytes={LOLA_BUFFER_SIZE}")));
+        assert!(!lines.iter().any(|line| line.starts_with("TRACE")));
+    }
+
+    #[test]
+    fn test_trace_frames_and_decode_warning() {
+        let (mut nao, mut other) = control_msg_pair();
+        nao.set_trace_frames(true);
+
+        let mut garbage = vec![0xc1; LOLA_BUFFER_SIZE];
+        garbage[0] = 0xab;
+        other.write_all(&canned_frame()).unwrap();
+        other.write_all(&garbage).unwrap();
+
+        let lines = record_spans(|| {
+            nao.read_nao_state().unwrap();
+            assert!(matches!(
+                nao.read_nao_state(),
+                Err(Error::FrameDecodeError { offset: 11, .. })
+            ));
+        });
+
+        let frame = Hex(&canned_frame()).to_string();
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("TRACE") && line.contains(&frame)));
+
+        let warning = lines.iter().find(|line| line.starts_with("WARN")).unwrap();
+        let dump = format!("frame=ab{}", "c1".repeat(DECODE_ERROR_DUMP_LEN - 1));
+        assert!(warning.ends_with(&dump), "{warning}");
+    }
+
+    #[test]
+    fn test_refused_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = LolaBackend::connect_with_path(dir.path().join("missing")).unwrap_err();
+
+        assert!(matches!(error, Error::NoLoLAConnection(_)));
+        assert!(error.is_connection_error());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let (nao, _other) = control_msg_pair();
+
+        assert_eq!(nao.capabilities(), BackendCapabilities::ALL);
+    }
+
+    #[test]
+    fn test_tcp_retry_fails_without_server() {
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let result = LolaBackend::connect_tcp_with_retry(1, Duration::from_millis(1), addr);
+        assert!(matches!(result, Err(Error::NoLoLAConnection(_))));
+    }
+
+    #[test]
+    fn test_retry_without_trailing_sleep() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("missing");
+        // connecting to a missing socket fails immediately, so a trailing sleep is the only way
+        // to spend a whole interval after the last attempt, even on a slow machine
+        let interval = Duration::from_millis(500);
+
+        // zero retries tries once, and returns the error immediately
+        let start = Instant::now();
+        let error =
+            LolaBackend::connect_with_path_with_retry(0, interval, &socket_path).unwrap_err();
+        assert!(matches!(error, Error::NoLoLAConnection(_)));
+        assert!(start.elapsed() < interval, "{:?}", start.elapsed());
+
+        let attempts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let policy = RetryPolicy::fixed(2, interval).on_attempt({
+            let attempts = attempts.clone();
+            move |progress| {
+                attempts
+                    .lock()
+                    .unwrap()
+                    .push((progress.attempt, Instant::now()));
+            }
+        });
+
+        // three attempts only wait twice in between
+        let start = Instant::now();
+        let error = LolaBackend::connect_with_policy_path(&policy, &socket_path).unwrap_err();
+        let end = Instant::now();
+        assert!(matches!(error, Error::NoLoLAConnection(_)));
+
+        let attempts = attempts.lock().unwrap();
+        let numbers: Vec<_> = attempts.iter().map(|(attempt, _)| *attempt).collect();
+        assert_eq!(numbers, [1, 2, 3]);
+        assert!(end - start >= 2 * interval, "{:?}", end - start);
+        let (_, last_attempt) = attempts[2];
+        assert!(end - last_attempt < interval, "{:?}", end - last_attempt);
+    }
+
+    fn control_msg_pair() -> (LolaBackend, UnixStream) {
+        let (stream, other) = UnixStream::pair().unwrap();
+        let nao = LolaBackend::new(LolaStream::Unix(stream), Endpoint::Unix(PathBuf::new()));
+
+        (nao, other)
+    }
+
+    #[test]
+    fn test_state_serde_roundtrip() {
+        let frame = canned_frame();
+        let state: LolaNaoState<'_> = rmp_serde::from_slice(&frame).unwrap();
+
+        let encoded = encode::to_vec_named(&state).unwrap();
+        assert_eq!(encoded, frame[..encoded.len()]);
+
+        let owned: LolaNaoStateOwned = rmp_serde::from_slice(&frame).unwrap();
+        assert_eq!(owned, state.clone().into_owned());
+        assert_eq!(owned.as_borrowed(), state);
+        assert_eq!(encode::to_vec_named(&owned).unwrap(), encoded);
+        assert_eq!(HardwareInfo::from(&owned), HardwareInfo::from(&state));
+        assert_eq!(NaoState::from(owned), NaoState::from(state));
+    }
+
+    #[test]
+    fn test_control_msg_serde_roundtrip() {
+        let msg = NaoControlMessage::builder()
+            .position(JointArray::fill(0.1))
+            .stiffness(JointArray::fill(0.9))
+            .chest(color::f32::MAGENTA)
+            .left_ear(LeftEar::from_fraction(0.6))
+            .right_eye(RightEye::fill(color::f32::CYAN))
+            .sonar(SonarMode::LeftOnly)
+            .build();
+
+        let lola = LolaControlMsg::from(msg.clone());
+        let buf = encode::to_vec_named(&lola).unwrap();
+        let decoded: LolaControlMsg = rmp_serde::from_slice(&buf).unwrap();
+
+        assert_eq!(decoded, lola);
+        assert_eq!(NaoControlMessage::from(decoded), msg);
+    }
+
+    #[test]
+    fn test_joint_order_roundtrip() {
+        let lola: [usize; 25] = std::array::from_fn(|i| i);
+        let joints: JointArray<usize> = lola.into_nidhogg();
+        assert_eq!(joints.right_shoulder_pitch, 18);
+        assert_eq!(joints.right_hip_roll, 13);
+        assert_eq!(IntoLoLA::<[usize; 25]>::into_lola(joints), lola);
+
+        let joints = JointArray::<usize>::try_from_iter(0..25).unwrap();
+        let lola: [usize; 25] = joints.clone().into_lola();
+        assert_eq!(lola[13], JointName::RightHipRoll.index());
+        assert_eq!(JointArray::<usize>::from_lola(lola), joints);
+    }
+
+    /// Decodes only the sonar actuator of an encoded control message.
+    fn encoded_sonar(msg: NaoControlMessage) -> [bool; 2] {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Sonar {
+            sonar: [bool; 2],
+        }
+
+        let buf = encode::to_vec_named(&LolaControlMsg::from(msg)).unwrap();
+        rmp_serde::from_slice::<Sonar>(&buf).unwrap().sonar
+    }
+
+    #[test]
+    fn test_sonar_modes_encode_to_emitters() {
+        let cases = [
+            (SonarMode::Off, [false, false]),
+            (SonarMode::LeftOnly, [true, false]),
+            (SonarMode::RightOnly, [false, true]),
+            (SonarMode::Both, [true, true]),
+            (SonarMode::Alternating { period_frames: 4 }, [true, false]),
+        ];
+
+        for (mode, emitters) in cases {
+            let msg = NaoControlMessage::builder().sonar(mode).build();
+            assert_eq!(encoded_sonar(msg), emitters, "{mode:?}");
+        }
+
+        #[allow(deprecated)]
+        let msg = NaoControlMessage::builder()
+            .sonar(crate::types::SonarEnabled {
+                left: false,
+                right: true,
+            })
+            .build();
+        assert_eq!(encoded_sonar(msg), [false, true]);
+    }
+
+    #[test]
+    fn test_default_enables_sonar() {
+        let msg = LolaControlMsg::from(NaoControlMessage::default());
+        assert_eq!(msg.sonar, [true, true]);
+
+        #[allow(deprecated)]
+        let msg = NaoControlMessage::builder()
+            .sonar(crate::types::SonarEnabled::default())
+            .build();
+        assert_eq!(encoded_sonar(msg), [true, true]);
+    }
+
+    #[test]
+    fn test_sonar_alternates_between_frames() {
+        let (mut nao, mut other) = control_msg_pair();
+        let msg = NaoControlMessage::builder()
+            .sonar(SonarMode::Alternating { period_frames: 2 })
+            .build();
+
+        let emitters: Vec<_> = (0..5)
+            .map(|_| {
+                nao.send_control_msg(msg.clone()).unwrap();
+                rmp_serde::from_read::<_, LolaControlMsg>(&mut other)
+                    .unwrap()
+                    .sonar
+            })
+            .collect();
+
+        assert_eq!(
+            emitters,
+            [
+                [true, false],
+                [true, false],
+                [false, true],
+                [false, true],
+                [true, false]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedup_keeps_alternating_sonar() {
+        let (nao, mut other) = control_msg_pair();
+        let mut nao = nao.with_dedup_config(DedupConfig {
+            epsilon: 0.01,
+            include_joints: true,
+        });
+        let msg = NaoControlMessage::builder()
+            .sonar(SonarMode::Alternating { period_frames: 1 })
+            .build();
+
+        // the emitters change every frame, so no message is a duplicate
+        let emitters: Vec<_> = (0..3)
+            .map(|_| {
+                nao.send_control_msg(msg.clone()).unwrap();
+                rmp_serde::from_read::<_, LolaControlMsg>(&mut other)
+                    .unwrap()
+                    .sonar
+            })
+            .collect();
+        assert_eq!(emitters, [[true, false], [false, true], [true, false]]);
+        assert_eq!(nao.skipped_writes(), 0);
+
+        // joints within epsilon are duplicates as well
+        let mut moved = NaoControlMessage::builder()
+            .sonar(SonarMode::Both)
+            .position(JointArray::fill(0.0))
+            .build();
+        nao.send_control_msg(moved.clone()).unwrap();
+        rmp_serde::from_read::<_, LolaControlMsg>(&mut other).unwrap();
+
+        moved.position.head_yaw = 0.005;
+        nao.send_control_msg(moved).unwrap();
+        assert_eq!(nao.skipped_writes(), 1);
+    }
+
+    #[test]
+    fn test_sonar_decodes_to_mode() {
+        for mode in [
+            SonarMode::Off,
+            SonarMode::LeftOnly,
+            SonarMode::RightOnly,
+            SonarMode::Both,
+        ] {
+            let msg = NaoControlMessage::builder().sonar(mode).build();
+            assert_eq!(
+                NaoControlMessage::from(LolaControlMsg::from(msg)).sonar,
+                mode
+            );
+        }
+    }
+
+    #[test]
+    fn test_nan_joint_is_rejected() {
+        let (mut nao, _other) = control_msg_pair();
+        let msg = NaoControlMessage {
+            position: JointArray {
+                left_knee_pitch: f32::NAN,
+                ..Default::default()
             },
-            r6: Rgb {
-                red: r6_r,
-                green: r6_g,
-                blue: r6_b,
+            ..Default::default()
+        };
+
+        let error = nao.send_control_msg(msg).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::InvalidControlValue { ref field, value } if field == "position.left_knee_pitch" && value.is_nan()
+        ));
+    }
+
+    #[test]
+    fn test_infinite_led_is_rejected() {
+        let (mut nao, _other) = control_msg_pair();
+        let msg = NaoControlMessage {
+            left_ear: LeftEar {
+                led0: f32::INFINITY,
+                ..Default::default()
             },
-            r7: Rgb {
-                red: r7_r,
-                green: r7_g,
-                blue: r7_b,
+            ..Default::default()
+        };
+        let error = nao.send_control_msg(msg).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::InvalidControlValue { ref field, value: f32::INFINITY } if field == "left_ear[0]"
+        ));
+    }
+
+    #[test]
+    fn test_led_intensities_are_clamped() {
+        let (mut nao, mut other) = control_msg_pair();
+        let msg = NaoControlMessage {
+            skull: Skull::fill(2.0),
+            ..Default::default()
+        };
+        nao.send_control_msg(msg).unwrap();
+
+        let received: LolaControlMsg = rmp_serde::from_read(&mut other).unwrap();
+        assert_eq!(received.skull, [1.0; 12]);
+    }
+
+    #[test]
+    fn test_unchecked_passes_raw_values() {
+        let (mut nao, mut other) = control_msg_pair();
+        let msg = NaoControlMessage {
+            position: JointArray {
+                head_yaw: f32::NAN,
+                ..Default::default()
             },
+            skull: Skull::fill(2.0),
+            ..Default::default()
+        };
+        nao.send_control_msg_unchecked(msg).unwrap();
+
+        let received: LolaControlMsg = rmp_serde::from_read(&mut other).unwrap();
+        assert!(received.position[0].is_nan());
+        assert_eq!(received.skull, [2.0; 12]);
+    }
+
+    #[test]
+    fn test_uncommanded_joints_hold_measured_position() {
+        let (mut nao, mut other) = control_msg_pair();
+        other.write_all(&canned_frame()).unwrap();
+        // none of the joints in the canned frame rest at `-1.0`
+        let state = nao.read_nao_state().unwrap();
+
+        nao.send_control_msg(NaoControlMessage::builder().build())
+            .unwrap();
+        let received: LolaControlMsg = rmp_serde::from_read(&mut other).unwrap();
+        assert_eq!(JointArray::from_lola(received.position), state.position);
+
+        let msg = NaoControlMessage {
+            position: JointArray::fill(0.5),
+            position_mask: JointMask::from_region(JointRegion::Head),
+            ..Default::default()
+        };
+        nao.send_control_msg(msg).unwrap();
+        let received: LolaControlMsg = rmp_serde::from_read(&mut other).unwrap();
+        let position = JointArray::from_lola(received.position);
+        assert_eq!(position.head_yaw, 0.5);
+        assert_eq!(position.head_pitch, 0.5);
+        assert_eq!(position.left_knee_pitch, state.position.left_knee_pitch);
+
+        // the default message still commands every joint
+        nao.send_control_msg(NaoControlMessage::default()).unwrap();
+        let received: LolaControlMsg = rmp_serde::from_read(&mut other).unwrap();
+        assert_eq!(received.position, [-1.0; 25]);
+    }
+
+    #[test]
+    fn test_right_eye_decodes_in_lola_order() {
+        // a captured control message, with a different red value at every LoLA position of the right eye
+        let mut r_eye = [0.0; 24];
+        for (position, red) in r_eye[..8].iter_mut().enumerate() {
+            *red = position as f32 / 10.0;
         }
+        let captured = rmp_serde::to_vec_named(&LolaControlMsg {
+            r_eye,
+            ..LolaControlMsg::from(NaoControlMessage::default())
+        })
+        .unwrap();
+
+        let lola: LolaControlMsg = rmp_serde::from_slice(&captured).unwrap();
+        let msg = NaoControlMessage::from(lola);
+
+        // `LoLA` sends led0 first, followed by the other LEDs in reverse order,
+        // this used to be decoded rotated by one LED as [0.7, 0.6, ..., 0.0]
+        assert_eq!(
+            msg.right_eye.as_array().map(|led| led.red),
+            [0.0, 0.7, 0.6, 0.5, 0.4, 0.3, 0.2, 0.1]
+        );
+        assert_eq!(
+            encode::to_vec_named(&LolaControlMsg::from(msg)).unwrap(),
+            captured
+        );
     }
-}
 
-impl FromLoLA<[f32; 12]> for Skull {
-    fn from_lola(value: [f32; 12]) -> Skull {
-        let [
-            left_front_1,
-            left_front_0,
-            left_middle_0,
-            left_rear_0,
-            left_rear_1,
-            left_rear_2,
-            right_rear_2,
-            right_rear_1,
-            right_rear_0,
-            right_middle_0,
-            right_front_0,
-            right_front_1,
-            // bad rustfmt
-        ] = value;
-
-        Skull {
-            left_front_0,
-            left_front_1,
-            left_middle_0,
-            left_rear_0,
-            left_rear_1,
-            left_rear_2,
-            right_front_0,
-            right_front_1,
-            right_middle_0,
-            right_rear_0,
-            right_rear_1,
-            right_rear_2,
+    #[test]
+    fn test_leds_encode_identically() {
+        let flat = NaoControlMessage::builder()
+            .chest(color::f32::RED)
+            .left_ear(LeftEar::fill(0.5))
+            .right_eye(RightEye::fill(color::f32::BLUE))
+            .right_foot(color::f32::LIME)
+            .build();
+        let grouped = NaoControlMessage::builder().leds(flat.leds()).build();
+
+        let encode = |msg: NaoControlMessage| {
+            encode::to_vec_named(&LolaControlMsg::from(msg).validated().unwrap()).unwrap()
+        };
+        assert_eq!(encode(flat), encode(grouped));
+    }
+
+    #[test]
+    fn test_color_is_clamped() {
+        let color: [f32; 3] = RgbF32::new(1.5, -0.2, 0.4).into_lola();
+
+        assert_eq!(color, [1.0, 0.0, 0.4]);
+    }
+
+    #[test]
+    fn test_mirrored_leds_share_lola_indices() {
+        let eye = LeftEye::from_array(std::array::from_fn(|i| {
+            RgbF32::new(i as f32, 0.5, 1.0 - i as f32)
+        }));
+        let left: [f32; 24] = eye.clone().into_lola();
+        let right: [f32; 24] = eye.mirror().into_lola();
+        assert_eq!(left, right);
+
+        let ear = LeftEar::from_array(std::array::from_fn(|i| i as f32));
+        let left: [f32; 10] = ear.clone().into_lola();
+        let right: [f32; 10] = ear.mirror().into_lola();
+        assert_eq!(left, right);
+    }
+
+    #[test]
+    fn test_skull_array_matches_lola_order() {
+        let skull = Skull::from_array(std::array::from_fn(|i| i as f32 / 10.0));
+
+        let lola: [f32; 12] = skull.clone().into_lola();
+        assert_eq!(lola, skull.as_array());
+        assert_eq!(Skull::from_lola(lola), skull);
+
+        let left: [f32; 12] = Skull::left_side(1.0).into_lola();
+        assert_eq!(
+            left,
+            [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]
+        );
+    }
+
+    /// The `LoLA` encoding of ears and eyes numbered by LED index, as sent by the
+    /// separate left and right types before they were unified into `Ear<S>` and `Eye<S>`.
+    #[test]
+    fn test_ear_and_eye_lola_order() {
+        let ear = |i: usize| i as f32;
+        let left: [f32; 10] = LeftEar::from_array(std::array::from_fn(ear)).into_lola();
+        let right: [f32; 10] = RightEar::from_array(std::array::from_fn(ear)).into_lola();
+        assert_eq!(left, [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        assert_eq!(right, [9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0, 0.0]);
+
+        let eye = |i: usize| RgbF32::new(i as f32, 10.0 + i as f32, 20.0 + i as f32);
+        let channels = |order: [f32; 8]| -> Vec<f32> {
+            [0.0, 10.0, 20.0]
+                .iter()
+                .flat_map(|offset| order.map(|i| offset + i))
+                .collect()
+        };
+        let left: [f32; 24] = LeftEye::from_array(std::array::from_fn(eye)).into_lola();
+        let right: [f32; 24] = RightEye::from_array(std::array::from_fn(eye)).into_lola();
+        assert_eq!(
+            left.to_vec(),
+            channels([7.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+        );
+        assert_eq!(
+            right.to_vec(),
+            channels([0.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0])
+        );
+
+        // the segment at angle `45° * i` is sent at `LoLA` index `i`
+        for (i, degrees) in (0..8).map(|i| (i, i as f32 * 45.0)) {
+            let mut eye = LeftEye::default();
+            eye.set_at_angle(degrees, RgbF32::new(1.0, 0.0, 0.0));
+            let lola: [f32; 24] = eye.into_lola();
+            assert_eq!(lola[i], 1.0, "left eye at {degrees}°");
+            assert_eq!(lola.iter().sum::<f32>(), 1.0);
+
+            let mut eye = RightEye::default();
+            eye.set_at_angle(degrees, RgbF32::new(0.0, 0.0, 1.0));
+            let lola: [f32; 24] = eye.into_lola();
+            assert_eq!(lola[16 + i], 1.0, "right eye at {degrees}°");
+            assert_eq!(lola.iter().sum::<f32>(), 1.0);
         }
+
+        // decoding is the inverse of encoding
+        assert_eq!(
+            RightEye::from_lola(right),
+            RightEye::from_array(std::array::from_fn(eye))
+        );
+        assert_eq!(
+            LeftEye::from_lola(left),
+            LeftEye::from_array(std::array::from_fn(eye))
+        );
     }
 }
@@ -0,0 +1,63 @@
+//! Mock backend that records control messages and replays queued states, used for testing.
+
+use std::collections::VecDeque;
+
+use crate::{
+    backend::{BackendCapabilities, ConnectWithRetry},
+    Error, NaoBackend, NaoControlMessage, NaoState, Result,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct MockBackend {
+    /// States returned by [`NaoBackend::read_nao_state`], in order.
+    ///
+    /// Once exhausted, the last returned state is repeated.
+    pub(crate) states: VecDeque<NaoState>,
+    /// All control messages that were sent to this backend.
+    pub(crate) sent: Vec<NaoControlMessage>,
+    /// Makes [`NaoBackend::read_nao_state`] fail with [`Error::ConnectionClosed`].
+    pub(crate) fail_reads: bool,
+    last_state: NaoState,
+}
+
+impl MockBackend {
+    pub(crate) fn with_states(states: impl IntoIterator<Item = NaoState>) -> Self {
+        Self {
+            states: states.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl NaoBackend for MockBackend {
+    fn connect() -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    fn send_control_msg(&mut self, update: NaoControlMessage) -> Result<()> {
+        self.sent.push(update);
+        Ok(())
+    }
+
+    fn read_nao_state(&mut self) -> Result<NaoState> {
+        if self.fail_reads {
+            return Err(Error::ConnectionClosed);
+        }
+
+        if let Some(state) = self.states.pop_front() {
+            self.last_state = state;
+        }
+
+        Ok(self.last_state.clone())
+    }
+
+    /// The queued states can contain any sensor data, but they are replayed as fast as they are read.
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            realtime: false,
+            ..BackendCapabilities::ALL
+        }
+    }
+}
+
+impl ConnectWithRetry for MockBackend {}
@@ -3,23 +3,46 @@
 //! This module provides support for various NAO backends.
 //! It also includes several traits that enhance the functionality of types that implement [`NaoBackend`].
 
+mod capabilities;
 #[cfg(feature = "lola")]
-mod lola;
-pub use lola::{LolaBackend, LolaControlMsg, LolaNaoState};
+mod frame;
+#[cfg(feature = "lola")]
+pub mod lola;
+#[cfg(test)]
+pub(crate) mod mock;
+mod retry;
+mod stats;
+mod tracker;
+
+pub use capabilities::BackendCapabilities;
+#[cfg(feature = "testing")]
+pub(crate) use lola::encode_state_frame;
+#[cfg(feature = "lola")]
+pub use lola::{
+    DedupConfig, LolaBackend, LolaControlMsg, LolaNaoState, LolaNaoStateOwned, LolaStateRef,
+    ReadOutcome, LOLA_SOCKET_ENV, TRACE_FRAMES_ENV,
+};
+pub use retry::{Backoff, RetryAttempt, RetryPolicy};
+pub(crate) use stats::FrameStatsCollector;
+pub use stats::{FrameStats, CYCLE_BUDGET, HISTOGRAM_BUCKETS};
+pub use tracker::{FrameMeta, FrameTracker};
 
 use std::any::type_name;
-use std::thread;
+use std::path::Path;
 use std::time::Duration;
 
 use crate::{error::Result, HardwareInfo, NaoBackend};
-use tracing::info;
 
 /// Trait that introduces [`ConnectWithRetry::connect_with_retry`] to a type that implements [`NaoBackend`].
 pub trait ConnectWithRetry: NaoBackend {
     /// Connects to a NAO by trying multiple times with an interval in between.
     ///
+    /// `retry_count` is the number of retries after the first attempt, so `0` tries once.
+    /// The error of the last attempt is returned without waiting another interval.
+    ///
     /// # Examples
-    /// ```no_run
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
     /// use nidhogg::{NaoBackend, backend::{LolaBackend, ConnectWithRetry}};
     /// use std::time::Duration;
     ///
@@ -28,28 +51,67 @@ pub trait ConnectWithRetry: NaoBackend {
     ///     .expect("Could not connect to the NAO! 😪");
     /// ```
     fn connect_with_retry(retry_count: u32, retry_interval: Duration) -> Result<Self> {
-        for i in 0..=retry_count {
-            info!(
-                "[{}/{}] Connecting to {}",
-                i,
-                retry_count,
-                type_name::<Self>()
-            );
+        Self::connect_with_policy(&RetryPolicy::fixed(retry_count, retry_interval))
+    }
 
-            let maybe_backend = Self::connect();
+    /// Connects to a NAO, retrying according to the provided [`RetryPolicy`].
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
+    /// use nidhogg::{NaoBackend, backend::{LolaBackend, ConnectWithRetry, RetryPolicy}};
+    /// use std::time::Duration;
+    ///
+    /// let policy = RetryPolicy::exponential(8, Duration::from_millis(100), 2.0).with_jitter(0.1);
+    /// let mut nao = LolaBackend::connect_with_policy(&policy)
+    ///     .expect("Could not connect to the NAO! 😪");
+    /// ```
+    fn connect_with_policy(policy: &RetryPolicy) -> Result<Self> {
+        policy.retry(type_name::<Self>(), Self::connect)
+    }
 
-            // We connected or this was the last try
-            if maybe_backend.is_ok() || i == retry_count {
-                return maybe_backend;
-            }
+    /// Connects to a NAO through the socket at the provided path, by trying multiple times with an interval in between.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
+    /// use nidhogg::{NaoBackend, backend::{LolaBackend, ConnectWithRetry}};
+    /// use std::time::Duration;
+    ///
+    /// let mut nao = LolaBackend::connect_with_retry_path(10, Duration::from_secs(1), "/tmp/robocup")
+    ///     .expect("Could not connect to the NAO! 😪");
+    /// ```
+    fn connect_with_retry_path(
+        retry_count: u32,
+        retry_interval: Duration,
+        path: impl AsRef<Path>,
+    ) -> Result<Self>
+    where
+        Self: ConnectWithPath,
+    {
+        Self::connect_with_policy_path(&RetryPolicy::fixed(retry_count, retry_interval), path)
+    }
 
-            thread::sleep(retry_interval);
-        }
+    /// Connects to a NAO through the socket at the provided path, retrying according to the provided [`RetryPolicy`].
+    fn connect_with_policy_path(policy: &RetryPolicy, path: impl AsRef<Path>) -> Result<Self>
+    where
+        Self: ConnectWithPath,
+    {
+        let path = path.as_ref();
+        let target = format!("{} at {}", type_name::<Self>(), path.display());
 
-        unreachable!()
+        policy.retry(&target, || {
+            <Self as ConnectWithPath>::connect_with_path(path)
+        })
     }
 }
 
+/// Trait for backends that connect to the NAO through a socket at a configurable path.
+pub trait ConnectWithPath: NaoBackend {
+    /// Connects to a NAO through the socket at the provided path.
+    fn connect_with_path(path: &Path) -> Result<Self>;
+}
+
 /// Trait that introduces [`ReadHardwareInfo::read_hardware_info`] to a type that implements [`NaoBackend`].
 pub trait ReadHardwareInfo: NaoBackend {
     /// Reads the [`HardwareInfo`] of the NAO.
@@ -57,7 +119,8 @@ pub trait ReadHardwareInfo: NaoBackend {
     /// The hardware info includes serial numbers and versions of the physical parts, which can be useful for finding out which robot you're connected to!
     ///
     /// # Examples
-    /// ```no_run
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
     /// use nidhogg::{NaoBackend, backend::{LolaBackend, ReadHardwareInfo}};
     /// use std::time::Duration;
     ///
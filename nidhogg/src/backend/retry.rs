@@ -0,0 +1,328 @@
+//! Implements the [`RetryPolicy`] used to connect to a backend that might not be available yet.
+
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use tracing::{info, info_span};
+
+use crate::Result;
+
+/// How the interval between two connection attempts grows with the number of failed attempts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Backoff {
+    /// Wait the same interval after every failed attempt.
+    Fixed(Duration),
+    /// Wait `initial` after the first failed attempt, and `step` longer after every following attempt.
+    Linear {
+        /// Interval after the first failed attempt.
+        initial: Duration,
+        /// Amount the interval grows by after every failed attempt.
+        step: Duration,
+    },
+    /// Wait `initial` after the first failed attempt, and multiply the interval by `factor` after every following attempt.
+    Exponential {
+        /// Interval after the first failed attempt.
+        initial: Duration,
+        /// Factor the interval is multiplied by after every failed attempt.
+        factor: f32,
+    },
+}
+
+/// Progress of a connection, passed to the callback of a [`RetryPolicy`] before every attempt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryAttempt<'a> {
+    /// The number of this attempt, starting at 1.
+    pub attempt: u32,
+    /// The maximum number of attempts that will be made.
+    pub max_attempts: u32,
+    /// Description of what is being connected to, such as the backend type and socket path.
+    pub target: &'a str,
+}
+
+type AttemptCallback = Arc<dyn Fn(&RetryAttempt<'_>) + Send + Sync>;
+
+/// Policy that decides how often, and how long apart, connecting to a backend is attempted.
+///
+/// By default every attempt is logged at the `INFO` level, use [`RetryPolicy::on_attempt`]
+/// to report progress differently.
+///
+/// # Example
+///
+#[cfg_attr(feature = "lola", doc = "```no_run")]
+#[cfg_attr(not(feature = "lola"), doc = "```ignore")]
+/// use std::time::Duration;
+/// use nidhogg::backend::{ConnectWithRetry, LolaBackend, RetryPolicy};
+///
+/// // wait 100ms, 200ms, 400ms, ... with up to 20% jitter, but never more than 2 seconds
+/// let policy = RetryPolicy::exponential(10, Duration::from_millis(100), 2.0)
+///     .with_max_interval(Duration::from_secs(2))
+///     .with_jitter(0.2)
+///     .on_attempt(|attempt| eprintln!("connecting ({}/{})", attempt.attempt, attempt.max_attempts));
+///
+/// let nao = LolaBackend::connect_with_policy(&policy).expect("Could not connect to the NAO! 😪");
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one.
+    ///
+    /// A value of `0` is treated as `1`, connecting is always attempted at least once.
+    pub max_attempts: u32,
+    /// How the interval between two attempts grows.
+    pub backoff: Backoff,
+    /// Upper limit for the interval between two attempts.
+    pub max_interval: Duration,
+    /// Fraction in the range `0.0..=1.0` by which each interval is randomly shortened.
+    ///
+    /// Jitter prevents multiple clients that lost their connection at the same time from retrying in lockstep.
+    pub jitter: f32,
+    on_attempt: Option<AttemptCallback>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::fixed(10, Duration::from_secs(1))
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("backoff", &self.backoff)
+            .field("max_interval", &self.max_interval)
+            .field("jitter", &self.jitter)
+            .field("on_attempt", &self.on_attempt.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl RetryPolicy {
+    /// Retry `retry_count` times after the first attempt, waiting `interval` in between.
+    ///
+    /// This is the behavior of [`ConnectWithRetry::connect_with_retry`](super::ConnectWithRetry::connect_with_retry).
+    pub fn fixed(retry_count: u32, interval: Duration) -> Self {
+        Self::new(retry_count.saturating_add(1), Backoff::Fixed(interval))
+    }
+
+    /// Attempt up to `max_attempts` times, waiting `step` longer after every failed attempt.
+    pub fn linear(max_attempts: u32, initial: Duration, step: Duration) -> Self {
+        Self::new(max_attempts, Backoff::Linear { initial, step })
+    }
+
+    /// Attempt up to `max_attempts` times, multiplying the interval by `factor` after every failed attempt.
+    pub fn exponential(max_attempts: u32, initial: Duration, factor: f32) -> Self {
+        Self::new(max_attempts, Backoff::Exponential { initial, factor })
+    }
+
+    fn new(max_attempts: u32, backoff: Backoff) -> Self {
+        Self {
+            max_attempts,
+            backoff,
+            max_interval: Duration::MAX,
+            jitter: 0.0,
+            on_attempt: None,
+        }
+    }
+
+    /// Limit the interval between two attempts to `max_interval`.
+    #[must_use]
+    pub fn with_max_interval(mut self, max_interval: Duration) -> Self {
+        self.max_interval = max_interval;
+        self
+    }
+
+    /// Randomly shorten every interval by up to the provided fraction, which is clamped to `0.0..=1.0`.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: f32) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Call `callback` before every attempt, instead of logging the attempt.
+    #[must_use]
+    pub fn on_attempt(
+        mut self,
+        callback: impl Fn(&RetryAttempt<'_>) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_attempt = Some(Arc::new(callback));
+        self
+    }
+
+    /// The interval to wait after the provided failed attempt, starting at 1, without jitter.
+    pub fn interval(&self, attempt: u32) -> Duration {
+        let failed = attempt.saturating_sub(1);
+        let interval = match self.backoff {
+            Backoff::Fixed(interval) => interval,
+            Backoff::Linear { initial, step } => {
+                initial.saturating_add(step.saturating_mul(failed))
+            }
+            Backoff::Exponential { initial, factor } => {
+                let factor = f64::from(factor.max(0.0)).powi(failed.min(i32::MAX as u32) as i32);
+                Duration::try_from_secs_f64(initial.as_secs_f64() * factor).unwrap_or(Duration::MAX)
+            }
+        };
+
+        interval.min(self.max_interval)
+    }
+
+    /// Call `connect` until it succeeds or the maximum number of attempts is reached.
+    ///
+    /// `target` describes what is being connected to, and is passed to the callback.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt if all attempts failed.
+    pub fn retry<T>(&self, target: &str, mut connect: impl FnMut() -> Result<T>) -> Result<T> {
+        let max_attempts = self.max_attempts.max(1);
+
+        let mut attempt = 1;
+        loop {
+            let _span = info_span!("connect", attempt, max_attempts).entered();
+            let progress = RetryAttempt {
+                attempt,
+                max_attempts,
+                target,
+            };
+            match &self.on_attempt {
+                Some(callback) => callback(&progress),
+                None => info!("[{attempt}/{max_attempts}] Connecting to {target}"),
+            }
+
+            let result = connect();
+            // We connected or this was the last try
+            if result.is_ok() || attempt >= max_attempts {
+                return result;
+            }
+
+            thread::sleep(self.jittered(self.interval(attempt)));
+            attempt += 1;
+        }
+    }
+
+    fn jittered(&self, interval: Duration) -> Duration {
+        if self.jitter <= 0.0 {
+            return interval;
+        }
+
+        // a randomly seeded hasher is random enough to spread out retries, without an extra dependency
+        let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+        interval.mul_f64(1.0 - f64::from(self.jitter) * random)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    use super::*;
+    use crate::{backend::mock::MockBackend, backend::ConnectWithRetry, Error};
+
+    const INTERVAL: Duration = Duration::from_millis(20);
+
+    /// Returns a connect function that fails `failures` times before succeeding.
+    fn flaky(failures: u32) -> impl FnMut() -> Result<u32> {
+        let mut calls = 0;
+        move || {
+            calls += 1;
+            if calls > failures {
+                Ok(calls)
+            } else {
+                Err(Error::ConnectionClosed)
+            }
+        }
+    }
+
+    #[test]
+    fn test_intervals() {
+        let fixed = RetryPolicy::fixed(3, INTERVAL);
+        assert_eq!(fixed.max_attempts, 4);
+        assert_eq!(fixed.interval(1), INTERVAL);
+        assert_eq!(fixed.interval(3), INTERVAL);
+
+        let linear = RetryPolicy::linear(5, INTERVAL, Duration::from_millis(10));
+        assert_eq!(linear.interval(1), INTERVAL);
+        assert_eq!(linear.interval(3), Duration::from_millis(40));
+
+        let exponential = RetryPolicy::exponential(5, INTERVAL, 2.0)
+            .with_max_interval(Duration::from_millis(100));
+        let intervals: Vec<_> = (1..=5)
+            .map(|attempt| exponential.interval(attempt))
+            .collect();
+        assert_eq!(intervals, [20, 40, 80, 100, 100].map(Duration::from_millis));
+
+        // large attempt numbers saturate instead of overflowing
+        assert_eq!(
+            RetryPolicy::exponential(u32::MAX, INTERVAL, 10.0).interval(u32::MAX),
+            Duration::MAX
+        );
+    }
+
+    #[test]
+    fn test_jitter_only_shortens() {
+        let policy = RetryPolicy::fixed(1, Duration::from_millis(100)).with_jitter(0.5);
+
+        for _ in 0..100 {
+            let interval = policy.jittered(policy.interval(1));
+            assert!(interval <= Duration::from_millis(100));
+            assert!(interval >= Duration::from_millis(50));
+        }
+        assert_eq!(RetryPolicy::default().with_jitter(7.0).jitter, 1.0);
+    }
+
+    #[test]
+    fn test_attempt_counts() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let policy = RetryPolicy::fixed(4, Duration::ZERO).on_attempt({
+            let attempts = attempts.clone();
+            move |progress| {
+                assert_eq!(progress.max_attempts, 5);
+                assert_eq!(progress.target, "flaky");
+                attempts.store(progress.attempt, Ordering::SeqCst);
+            }
+        });
+
+        assert_eq!(policy.retry("flaky", flaky(2)).unwrap(), 3);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        assert!(policy.retry("flaky", flaky(10)).is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 5);
+
+        // zero attempts still tries once
+        let once = RetryPolicy::linear(0, INTERVAL, INTERVAL);
+        assert!(once.retry("flaky", flaky(1)).is_err());
+    }
+
+    #[test]
+    fn test_backoff_timing() {
+        let starts = Arc::new(Mutex::new(Vec::new()));
+        let policy = RetryPolicy::linear(4, INTERVAL, INTERVAL).on_attempt({
+            let starts = starts.clone();
+            move |_| starts.lock().unwrap().push(Instant::now())
+        });
+
+        assert!(policy.retry("flaky", flaky(4)).is_err());
+
+        // waits 20ms, 40ms and 60ms in between the four attempts, a slow machine can only wait longer
+        let starts = starts.lock().unwrap();
+        let waits: Vec<_> = starts.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        assert_eq!(waits.len(), 3);
+        for (wait, millis) in waits.iter().zip([20, 40, 60]) {
+            assert!(*wait >= Duration::from_millis(millis), "{waits:?}");
+        }
+    }
+
+    #[test]
+    fn test_connect_with_policy() {
+        let policy = RetryPolicy::fixed(0, INTERVAL).on_attempt(|progress| {
+            assert!(progress.target.ends_with("MockBackend"));
+        });
+
+        assert!(MockBackend::connect_with_policy(&policy).is_ok());
+    }
+}
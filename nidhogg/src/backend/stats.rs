@@ -0,0 +1,172 @@
+//! Cycle time statistics for backends that receive frames at a fixed rate.
+
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+/// Time `LoLA` allows between sending a frame and receiving the response.
+pub const CYCLE_BUDGET: Duration = Duration::from_millis(12);
+
+/// Consecutive reads that arrive closer together than this indicate a backlog of frames.
+const BACKLOG_THRESHOLD: Duration = Duration::from_millis(1);
+
+/// Number of buckets in [`FrameStats::histogram`].
+pub const HISTOGRAM_BUCKETS: usize = 16;
+
+/// Snapshot of the cycle time statistics collected by a backend.
+///
+/// A cycle is the time between reading a frame and sending the response.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Number of completed cycles.
+    pub cycles: u64,
+    /// Mean duration of a cycle.
+    pub mean_cycle: Duration,
+    /// Longest duration of a cycle.
+    pub max_cycle: Duration,
+    /// Number of cycles that took longer than [`CYCLE_BUDGET`].
+    pub over_budget: u64,
+    /// Number of frames that were read less than a millisecond after the previous frame,
+    /// which means the frames were queued up and at least one cycle was missed.
+    pub dropped_frames: u64,
+    /// Number of frames that were missed according to the [`FrameTracker`](super::FrameTracker),
+    /// which is only counted if the backend tracks frames.
+    pub missed_frames: u64,
+    /// Cycle duration histogram with buckets of one millisecond.
+    ///
+    /// Bucket `i` counts the cycles that took `i` up to `i + 1` milliseconds,
+    /// the last bucket also counts all longer cycles.
+    pub histogram: [u64; HISTOGRAM_BUCKETS],
+}
+
+/// Collects [`FrameStats`] from read and send timestamps.
+#[derive(Debug, Default)]
+pub(crate) struct FrameStatsCollector {
+    stats: FrameStats,
+    total: Duration,
+    last_read: Option<Instant>,
+    cycle_start: Option<Instant>,
+}
+
+impl FrameStatsCollector {
+    /// Record that a frame was read at `now`, which starts a new cycle.
+    pub(crate) fn record_read(&mut self, now: Instant) {
+        if self
+            .last_read
+            .is_some_and(|last| now.duration_since(last) < BACKLOG_THRESHOLD)
+        {
+            self.stats.dropped_frames += 1;
+        }
+
+        self.last_read = Some(now);
+        self.cycle_start = Some(now);
+    }
+
+    /// Record that `missed` frames were missed before the frame that was read last.
+    #[cfg(feature = "lola")]
+    pub(crate) fn record_missed(&mut self, missed: u32) {
+        self.stats.missed_frames += u64::from(missed);
+    }
+
+    /// Record that a response was sent at `now`, which completes the current cycle.
+    pub(crate) fn record_send(&mut self, now: Instant) {
+        let Some(start) = self.cycle_start.take() else {
+            return;
+        };
+        let cycle = now.duration_since(start);
+
+        self.stats.cycles += 1;
+        self.total += cycle;
+        self.stats.max_cycle = self.stats.max_cycle.max(cycle);
+
+        let bucket = (cycle.as_millis() as usize).min(HISTOGRAM_BUCKETS - 1);
+        self.stats.histogram[bucket] += 1;
+
+        if cycle > CYCLE_BUDGET {
+            self.stats.over_budget += 1;
+            warn!(
+                "Cycle took {:?}, which exceeds the budget of {:?}",
+                cycle, CYCLE_BUDGET
+            );
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> FrameStats {
+        let mean_cycle = match u32::try_from(self.stats.cycles) {
+            Ok(0) => Duration::ZERO,
+            Ok(cycles) => self.total / cycles,
+            Err(_) => self.total.div_f64(self.stats.cycles as f64),
+        };
+
+        FrameStats {
+            mean_cycle,
+            ..self.stats.clone()
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_cycle_statistics() {
+        let start = Instant::now();
+        let mut collector = FrameStatsCollector::default();
+
+        for (frame, cycle) in [2, 4, 15].into_iter().enumerate() {
+            let read = start + ms(12 * frame as u64);
+            collector.record_read(read);
+            collector.record_send(read + ms(cycle));
+        }
+
+        let stats = collector.snapshot();
+        assert_eq!(stats.cycles, 3);
+        assert_eq!(stats.mean_cycle, ms(7));
+        assert_eq!(stats.max_cycle, ms(15));
+        assert_eq!(stats.over_budget, 1);
+        assert_eq!(stats.dropped_frames, 0);
+        assert_eq!(stats.histogram[2], 1);
+        assert_eq!(stats.histogram[4], 1);
+        assert_eq!(stats.histogram[15], 1);
+    }
+
+    #[test]
+    fn test_backlog_counts_dropped_frames() {
+        let start = Instant::now();
+        let mut collector = FrameStatsCollector::default();
+
+        collector.record_read(start);
+        collector.record_read(start + Duration::from_micros(100));
+        collector.record_read(start + ms(12));
+
+        assert_eq!(collector.snapshot().dropped_frames, 1);
+    }
+
+    #[test]
+    fn test_send_without_read_is_ignored() {
+        let mut collector = FrameStatsCollector::default();
+        collector.record_send(Instant::now());
+
+        assert_eq!(collector.snapshot(), FrameStats::default());
+    }
+
+    #[test]
+    fn test_reset() {
+        let start = Instant::now();
+        let mut collector = FrameStatsCollector::default();
+        collector.record_read(start);
+        collector.record_send(start + ms(20));
+
+        collector.reset();
+        assert_eq!(collector.snapshot(), FrameStats::default());
+    }
+}
@@ -0,0 +1,214 @@
+//! Detects frames that were missed, for backends that receive frames at a fixed rate without a sequence number.
+
+use std::time::{Duration, Instant};
+
+/// Gaps that are longer than this many nominal periods count as missed frames.
+const MISSED_THRESHOLD: f64 = 1.5;
+
+/// Information about a frame, recorded by a [`FrameTracker`] when it arrived.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameMeta {
+    /// Sequence number of the frame, counting every frame that was read starting at 0.
+    pub seq: u64,
+    /// Time since the previous frame arrived, or zero for the first frame.
+    pub gap: Duration,
+    /// Estimated number of frames that were missed between the previous frame and this one.
+    ///
+    /// This is always zero while the nominal period is being estimated.
+    pub missed: u32,
+}
+
+/// Tracks the arrival of frames, to detect frames that were missed after a slow cycle.
+///
+/// The nominal period between frames is estimated from the median gap of the first frames.
+/// After that, a gap that exceeds 1.5 periods is counted as one or more missed frames.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use nidhogg::backend::FrameTracker;
+///
+/// let mut tracker = FrameTracker::new(4);
+/// let start = Instant::now();
+/// let at = |millis| start + Duration::from_millis(millis);
+///
+/// for millis in [0, 12, 24, 36] {
+///     tracker.record(at(millis));
+/// }
+/// assert_eq!(tracker.period(), Some(Duration::from_millis(12)));
+///
+/// // two frames were missed after a slow cycle
+/// let meta = tracker.record(at(72));
+/// assert_eq!(meta.seq, 4);
+/// assert_eq!(meta.gap, Duration::from_millis(36));
+/// assert_eq!(meta.missed, 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrameTracker {
+    calibration_frames: usize,
+    /// Gaps between the first frames, used to estimate the period.
+    calibration: Vec<Duration>,
+    period: Option<Duration>,
+    last_arrival: Option<Instant>,
+    seq: u64,
+    missed_frames: u64,
+}
+
+impl Default for FrameTracker {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl FrameTracker {
+    /// Create a [`FrameTracker`] that estimates the nominal period from the first `calibration_frames` frames.
+    ///
+    /// At least two frames are needed to estimate the period.
+    pub fn new(calibration_frames: usize) -> Self {
+        let calibration_frames = calibration_frames.max(2);
+
+        Self {
+            calibration_frames,
+            calibration: Vec::with_capacity(calibration_frames - 1),
+            period: None,
+            last_arrival: None,
+            seq: 0,
+            missed_frames: 0,
+        }
+    }
+
+    /// The estimated nominal period between two frames, or [`None`] while it is being estimated.
+    pub fn period(&self) -> Option<Duration> {
+        self.period
+    }
+
+    /// The total number of missed frames.
+    pub fn missed_frames(&self) -> u64 {
+        self.missed_frames
+    }
+
+    /// Record that a frame arrived at `now`.
+    pub fn record(&mut self, now: Instant) -> FrameMeta {
+        let gap = self
+            .last_arrival
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+
+        let missed = match self.period {
+            Some(period) => missed(gap, period),
+            None => {
+                if self.last_arrival.is_some() {
+                    self.calibrate(gap);
+                }
+                0
+            }
+        };
+
+        let meta = FrameMeta {
+            seq: self.seq,
+            gap,
+            missed,
+        };
+
+        self.last_arrival = Some(now);
+        self.seq += 1;
+        self.missed_frames += u64::from(missed);
+
+        meta
+    }
+
+    /// Forget when the last frame arrived, so the gap to the next frame is not counted.
+    ///
+    /// This should be called after reconnecting. The sequence numbers and the period are kept.
+    pub fn restart(&mut self) {
+        self.last_arrival = None;
+    }
+
+    fn calibrate(&mut self, gap: Duration) {
+        self.calibration.push(gap);
+        if self.calibration.len() + 1 < self.calibration_frames {
+            return;
+        }
+
+        // the median ignores the odd delayed or queued frame
+        self.calibration.sort_unstable();
+        let period = self.calibration[self.calibration.len() / 2];
+        if !period.is_zero() {
+            self.period = Some(period);
+        }
+        self.calibration.clear();
+    }
+}
+
+/// Estimates the number of frames that were missed during `gap`.
+fn missed(gap: Duration, period: Duration) -> u32 {
+    let periods = gap.as_secs_f64() / period.as_secs_f64();
+    if periods <= MISSED_THRESHOLD {
+        return 0;
+    }
+
+    (periods.round() as u32).saturating_sub(1).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calibrated(start: Instant) -> FrameTracker {
+        let mut tracker = FrameTracker::new(5);
+        for frame in 0..5 {
+            assert_eq!(tracker.record(start + ms(12 * frame)).missed, 0);
+        }
+        tracker
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_period_ignores_outliers() {
+        let start = Instant::now();
+        let mut tracker = FrameTracker::new(6);
+
+        // a delayed frame, and a frame that was queued behind it
+        for millis in [0, 12, 24, 60, 61, 72] {
+            assert_eq!(tracker.record(start + ms(millis)).missed, 0);
+            assert_eq!(tracker.missed_frames(), 0);
+        }
+
+        assert_eq!(tracker.period(), Some(ms(12)));
+    }
+
+    #[test]
+    fn test_missed_frames() {
+        let start = Instant::now();
+        let mut tracker = calibrated(start);
+
+        // jitter within half a period is not counted
+        let meta = tracker.record(start + ms(48 + 17));
+        assert_eq!(meta.seq, 5);
+        assert_eq!(meta.gap, ms(17));
+        assert_eq!(meta.missed, 0);
+
+        let meta = tracker.record(start + ms(65 + 19));
+        assert_eq!(meta.missed, 1);
+
+        let meta = tracker.record(start + ms(84 + 50));
+        assert_eq!(meta.missed, 3);
+        assert_eq!(tracker.missed_frames(), 4);
+    }
+
+    #[test]
+    fn test_restart() {
+        let start = Instant::now();
+        let mut tracker = calibrated(start);
+
+        tracker.restart();
+        let meta = tracker.record(start + ms(1000));
+        assert_eq!(meta.seq, 5);
+        assert_eq!(meta.gap, Duration::ZERO);
+        assert_eq!(meta.missed, 0);
+        assert_eq!(tracker.period(), Some(ms(12)));
+    }
+}
@@ -0,0 +1,280 @@
+//! Integration of nidhogg backends with the [`bevy_ecs`] scheduler.
+//!
+//! The [`NidhoggPlugin`] keeps the [`NaoState`] and [`NaoControlMessage`] resources in sync with the robot.
+//! Backend errors are never raised inside a system, instead they are reported through the [`NaoConnectionStatus`] resource.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use bevy_ecs::{prelude::*, schedule::ScheduleLabel};
+//! use nidhogg::{backend::LolaBackend, bevy::NidhoggPlugin, NaoState};
+//!
+//! #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+//! struct PreUpdate;
+//!
+//! #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+//! struct PostUpdate;
+//!
+//! let mut world = World::new();
+//! NidhoggPlugin::<LolaBackend>::default().build(&mut world, PreUpdate, PostUpdate);
+//!
+//! loop {
+//!     world.run_schedule(PreUpdate);
+//!     println!("battery: {:?}", world.resource::<NaoState>().battery);
+//!     world.run_schedule(PostUpdate);
+//! }
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use bevy_ecs::schedule::{ScheduleLabel, Schedules};
+use tracing::warn;
+
+use crate::{backend::ConnectWithRetry, NaoControlMessage, NaoState};
+
+/// Resource that owns the backend used by the [`NidhoggPlugin`].
+#[derive(Resource, Debug)]
+pub struct Backend<B>(pub B);
+
+impl<B> Deref for Backend<B> {
+    type Target = B;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<B> DerefMut for Backend<B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Status of the connection with the robot, as last observed by the [`NidhoggPlugin`].
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub enum NaoConnectionStatus {
+    /// No connection has been made yet.
+    #[default]
+    Disconnected,
+    /// The last interaction with the backend succeeded.
+    Connected,
+    /// The last interaction with the backend failed with the contained error message.
+    Failed(String),
+}
+
+impl NaoConnectionStatus {
+    /// Returns `true` if the last interaction with the backend succeeded.
+    pub fn is_connected(&self) -> bool {
+        matches!(self, NaoConnectionStatus::Connected)
+    }
+}
+
+/// Connects a backend and keeps the [`NaoState`] and [`NaoControlMessage`] resources in sync with the robot.
+///
+/// The backend is connected when the plugin is built, retrying according to
+/// [`NidhoggPlugin::retry_count`] and [`NidhoggPlugin::retry_interval`].
+pub struct NidhoggPlugin<B> {
+    /// Amount of times connecting to the backend is retried.
+    pub retry_count: u32,
+    /// Time between two connection attempts.
+    pub retry_interval: Duration,
+    _backend: PhantomData<fn() -> B>,
+}
+
+impl<B> fmt::Debug for NidhoggPlugin<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NidhoggPlugin")
+            .field("retry_count", &self.retry_count)
+            .field("retry_interval", &self.retry_interval)
+            .finish()
+    }
+}
+
+impl<B> Default for NidhoggPlugin<B> {
+    fn default() -> Self {
+        Self::new(10, Duration::from_secs(1))
+    }
+}
+
+impl<B> NidhoggPlugin<B> {
+    /// Create a new [`NidhoggPlugin`] that retries connecting `retry_count` times, waiting `retry_interval` in between.
+    pub fn new(retry_count: u32, retry_interval: Duration) -> Self {
+        Self {
+            retry_count,
+            retry_interval,
+            _backend: PhantomData,
+        }
+    }
+}
+
+impl<B: ConnectWithRetry + Send + Sync + 'static> NidhoggPlugin<B> {
+    /// Connects the backend and adds the resources and systems of the plugin to `world`.
+    ///
+    /// The state is read in `read_schedule`, and the control message is sent in `send_schedule`.
+    /// When using `bevy_app`, these are typically `PreUpdate` and `PostUpdate`.
+    pub fn build(
+        &self,
+        world: &mut World,
+        read_schedule: impl ScheduleLabel,
+        send_schedule: impl ScheduleLabel,
+    ) {
+        world.init_resource::<NaoState>();
+        world.init_resource::<NaoControlMessage>();
+
+        let status = match B::connect_with_retry(self.retry_count, self.retry_interval) {
+            Ok(backend) => {
+                world.insert_resource(Backend(backend));
+                NaoConnectionStatus::Connected
+            }
+            Err(error) => {
+                warn!("Failed to connect to the backend: {error}");
+                NaoConnectionStatus::Failed(error.to_string())
+            }
+        };
+        world.insert_resource(status);
+
+        let mut schedules = world.get_resource_or_init::<Schedules>();
+        schedules.add_systems(read_schedule, read_nao_state::<B>);
+        schedules.add_systems(send_schedule, send_control_message::<B>);
+    }
+}
+
+fn read_nao_state<B: ConnectWithRetry + Send + Sync + 'static>(
+    backend: Option<ResMut<'_, Backend<B>>>,
+    mut state: ResMut<'_, NaoState>,
+    mut status: ResMut<'_, NaoConnectionStatus>,
+) {
+    let Some(mut backend) = backend else {
+        return;
+    };
+
+    match backend.read_nao_state() {
+        Ok(new_state) => {
+            *state = new_state;
+            status.set_if_neq(NaoConnectionStatus::Connected);
+        }
+        Err(error) => {
+            warn!("Failed to read the robot state: {error}");
+            status.set_if_neq(NaoConnectionStatus::Failed(error.to_string()));
+        }
+    }
+}
+
+fn send_control_message<B: ConnectWithRetry + Send + Sync + 'static>(
+    backend: Option<ResMut<'_, Backend<B>>>,
+    msg: Res<'_, NaoControlMessage>,
+    mut status: ResMut<'_, NaoConnectionStatus>,
+) {
+    let Some(mut backend) = backend else {
+        return;
+    };
+
+    if let Err(error) = backend.send_control_msg(msg.clone()) {
+        warn!("Failed to send the control message: {error}");
+        status.set_if_neq(NaoConnectionStatus::Failed(error.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{backend::mock::MockBackend, types::JointArray, Error, NaoBackend, Result};
+
+    #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+    struct PreUpdate;
+
+    #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+    struct PostUpdate;
+
+    fn world() -> World {
+        let mut world = World::new();
+        NidhoggPlugin::<MockBackend>::new(0, Duration::ZERO)
+            .build(&mut world, PreUpdate, PostUpdate);
+        world
+    }
+
+    fn run_frame(world: &mut World) {
+        world.run_schedule(PreUpdate);
+        world.run_schedule(PostUpdate);
+    }
+
+    #[test]
+    fn test_state_is_read_and_control_is_sent() {
+        let mut world = world();
+        let state = NaoState {
+            position: JointArray {
+                head_yaw: 0.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        world
+            .resource_mut::<Backend<MockBackend>>()
+            .states
+            .push_back(state.clone());
+
+        world.resource_mut::<NaoControlMessage>().stiffness.head_yaw = 0.3;
+        run_frame(&mut world);
+
+        assert_eq!(*world.resource::<NaoState>(), state);
+        assert!(world.resource::<NaoConnectionStatus>().is_connected());
+
+        let sent = &world.resource::<Backend<MockBackend>>().sent;
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].stiffness.head_yaw, 0.3);
+    }
+
+    #[test]
+    fn test_read_errors_are_reported() {
+        let mut world = world();
+        world.resource_mut::<Backend<MockBackend>>().fail_reads = true;
+
+        run_frame(&mut world);
+        assert_eq!(
+            *world.resource::<NaoConnectionStatus>(),
+            NaoConnectionStatus::Failed(Error::ConnectionClosed.to_string())
+        );
+
+        world.resource_mut::<Backend<MockBackend>>().fail_reads = false;
+        run_frame(&mut world);
+        assert!(world.resource::<NaoConnectionStatus>().is_connected());
+    }
+
+    #[derive(Debug)]
+    struct Unreachable;
+
+    impl NaoBackend for Unreachable {
+        fn connect() -> Result<Self> {
+            Err(Error::ConnectionClosed)
+        }
+
+        fn send_control_msg(&mut self, _update: NaoControlMessage) -> Result<()> {
+            unreachable!()
+        }
+
+        fn read_nao_state(&mut self) -> Result<NaoState> {
+            unreachable!()
+        }
+    }
+
+    impl ConnectWithRetry for Unreachable {}
+
+    #[test]
+    fn test_connection_failure() {
+        let mut world = World::new();
+        NidhoggPlugin::<Unreachable>::new(1, Duration::ZERO)
+            .build(&mut world, PreUpdate, PostUpdate);
+
+        // the systems do nothing without a backend
+        run_frame(&mut world);
+        assert!(matches!(
+            world.resource::<NaoConnectionStatus>(),
+            NaoConnectionStatus::Failed(_)
+        ));
+        assert!(world.get_resource::<Backend<Unreachable>>().is_none());
+    }
+}
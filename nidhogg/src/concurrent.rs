@@ -0,0 +1,332 @@
+//! Run the backend IO on a dedicated thread, and share the latest [`NaoState`] with other threads.
+//!
+//! # Example
+//!
+#![cfg_attr(feature = "lola", doc = "```no_run")]
+#![cfg_attr(not(feature = "lola"), doc = "```ignore")]
+//! use nidhogg::{backend::LolaBackend, concurrent::spawn_state_reader, NaoBackend, NaoControlMessage};
+//!
+//! let nao = LolaBackend::connect().unwrap();
+//! let (states, control, io) = spawn_state_reader(nao);
+//!
+//! // The freshest state is available from any thread
+//! let state = states.latest();
+//! control.send(NaoControlMessage::holding(&state)).unwrap();
+//!
+//! // Stop the IO thread and check whether the backend failed
+//! control.stop();
+//! io.join().unwrap().unwrap();
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::{Error, NaoBackend, NaoControlMessage, NaoState, Result};
+
+/// State shared between the IO thread and its handles.
+#[derive(Debug, Default)]
+struct Shared {
+    latest: Mutex<(NaoState, u64)>,
+    updated: Condvar,
+    stopped: AtomicBool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, (NaoState, u64)> {
+        // the lock is only held while cloning or replacing the state, which cannot leave it inconsistent
+        self.latest
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn publish(&self, state: NaoState) {
+        let mut latest = self.lock();
+        latest.0 = state;
+        latest.1 += 1;
+        self.updated.notify_all();
+    }
+
+    fn stop(&self) {
+        let _latest = self.lock();
+        self.stopped.store(true, Ordering::Release);
+        self.updated.notify_all();
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Acquire)
+    }
+}
+
+/// Marks the IO thread as stopped when it exits, including when the backend panics.
+struct StopOnDrop(Arc<Shared>);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.stop();
+    }
+}
+
+/// Handle to the latest [`NaoState`] read by the IO thread.
+///
+/// Every state read from the backend gets a sequence number, which increases by one for each new state.
+/// Sequence number `0` means no state has been read yet.
+#[derive(Debug, Clone)]
+pub struct StateReceiver {
+    shared: Arc<Shared>,
+}
+
+impl StateReceiver {
+    /// Returns a copy of the latest state.
+    ///
+    /// Before the first state has been read, this returns [`NaoState::default`].
+    pub fn latest(&self) -> NaoState {
+        self.shared.lock().0.clone()
+    }
+
+    /// Returns the sequence number of the latest state.
+    pub fn sequence(&self) -> u64 {
+        self.shared.lock().1
+    }
+
+    /// Returns a copy of the latest state, together with its sequence number.
+    pub fn latest_with_sequence(&self) -> (NaoState, u64) {
+        self.shared.lock().clone()
+    }
+
+    /// Blocks until a state newer than `sequence` is available, and returns it with its sequence number.
+    ///
+    /// Returns [`None`] if the IO thread stopped before a newer state was read.
+    pub fn wait_for_newer(&self, sequence: u64) -> Option<(NaoState, u64)> {
+        let latest = self
+            .shared
+            .updated
+            .wait_while(self.shared.lock(), |latest| {
+                latest.1 <= sequence && !self.shared.is_stopped()
+            })
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        (latest.1 > sequence).then(|| latest.clone())
+    }
+
+    /// Returns `true` if the IO thread has stopped.
+    pub fn is_stopped(&self) -> bool {
+        self.shared.is_stopped()
+    }
+
+    /// Stops the IO thread after its current cycle.
+    pub fn stop(&self) {
+        self.shared.stop();
+    }
+}
+
+/// Handle for sending [`NaoControlMessage`]s to the IO thread.
+///
+/// The IO thread sends the most recent message each cycle, and keeps sending it until a new one arrives.
+/// Once every sender is dropped, the IO thread stops.
+#[derive(Debug, Clone)]
+pub struct ControlSender {
+    sender: Sender<NaoControlMessage>,
+    shared: Arc<Shared>,
+}
+
+impl ControlSender {
+    /// Queues a control message to be sent in the next cycle of the IO thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConnectionClosed`] if the IO thread has stopped.
+    pub fn send(&self, msg: NaoControlMessage) -> Result<()> {
+        if self.shared.is_stopped() {
+            return Err(Error::ConnectionClosed);
+        }
+
+        self.sender.send(msg).map_err(|_| Error::ConnectionClosed)
+    }
+
+    /// Stops the IO thread after its current cycle.
+    pub fn stop(self) {
+        self.shared.stop();
+    }
+}
+
+/// Moves `backend` to a new thread that continuously reads the robot state and sends control messages.
+///
+/// The thread runs at the rate of the backend, which for LoLA is once every 12ms.
+/// It stops when every [`ControlSender`] is dropped, when `stop` is called on one of the handles,
+/// or when the backend returns an error, which is then returned by the [`JoinHandle`].
+pub fn spawn_state_reader<B: NaoBackend + Send + 'static>(
+    backend: B,
+) -> (StateReceiver, ControlSender, JoinHandle<Result<()>>) {
+    let shared = Arc::new(Shared::default());
+    let (sender, receiver) = mpsc::channel();
+
+    let guard = StopOnDrop(shared.clone());
+    let handle = thread::spawn(move || {
+        let guard = guard;
+        run(backend, &receiver, &guard.0)
+    });
+
+    (
+        StateReceiver {
+            shared: shared.clone(),
+        },
+        ControlSender { sender, shared },
+        handle,
+    )
+}
+
+fn run<B: NaoBackend>(
+    mut backend: B,
+    receiver: &Receiver<NaoControlMessage>,
+    shared: &Shared,
+) -> Result<()> {
+    let mut control = None;
+
+    while !shared.is_stopped() {
+        shared.publish(backend.read_nao_state()?);
+
+        loop {
+            match receiver.try_recv() {
+                Ok(msg) => control = Some(msg),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Ok(()),
+            }
+        }
+
+        if let Some(msg) = &control {
+            backend.send_control_msg(msg.clone())?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use crate::types::JointArray;
+
+    /// Gives the test access to the mock backend after it has been moved to the IO thread.
+    #[derive(Debug, Clone, Default)]
+    struct SharedMock(Arc<Mutex<MockBackend>>);
+
+    impl NaoBackend for SharedMock {
+        fn connect() -> Result<Self> {
+            Ok(Self::default())
+        }
+
+        fn send_control_msg(&mut self, update: NaoControlMessage) -> Result<()> {
+            self.0.lock().unwrap().send_control_msg(update)
+        }
+
+        fn read_nao_state(&mut self) -> Result<NaoState> {
+            // pace the mock like a real backend, so the test threads get a chance to run
+            thread::sleep(Duration::from_millis(1));
+            self.0.lock().unwrap().read_nao_state()
+        }
+    }
+
+    /// Backend that fails after a number of reads.
+    #[derive(Debug)]
+    struct FailingBackend(usize);
+
+    impl NaoBackend for FailingBackend {
+        fn connect() -> Result<Self> {
+            Ok(Self(0))
+        }
+
+        fn send_control_msg(&mut self, _update: NaoControlMessage) -> Result<()> {
+            Ok(())
+        }
+
+        fn read_nao_state(&mut self) -> Result<NaoState> {
+            match self.0.checked_sub(1) {
+                Some(remaining) => {
+                    self.0 = remaining;
+                    Ok(NaoState::default())
+                }
+                None => Err(Error::ConnectionClosed),
+            }
+        }
+    }
+
+    fn state(head_yaw: f32) -> NaoState {
+        NaoState {
+            position: JointArray {
+                head_yaw,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_latest_state() {
+        let mock = SharedMock::default();
+        mock.0.lock().unwrap().states = (1..=5).map(|i| state(i as f32)).collect();
+
+        let (states, control, io) = spawn_state_reader(mock);
+
+        let (_, sequence) = states.wait_for_newer(5).unwrap();
+        assert!(sequence > 5);
+        assert_eq!(states.latest().position.head_yaw, 5.0);
+        assert!(states.sequence() >= sequence);
+
+        drop(control);
+        io.join().unwrap().unwrap();
+        assert!(states.is_stopped());
+        assert_eq!(states.wait_for_newer(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_control_messages_are_repeated() {
+        let mock = SharedMock::default();
+        let (states, control, io) = spawn_state_reader(mock.clone());
+
+        let msg = NaoControlMessage {
+            position: JointArray {
+                head_yaw: 0.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        control.send(msg.clone()).unwrap();
+
+        let (_, sequence) = states.wait_for_newer(0).unwrap();
+        states.wait_for_newer(sequence + 5).unwrap();
+
+        control.stop();
+        io.join().unwrap().unwrap();
+
+        let sent = mock.0.lock().unwrap().sent.clone();
+        assert!(sent.len() >= 2, "message was not repeated");
+        assert!(sent.iter().all(|sent| *sent == msg));
+    }
+
+    #[test]
+    fn test_stop() {
+        let (states, control, io) = spawn_state_reader(SharedMock::default());
+
+        states.stop();
+        io.join().unwrap().unwrap();
+
+        assert!(matches!(
+            control.send(NaoControlMessage::default()),
+            Err(Error::ConnectionClosed)
+        ));
+    }
+
+    #[test]
+    fn test_backend_error_is_propagated() {
+        let (states, control, io) = spawn_state_reader(FailingBackend(3));
+
+        assert!(matches!(io.join().unwrap(), Err(Error::ConnectionClosed)));
+        assert_eq!(states.sequence(), 3);
+        assert!(control.send(NaoControlMessage::default()).is_err());
+    }
+}
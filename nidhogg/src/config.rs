@@ -0,0 +1,371 @@
+//! Per-robot configuration files, selected by the [`HardwareInfo`] of the connected robot.
+//!
+//! Every robot of a team has slightly different joint offsets and FSR calibrations. A [`RobotConfigStore`]
+//! loads a directory with one file per robot, and selects the [`RobotConfig`] of the connected robot by its
+//! [`HardwareInfo::body_id`], falling back to its [`HardwareInfo::head_id`].
+//!
+//! Files with a `.json` extension are always supported, files with a `.toml` extension require the
+//! `toml` feature. Other files in the directory are ignored. The joint offsets are a map keyed by joint
+//! name, as described in [`named_joints`](crate::types::named_joints), joints that are omitted have no offset:
+//!
+//! ```toml
+//! body_id = "P0000074A04S94700023"
+//! display_name = "Freya"
+//!
+//! [joint_offsets]
+//! HeadYaw = 0.02
+//! LKneePitch = -0.01
+//! ```
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nidhogg::{backend::{LolaBackend, ReadHardwareInfo}, config::RobotConfigStore, facade::Nao, NaoBackend};
+//!
+//! let store = RobotConfigStore::load("/home/nao/robots").unwrap();
+//!
+//! let mut backend = LolaBackend::connect().unwrap();
+//! let info = backend.read_hardware_info().unwrap();
+//! let config = store.select(&info).cloned().unwrap_or_default();
+//!
+//! println!("connected to {}", config.display_name());
+//! let mut nao = Nao::with_backend(backend).with_robot_config(&config);
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    control::BacklashConfig, input::FsrCalibration, types::JointArray, Error, HardwareInfo, Result,
+};
+
+/// The configuration of a single robot, as stored in one file of a [`RobotConfigStore`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RobotConfig {
+    /// The [`HardwareInfo::body_id`] of the robot.
+    pub body_id: Option<String>,
+    /// The [`HardwareInfo::head_id`] of the robot, used when no configuration matches the body id.
+    pub head_id: Option<String>,
+    /// Human readable name of the robot.
+    pub display_name: Option<String>,
+    /// Offset of each joint in radians, which is added to the commanded positions.
+    #[serde(with = "crate::types::named_joints")]
+    pub joint_offsets: JointArray<f32>,
+    /// Calibration of the force sensitive resistors in the feet.
+    pub fsr_calibration: Option<FsrCalibration>,
+    /// Backlash of the joint gears, see [`BacklashCompensator`](crate::control::BacklashCompensator).
+    pub backlash: Option<BacklashConfig>,
+}
+
+impl RobotConfig {
+    /// The offset of each joint in radians.
+    pub fn joint_offsets(&self) -> JointArray<f32> {
+        self.joint_offsets.clone()
+    }
+
+    /// The calibration of the force sensitive resistors, if the robot has been calibrated.
+    pub fn fsr_calibration(&self) -> Option<FsrCalibration> {
+        self.fsr_calibration.clone()
+    }
+
+    /// The backlash of the joint gears, if it has been measured.
+    pub fn backlash(&self) -> Option<BacklashConfig> {
+        self.backlash.clone()
+    }
+
+    /// The name of the robot, falling back to its body id, then its head id.
+    ///
+    /// Returns `"unknown robot"` if the configuration contains neither.
+    pub fn display_name(&self) -> &str {
+        self.display_name
+            .as_deref()
+            .or(self.body_id.as_deref())
+            .or(self.head_id.as_deref())
+            .unwrap_or("unknown robot")
+    }
+
+    /// Add the joint offsets to commanded `positions`, before they are sent to the robot.
+    ///
+    /// This is the inverse of [`RobotConfig::remove_offsets`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{config::RobotConfig, types::{FillExt, JointArray}};
+    ///
+    /// let config = RobotConfig {
+    ///     joint_offsets: JointArray::fill(0.25),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let commanded = JointArray::fill(1.0);
+    /// let sent = config.apply_offsets(&commanded);
+    /// assert_eq!(sent, JointArray::fill(1.25));
+    /// assert_eq!(config.remove_offsets(&sent), commanded);
+    /// ```
+    pub fn apply_offsets(&self, positions: &JointArray<f32>) -> JointArray<f32> {
+        positions
+            .clone()
+            .zip(self.joint_offsets.clone())
+            .map(|(position, offset)| position + offset)
+    }
+
+    /// Subtract the joint offsets from measured `positions`, after they are read from the robot.
+    ///
+    /// This is the inverse of [`RobotConfig::apply_offsets`].
+    pub fn remove_offsets(&self, positions: &JointArray<f32>) -> JointArray<f32> {
+        positions
+            .clone()
+            .zip(self.joint_offsets.clone())
+            .map(|(position, offset)| position - offset)
+    }
+
+    /// Parse a configuration file, using its extension to pick the format.
+    fn from_file(path: &Path) -> Result<Option<Self>> {
+        let error = |reason: String| Error::RobotConfigError {
+            path: path.to_path_buf(),
+            reason,
+        };
+
+        let format = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Format::Json,
+            Some("toml") => Format::Toml,
+            _ => return Ok(None),
+        };
+        let contents = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+
+        match format {
+            Format::Json => serde_json::from_str(&contents).map_err(|e| error(e.to_string())),
+            #[cfg(feature = "toml")]
+            Format::Toml => toml::from_str(&contents).map_err(|e| error(e.to_string())),
+            #[cfg(not(feature = "toml"))]
+            Format::Toml => Err(error("TOML files require the `toml` feature".to_string())),
+        }
+        .map(Some)
+    }
+}
+
+/// The file formats of a [`RobotConfigStore`].
+enum Format {
+    Json,
+    Toml,
+}
+
+/// The configurations of all robots of a team, loaded from a directory with one file per robot.
+///
+/// See the [module documentation](crate::config) for the file format.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{config::{RobotConfig, RobotConfigStore}, HardwareInfo};
+///
+/// let store = RobotConfigStore::from_configs(vec![RobotConfig {
+///     head_id: Some("P0000073A07S94500188".to_string()),
+///     display_name: Some("Freya".to_string()),
+///     ..Default::default()
+/// }]);
+///
+/// let info = HardwareInfo {
+///     body_id: "P0000074A04S94700023".to_string(),
+///     body_version: "6.0.0".to_string(),
+///     head_id: "P0000073A07S94500188".to_string(),
+///     head_version: "6.0.0".to_string(),
+/// };
+///
+/// // no configuration matches the body id, so the head id is used
+/// assert_eq!(store.select(&info).unwrap().display_name(), "Freya");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RobotConfigStore {
+    configs: Vec<RobotConfig>,
+}
+
+impl RobotConfigStore {
+    /// Create a [`RobotConfigStore`] from configurations that are already loaded.
+    pub fn from_configs(configs: Vec<RobotConfig>) -> Self {
+        Self { configs }
+    }
+
+    /// Load every `.json` and `.toml` file in `dir`, ignoring other files and subdirectories.
+    ///
+    /// Returns an error if a file can not be parsed, or if two files configure the same body or head id.
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self> {
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<PathBuf>>>()?;
+        paths.sort();
+
+        let mut configs: Vec<(PathBuf, RobotConfig)> = Vec::new();
+        for path in paths {
+            if !path.is_file() {
+                continue;
+            }
+            let Some(config) = RobotConfig::from_file(&path)? else {
+                continue;
+            };
+
+            let duplicate = configs.iter().find(|(_, other)| {
+                same_id(&config.body_id, &other.body_id) || same_id(&config.head_id, &other.head_id)
+            });
+            if let Some((other, _)) = duplicate {
+                return Err(Error::RobotConfigError {
+                    reason: format!("the robot is already configured in `{}`", other.display()),
+                    path,
+                });
+            }
+
+            configs.push((path, config));
+        }
+
+        Ok(Self::from_configs(
+            configs.into_iter().map(|(_, config)| config).collect(),
+        ))
+    }
+
+    /// Returns the configuration of the robot with the provided hardware, if it is known.
+    ///
+    /// A configuration matching the [`HardwareInfo::body_id`] takes precedence over one matching
+    /// the [`HardwareInfo::head_id`].
+    pub fn select(&self, info: &HardwareInfo) -> Option<&RobotConfig> {
+        let matching = |id: &Option<String>, expected: &str| {
+            id.as_deref().is_some_and(|id| id.trim() == expected.trim())
+        };
+
+        self.configs
+            .iter()
+            .find(|config| matching(&config.body_id, &info.body_id))
+            .or_else(|| {
+                self.configs
+                    .iter()
+                    .find(|config| matching(&config.head_id, &info.head_id))
+            })
+    }
+
+    /// The number of robots in the store.
+    pub fn len(&self) -> usize {
+        self.configs.len()
+    }
+
+    /// Returns `true` if the store contains no robots.
+    pub fn is_empty(&self) -> bool {
+        self.configs.is_empty()
+    }
+}
+
+fn same_id(a: &Option<String>, b: &Option<String>) -> bool {
+    matches!((a, b), (Some(a), Some(b)) if a.trim() == b.trim())
+}
+
+// the fixtures include a TOML file
+#[cfg(all(test, feature = "toml"))]
+mod tests {
+    use super::*;
+    use crate::types::FillExt;
+
+    fn info(body_id: &str, head_id: &str) -> HardwareInfo {
+        HardwareInfo {
+            body_id: body_id.to_string(),
+            body_version: "6.0.0".to_string(),
+            head_id: head_id.to_string(),
+            head_version: "6.0.0".to_string(),
+        }
+    }
+
+    fn store() -> (tempfile::TempDir, RobotConfigStore) {
+        let dir = tempfile::tempdir().unwrap();
+
+        fs::write(
+            dir.path().join("freya.toml"),
+            r#"
+                body_id = "BODY-FREYA"
+                head_id = "HEAD-FREYA"
+                display_name = "Freya"
+
+                [joint_offsets]
+                HeadYaw = 0.1
+                left_knee_pitch = -0.05
+
+                [backlash]
+                play = { LKneePitch = 0.02 }
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("odin.json"),
+            r#"{ "body_id": "BODY-ODIN", "head_id": "HEAD-ODIN", "joint_offsets": { "HeadYaw": -0.2 } }"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), "not a robot").unwrap();
+
+        let store = RobotConfigStore::load(dir.path()).unwrap();
+        (dir, store)
+    }
+
+    #[test]
+    fn test_select_by_id() {
+        let (_dir, store) = store();
+        assert_eq!(store.len(), 2);
+
+        let freya = store.select(&info("BODY-FREYA", "HEAD-FREYA")).unwrap();
+        assert_eq!(freya.display_name(), "Freya");
+        assert_eq!(freya.joint_offsets().head_yaw, 0.1);
+        assert_eq!(freya.joint_offsets().left_knee_pitch, -0.05);
+        assert_eq!(freya.joint_offsets().right_knee_pitch, 0.0);
+        assert_eq!(freya.fsr_calibration(), None);
+
+        let backlash = freya.backlash().unwrap();
+        assert_eq!(backlash.play.left_knee_pitch, 0.02);
+        assert_eq!(backlash.dead_band, BacklashConfig::default().dead_band);
+
+        // the head of odin on the body of freya, the body id wins
+        let swapped = store.select(&info("BODY-FREYA\n", "HEAD-ODIN")).unwrap();
+        assert_eq!(swapped.display_name(), "Freya");
+
+        // an unknown body falls back to the head id
+        let odin = store.select(&info("BODY-NEW", "HEAD-ODIN")).unwrap();
+        assert_eq!(odin.display_name(), "BODY-ODIN");
+        assert_eq!(odin.joint_offsets().head_yaw, -0.2);
+
+        assert_eq!(store.select(&info("BODY-NEW", "HEAD-NEW")), None);
+    }
+
+    #[test]
+    fn test_offsets_round_trip() {
+        let (_dir, store) = store();
+        let freya = store.select(&info("BODY-FREYA", "HEAD-FREYA")).unwrap();
+
+        let commanded = JointArray::fill(0.5);
+        let sent = freya.apply_offsets(&commanded);
+        assert_eq!(sent.head_yaw, 0.6);
+        assert_eq!(sent.head_pitch, 0.5);
+        assert_eq!(freya.remove_offsets(&sent), commanded);
+    }
+
+    #[test]
+    fn test_load_rejects_duplicates_and_invalid_files() {
+        let (dir, _) = store();
+
+        fs::write(
+            dir.path().join("freya_copy.json"),
+            r#"{ "body_id": "BODY-FREYA" }"#,
+        )
+        .unwrap();
+        let error = RobotConfigStore::load(dir.path()).unwrap_err();
+        assert!(error.to_string().contains("freya.toml"), "{error}");
+
+        fs::remove_file(dir.path().join("freya_copy.json")).unwrap();
+        fs::write(
+            dir.path().join("broken.json"),
+            r#"{ "joint_offsets": { "Tail": 1.0 } }"#,
+        )
+        .unwrap();
+        let error = RobotConfigStore::load(dir.path()).unwrap_err();
+        assert!(
+            matches!(error, Error::RobotConfigError { ref path, .. } if path.ends_with("broken.json"))
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Implements the [`BacklashCompensator`], which compensates the play in worn joint gears.
+
+use crate::types::{FillExt, JointArray};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the [`BacklashCompensator`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct BacklashConfig {
+    /// The play of each joint in radians, half of which is added in the direction of motion.
+    #[cfg_attr(feature = "serde", serde(with = "crate::types::named_joints"))]
+    pub play: JointArray<f32>,
+    /// Distance in radians the commanded position has to move against the current direction before it reverses.
+    ///
+    /// This prevents the offset from chattering when the commanded position is noisy.
+    pub dead_band: f32,
+}
+
+impl Default for BacklashConfig {
+    fn default() -> Self {
+        Self {
+            play: JointArray::fill(0.0),
+            dead_band: 0.005,
+        }
+    }
+}
+
+/// Direction of motion of a single joint, tracked from successive position commands.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct JointMotion {
+    /// The furthest position commanded in the current direction.
+    extreme: f32,
+    /// `1.0` when moving up, `-1.0` when moving down and `0.0` before the first move.
+    direction: f32,
+}
+
+impl JointMotion {
+    fn update(&mut self, position: f32, dead_band: f32) {
+        let moved = position - self.extreme;
+
+        if moved * self.direction > 0.0 {
+            self.extreme = position;
+        } else if moved.abs() > dead_band {
+            self.extreme = position;
+            self.direction = moved.signum();
+        }
+    }
+}
+
+/// Compensates the backlash of worn joint gears, by adding a direction dependent offset to the commanded positions.
+///
+/// The direction of motion of each joint is tracked from the positions passed to
+/// [`BacklashCompensator::compensate`], and half of the play is added in that direction.
+/// The direction only reverses once the commanded position moved back by more than the
+/// [`BacklashConfig::dead_band`], so each reversal produces a single step in the offset.
+/// Joints that have not moved yet are not compensated.
+///
+/// Use [`SafeBackend::with_backlash_compensation`](crate::safety::SafeBackend::with_backlash_compensation)
+/// to compensate every control message before the safety limits are applied.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{control::{BacklashCompensator, BacklashConfig}, types::{FillExt, JointArray}};
+///
+/// let mut compensator = BacklashCompensator::new(BacklashConfig {
+///     play: JointArray::fill(0.5),
+///     dead_band: 0.1,
+/// });
+///
+/// compensator.compensate(JointArray::fill(0.0));
+/// let moving_up = compensator.compensate(JointArray::fill(1.0));
+/// assert_eq!(moving_up.head_yaw, 1.25);
+///
+/// let moving_down = compensator.compensate(JointArray::fill(0.5));
+/// assert_eq!(moving_down.head_yaw, 0.25);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BacklashCompensator {
+    config: BacklashConfig,
+    motion: Option<JointArray<JointMotion>>,
+}
+
+impl BacklashCompensator {
+    /// Create a new [`BacklashCompensator`] using the provided configuration.
+    pub fn new(config: BacklashConfig) -> Self {
+        Self {
+            config,
+            motion: None,
+        }
+    }
+
+    /// The configuration used by this compensator.
+    pub fn config(&self) -> &BacklashConfig {
+        &self.config
+    }
+
+    /// Update the direction of motion using the commanded `positions`, and return the compensated positions.
+    pub fn compensate(&mut self, positions: JointArray<f32>) -> JointArray<f32> {
+        let dead_band = self.config.dead_band;
+        let motion = self.motion.get_or_insert_with(|| {
+            positions.clone().map(|extreme| JointMotion {
+                extreme,
+                direction: 0.0,
+            })
+        });
+
+        for (motion, position) in motion.as_array_mut().into_iter().zip(&positions) {
+            motion.update(*position, dead_band);
+        }
+
+        positions
+            .zip(motion.clone())
+            .zip(self.config.play.clone())
+            .map(|((position, motion), play)| position + motion.direction * play / 2.0)
+    }
+
+    /// The offset currently added to the position of every joint.
+    pub fn offsets(&self) -> JointArray<f32> {
+        match &self.motion {
+            Some(motion) => motion
+                .clone()
+                .zip(self.config.play.clone())
+                .map(|(motion, play)| motion.direction * play / 2.0),
+            None => JointArray::fill(0.0),
+        }
+    }
+
+    /// Forget the direction of motion, so no joints are compensated until they move again.
+    pub fn reset(&mut self) {
+        self.motion = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compensator() -> BacklashCompensator {
+        BacklashCompensator::new(BacklashConfig {
+            play: JointArray::fill(0.02),
+            dead_band: 0.025,
+        })
+    }
+
+    /// The offsets added to the head yaw, while it is commanded along `positions`.
+    fn offsets(compensator: &mut BacklashCompensator, positions: &[f32]) -> Vec<f32> {
+        positions
+            .iter()
+            .map(|&position| {
+                let compensated = compensator.compensate(JointArray::fill(position));
+                let offset = compensated.head_yaw - position;
+                assert!((compensator.offsets().head_yaw - offset).abs() < 1e-6);
+
+                // round to avoid floating point noise in the comparisons
+                (offset * 1000.0).round() / 1000.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reversal_steps_once_after_dead_band() {
+        let mut compensator = compensator();
+
+        // up in steps of 0.01, then down again
+        let positions: Vec<f32> = (0..=10)
+            .chain((0..10).rev())
+            .map(|step| step as f32 * 0.01)
+            .collect();
+        let offsets = offsets(&mut compensator, &positions);
+
+        // the first two steps are within the dead band, the direction is unknown
+        assert_eq!(offsets[..3], [0.0, 0.0, 0.0]);
+        assert!(offsets[3..=12].iter().all(|&offset| offset == 0.01));
+        // reversing at 0.1, the offset steps once when the position drops below 0.075
+        assert!(offsets[13..].iter().all(|&offset| offset == -0.01));
+
+        let steps = offsets.windows(2).filter(|w| w[0] != w[1]).count();
+        assert_eq!(steps, 2);
+    }
+
+    #[test]
+    fn test_noise_does_not_chatter() {
+        let mut compensator = compensator();
+
+        // jitter of 0.02 around 0.06, which is less than the dead band
+        let mut positions = vec![0.0, 0.05];
+        positions.extend((0..20).map(|i| if i % 2 == 0 { 0.07 } else { 0.05 }));
+        let offsets = offsets(&mut compensator, &positions);
+
+        assert!(offsets[1..].iter().all(|&offset| offset == 0.01));
+
+        compensator.reset();
+        assert_eq!(compensator.offsets(), JointArray::fill(0.0));
+    }
+}
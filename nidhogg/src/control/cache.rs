@@ -0,0 +1,215 @@
+use crate::{
+    types::{color::RgbF32, JointArray, LeftEar, LeftEye, RightEar, RightEye, Skull, SonarMode},
+    NaoControlMessage, NaoState,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy")]
+use bevy_ecs::prelude::Resource;
+
+/// A control message in which every value is optional.
+///
+/// Values that are `None` are taken from the previous message when merged using
+/// [`ControlMessageCache::merge`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PartialControlMessage {
+    pub position: JointArray<Option<f32>>,
+    pub stiffness: JointArray<Option<f32>>,
+    pub sonar: Option<SonarMode>,
+
+    // LEDs
+    pub left_ear: Option<LeftEar>,
+    pub right_ear: Option<RightEar>,
+    pub chest: Option<RgbF32>,
+    pub left_eye: Option<LeftEye>,
+    pub right_eye: Option<RightEye>,
+    pub left_foot: Option<RgbF32>,
+    pub right_foot: Option<RgbF32>,
+    pub skull: Option<Skull>,
+}
+
+impl PartialControlMessage {
+    /// Resolve the partial message against the measured `state`.
+    ///
+    /// Joints without a position or stiffness hold their measured values,
+    /// all other unspecified values are set to their defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{control::PartialControlMessage, types::JointArray, NaoState};
+    ///
+    /// let state = NaoState {
+    ///     position: JointArray {
+    ///         left_shoulder_pitch: 1.4,
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let msg = PartialControlMessage::default().holding(&state);
+    /// assert_eq!(msg.position.left_shoulder_pitch, 1.4);
+    /// ```
+    #[must_use]
+    pub fn holding(self, state: &NaoState) -> NaoControlMessage {
+        ControlMessageCache::new(NaoControlMessage::holding(state)).merge(self)
+    }
+}
+
+/// Remembers the last [`NaoControlMessage`] that was sent, so new messages only have to specify
+/// the values that changed.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{control::{ControlMessageCache, PartialControlMessage}, types::{color, JointArray}};
+///
+/// let mut cache = ControlMessageCache::default();
+///
+/// let msg = cache.merge(PartialControlMessage {
+///     position: JointArray {
+///         head_yaw: Some(0.5),
+///         ..Default::default()
+///     },
+///     chest: Some(color::f32::RED),
+///     ..Default::default()
+/// });
+/// assert_eq!(msg.position.head_yaw, 0.5);
+///
+/// // The chest stays red, because it is not specified in the next message
+/// let msg = cache.merge(PartialControlMessage::default());
+/// assert_eq!(msg.position.head_yaw, 0.5);
+/// assert_eq!(msg.chest, color::f32::RED);
+/// ```
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct ControlMessageCache {
+    last: NaoControlMessage,
+}
+
+impl ControlMessageCache {
+    /// Create a new cache, using `initial` as the previous message.
+    #[must_use]
+    pub fn new(initial: NaoControlMessage) -> Self {
+        Self { last: initial }
+    }
+
+    /// The last message that was stored in the cache.
+    pub fn last(&self) -> &NaoControlMessage {
+        &self.last
+    }
+
+    /// Store a complete message in the cache, replacing the previous message.
+    pub fn record(&mut self, msg: NaoControlMessage) {
+        self.last = msg;
+    }
+
+    /// Merge the partial message with the previous message, and store the result in the cache.
+    ///
+    /// Values that are not specified in `partial` keep their previous value.
+    /// Joints with a position in `partial` are selected in the `position_mask`.
+    pub fn merge(&mut self, partial: PartialControlMessage) -> NaoControlMessage {
+        let last = &self.last;
+
+        let msg = NaoControlMessage {
+            position: partial.position.unwrap_or(&last.position),
+            position_mask: last.position_mask.clone()
+                | partial.position.as_ref().map(Option::is_some),
+            stiffness: partial.stiffness.unwrap_or(&last.stiffness),
+            sonar: partial.sonar.unwrap_or(last.sonar),
+            left_ear: partial.left_ear.unwrap_or_else(|| last.left_ear.clone()),
+            right_ear: partial.right_ear.unwrap_or_else(|| last.right_ear.clone()),
+            chest: partial.chest.unwrap_or(last.chest),
+            left_eye: partial.left_eye.unwrap_or_else(|| last.left_eye.clone()),
+            right_eye: partial.right_eye.unwrap_or_else(|| last.right_eye.clone()),
+            left_foot: partial.left_foot.unwrap_or(last.left_foot),
+            right_foot: partial.right_foot.unwrap_or(last.right_foot),
+            skull: partial.skull.unwrap_or_else(|| last.skull.clone()),
+        };
+
+        self.last = msg.clone();
+        msg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{color, FillExt};
+
+    fn previous() -> NaoControlMessage {
+        NaoControlMessage::builder()
+            .position(JointArray::fill(0.25))
+            .stiffness(JointArray::fill(0.8))
+            .chest(color::f32::MAGENTA)
+            .left_eye(LeftEye::fill(color::f32::CYAN))
+            .build()
+    }
+
+    #[test]
+    fn test_merge_preserves_untouched_joints() {
+        let mut cache = ControlMessageCache::new(previous());
+
+        let msg = cache.merge(PartialControlMessage {
+            position: JointArray {
+                left_knee_pitch: Some(1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(msg.position.left_knee_pitch, 1.0);
+        assert_eq!(msg.position.right_knee_pitch, 0.25);
+        assert_eq!(msg.position.head_yaw, 0.25);
+        assert_eq!(msg.stiffness, JointArray::fill(0.8));
+        assert_eq!(cache.last(), &msg);
+    }
+
+    #[test]
+    fn test_empty_message_holds_measured_position() {
+        let state = NaoState {
+            position: JointArray {
+                left_shoulder_pitch: 1.4,
+                ..Default::default()
+            },
+            stiffness: JointArray::fill(0.5),
+            ..Default::default()
+        };
+
+        let msg = PartialControlMessage {
+            position: JointArray {
+                head_yaw: Some(0.3),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .holding(&state);
+
+        assert_eq!(msg.position.head_yaw, 0.3);
+        assert_eq!(msg.position.left_shoulder_pitch, 1.4);
+        assert_eq!(msg.position.right_shoulder_pitch, 0.0);
+        assert_eq!(msg.stiffness, JointArray::fill(0.5));
+    }
+
+    #[test]
+    fn test_merge_preserves_untouched_leds() {
+        let mut cache = ControlMessageCache::new(previous());
+
+        let msg = cache.merge(PartialControlMessage {
+            chest: Some(color::f32::RED),
+            ..Default::default()
+        });
+
+        assert_eq!(msg.chest, color::f32::RED);
+        assert_eq!(msg.left_eye, LeftEye::fill(color::f32::CYAN));
+        assert_eq!(msg.right_eye, RightEye::default());
+
+        // the merged message becomes the new previous message
+        let msg = cache.merge(PartialControlMessage::default());
+        assert_eq!(msg.chest, color::f32::RED);
+    }
+}
@@ -0,0 +1,10 @@
+//! Utilities for constructing control messages.
+//!
+
+mod backlash;
+mod cache;
+mod part;
+
+pub use backlash::{BacklashCompensator, BacklashConfig};
+pub use cache::{ControlMessageCache, PartialControlMessage};
+pub use part::ControlPart;
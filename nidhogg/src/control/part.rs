@@ -0,0 +1,138 @@
+use crate::{
+    leds::{LedState, LedStateDelta},
+    types::{JointArray, SonarMode},
+    NaoControlMessage,
+};
+
+/// A part of a [`NaoControlMessage`], contributed by a single subsystem.
+///
+/// Parts are combined into a message using [`NaoControlMessage::compose`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ControlPart {
+    /// The position and stiffness of all joints, in that order.
+    Joints(JointArray<f32>, JointArray<f32>),
+    /// The state of all LEDs.
+    Leds(LedState),
+    /// The LED groups that are set in the delta, other LEDs are left untouched.
+    LedDelta(LedStateDelta),
+    /// The emitters used by the sonar sensors.
+    Sonar(SonarMode),
+}
+
+impl ControlPart {
+    /// Applies the part to `msg`, overwriting the values it contains.
+    fn apply(self, msg: &mut NaoControlMessage) {
+        match self {
+            ControlPart::Joints(position, stiffness) => {
+                msg.position = position;
+                msg.stiffness = stiffness;
+            }
+            ControlPart::Leds(leds) => msg.set_leds(leds),
+            ControlPart::LedDelta(delta) => delta.apply(msg),
+            ControlPart::Sonar(sonar) => msg.sonar = sonar,
+        }
+    }
+}
+
+impl NaoControlMessage {
+    /// Combines the parts produced by separate subsystems into a single message.
+    ///
+    /// Each part only sets its own group of fields, so parts of different groups can be passed in any order.
+    /// If multiple parts set the same field, the last one wins.
+    /// Fields that are not set by any part keep the values of [`NaoControlMessage::default`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{
+    ///     control::ControlPart,
+    ///     leds::LedState,
+    ///     types::{color, FillExt, JointArray},
+    ///     NaoControlMessage,
+    /// };
+    ///
+    /// let motion = ControlPart::Joints(JointArray::fill(0.0), JointArray::fill(0.8));
+    /// let behavior = ControlPart::Leds(LedState::builder().chest(color::f32::RED).build());
+    ///
+    /// let msg = NaoControlMessage::compose([behavior, motion]);
+    /// assert_eq!(msg.stiffness, JointArray::fill(0.8));
+    /// assert_eq!(msg.chest, color::f32::RED);
+    /// ```
+    #[must_use]
+    pub fn compose(parts: impl IntoIterator<Item = ControlPart>) -> NaoControlMessage {
+        let mut msg = NaoControlMessage::default();
+        for part in parts {
+            part.apply(&mut msg);
+        }
+
+        msg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{color, FillExt, Skull};
+
+    #[test]
+    fn test_missing_parts_are_default() {
+        assert_eq!(NaoControlMessage::compose([]), NaoControlMessage::default());
+
+        let sonar = SonarMode::LeftOnly;
+        let msg = NaoControlMessage::compose([ControlPart::Sonar(sonar)]);
+        assert_eq!(
+            msg,
+            NaoControlMessage {
+                sonar,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_disjoint_parts_are_order_independent() {
+        let joints = ControlPart::Joints(JointArray::fill(0.1), JointArray::fill(0.5));
+        let leds = ControlPart::Leds(LedState::builder().chest(color::f32::BLUE).build());
+
+        assert_eq!(
+            NaoControlMessage::compose([joints.clone(), leds.clone()]),
+            NaoControlMessage::compose([leds, joints])
+        );
+    }
+
+    #[test]
+    fn test_conflicting_parts_last_wins() {
+        let msg = NaoControlMessage::compose([
+            ControlPart::Joints(JointArray::fill(0.1), JointArray::fill(0.5)),
+            ControlPart::Joints(JointArray::fill(0.2), JointArray::fill(0.7)),
+        ]);
+        assert_eq!(msg.position, JointArray::fill(0.2));
+        assert_eq!(msg.stiffness, JointArray::fill(0.7));
+
+        // a delta only overwrites the groups it sets
+        let msg = NaoControlMessage::compose([
+            ControlPart::Leds(
+                LedState::builder()
+                    .chest(color::f32::BLUE)
+                    .skull(Skull::fill(0.5))
+                    .build(),
+            ),
+            ControlPart::LedDelta(LedStateDelta {
+                chest: Some(color::f32::RED),
+                ..Default::default()
+            }),
+        ]);
+        assert_eq!(msg.chest, color::f32::RED);
+        assert_eq!(msg.skull, Skull::fill(0.5));
+
+        // a full LED state overwrites an earlier delta
+        let msg = NaoControlMessage::compose([
+            ControlPart::LedDelta(LedStateDelta {
+                chest: Some(color::f32::RED),
+                ..Default::default()
+            }),
+            ControlPart::Leds(LedState::default()),
+        ]);
+        assert_eq!(msg.leds(), LedState::default());
+    }
+}
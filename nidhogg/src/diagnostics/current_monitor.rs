@@ -0,0 +1,202 @@
+//! Implements [`CurrentMonitor`], which tracks joint currents over time to detect stalled joints.
+
+use std::collections::VecDeque;
+
+use crate::{
+    types::{JointArray, JointName},
+    NaoState,
+};
+
+/// Configuration for the [`CurrentMonitor`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurrentMonitorConfig {
+    /// Weight of a new measurement in the exponential average of the current, between `0.0` and `1.0`.
+    pub smoothing: f32,
+    /// Averaged current in amperes above which a joint that does not move is considered stalled.
+    pub stall_current: f32,
+    /// Maximum change in position in radians over [`CurrentMonitorConfig::stall_cycles`] for a joint to be considered not moving.
+    pub stall_motion: f32,
+    /// Amount of cycles a joint has to draw current without moving to be considered stalled.
+    pub stall_cycles: usize,
+}
+
+impl Default for CurrentMonitorConfig {
+    fn default() -> Self {
+        Self {
+            smoothing: 0.1,
+            stall_current: 1.0,
+            stall_motion: 0.01,
+            // roughly half a second
+            stall_cycles: 40,
+        }
+    }
+}
+
+/// Tracks the current drawn by each joint over time, and detects stalled joints.
+///
+/// A joint is stalled when it draws a lot of current, but does not move.
+/// This typically happens when a joint pushes against an obstacle, or when the robot is stuck in an awkward position.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{NaoState, diagnostics::CurrentMonitor, types::JointName};
+///
+/// let mut monitor = CurrentMonitor::default();
+///
+/// let mut state = NaoState::default();
+/// state.current.left_knee_pitch = 2.0;
+///
+/// for _ in 0..100 {
+///     monitor.update(&state);
+/// }
+///
+/// assert_eq!(monitor.stalled_joints(), vec![JointName::LeftKneePitch]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CurrentMonitor {
+    config: CurrentMonitorConfig,
+    average: Option<JointArray<f32>>,
+    positions: VecDeque<JointArray<f32>>,
+}
+
+impl CurrentMonitor {
+    /// Create a new [`CurrentMonitor`] using the provided configuration.
+    pub fn new(config: CurrentMonitorConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// The configuration used by this monitor.
+    pub fn config(&self) -> &CurrentMonitorConfig {
+        &self.config
+    }
+
+    /// Update the monitor with the current and position values of a new state.
+    pub fn update(&mut self, state: &NaoState) {
+        let smoothing = self.config.smoothing;
+        self.average = Some(match self.average.take() {
+            Some(average) => average
+                .zip(state.current.clone())
+                .map(|(average, current)| average + smoothing * (current - average)),
+            None => state.current.clone(),
+        });
+
+        if self.positions.len() > self.config.stall_cycles {
+            self.positions.pop_front();
+        }
+        self.positions.push_back(state.position.clone());
+    }
+
+    /// Returns the exponentially averaged current of each joint in amperes.
+    ///
+    /// Before the first update, this is zero for every joint.
+    pub fn average_current(&self) -> JointArray<f32> {
+        self.average.clone().unwrap_or_default()
+    }
+
+    /// Returns all joints that drew more than [`CurrentMonitorConfig::stall_current`] on average,
+    /// while moving less than [`CurrentMonitorConfig::stall_motion`] in the last [`CurrentMonitorConfig::stall_cycles`] cycles.
+    ///
+    /// This is empty until the monitor has been updated for at least [`CurrentMonitorConfig::stall_cycles`] cycles.
+    pub fn stalled_joints(&self) -> Vec<JointName> {
+        let Some(average) = &self.average else {
+            return Vec::new();
+        };
+
+        if self.positions.len() <= self.config.stall_cycles {
+            return Vec::new();
+        }
+
+        average
+            .iter_named()
+            .filter(|(_, &current)| current > self.config.stall_current)
+            .filter(|(name, _)| {
+                let (min, max) = self
+                    .positions
+                    .iter()
+                    .map(|positions| positions[*name])
+                    .fold(
+                        (f32::INFINITY, f32::NEG_INFINITY),
+                        |(min, max), position| (min.min(position), max.max(position)),
+                    );
+
+                max - min < self.config.stall_motion
+            })
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Clears the averaged currents and position history.
+    pub fn reset(&mut self) {
+        self.average = None;
+        self.positions.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A knee that is pushed against an obstacle, and a hip that draws as much current but keeps moving.
+    fn state(cycle: usize) -> NaoState {
+        NaoState {
+            current: JointArray {
+                left_knee_pitch: 1.8,
+                left_hip_pitch: 1.8,
+                right_knee_pitch: 0.3,
+                ..Default::default()
+            },
+            position: JointArray {
+                left_knee_pitch: 1.2 + (cycle % 2) as f32 * 0.002,
+                left_hip_pitch: -0.5 + cycle as f32 * 0.005,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_stalled_knee() {
+        let mut monitor = CurrentMonitor::default();
+        let cycles = monitor.config().stall_cycles;
+
+        for cycle in 0..cycles {
+            monitor.update(&state(cycle));
+        }
+        assert!(monitor.stalled_joints().is_empty(), "not enough history");
+
+        monitor.update(&state(cycles));
+        assert_eq!(monitor.stalled_joints(), vec![JointName::LeftKneePitch]);
+        assert!((monitor.average_current().left_knee_pitch - 1.8).abs() < 1e-5);
+
+        monitor.reset();
+        assert!(monitor.stalled_joints().is_empty());
+    }
+
+    #[test]
+    fn test_current_is_averaged() {
+        let mut monitor = CurrentMonitor::new(CurrentMonitorConfig {
+            smoothing: 0.5,
+            stall_cycles: 2,
+            ..Default::default()
+        });
+
+        let mut state = NaoState::default();
+        state.current.head_yaw = 4.0;
+        monitor.update(&state);
+        monitor.update(&state);
+
+        // a short spike does not stall the joint
+        state.current.head_yaw = 0.0;
+        monitor.update(&state);
+        monitor.update(&state);
+        assert_eq!(monitor.average_current().head_yaw, 1.0);
+        assert!(monitor.stalled_joints().is_empty());
+
+        monitor.update(&state);
+        assert_eq!(monitor.average_current().head_yaw, 0.5);
+    }
+}
@@ -0,0 +1,295 @@
+//! Implements [`JointDiagnostics`], which interprets the per-joint diagnostic values of a [`NaoState`].
+
+use crate::{
+    types::{JointArray, JointName, JointRegion},
+    NaoState,
+};
+
+/// Temperature status of a joint, as reported by `LoLA`.
+///
+/// When a joint reaches a higher temperature status, the motor board limits the
+/// current (and therefore the available torque) of that joint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TempStatus {
+    /// The joint is operating within normal temperatures.
+    #[default]
+    Normal,
+    /// The joint is hot.
+    Hot,
+    /// The joint is very hot, its stiffness is being limited.
+    VeryHot,
+    /// The joint is critically hot, its stiffness is severely limited.
+    Critical,
+}
+
+impl TempStatus {
+    /// Decode a raw `LoLA` status value.
+    ///
+    /// Values below `0` are interpreted as [`TempStatus::Normal`], and values above `3` as [`TempStatus::Critical`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::diagnostics::TempStatus;
+    ///
+    /// assert_eq!(TempStatus::from_raw(0), TempStatus::Normal);
+    /// assert_eq!(TempStatus::from_raw(2), TempStatus::VeryHot);
+    /// ```
+    pub fn from_raw(status: i32) -> Self {
+        match status {
+            i32::MIN..=0 => TempStatus::Normal,
+            1 => TempStatus::Hot,
+            2 => TempStatus::VeryHot,
+            _ => TempStatus::Critical,
+        }
+    }
+}
+
+impl From<i32> for TempStatus {
+    fn from(status: i32) -> Self {
+        TempStatus::from_raw(status)
+    }
+}
+
+/// Per-joint diagnostics, built from the temperature, status and current values of a [`NaoState`].
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{NaoState, diagnostics::{JointDiagnostics, TempStatus}, types::JointName};
+///
+/// let mut state = NaoState::default();
+/// state.temperature.left_knee_pitch = 72.0;
+/// state.status.left_knee_pitch = 2;
+///
+/// let diagnostics = JointDiagnostics::from(&state);
+///
+/// assert_eq!(diagnostics.hottest_joint(), (JointName::LeftKneePitch, 72.0));
+/// assert_eq!(diagnostics.status.left_knee_pitch, TempStatus::VeryHot);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JointDiagnostics {
+    /// Temperature of each joint in degrees Celsius.
+    pub temperature: JointArray<f32>,
+    /// Decoded temperature status of each joint.
+    pub status: JointArray<TempStatus>,
+    /// Current drawn by each joint in amperes.
+    pub current: JointArray<f32>,
+}
+
+/// Current drawn by each region of the body in amperes, as returned by [`JointDiagnostics::current_by_region`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RegionCurrent {
+    pub head: f32,
+    pub left_arm: f32,
+    pub right_arm: f32,
+    pub left_leg: f32,
+    pub right_leg: f32,
+}
+
+impl JointDiagnostics {
+    /// Returns the joint with the highest temperature, together with that temperature.
+    ///
+    /// If multiple joints share the highest temperature, the first one in [`JointName::ALL`] is returned.
+    pub fn hottest_joint(&self) -> (JointName, f32) {
+        let (name, &temp) = self.temperature.max_by(f32::total_cmp);
+        (name, temp)
+    }
+
+    /// Returns the names of all joints with a temperature above the provided value, in degrees Celsius.
+    pub fn joints_above(&self, celsius: f32) -> Vec<JointName> {
+        self.temperature
+            .iter_named()
+            .filter(|(_, &temp)| temp > celsius)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Returns the names of all joints with a temperature status of at least `status`.
+    pub fn joints_with_status(&self, status: TempStatus) -> Vec<JointName> {
+        self.status
+            .iter_named()
+            .filter(|(_, &joint_status)| joint_status >= status)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Returns the total current drawn by all joints, in amperes.
+    pub fn total_current(&self) -> f32 {
+        self.current.sum()
+    }
+
+    /// Returns the current drawn by each region of the body, in amperes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{NaoState, diagnostics::JointDiagnostics};
+    ///
+    /// let mut state = NaoState::default();
+    /// state.current.left_knee_pitch = 1.2;
+    /// state.current.left_hip_pitch = 0.3;
+    ///
+    /// let diagnostics = JointDiagnostics::from(&state);
+    /// assert_eq!(diagnostics.current_by_region().left_leg, 1.5);
+    /// ```
+    pub fn current_by_region(&self) -> RegionCurrent {
+        let region = |region: JointRegion| {
+            self.current
+                .iter_named()
+                .filter(|(name, _)| region.contains(*name))
+                .map(|(_, current)| current)
+                .sum()
+        };
+
+        RegionCurrent {
+            head: region(JointRegion::Head),
+            left_arm: region(JointRegion::LeftArm),
+            right_arm: region(JointRegion::RightArm),
+            left_leg: region(JointRegion::LeftLeg),
+            right_leg: region(JointRegion::RightLeg),
+        }
+    }
+
+    /// Estimates the power drawn by all joints in watts, given the voltage of the motor bus.
+    ///
+    /// This ignores losses in the motor boards, so the actual power drawn from the battery is higher.
+    pub fn power_estimate_watts(&self, bus_voltage: f32) -> f32 {
+        self.total_current() * bus_voltage
+    }
+
+    /// Returns all joints that draw more than the provided current in amperes, together with their current.
+    pub fn joints_with_current_above(&self, amps: f32) -> Vec<(JointName, f32)> {
+        self.current
+            .iter_named()
+            .filter(|(_, &current)| current > amps)
+            .map(|(name, &current)| (name, current))
+            .collect()
+    }
+
+    /// Returns the highest temperature status of all joints.
+    pub fn worst_status(&self) -> TempStatus {
+        *self.status.max_by(Ord::cmp).1
+    }
+}
+
+impl From<&NaoState> for JointDiagnostics {
+    fn from(state: &NaoState) -> Self {
+        Self {
+            temperature: state.temperature.clone(),
+            status: state.status.clone().map(TempStatus::from_raw),
+            current: state.current.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FillExt;
+
+    fn state(temperature: JointArray<f32>, status: JointArray<i32>) -> NaoState {
+        NaoState {
+            temperature,
+            status,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode_status() {
+        let status = JointArray {
+            head_yaw: 1,
+            left_hip_pitch: 2,
+            right_ankle_roll: 3,
+            right_hand: 7,
+            left_hand: -1,
+            ..Default::default()
+        };
+        let diagnostics = JointDiagnostics::from(&state(JointArray::default(), status));
+
+        assert_eq!(diagnostics.status.head_yaw, TempStatus::Hot);
+        assert_eq!(diagnostics.status.head_pitch, TempStatus::Normal);
+        assert_eq!(diagnostics.status.left_hip_pitch, TempStatus::VeryHot);
+        assert_eq!(diagnostics.status.right_ankle_roll, TempStatus::Critical);
+        assert_eq!(diagnostics.status.right_hand, TempStatus::Critical);
+        assert_eq!(diagnostics.status.left_hand, TempStatus::Normal);
+        assert_eq!(diagnostics.worst_status(), TempStatus::Critical);
+        assert_eq!(
+            diagnostics.joints_with_status(TempStatus::VeryHot),
+            vec![
+                JointName::LeftHipPitch,
+                JointName::RightAnkleRoll,
+                JointName::RightHand
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hottest_joint() {
+        let temperature = JointArray {
+            right_knee_pitch: 61.0,
+            left_ankle_pitch: 58.0,
+            ..JointArray::fill(35.0)
+        };
+        let diagnostics = JointDiagnostics::from(&state(temperature, JointArray::default()));
+
+        assert_eq!(
+            diagnostics.hottest_joint(),
+            (JointName::RightKneePitch, 61.0)
+        );
+    }
+
+    #[test]
+    fn test_joints_above() {
+        let temperature = JointArray {
+            head_yaw: 50.0,
+            right_knee_pitch: 61.0,
+            left_ankle_pitch: 58.0,
+            ..JointArray::fill(35.0)
+        };
+        let diagnostics = JointDiagnostics::from(&state(temperature, JointArray::default()));
+
+        assert_eq!(
+            diagnostics.joints_above(55.0),
+            vec![JointName::LeftAnklePitch, JointName::RightKneePitch]
+        );
+        assert!(diagnostics.joints_above(70.0).is_empty());
+        assert_eq!(diagnostics.joints_above(0.0).len(), 25);
+    }
+
+    #[test]
+    fn test_current_aggregates() {
+        let state = NaoState {
+            current: JointArray {
+                head_yaw: 0.1,
+                right_shoulder_pitch: 0.4,
+                left_hip_yaw_pitch: 0.5,
+                right_knee_pitch: 1.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let diagnostics = JointDiagnostics::from(&state);
+
+        assert!((diagnostics.total_current() - 2.5).abs() < 1e-6);
+        assert!((diagnostics.power_estimate_watts(24.0) - 60.0).abs() < 1e-4);
+        assert_eq!(
+            diagnostics.current_by_region(),
+            RegionCurrent {
+                head: 0.1,
+                left_arm: 0.0,
+                right_arm: 0.4,
+                left_leg: 0.5,
+                right_leg: 1.5,
+            }
+        );
+        assert_eq!(
+            diagnostics.joints_with_current_above(0.45),
+            vec![
+                (JointName::LeftHipYawPitch, 0.5),
+                (JointName::RightKneePitch, 1.5)
+            ]
+        );
+    }
+}
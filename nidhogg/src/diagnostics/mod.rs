@@ -0,0 +1,10 @@
+//! Helpers for interpreting the diagnostic values reported by the NAO, such as joint temperatures and currents.
+//!
+
+mod current_monitor;
+mod joint_diagnostics;
+mod recorder;
+
+pub use current_monitor::{CurrentMonitor, CurrentMonitorConfig};
+pub use joint_diagnostics::{JointDiagnostics, RegionCurrent, TempStatus};
+pub use recorder::{DiagnosticsRecorder, DiagnosticsRecorderConfig, DiagnosticsSample};
@@ -0,0 +1,346 @@
+//! Implements the [`DiagnosticsRecorder`], which keeps a downsampled history of the diagnostic values.
+
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    types::{Battery, JointArray, JointName},
+    NaoState,
+};
+
+/// Configuration for the [`DiagnosticsRecorder`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiagnosticsRecorderConfig {
+    /// Maximum number of samples that are kept, older samples are overwritten.
+    pub capacity: usize,
+    /// Time between two samples.
+    pub interval: Duration,
+}
+
+impl Default for DiagnosticsRecorderConfig {
+    fn default() -> Self {
+        Self {
+            // one hour of history
+            capacity: 3600,
+            interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// The diagnostic values of a single [`NaoState`], as stored by the [`DiagnosticsRecorder`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiagnosticsSample {
+    /// The time at which the state was recorded.
+    pub time: Instant,
+    /// The temperature of each joint in degrees Celsius (°C).
+    pub temperature: JointArray<f32>,
+    /// The electric current of each joint in amperes (A).
+    pub current: JointArray<f32>,
+    /// The status of each joint, as reported by `LoLA`.
+    pub status: JointArray<i32>,
+    /// The state of the battery.
+    pub battery: Battery,
+}
+
+/// Keeps a downsampled history of the joint temperatures, currents and status, and of the battery.
+///
+/// The recorder is updated with every state, but only stores a sample every [`DiagnosticsRecorderConfig::interval`].
+/// Samples are stored in a ring buffer of [`DiagnosticsRecorderConfig::capacity`] samples, which is
+/// allocated when the recorder is created, so recording never allocates. This makes it cheap enough
+/// to always keep a recorder running, and export the history with [`DiagnosticsRecorder::export_csv`]
+/// when needed.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use nidhogg::{diagnostics::DiagnosticsRecorder, types::JointName, NaoState};
+///
+/// let mut recorder = DiagnosticsRecorder::default();
+/// let start = Instant::now();
+///
+/// let mut state = NaoState::default();
+/// for cycle in 0..250 {
+///     state.temperature.left_knee_pitch = 40.0 + (cycle / 50) as f32;
+///     recorder.update(&state, start + Duration::from_millis(12 * cycle));
+/// }
+///
+/// // one sample per second
+/// assert_eq!(recorder.len(), 3);
+///
+/// let (joint, temperature, _) = recorder.max_temperature_seen().unwrap();
+/// assert_eq!(joint, JointName::LeftKneePitch);
+/// assert_eq!(temperature, 44.0);
+///
+/// let mut csv = Vec::new();
+/// recorder.export_csv(&mut csv).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct DiagnosticsRecorder {
+    config: DiagnosticsRecorderConfig,
+    samples: Vec<DiagnosticsSample>,
+    /// Index in `samples` where the next sample is written.
+    next: usize,
+    next_sample_time: Option<Instant>,
+    max_temperature: Option<(JointName, f32, Instant)>,
+}
+
+impl Default for DiagnosticsRecorder {
+    fn default() -> Self {
+        Self::new(DiagnosticsRecorderConfig::default())
+    }
+}
+
+impl DiagnosticsRecorder {
+    /// Create a new [`DiagnosticsRecorder`] using the provided configuration.
+    pub fn new(config: DiagnosticsRecorderConfig) -> Self {
+        Self {
+            samples: Vec::with_capacity(config.capacity),
+            config,
+            next: 0,
+            next_sample_time: None,
+            max_temperature: None,
+        }
+    }
+
+    /// The configuration used by this recorder.
+    pub fn config(&self) -> &DiagnosticsRecorderConfig {
+        &self.config
+    }
+
+    /// Update the recorder with the state measured at `now`.
+    ///
+    /// A sample is stored if at least [`DiagnosticsRecorderConfig::interval`] passed since the previous one
+    /// was due. Samples that were missed because the recorder was not updated in time are skipped.
+    ///
+    /// Returns `true` if a sample was stored.
+    pub fn update(&mut self, state: &NaoState, now: Instant) -> bool {
+        self.update_max_temperature(state, now);
+
+        if self.next_sample_time.is_some_and(|time| now < time) {
+            return false;
+        }
+
+        let mut next_sample_time = self.next_sample_time.unwrap_or(now) + self.config.interval;
+        if next_sample_time <= now {
+            next_sample_time = now + self.config.interval;
+        }
+        self.next_sample_time = Some(next_sample_time);
+
+        self.store(state, now);
+        true
+    }
+
+    fn update_max_temperature(&mut self, state: &NaoState, now: Instant) {
+        for (joint, &temperature) in state.temperature.iter_named() {
+            if self
+                .max_temperature
+                .is_none_or(|(_, max_temperature, _)| temperature > max_temperature)
+            {
+                self.max_temperature = Some((joint, temperature, now));
+            }
+        }
+    }
+
+    fn store(&mut self, state: &NaoState, now: Instant) {
+        if self.config.capacity == 0 {
+            return;
+        }
+
+        if self.samples.len() < self.config.capacity {
+            self.samples.push(DiagnosticsSample {
+                time: now,
+                temperature: state.temperature.clone(),
+                current: state.current.clone(),
+                status: state.status.clone(),
+                battery: state.battery.clone(),
+            });
+        } else {
+            // overwrite the fields in place, so no new sample is allocated
+            let sample = &mut self.samples[self.next];
+            sample.time = now;
+            sample.temperature.clone_from(&state.temperature);
+            sample.current.clone_from(&state.current);
+            sample.status.clone_from(&state.status);
+            sample.battery.clone_from(&state.battery);
+        }
+
+        self.next = (self.next + 1) % self.config.capacity;
+    }
+
+    /// The stored samples, from oldest to newest.
+    pub fn samples(&self) -> impl Iterator<Item = &DiagnosticsSample> {
+        // until the buffer is full, `next` is the end of the samples
+        self.samples[self.next..]
+            .iter()
+            .chain(&self.samples[..self.next])
+    }
+
+    /// The number of stored samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns `true` if no samples are stored.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The highest temperature of any joint in degrees Celsius, together with the joint and time at which it was measured.
+    ///
+    /// This considers every state the recorder was updated with, not only the stored samples.
+    /// Returns [`None`] if the recorder was never updated.
+    pub fn max_temperature_seen(&self) -> Option<(JointName, f32, Instant)> {
+        self.max_temperature
+    }
+
+    /// Write the stored samples to `writer` as CSV, from oldest to newest.
+    ///
+    /// The first column contains the time of each sample in seconds since the oldest sample, followed
+    /// by the battery values, and the temperature, current and status of every joint.
+    pub fn export_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(
+            writer,
+            "time,battery_charge,battery_current,battery_status,battery_temperature"
+        )?;
+        for quantity in ["temperature", "current", "status"] {
+            for joint in JointName::ALL {
+                write!(writer, ",{joint}_{quantity}")?;
+            }
+        }
+        writeln!(writer)?;
+
+        let Some(start) = self.samples().next().map(|sample| sample.time) else {
+            return Ok(());
+        };
+
+        for sample in self.samples() {
+            let battery = &sample.battery;
+            write!(
+                writer,
+                "{:.3},{},{},{},{}",
+                sample.time.duration_since(start).as_secs_f32(),
+                battery.charge,
+                battery.current,
+                battery.status,
+                battery.temperature
+            )?;
+            for value in [&sample.temperature, &sample.current].into_iter().flatten() {
+                write!(writer, ",{value}")?;
+            }
+            for value in &sample.status {
+                write!(writer, ",{value}")?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FillExt;
+
+    fn recorder(capacity: usize, interval_ms: u64) -> DiagnosticsRecorder {
+        DiagnosticsRecorder::new(DiagnosticsRecorderConfig {
+            capacity,
+            interval: Duration::from_millis(interval_ms),
+        })
+    }
+
+    fn state(temperature: f32) -> NaoState {
+        NaoState {
+            temperature: JointArray::fill(temperature),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ring_wraps() {
+        let mut recorder = recorder(3, 10);
+        let start = Instant::now();
+
+        for i in 0..5 {
+            recorder.update(&state(i as f32), start + Duration::from_millis(10 * i));
+        }
+
+        assert_eq!(recorder.len(), 3);
+        assert_eq!(recorder.samples.capacity(), 3);
+        let temperatures: Vec<_> = recorder
+            .samples()
+            .map(|sample| sample.temperature.head_yaw)
+            .collect();
+        assert_eq!(temperatures, [2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_sampling_respects_interval() {
+        let mut recorder = recorder(10, 100);
+        let start = Instant::now();
+
+        let sampled: Vec<u64> = [0, 30, 95, 101, 150, 199, 230, 320, 500, 560, 610]
+            .into_iter()
+            .filter(|&ms| recorder.update(&state(30.0), start + Duration::from_millis(ms)))
+            .collect();
+
+        // late samples do not delay the following ones, but missed samples are skipped
+        assert_eq!(sampled, [0, 101, 230, 320, 500, 610]);
+        assert_eq!(recorder.len(), 6);
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let mut recorder = recorder(2, 10);
+        let start = Instant::now();
+
+        let mut csv = Vec::new();
+        recorder.export_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 1);
+
+        for i in 0..3 {
+            recorder.update(&state(40.0), start + Duration::from_millis(10 * i));
+        }
+
+        let mut csv = Vec::new();
+        recorder.export_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("time,battery_charge,"));
+        assert!(lines[0].contains(",head_yaw_temperature,"));
+        assert!(lines[0].ends_with(",right_hand_status"));
+        assert!(lines[1].starts_with("0.000,0,"));
+        assert!(lines[2].starts_with("0.010,0,"));
+
+        let columns = lines[0].split(',').count();
+        assert_eq!(columns, 5 + 3 * 25);
+        assert!(lines.iter().all(|line| line.split(',').count() == columns));
+    }
+
+    #[test]
+    fn test_max_temperature_seen() {
+        let mut recorder = recorder(1, 1000);
+        let start = Instant::now();
+        assert_eq!(recorder.max_temperature_seen(), None);
+
+        let mut hot = state(40.0);
+        hot.temperature.right_ankle_pitch = 70.0;
+        let hot_time = start + Duration::from_millis(10);
+
+        recorder.update(&state(40.0), start);
+        recorder.update(&hot, hot_time);
+        recorder.update(&state(50.0), start + Duration::from_millis(20));
+
+        // the hot state was not sampled, but is still seen
+        assert_eq!(recorder.len(), 1);
+        assert_eq!(
+            recorder.max_temperature_seen(),
+            Some((JointName::RightAnklePitch, 70.0, hot_time))
+        );
+    }
+}
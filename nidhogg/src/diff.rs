@@ -0,0 +1,441 @@
+//! Differences between consecutive state and control messages, to log only what changed.
+
+use std::fmt;
+
+use crate::{
+    types::{Battery, JointArray, JointName, SonarMode, Touch},
+    NaoControlMessage, NaoState,
+};
+
+/// Change of the value of a single joint.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JointChange<T = f32> {
+    pub joint: JointName,
+    pub old: T,
+    pub new: T,
+}
+
+/// A touch sensor that was pressed or released.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TouchEdge {
+    /// Name of the touch sensor, as in the fields of [`Touch`].
+    pub sensor: &'static str,
+    /// `true` if the sensor was pressed, `false` if it was released.
+    pub pressed: bool,
+}
+
+/// Changes between two [`NaoControlMessage`]s, as returned by [`NaoControlMessage::diff`].
+///
+/// The [`Display`](fmt::Display) implementation produces a compact one line summary, meant for logging.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{types::color, NaoControlMessage};
+///
+/// let previous = NaoControlMessage::unstiff();
+/// let mut current = previous.clone();
+/// current.position.head_yaw = 0.5;
+/// current.chest = color::f32::RED;
+///
+/// let diff = previous.diff(&current);
+/// assert_eq!(diff.position.len(), 1);
+/// assert_eq!(diff.to_string(), "position {head_yaw: 0.000 -> 0.500}, leds [chest]");
+/// assert!(current.diff(&current).is_empty());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ControlDiff {
+    /// Joints whose position changed by more than the epsilon.
+    pub position: Vec<JointChange>,
+    /// Joints whose stiffness changed by more than the epsilon.
+    pub stiffness: Vec<JointChange>,
+    /// The old and new sonar mode, if it changed.
+    pub sonar: Option<(SonarMode, SonarMode)>,
+    /// Names of the LED groups that changed, as in the fields of [`LedState`](crate::leds::LedState).
+    pub leds: Vec<&'static str>,
+}
+
+impl ControlDiff {
+    /// The epsilon used by [`NaoControlMessage::diff`], below which joint values are considered unchanged.
+    pub const DEFAULT_EPSILON: f32 = 1e-4;
+
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.position.is_empty()
+            && self.stiffness.is_empty()
+            && self.sonar.is_none()
+            && self.leds.is_empty()
+    }
+}
+
+impl NaoControlMessage {
+    /// Returns what changed from this message to `other`, ignoring joint changes up to [`ControlDiff::DEFAULT_EPSILON`].
+    pub fn diff(&self, other: &Self) -> ControlDiff {
+        self.diff_with_epsilon(other, ControlDiff::DEFAULT_EPSILON)
+    }
+
+    /// Returns what changed from this message to `other`, ignoring joint changes up to `epsilon`.
+    ///
+    /// LED groups are compared exactly.
+    pub fn diff_with_epsilon(&self, other: &Self, epsilon: f32) -> ControlDiff {
+        ControlDiff {
+            position: changed_joints(&self.position, &other.position, |old, new| {
+                (old - new).abs() > epsilon
+            }),
+            stiffness: changed_joints(&self.stiffness, &other.stiffness, |old, new| {
+                (old - new).abs() > epsilon
+            }),
+            sonar: (self.sonar != other.sonar).then_some((self.sonar, other.sonar)),
+            leds: changed_led_groups(self, other),
+        }
+    }
+}
+
+impl fmt::Display for ControlDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+
+        if !self.position.is_empty() {
+            parts.push(format!("position {}", format_changes(&self.position)));
+        }
+        if !self.stiffness.is_empty() {
+            parts.push(format!("stiffness {}", format_changes(&self.stiffness)));
+        }
+        if let Some((old, new)) = self.sonar {
+            parts.push(format!("sonar {old:?} -> {new:?}"));
+        }
+        if !self.leds.is_empty() {
+            parts.push(format!("leds [{}]", self.leds.join(", ")));
+        }
+
+        write_parts(f, &parts)
+    }
+}
+
+/// Changes between two [`NaoState`]s, as returned by [`NaoState::diff`].
+///
+/// Only the values that change rarely and are relevant for debugging are compared: the touch
+/// sensors that were pressed or released, the battery and the joint status.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::NaoState;
+///
+/// let previous = NaoState::default();
+/// let mut current = previous.clone();
+/// current.touch.chest_board = 1.0;
+/// current.status.left_knee_pitch = 1;
+///
+/// let diff = previous.diff(&current);
+/// assert_eq!(diff.to_string(), "touch [chest_board pressed], status {left_knee_pitch: 0 -> 1}");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateDiff {
+    /// Touch sensors that crossed [`StateDiff::TOUCH_THRESHOLD`].
+    pub touch: Vec<TouchEdge>,
+    /// The old and new battery, if its charge changed by more than [`ControlDiff::DEFAULT_EPSILON`] or its status changed.
+    pub battery: Option<(Battery, Battery)>,
+    /// Joints whose status changed.
+    pub status: Vec<JointChange<i32>>,
+}
+
+impl StateDiff {
+    /// Touch value above which a touch sensor is considered pressed.
+    pub const TOUCH_THRESHOLD: f32 = 0.5;
+
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.touch.is_empty() && self.battery.is_none() && self.status.is_empty()
+    }
+}
+
+impl NaoState {
+    /// Returns what changed from this state to `other`, see [`StateDiff`].
+    pub fn diff(&self, other: &Self) -> StateDiff {
+        let touch = named_touch(&self.touch)
+            .into_iter()
+            .zip(named_touch(&other.touch))
+            .filter_map(|((sensor, old), (_, new))| {
+                let was_pressed = old > StateDiff::TOUCH_THRESHOLD;
+                let pressed = new > StateDiff::TOUCH_THRESHOLD;
+                (was_pressed != pressed).then_some(TouchEdge { sensor, pressed })
+            })
+            .collect();
+
+        let battery_changed = (self.battery.charge - other.battery.charge).abs()
+            > ControlDiff::DEFAULT_EPSILON
+            || self.battery.status != other.battery.status;
+
+        StateDiff {
+            touch,
+            battery: battery_changed.then(|| (self.battery.clone(), other.battery.clone())),
+            status: changed_joints(&self.status, &other.status, |old, new| old != new),
+        }
+    }
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+
+        if !self.touch.is_empty() {
+            let edges = self
+                .touch
+                .iter()
+                .map(|edge| {
+                    let action = if edge.pressed { "pressed" } else { "released" };
+                    format!("{} {action}", edge.sensor)
+                })
+                .collect::<Vec<_>>();
+            parts.push(format!("touch [{}]", edges.join(", ")));
+        }
+        if let Some((old, new)) = &self.battery {
+            let mut battery = format!(
+                "battery {:.1}% -> {:.1}%",
+                old.charge * 100.0,
+                new.charge * 100.0
+            );
+            if old.status != new.status {
+                battery.push_str(&format!(" (status {} -> {})", old.status, new.status));
+            }
+            parts.push(battery);
+        }
+        if !self.status.is_empty() {
+            parts.push(format!("status {}", format_changes(&self.status)));
+        }
+
+        write_parts(f, &parts)
+    }
+}
+
+/// Names of the LED groups that differ between `old` and `new`.
+pub(crate) fn changed_led_groups(
+    old: &NaoControlMessage,
+    new: &NaoControlMessage,
+) -> Vec<&'static str> {
+    [
+        ("left_ear", old.left_ear != new.left_ear),
+        ("right_ear", old.right_ear != new.right_ear),
+        ("chest", old.chest != new.chest),
+        ("left_eye", old.left_eye != new.left_eye),
+        ("right_eye", old.right_eye != new.right_eye),
+        ("left_foot", old.left_foot != new.left_foot),
+        ("right_foot", old.right_foot != new.right_foot),
+        ("skull", old.skull != new.skull),
+    ]
+    .into_iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(name, _)| name)
+    .collect()
+}
+
+/// The joints for which `changed` returns `true`.
+fn changed_joints<T: Copy>(
+    old: &JointArray<T>,
+    new: &JointArray<T>,
+    changed: impl Fn(T, T) -> bool,
+) -> Vec<JointChange<T>> {
+    old.iter_named()
+        .zip(new)
+        .filter(|((_, old), new)| changed(**old, **new))
+        .map(|((joint, old), new)| JointChange {
+            joint,
+            old: *old,
+            new: *new,
+        })
+        .collect()
+}
+
+/// The touch sensors of `touch`, together with their names.
+fn named_touch(touch: &Touch) -> [(&'static str, f32); 14] {
+    [
+        ("chest_board", touch.chest_board),
+        ("head_front", touch.head_front),
+        ("head_middle", touch.head_middle),
+        ("head_rear", touch.head_rear),
+        ("left_foot_left", touch.left_foot_left),
+        ("left_foot_right", touch.left_foot_right),
+        ("left_hand_back", touch.left_hand_back),
+        ("left_hand_left", touch.left_hand_left),
+        ("left_hand_right", touch.left_hand_right),
+        ("right_foot_left", touch.right_foot_left),
+        ("right_foot_right", touch.right_foot_right),
+        ("right_hand_back", touch.right_hand_back),
+        ("right_hand_left", touch.right_hand_left),
+        ("right_hand_right", touch.right_hand_right),
+    ]
+}
+
+/// Formats the joint changes as `{joint: old -> new, ...}`.
+fn format_changes<T: DisplayValue>(changes: &[JointChange<T>]) -> String {
+    let changes = changes
+        .iter()
+        .map(|change| {
+            format!(
+                "{}: {} -> {}",
+                change.joint,
+                change.old.display_value(),
+                change.new.display_value()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!("{{{}}}", changes.join(", "))
+}
+
+fn write_parts(f: &mut fmt::Formatter<'_>, parts: &[String]) -> fmt::Result {
+    if parts.is_empty() {
+        f.write_str("no changes")
+    } else {
+        f.write_str(&parts.join(", "))
+    }
+}
+
+/// Formats joint values with the precision used in the summaries.
+trait DisplayValue: Copy {
+    fn display_value(self) -> String;
+}
+
+impl DisplayValue for f32 {
+    fn display_value(self) -> String {
+        format!("{self:.3}")
+    }
+}
+
+impl DisplayValue for i32 {
+    fn display_value(self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{color, FillExt, LeftEye};
+
+    #[test]
+    fn test_control_diff() {
+        let old = NaoControlMessage::builder()
+            .position(JointArray::fill(0.0))
+            .stiffness(JointArray::fill(0.8))
+            .build();
+        let new = NaoControlMessage {
+            position: JointArray {
+                head_yaw: 0.5,
+                left_knee_pitch: -0.25,
+                ..JointArray::fill(0.0)
+            },
+            sonar: SonarMode::Off,
+            left_eye: LeftEye::fill(color::f32::CYAN),
+            chest: color::f32::RED,
+            ..old.clone()
+        };
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff,
+            ControlDiff {
+                position: vec![
+                    JointChange {
+                        joint: JointName::HeadYaw,
+                        old: 0.0,
+                        new: 0.5
+                    },
+                    JointChange {
+                        joint: JointName::LeftKneePitch,
+                        old: 0.0,
+                        new: -0.25
+                    },
+                ],
+                stiffness: vec![],
+                sonar: Some((SonarMode::Both, SonarMode::Off)),
+                leds: vec!["chest", "left_eye"],
+            }
+        );
+        assert_eq!(
+            diff.to_string(),
+            "position {head_yaw: 0.000 -> 0.500, left_knee_pitch: 0.000 -> -0.250}, sonar Both -> Off, leds [chest, left_eye]"
+        );
+
+        assert!(old.diff(&old).is_empty());
+        assert_eq!(old.diff(&old).to_string(), "no changes");
+    }
+
+    #[test]
+    fn test_control_diff_epsilon() {
+        let old = NaoControlMessage::unstiff();
+        let mut new = old.clone();
+        new.position.head_yaw = 0.00005;
+        new.stiffness.head_pitch = 0.0002;
+
+        let diff = old.diff(&new);
+        assert!(diff.position.is_empty());
+        assert_eq!(diff.stiffness.len(), 1);
+        assert_eq!(diff.stiffness[0].joint, JointName::HeadPitch);
+
+        assert!(old.diff_with_epsilon(&new, 0.001).is_empty());
+        assert_eq!(old.diff_with_epsilon(&new, 0.0).position.len(), 1);
+    }
+
+    #[test]
+    fn test_state_diff() {
+        let old = NaoState {
+            touch: Touch {
+                head_front: 1.0,
+                left_foot_left: 0.3,
+                ..Default::default()
+            },
+            battery: Battery {
+                charge: 0.8,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut new = old.clone();
+        new.touch.head_front = 0.0;
+        new.touch.left_foot_left = 0.6;
+        new.touch.chest_board = 0.4;
+        new.battery.charge = 0.79;
+        new.battery.current = -1.5;
+        new.status.right_ankle_roll = 2;
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.touch,
+            vec![
+                TouchEdge {
+                    sensor: "head_front",
+                    pressed: false
+                },
+                TouchEdge {
+                    sensor: "left_foot_left",
+                    pressed: true
+                },
+            ]
+        );
+        assert_eq!(
+            diff.battery,
+            Some((old.battery.clone(), new.battery.clone()))
+        );
+        assert_eq!(
+            diff.status,
+            vec![JointChange {
+                joint: JointName::RightAnkleRoll,
+                old: 0,
+                new: 2
+            }]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "touch [head_front released, left_foot_left pressed], battery 80.0% -> 79.0%, status {right_ankle_roll: 0 -> 2}"
+        );
+
+        // noise in values that are not compared does not show up in the diff
+        let mut noisy = old.clone();
+        noisy.battery.current = 0.3;
+        noisy.position.head_yaw = 0.1;
+        noisy.touch.head_front = 0.9;
+        assert!(old.diff(&noisy).is_empty());
+    }
+}
@@ -1,23 +1,283 @@
+#[cfg(feature = "miette")]
 use miette::Diagnostic;
 use thiserror::Error;
 
+use crate::types::ParseJointNameError;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Error, Diagnostic, Debug)]
+/// Errors returned by nidhogg.
+///
+/// With the `miette` feature, this implements [`miette::Diagnostic`], with an error code and help text for each variant.
+#[derive(Error, Debug)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
 #[non_exhaustive]
 pub enum Error {
     #[cfg(feature = "lola")]
     #[error("Could not connect to LoLA socket")]
-    #[diagnostic(help("- Are you trying to connect to the simulation? This backend only supports real NAOs!
-- Are you running the code locally? Connecting with LoLA only works when ran on a NAO!
-- Are you using `LoLABackend::connect_with_retry` instead of `LoLABackend::connect`? You might not always get a connection the first time!"))]
-    NoLoLAConnection(#[from] std::io::Error),
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nidhogg::lola::connect),
+            help("Is this running on the robot? Check that /tmp/robocup exists and LoLA is active.
+- Are you trying to connect to the simulation? Use `LolaBackend::connect_tcp` for simulators that expose LoLA over TCP!
+- Are you using `LolaBackend::connect_with_retry` instead of `LolaBackend::connect`? You might not always get a connection the first time!")
+        )
+    )]
+    NoLoLAConnection(#[source] std::io::Error),
+
+    #[error("The connection to the backend was closed")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nidhogg::connection_closed),
+            help("The backend stopped or restarted, use `LolaBackend::with_reconnect` to reconnect automatically")
+        )
+    )]
+    ConnectionClosed,
+
+    #[error("Timed out while reading from the backend")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nidhogg::read_timeout),
+            help("The backend did not send a state within the read timeout, check that it is still running")
+        )
+    )]
+    ReadTimeout,
+
+    #[error("IO error while communicating with the backend")]
+    #[cfg_attr(feature = "miette", diagnostic(code(nidhogg::io)))]
+    Io(#[from] std::io::Error),
 
     #[cfg(feature = "lola")]
     #[error("Failed to decode MessagePack message")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nidhogg::lola::decode),
+            help("The message does not match the LoLA message format, is the other side a LoLA compatible server?")
+        )
+    )]
     MsgPackDecodeError(#[from] rmp_serde::decode::Error),
 
+    #[cfg(feature = "lola")]
+    #[error("Failed to decode LoLA frame at byte {offset}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nidhogg::lola::decode_frame),
+            help("The frame does not match the LoLA state format, is the other side a LoLA compatible server with a supported firmware version?")
+        )
+    )]
+    FrameDecodeError {
+        #[source]
+        source: rmp_serde::decode::Error,
+        /// Offset of the byte where decoding failed.
+        offset: usize,
+        /// Hexadecimal dump of the frame up to the byte where decoding failed.
+        #[cfg_attr(feature = "miette", source_code)]
+        dump: String,
+        /// Span of the byte where decoding failed in `dump`.
+        #[cfg_attr(feature = "miette", label("decoding failed here"))]
+        span: (usize, usize),
+    },
+
     #[cfg(feature = "lola")]
     #[error("Failed to encode MessagePack message")]
+    #[cfg_attr(feature = "miette", diagnostic(code(nidhogg::lola::encode)))]
     MsgPackEncodeError(#[from] rmp_serde::encode::Error),
+
+    #[cfg(feature = "lola")]
+    #[error("Expected a LoLA frame of {expected} bytes, but received a frame of {got} bytes")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nidhogg::lola::frame_size),
+            help("The size of LoLA frames can change with firmware updates, create the `LolaBackend` without `with_strict_frame_size` to accept frames of any size")
+        )
+    )]
+    UnexpectedFrameSize { expected: usize, got: usize },
+
+    #[cfg(feature = "serde")]
+    #[error("Failed to parse motion file")]
+    #[cfg_attr(feature = "miette", diagnostic(code(nidhogg::motion::parse)))]
+    MotionParseError(#[from] serde_json::Error),
+
+    #[cfg(feature = "serde")]
+    #[error("Failed to load robot configuration `{}`: {reason}", path.display())]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nidhogg::config::load),
+            help("Robot configurations are `.json` files, or `.toml` files when the `toml` feature is enabled, and each robot may only be configured once")
+        )
+    )]
+    RobotConfigError {
+        path: std::path::PathBuf,
+        reason: String,
+    },
+
+    #[error("Control message contains invalid value {value} for `{field}`")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nidhogg::control::invalid_value),
+            help("Position, stiffness and LED values must be finite numbers")
+        )
+    )]
+    InvalidControlValue { field: String, value: f32 },
+
+    #[error("Keyframe {keyframe} of the motion file contains an invalid joint")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nidhogg::motion::invalid_joint),
+            help("Joints are named by their LoLA name such as `HeadYaw`, or their field name such as `head_yaw`")
+        )
+    )]
+    InvalidMotionJoint {
+        keyframe: usize,
+        #[source]
+        source: ParseJointNameError,
+    },
+}
+
+impl Error {
+    /// Convert an error that occurred while decoding `frame`, pointing at the byte where decoding failed.
+    ///
+    /// `position` is the number of bytes that were read by the decoder when it failed.
+    #[cfg(feature = "lola")]
+    pub(crate) fn from_frame_decode(
+        source: rmp_serde::decode::Error,
+        frame: &[u8],
+        position: usize,
+    ) -> Self {
+        /// Number of bytes on each line of the dump.
+        const LINE_BYTES: usize = 16;
+
+        let offset = position
+            .saturating_sub(1)
+            .min(frame.len().saturating_sub(1));
+        let end = frame.len().min((offset / LINE_BYTES + 1) * LINE_BYTES);
+
+        let mut dump = String::with_capacity(end * 3);
+        for (i, byte) in frame[..end].iter().enumerate() {
+            let separator = if (i + 1) % LINE_BYTES == 0 { '\n' } else { ' ' };
+            dump.push_str(&format!("{byte:02x}{separator}"));
+        }
+
+        Error::FrameDecodeError {
+            source,
+            offset,
+            dump,
+            // every byte takes two digits and a separator
+            span: (offset * 3, 2),
+        }
+    }
+
+    /// Convert an IO error that occurred while reading or writing a backend connection.
+    ///
+    /// An unexpected EOF or a broken pipe results in [`Error::ConnectionClosed`], and timeouts
+    /// in [`Error::ReadTimeout`].
+    #[cfg(feature = "lola")]
+    pub(crate) fn from_transfer(error: std::io::Error) -> Self {
+        use std::io::ErrorKind;
+
+        match error.kind() {
+            ErrorKind::UnexpectedEof | ErrorKind::BrokenPipe | ErrorKind::ConnectionReset => {
+                Error::ConnectionClosed
+            }
+            ErrorKind::WouldBlock | ErrorKind::TimedOut => Error::ReadTimeout,
+            _ => Error::Io(error),
+        }
+    }
+
+    /// Returns `true` if the error was caused by failing to connect to, or losing the connection with, the backend.
+    pub fn is_connection_error(&self) -> bool {
+        match self {
+            #[cfg(feature = "lola")]
+            Error::NoLoLAConnection(_) => true,
+            Error::ConnectionClosed => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the error was caused by a message that could not be decoded.
+    pub fn is_decode_error(&self) -> bool {
+        match self {
+            #[cfg(feature = "lola")]
+            Error::MsgPackDecodeError(_) | Error::FrameDecodeError { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "lola"))]
+mod tests {
+    use std::io::{self, ErrorKind};
+
+    use super::*;
+
+    #[test]
+    fn test_from_transfer() {
+        let eof = Error::from_transfer(io::Error::from(ErrorKind::UnexpectedEof));
+        assert!(matches!(eof, Error::ConnectionClosed));
+        assert!(eof.is_connection_error());
+
+        let timeout = Error::from_transfer(io::Error::from(ErrorKind::WouldBlock));
+        assert!(matches!(timeout, Error::ReadTimeout));
+        assert!(!timeout.is_connection_error());
+
+        let other = Error::from_transfer(io::Error::from(ErrorKind::PermissionDenied));
+        assert!(matches!(other, Error::Io(_)));
+    }
+
+    #[test]
+    fn test_source_chain() {
+        use std::error::Error as _;
+
+        let error = Error::from(io::Error::other("oops"));
+        assert_eq!(error.source().unwrap().to_string(), "oops");
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_connection_diagnostic() {
+        use miette::Diagnostic;
+
+        let error = Error::NoLoLAConnection(io::Error::from(ErrorKind::NotFound));
+        assert_eq!(error.code().unwrap().to_string(), "nidhogg::lola::connect");
+        assert!(error.help().unwrap().to_string().contains("/tmp/robocup"));
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_decode_diagnostic() {
+        use miette::Diagnostic;
+
+        let mut frame = [0x90; 40];
+        frame[20] = 0xc1;
+        let source = rmp_serde::from_slice::<u8>(&[0xc1]).unwrap_err();
+        let error = Error::from_frame_decode(source, &frame, 21);
+
+        assert!(error.is_decode_error());
+        assert_eq!(error.to_string(), "Failed to decode LoLA frame at byte 20");
+        assert_eq!(
+            error.code().unwrap().to_string(),
+            "nidhogg::lola::decode_frame"
+        );
+        assert!(error.help().is_some());
+
+        // the dump ends with the line of the failing byte
+        let Error::FrameDecodeError { ref dump, .. } = error else {
+            panic!("expected a frame decode error, got {error:?}");
+        };
+        assert_eq!(dump.len(), 32 * 3);
+
+        let label = error.labels().unwrap().next().unwrap();
+        assert_eq!(label.offset(), 60);
+        assert_eq!(&dump[label.offset()..][..label.len()], "c1");
+        assert!(error.source_code().is_some());
+    }
 }
@@ -0,0 +1,332 @@
+//! A batteries-included [`Nao`] type, for programs that just want to move the robot.
+//!
+//! [`Nao`] combines a backend with a [`SafeBackend`], cycle time statistics and [`JointDiagnostics`],
+//! and runs one read/write cycle per [`Nao::tick`].
+//! It is optional sugar over the [`NaoBackend`] trait, everything it does can also be done
+//! by combining the underlying types directly.
+//!
+//! # Example
+//!
+#![cfg_attr(feature = "lola", doc = "```no_run")]
+#![cfg_attr(not(feature = "lola"), doc = "```ignore")]
+//! use nidhogg::{facade::Nao, types::{color, FillExt, JointArray}};
+//!
+//! let mut nao = Nao::connect_lola().unwrap();
+//!
+//! nao.set_joints(JointArray::fill(0.0), JointArray::fill(0.2));
+//! nao.set_led(nidhogg::leds::LedStateDelta {
+//!     chest: Some(color::f32::GREEN),
+//!     ..Default::default()
+//! });
+//!
+//! loop {
+//!     let state = nao.tick().unwrap();
+//!     println!("battery: {:.0}%", state.battery.charge * 100.0);
+//! }
+//! ```
+
+use std::time::Instant;
+
+use crate::{
+    backend::{FrameStats, FrameStatsCollector},
+    diagnostics::JointDiagnostics,
+    leds::LedStateDelta,
+    safety::{SafeBackend, SafetyPolicy, ViolationCounters},
+    types::JointArray,
+    NaoBackend, NaoControlMessage, NaoState, Result,
+};
+
+#[cfg(feature = "lola")]
+use crate::backend::LolaBackend;
+#[cfg(feature = "serde")]
+use crate::config::RobotConfig;
+
+/// High level interface to the robot, combining a backend with safety limits, statistics and diagnostics.
+///
+/// Commands set with [`Nao::set_joints`] and [`Nao::set_led`] are stored, and sent on every
+/// [`Nao::tick`] until they are replaced. Until [`Nao::set_joints`] is called, the robot holds
+/// its measured position and stiffness.
+///
+/// # Ordering
+///
+/// Each [`Nao::tick`] performs the following steps, in order:
+/// 1. read a new [`NaoState`] from the backend, which replaces [`Nao::state`] and [`Nao::diagnostics`],
+/// 2. merge the stored joint and LED commands into a single [`NaoControlMessage`], and add the
+///    [joint offsets](Nao::with_joint_offsets),
+/// 3. apply the [`SafetyPolicy`], using the temperatures from the state read in step 1,
+/// 4. send the resulting message to the backend.
+///
+/// If reading the state fails, no message is sent in that tick.
+///
+/// Wrap the backend in a [`SafetyWatchdog`](crate::safety::SafetyWatchdog) to remove the stiffness
+/// when the ticks stop, or when the program panics.
+#[derive(Debug)]
+pub struct Nao<B: NaoBackend> {
+    backend: SafeBackend<B>,
+    state: NaoState,
+    diagnostics: JointDiagnostics,
+    control: NaoControlMessage,
+    joints_set: bool,
+    joint_offsets: JointArray<f32>,
+    stats: FrameStatsCollector,
+}
+
+#[cfg(feature = "lola")]
+impl Nao<LolaBackend> {
+    /// Connect to `LoLA`, using the default [`SafetyPolicy`].
+    pub fn connect_lola() -> Result<Self> {
+        LolaBackend::connect().map(Self::with_backend)
+    }
+}
+
+impl<B: NaoBackend> Nao<B> {
+    /// Create a [`Nao`] using an already connected backend and the default [`SafetyPolicy`].
+    pub fn with_backend(backend: B) -> Self {
+        Self::with_policy(backend, SafetyPolicy::default())
+    }
+
+    /// Create a [`Nao`] using an already connected backend and the provided [`SafetyPolicy`].
+    pub fn with_policy(backend: B, policy: SafetyPolicy) -> Self {
+        Self {
+            backend: SafeBackend::with_policy(backend, policy),
+            state: NaoState::default(),
+            diagnostics: JointDiagnostics::default(),
+            control: NaoControlMessage::default(),
+            joints_set: false,
+            joint_offsets: JointArray::default(),
+            stats: FrameStatsCollector::default(),
+        }
+    }
+
+    /// Apply joint offsets, which are subtracted from the measured positions and added to the commanded positions.
+    ///
+    /// With offsets, [`Nao::state`] and [`Nao::set_joints`] use the corrected positions, while the
+    /// backend and the [`SafetyPolicy`] see the raw positions of the robot.
+    #[must_use]
+    pub fn with_joint_offsets(mut self, offsets: JointArray<f32>) -> Self {
+        self.joint_offsets = offsets;
+        self
+    }
+
+    /// Apply the joint offsets of a [`RobotConfig`], see [`Nao::with_joint_offsets`].
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn with_robot_config(self, config: &RobotConfig) -> Self {
+        self.with_joint_offsets(config.joint_offsets())
+    }
+
+    /// The state read in the most recent [`Nao::tick`].
+    ///
+    /// Before the first tick, this is [`NaoState::default`].
+    pub fn state(&self) -> &NaoState {
+        &self.state
+    }
+
+    /// Request joint positions and stiffness, to be sent from the next [`Nao::tick`] onwards.
+    ///
+    /// The positions are subject to the [`SafetyPolicy`], so large changes are spread over multiple ticks.
+    pub fn set_joints(&mut self, position: JointArray<f32>, stiffness: JointArray<f32>) {
+        self.control.position = position;
+        self.control.stiffness = stiffness;
+        self.joints_set = true;
+    }
+
+    /// Set (a subset of) the LEDs, to be sent from the next [`Nao::tick`] onwards.
+    ///
+    /// LED groups that are not contained in `leds` keep their previous value.
+    pub fn set_led(&mut self, leds: impl Into<LedStateDelta>) {
+        leds.into().apply(&mut self.control);
+    }
+
+    /// Run a single cycle: read the state, then send the stored commands with the safety limits applied.
+    ///
+    /// See the [ordering](Nao#ordering) guarantees of [`Nao`].
+    pub fn tick(&mut self) -> Result<&NaoState> {
+        self.state = self.backend.read_nao_state()?;
+        self.stats.record_read(Instant::now());
+        self.state.position = offset(&self.state.position, &self.joint_offsets, |p, o| p - o);
+        self.diagnostics = JointDiagnostics::from(&self.state);
+
+        let mut msg = self.control.clone();
+        if !self.joints_set {
+            msg.position = self.state.position.clone();
+            msg.stiffness = self.state.stiffness.clone();
+        }
+        msg.position = offset(&msg.position, &self.joint_offsets, |p, o| p + o);
+
+        self.backend.send_control_msg(msg)?;
+        self.stats.record_send(Instant::now());
+
+        Ok(&self.state)
+    }
+
+    /// Returns a snapshot of the cycle time statistics of the ticks so far.
+    pub fn frame_stats(&self) -> FrameStats {
+        self.stats.snapshot()
+    }
+
+    /// Reset the collected cycle time statistics.
+    pub fn reset_frame_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    /// Diagnostics of the joints, computed from the state read in the most recent [`Nao::tick`].
+    pub fn diagnostics(&self) -> &JointDiagnostics {
+        &self.diagnostics
+    }
+
+    /// The number of joints affected by each kind of safety violation so far.
+    pub fn violations(&self) -> &ViolationCounters {
+        self.backend.counters()
+    }
+
+    /// Reference to the wrapped backend.
+    pub fn backend(&self) -> &B {
+        self.backend.inner()
+    }
+
+    /// Unwrap this [`Nao`], returning the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.backend.into_inner()
+    }
+}
+
+fn offset(
+    positions: &JointArray<f32>,
+    offsets: &JointArray<f32>,
+    f: impl Fn(f32, f32) -> f32,
+) -> JointArray<f32> {
+    positions
+        .clone()
+        .zip(offsets.clone())
+        .map(|(position, offset)| f(position, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::mock::MockBackend,
+        leds::LedState,
+        types::{color, FillExt},
+    };
+
+    fn state(position: f32) -> NaoState {
+        NaoState {
+            position: JointArray::fill(position),
+            stiffness: JointArray::fill(0.5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_holds_measured_position_until_joints_are_set() {
+        let mut nao = Nao::with_backend(MockBackend::with_states([state(0.2), state(0.3)]));
+
+        nao.tick().unwrap();
+        let sent = &nao.backend().sent[0];
+        assert_eq!(sent.position.head_yaw, 0.2);
+        assert_eq!(sent.stiffness, JointArray::fill(0.5));
+
+        nao.set_joints(JointArray::fill(0.25), JointArray::fill(1.0));
+        nao.tick().unwrap();
+        nao.tick().unwrap();
+
+        // the requested joints are repeated every tick
+        let sent = &nao.backend().sent;
+        assert_eq!(sent.len(), 3);
+        assert!(sent[1..].iter().all(|msg| msg.position.head_yaw == 0.25));
+    }
+
+    #[test]
+    fn test_tick_reads_before_writing() {
+        let mut hot = state(0.0);
+        hot.temperature.left_knee_pitch = 80.0;
+        let mut nao = Nao::with_backend(MockBackend::with_states([state(0.0), hot]));
+
+        nao.set_joints(JointArray::fill(0.0), JointArray::fill(1.0));
+        assert_eq!(nao.tick().unwrap().temperature.left_knee_pitch, 0.0);
+        assert_eq!(nao.backend().sent[0].stiffness.left_knee_pitch, 1.0);
+
+        // the temperature read in this tick already limits the message sent in this tick
+        nao.tick().unwrap();
+        assert_eq!(nao.state().temperature.left_knee_pitch, 80.0);
+        assert_eq!(nao.diagnostics().hottest_joint().1, 80.0);
+        assert_eq!(nao.backend().sent[1].stiffness.left_knee_pitch, 0.0);
+        assert_eq!(nao.violations().over_temperature, 1);
+    }
+
+    #[test]
+    fn test_safety_limits_are_applied() {
+        let mut nao = Nao::with_backend(MockBackend::with_states([state(0.0)]));
+
+        nao.set_joints(JointArray::fill(1.0), JointArray::fill(1.0));
+        nao.tick().unwrap();
+
+        let max_delta = SafetyPolicy::default().max_position_delta;
+        assert_eq!(nao.backend().sent[0].position.head_yaw, max_delta);
+        assert!(nao.violations().rate_limit > 0);
+    }
+
+    #[test]
+    fn test_leds_are_merged() {
+        let mut nao = Nao::with_backend(MockBackend::default());
+
+        nao.set_led(LedStateDelta {
+            chest: Some(color::f32::RED),
+            ..Default::default()
+        });
+        nao.set_led(LedStateDelta {
+            left_foot: Some(color::f32::BLUE),
+            ..Default::default()
+        });
+        nao.tick().unwrap();
+
+        let sent = &nao.backend().sent[0];
+        assert_eq!(sent.chest, color::f32::RED);
+        assert_eq!(sent.left_foot, color::f32::BLUE);
+
+        nao.set_led(LedState::default());
+        nao.tick().unwrap();
+        assert_eq!(nao.backend().sent[1].leds(), LedState::default());
+    }
+
+    #[test]
+    fn test_failed_read_does_not_write() {
+        let mut backend = MockBackend::with_states([state(0.2)]);
+        backend.fail_reads = true;
+        let mut nao = Nao::with_backend(backend);
+
+        assert!(nao.tick().is_err());
+        assert!(nao.backend().sent.is_empty());
+        assert_eq!(nao.state(), &NaoState::default());
+        assert_eq!(nao.frame_stats().cycles, 0);
+    }
+
+    #[test]
+    fn test_joint_offsets() {
+        let mut nao = Nao::with_backend(MockBackend::with_states([state(0.5), state(0.5)]))
+            .with_joint_offsets(JointArray::fill(0.25));
+
+        // the state is corrected, while holding the position sends the raw position back
+        assert_eq!(nao.tick().unwrap().position, JointArray::fill(0.25));
+        assert_eq!(nao.backend().sent[0].position.head_yaw, 0.5);
+
+        nao.set_joints(JointArray::fill(0.3125), JointArray::fill(1.0));
+        nao.tick().unwrap();
+        assert_eq!(nao.backend().sent[1].position.head_yaw, 0.5625);
+    }
+
+    #[test]
+    fn test_frame_stats() {
+        let mut nao = Nao::with_backend(MockBackend::default());
+
+        for _ in 0..3 {
+            nao.tick().unwrap();
+        }
+        assert_eq!(nao.frame_stats().cycles, 3);
+
+        nao.reset_frame_stats();
+        assert_eq!(nao.frame_stats(), FrameStats::default());
+    }
+}
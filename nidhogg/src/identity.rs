@@ -0,0 +1,90 @@
+//! Implements [`RobotIdentity`], which maps hardware identifiers to human readable robot names.
+
+use std::collections::HashMap;
+
+use crate::HardwareInfo;
+
+#[cfg(feature = "bevy")]
+use bevy_ecs::prelude::Resource;
+
+/// Table of human readable robot names, keyed by the [`HardwareInfo::head_id`] of each robot.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use nidhogg::{HardwareInfo, RobotIdentity};
+///
+/// let identity = RobotIdentity::from_map(HashMap::from([(
+///     "P0000073A07S94500188".to_string(),
+///     "Freya".to_string(),
+/// )]));
+///
+/// let info = HardwareInfo {
+///     body_id: "P0000074A04S94700023".to_string(),
+///     body_version: "6.0.0".to_string(),
+///     head_id: "P0000073A07S94500188".to_string(),
+///     head_version: "6.0.0".to_string(),
+/// };
+///
+/// assert_eq!(identity.name(&info), Some("Freya"));
+/// assert_eq!(identity.describe(&info), "robot 'Freya'");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct RobotIdentity {
+    names: HashMap<String, String>,
+}
+
+impl RobotIdentity {
+    /// Create a [`RobotIdentity`] from a map of head ids to robot names.
+    pub fn from_map(names: HashMap<String, String>) -> Self {
+        Self { names }
+    }
+
+    /// Returns the name of the robot with the provided hardware, if it is known.
+    pub fn name(&self, info: &HardwareInfo) -> Option<&str> {
+        self.names.get(info.head_id.trim()).map(String::as_str)
+    }
+
+    /// Describes the robot for use in log messages, using its name if known and its head id otherwise.
+    pub fn describe(&self, info: &HardwareInfo) -> String {
+        match self.name(info) {
+            Some(name) => format!("robot '{name}'"),
+            None => format!("robot with head id {}", info.head_id.trim()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(head_id: &str) -> HardwareInfo {
+        HardwareInfo {
+            body_id: "P0000074A04S94700023".to_string(),
+            body_version: "6.0.0".to_string(),
+            head_id: head_id.to_string(),
+            head_version: "6.0.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_describe() {
+        let identity = RobotIdentity::from_map(HashMap::from([
+            ("P0000073A07S94500188".to_string(), "Freya".to_string()),
+            ("P0000073A07S94500201".to_string(), "Odin".to_string()),
+        ]));
+
+        assert_eq!(identity.name(&info("P0000073A07S94500201")), Some("Odin"));
+        // LoLA pads some identifiers with whitespace
+        assert_eq!(identity.name(&info("P0000073A07S94500188 ")), Some("Freya"));
+
+        let unknown = info("P0000073A07S94500999");
+        assert_eq!(identity.name(&unknown), None);
+        assert_eq!(
+            identity.describe(&unknown),
+            "robot with head id P0000073A07S94500999"
+        );
+    }
+}
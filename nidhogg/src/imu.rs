@@ -0,0 +1,209 @@
+//! Axis conventions of the inertial measurement unit, and conversions between them.
+//!
+//! The [`accelerometer`](crate::NaoState::accelerometer) and [`gyroscope`](crate::NaoState::gyroscope)
+//! of a [`NaoState`](crate::NaoState) are in the robot frame of the torso, exactly as `LoLA` reports them:
+//!
+//! - x points forward, y points to the left and z points up,
+//! - the accelerometer measures the proper acceleration, so a robot at rest measures gravity pointing up,
+//! - the gyroscope measures counterclockwise rotation around each axis as positive.
+//!
+//! A robot at rest reads the following accelerometer values, in multiples of g:
+//!
+//! | Pose | x | y | z |
+//! |-|-|-|-|
+//! | Upright | 0 | 0 | 1 |
+//! | Lying on its front | -1 | 0 | 0 |
+//! | Lying on its back | 1 | 0 | 0 |
+//! | Lying on its left side | 0 | -1 | 0 |
+//! | Lying on its right side | 0 | 1 | 0 |
+//!
+//! `NAOqi` reports the accelerometer as the acceleration of gravity instead, which has the
+//! opposite sign on every axis. Use [`to_aldebaran_frame`] and [`to_robot_frame`] to convert
+//! between the two, instead of flipping signs by hand.
+//!
+//! # Example
+//!
+//! ```
+//! use nalgebra::Vector3;
+//! use nidhogg::imu;
+//!
+//! let upright = Vector3::new(0.0, 0.0, imu::GRAVITY);
+//!
+//! let aldebaran = imu::to_aldebaran_frame(upright);
+//! assert_eq!(aldebaran.z, -imu::GRAVITY);
+//! assert_eq!(imu::to_robot_frame(aldebaran), upright);
+//! ```
+
+use std::ops::{BitOr, Neg};
+
+use nalgebra::{Scalar, UnitQuaternion, Vector3};
+
+/// Standard gravity in m/s².
+pub const GRAVITY: f32 = 9.81;
+
+/// The axes that are flipped when converting between the robot frame and the `NAOqi` accelerometer.
+pub const ALDEBARAN_ACCELEROMETER_FLIP: AxisMask = AxisMask::ALL;
+
+/// A selection of the x, y and z axes, used to flip the sign of a vector per axis.
+///
+/// # Example
+///
+/// ```
+/// use nalgebra::Vector3;
+/// use nidhogg::imu::{AxisMask, Flip};
+///
+/// let flipped = Vector3::new(1.0, 2.0, 3.0).flipped(AxisMask::Y | AxisMask::Z);
+/// assert_eq!(flipped, Vector3::new(1.0, -2.0, -3.0));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AxisMask {
+    pub x: bool,
+    pub y: bool,
+    pub z: bool,
+}
+
+impl AxisMask {
+    /// No axes.
+    pub const NONE: Self = Self {
+        x: false,
+        y: false,
+        z: false,
+    };
+
+    /// Only the x axis.
+    pub const X: Self = Self {
+        x: true,
+        ..Self::NONE
+    };
+
+    /// Only the y axis.
+    pub const Y: Self = Self {
+        y: true,
+        ..Self::NONE
+    };
+
+    /// Only the z axis.
+    pub const Z: Self = Self {
+        z: true,
+        ..Self::NONE
+    };
+
+    /// All three axes.
+    pub const ALL: Self = Self {
+        x: true,
+        y: true,
+        z: true,
+    };
+}
+
+impl BitOr for AxisMask {
+    type Output = AxisMask;
+
+    fn bitor(self, rhs: AxisMask) -> AxisMask {
+        AxisMask {
+            x: self.x || rhs.x,
+            y: self.y || rhs.y,
+            z: self.z || rhs.z,
+        }
+    }
+}
+
+/// Trait that introduces [`Flip::flipped`] to vectors.
+pub trait Flip {
+    /// Returns a copy with the sign of the selected axes flipped.
+    #[must_use]
+    fn flipped(&self, axes: AxisMask) -> Self;
+}
+
+impl<T: Scalar + Copy + Neg<Output = T>> Flip for Vector3<T> {
+    fn flipped(&self, axes: AxisMask) -> Self {
+        let flip = |value: T, flipped: bool| if flipped { -value } else { value };
+
+        Vector3::new(
+            flip(self.x, axes.x),
+            flip(self.y, axes.y),
+            flip(self.z, axes.z),
+        )
+    }
+}
+
+/// Converts an accelerometer measurement from the robot frame to the `NAOqi` convention.
+pub fn to_aldebaran_frame(accelerometer: Vector3<f32>) -> Vector3<f32> {
+    accelerometer.flipped(ALDEBARAN_ACCELEROMETER_FLIP)
+}
+
+/// Converts an accelerometer measurement from the `NAOqi` convention to the robot frame.
+pub fn to_robot_frame(accelerometer: Vector3<f32>) -> Vector3<f32> {
+    accelerometer.flipped(ALDEBARAN_ACCELEROMETER_FLIP)
+}
+
+/// The accelerometer measurement of a robot at rest, with its torso at `orientation` in the world.
+///
+/// This is useful to create realistic states for simulations and tests.
+///
+/// # Example
+///
+/// ```
+/// use nalgebra::UnitQuaternion;
+/// use nidhogg::imu;
+///
+/// // tipped over onto its front, by pitching forward a quarter turn
+/// let fallen = UnitQuaternion::from_euler_angles(0.0, std::f32::consts::FRAC_PI_2, 0.0);
+///
+/// let accelerometer = imu::proper_acceleration(&fallen);
+/// assert!((accelerometer.x + imu::GRAVITY).abs() < 1e-5);
+/// ```
+pub fn proper_acceleration(orientation: &UnitQuaternion<f32>) -> Vector3<f32> {
+    orientation.inverse_transform_vector(&Vector3::new(0.0, 0.0, GRAVITY))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+
+    fn rounded(vector: Vector3<f32>) -> Vector3<f32> {
+        vector.map(|value| (value / GRAVITY).round())
+    }
+
+    #[test]
+    fn test_resting_poses() {
+        // (roll, pitch) of the torso, and the documented accelerometer signs
+        let poses = [
+            ("upright", (0.0, 0.0), [0.0, 0.0, 1.0]),
+            ("front", (0.0, FRAC_PI_2), [-1.0, 0.0, 0.0]),
+            ("back", (0.0, -FRAC_PI_2), [1.0, 0.0, 0.0]),
+            ("left", (-FRAC_PI_2, 0.0), [0.0, -1.0, 0.0]),
+            ("right", (FRAC_PI_2, 0.0), [0.0, 1.0, 0.0]),
+            ("upside down", (PI, 0.0), [0.0, 0.0, -1.0]),
+        ];
+
+        for (pose, (roll, pitch), expected) in poses {
+            let orientation = UnitQuaternion::from_euler_angles(roll, pitch, 0.0);
+            let accelerometer = proper_acceleration(&orientation);
+
+            assert_eq!(rounded(accelerometer), Vector3::from(expected), "{pose}");
+            assert_eq!(
+                rounded(to_aldebaran_frame(accelerometer)),
+                -Vector3::from(expected),
+                "{pose}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_conversions_are_inverse() {
+        let accelerometer = Vector3::new(1.0, -2.0, 9.0);
+
+        assert_eq!(
+            to_robot_frame(to_aldebaran_frame(accelerometer)),
+            accelerometer
+        );
+        assert_eq!(accelerometer.flipped(AxisMask::NONE), accelerometer);
+        assert_eq!(
+            accelerometer.flipped(AxisMask::X | AxisMask::Y | AxisMask::Z),
+            -accelerometer
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Implements the [`ButtonInterface`], which recognizes press patterns on the chest button and foot bumpers.
+
+use std::time::{Duration, Instant};
+
+use crate::types::Touch;
+
+/// The side of the robot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A high level button event, as emitted by the [`ButtonInterface`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ButtonEvent {
+    /// The chest button was pressed once.
+    ChestSinglePress,
+    /// The chest button was pressed twice in quick succession.
+    ChestDoublePress,
+    /// The chest button was pressed three (or more) times in quick succession.
+    ChestTriplePress,
+    /// The chest button was held down for at least [`ButtonInterfaceConfig::long_press`].
+    ChestLongPress,
+    /// One of the bumpers of the foot on the provided side was pressed.
+    FootBumperPressed(Side),
+}
+
+/// Configuration for the [`ButtonInterface`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ButtonInterfaceConfig {
+    /// Touch value above which a button is considered pressed.
+    pub press_threshold: f32,
+    /// Maximum time between releasing the chest button and pressing it again for both
+    /// presses to count towards the same pattern.
+    pub press_window: Duration,
+    /// Minimum time the chest button has to be held down to count as a long press.
+    pub long_press: Duration,
+}
+
+impl Default for ButtonInterfaceConfig {
+    fn default() -> Self {
+        Self {
+            press_threshold: 0.5,
+            press_window: Duration::from_millis(350),
+            long_press: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Recognizes the press patterns on the chest button and foot bumpers defined by the SPL rules.
+///
+/// Chest presses are only reported once the press window has passed without another press,
+/// so a double press is never reported as a single press first.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use nidhogg::{input::{ButtonEvent, ButtonInterface}, types::Touch};
+///
+/// let mut buttons = ButtonInterface::default();
+/// let start = Instant::now();
+///
+/// let pressed = Touch { chest_board: 1.0, ..Default::default() };
+/// buttons.update(&pressed, start);
+/// buttons.update(&Touch::default(), start + Duration::from_millis(100));
+///
+/// // after the press window has passed, the single press is reported
+/// let events = buttons.update(&Touch::default(), start + Duration::from_millis(500));
+/// assert_eq!(events, vec![ButtonEvent::ChestSinglePress]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ButtonInterface {
+    config: ButtonInterfaceConfig,
+    chest_pressed_since: Option<Instant>,
+    long_press_reported: bool,
+    pending_presses: u32,
+    last_release: Option<Instant>,
+    left_bumper_pressed: bool,
+    right_bumper_pressed: bool,
+}
+
+impl ButtonInterface {
+    /// Create a new [`ButtonInterface`] using the provided configuration.
+    pub fn new(config: ButtonInterfaceConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// The configuration used by this interface.
+    pub fn config(&self) -> &ButtonInterfaceConfig {
+        &self.config
+    }
+
+    /// Update the interface with the touch values measured at `now`, and return the recognized events.
+    pub fn update(&mut self, touch: &Touch, now: Instant) -> Vec<ButtonEvent> {
+        let mut events = Vec::new();
+        let threshold = self.config.press_threshold;
+
+        self.update_chest(touch.chest_board > threshold, now, &mut events);
+
+        let left = touch.left_foot().any_pressed(threshold);
+        if left && !self.left_bumper_pressed {
+            events.push(ButtonEvent::FootBumperPressed(Side::Left));
+        }
+        self.left_bumper_pressed = left;
+
+        let right = touch.right_foot().any_pressed(threshold);
+        if right && !self.right_bumper_pressed {
+            events.push(ButtonEvent::FootBumperPressed(Side::Right));
+        }
+        self.right_bumper_pressed = right;
+
+        events
+    }
+
+    fn update_chest(&mut self, pressed: bool, now: Instant, events: &mut Vec<ButtonEvent>) {
+        match (self.chest_pressed_since, pressed) {
+            (None, true) => {
+                self.chest_pressed_since = Some(now);
+                self.long_press_reported = false;
+            }
+            (Some(since), true) => {
+                if !self.long_press_reported && now.duration_since(since) >= self.config.long_press
+                {
+                    // a long press ends the current pattern
+                    self.long_press_reported = true;
+                    self.pending_presses = 0;
+                    events.push(ButtonEvent::ChestLongPress);
+                }
+            }
+            (Some(_), false) => {
+                self.chest_pressed_since = None;
+
+                if !self.long_press_reported {
+                    self.pending_presses += 1;
+                    self.last_release = Some(now);
+
+                    // there is no pattern with more presses, so there is no need to wait.
+                    if self.pending_presses >= 3 {
+                        self.flush_presses(events);
+                    }
+                }
+            }
+            (None, false) => {
+                let window_passed = self
+                    .last_release
+                    .is_some_and(|release| now.duration_since(release) > self.config.press_window);
+
+                if window_passed {
+                    self.flush_presses(events);
+                }
+            }
+        }
+    }
+
+    fn flush_presses(&mut self, events: &mut Vec<ButtonEvent>) {
+        match self.pending_presses {
+            0 => {}
+            1 => events.push(ButtonEvent::ChestSinglePress),
+            2 => events.push(ButtonEvent::ChestDoublePress),
+            _ => events.push(ButtonEvent::ChestTriplePress),
+        }
+
+        self.pending_presses = 0;
+        self.last_release = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CYCLE: Duration = Duration::from_millis(12);
+
+    /// Drives a [`ButtonInterface`] with a sequence of chest button states, one per cycle.
+    struct Driver {
+        buttons: ButtonInterface,
+        now: Instant,
+        events: Vec<(Duration, ButtonEvent)>,
+        start: Instant,
+    }
+
+    impl Driver {
+        fn new() -> Self {
+            let now = Instant::now();
+            Self {
+                buttons: ButtonInterface::default(),
+                now,
+                events: Vec::new(),
+                start: now,
+            }
+        }
+
+        fn run(&mut self, touch: Touch, duration: Duration) -> &mut Self {
+            let end = self.now + duration;
+            while self.now < end {
+                self.now += CYCLE;
+                for event in self.buttons.update(&touch, self.now) {
+                    self.events.push((self.now - self.start, event));
+                }
+            }
+            self
+        }
+
+        fn press(&mut self, millis: u64) -> &mut Self {
+            self.run(chest(), Duration::from_millis(millis))
+        }
+
+        fn release(&mut self, millis: u64) -> &mut Self {
+            self.run(Touch::default(), Duration::from_millis(millis))
+        }
+
+        fn events(&self) -> Vec<ButtonEvent> {
+            self.events.iter().map(|(_, event)| *event).collect()
+        }
+    }
+
+    fn chest() -> Touch {
+        Touch {
+            chest_board: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_single_press() {
+        let mut driver = Driver::new();
+        driver.press(100).release(200);
+        assert!(driver.events().is_empty(), "reported before the window");
+
+        driver.release(200);
+        assert_eq!(driver.events(), vec![ButtonEvent::ChestSinglePress]);
+    }
+
+    #[test]
+    fn test_double_press() {
+        let mut driver = Driver::new();
+        driver.press(100).release(150).press(100).release(1000);
+
+        assert_eq!(driver.events(), vec![ButtonEvent::ChestDoublePress]);
+    }
+
+    #[test]
+    fn test_triple_press_is_reported_immediately() {
+        let mut driver = Driver::new();
+        driver
+            .press(80)
+            .release(100)
+            .press(80)
+            .release(100)
+            .press(80);
+        assert!(driver.events().is_empty());
+
+        driver.release(12);
+        assert_eq!(driver.events(), vec![ButtonEvent::ChestTriplePress]);
+
+        driver.release(1000);
+        assert_eq!(driver.events(), vec![ButtonEvent::ChestTriplePress]);
+    }
+
+    #[test]
+    fn test_presses_outside_window_are_separate() {
+        let mut driver = Driver::new();
+        driver.press(100).release(500).press(100).release(500);
+
+        assert_eq!(
+            driver.events(),
+            vec![ButtonEvent::ChestSinglePress, ButtonEvent::ChestSinglePress]
+        );
+    }
+
+    #[test]
+    fn test_single_press_waits_for_window() {
+        let mut driver = Driver::new();
+        driver.press(100).release(1000);
+
+        let (time, _) = driver.events[0];
+        let released = Duration::from_millis(108);
+        let window = driver.buttons.config().press_window;
+        assert!(time > released + window);
+        assert!(time <= released + window + 2 * CYCLE);
+    }
+
+    #[test]
+    fn test_long_press() {
+        let mut driver = Driver::new();
+        driver.press(1500);
+        assert_eq!(driver.events(), vec![ButtonEvent::ChestLongPress]);
+
+        // releasing after a long press does not count as another press
+        driver.release(1000);
+        assert_eq!(driver.events(), vec![ButtonEvent::ChestLongPress]);
+    }
+
+    #[test]
+    fn test_long_press_cancels_pending_presses() {
+        let mut driver = Driver::new();
+        driver.press(100).release(100).press(1200).release(1000);
+
+        assert_eq!(driver.events(), vec![ButtonEvent::ChestLongPress]);
+    }
+
+    #[test]
+    fn test_noise_below_threshold_is_ignored() {
+        let mut driver = Driver::new();
+        let noisy = Touch {
+            chest_board: 0.3,
+            left_foot_left: 0.2,
+            ..Default::default()
+        };
+        driver.run(noisy, Duration::from_secs(2));
+
+        assert!(driver.events().is_empty());
+    }
+
+    #[test]
+    fn test_foot_bumpers() {
+        let mut driver = Driver::new();
+        let left = Touch {
+            left_foot_right: 1.0,
+            ..Default::default()
+        };
+        let both = Touch {
+            left_foot_left: 1.0,
+            right_foot_left: 1.0,
+            ..Default::default()
+        };
+
+        driver
+            .run(left, Duration::from_millis(100))
+            .run(both.clone(), Duration::from_millis(100))
+            .release(100)
+            .run(both, Duration::from_millis(100));
+
+        assert_eq!(
+            driver.events(),
+            vec![
+                ButtonEvent::FootBumperPressed(Side::Left),
+                ButtonEvent::FootBumperPressed(Side::Right),
+                ButtonEvent::FootBumperPressed(Side::Left),
+                ButtonEvent::FootBumperPressed(Side::Right),
+            ]
+        );
+    }
+}
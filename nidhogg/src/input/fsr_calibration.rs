@@ -0,0 +1,290 @@
+//! Implements the [`FsrCalibration`], which corrects the offset and scale of each force sensitive resistor.
+
+use std::collections::HashMap;
+
+use crate::types::{FillExt, Fsr, FsrFoot};
+use crate::HardwareInfo;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy")]
+use bevy_ecs::prelude::Resource;
+
+/// Per-sensor offsets and scales for the force sensitive resistors in the feet.
+///
+/// Real FSRs drift over time, an unloaded robot often reads 0.1 to 0.3 kg per sensor.
+/// A calibrated value is computed as `(raw - offset) * scale`, clamped to zero.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{input::FsrCalibration, types::{FillExt, Fsr, FsrFoot}};
+///
+/// let unloaded = Fsr {
+///     left_foot: FsrFoot::fill(0.2),
+///     right_foot: FsrFoot::fill(0.1),
+/// };
+///
+/// let calibration = FsrCalibration::calibrate_zero(&[unloaded.clone()]);
+/// assert_eq!(calibration.apply(&unloaded).sum(), 0.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct FsrCalibration {
+    /// Reading of each sensor while the feet are unloaded.
+    pub offsets: Fsr,
+    /// Factor each sensor is multiplied with after subtracting the offset.
+    pub scales: Fsr,
+}
+
+impl Default for FsrCalibration {
+    fn default() -> Self {
+        Self {
+            offsets: Fsr::default(),
+            scales: uniform(1.0),
+        }
+    }
+}
+
+impl FsrCalibration {
+    /// Create a calibration with the offsets set to the average of `samples`, and unit scales.
+    ///
+    /// The samples should be recorded while the feet are not touching anything, for instance while
+    /// the robot is being held up. Without samples this returns [`FsrCalibration::default`].
+    pub fn calibrate_zero(samples: &[Fsr]) -> Self {
+        Self {
+            offsets: mean(samples).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// Update the scales so the average of `samples` is calibrated to the `expected` weight on each sensor.
+    ///
+    /// The samples should be recorded while a known weight rests on the sensors, after the offsets have
+    /// been calibrated using [`FsrCalibration::calibrate_zero`].
+    /// Sensors that do not measure any load keep their current scale.
+    #[must_use]
+    pub fn calibrate_scales(mut self, samples: &[Fsr], expected: &Fsr) -> Self {
+        let Some(measured) = mean(samples) else {
+            return self;
+        };
+        let measured = measured - self.offsets.clone();
+
+        let scale = |scale: f32, measured: f32, expected: f32| {
+            if measured > f32::EPSILON {
+                expected / measured
+            } else {
+                scale
+            }
+        };
+        self.scales = Fsr {
+            left_foot: zip_foot(
+                &self.scales.left_foot,
+                &measured.left_foot,
+                &expected.left_foot,
+                scale,
+            ),
+            right_foot: zip_foot(
+                &self.scales.right_foot,
+                &measured.right_foot,
+                &expected.right_foot,
+                scale,
+            ),
+        };
+        self
+    }
+
+    /// Apply the calibration to a raw FSR reading.
+    ///
+    /// Sensors reading less than their offset are clamped to zero.
+    pub fn apply(&self, raw: &Fsr) -> Fsr {
+        let calibrated = (raw.clone() - self.offsets.clone()) * self.scales.clone();
+
+        Fsr {
+            left_foot: calibrated.left_foot.sup(&FsrFoot::default()),
+            right_foot: calibrated.right_foot.sup(&FsrFoot::default()),
+        }
+    }
+}
+
+/// Table of [`FsrCalibration`]s, keyed by the [`HardwareInfo::body_id`] of each robot.
+///
+/// The feet are part of the body, so the calibration stays with the body when heads are swapped.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{input::{FsrCalibration, FsrCalibrations}, HardwareInfo};
+///
+/// let info = HardwareInfo {
+///     body_id: "P0000074A04S94700023".to_string(),
+///     body_version: "6.0.0".to_string(),
+///     head_id: "P0000073A07S94500188".to_string(),
+///     head_version: "6.0.0".to_string(),
+/// };
+///
+/// let mut calibrations = FsrCalibrations::default();
+/// calibrations.insert(&info, FsrCalibration::default());
+///
+/// assert_eq!(calibrations.get(&info), Some(&FsrCalibration::default()));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct FsrCalibrations {
+    calibrations: HashMap<String, FsrCalibration>,
+}
+
+impl FsrCalibrations {
+    /// Create a [`FsrCalibrations`] from a map of body ids to calibrations.
+    pub fn from_map(calibrations: HashMap<String, FsrCalibration>) -> Self {
+        Self { calibrations }
+    }
+
+    /// Returns the calibration of the robot with the provided hardware, if it is known.
+    pub fn get(&self, info: &HardwareInfo) -> Option<&FsrCalibration> {
+        self.calibrations.get(info.body_id.trim())
+    }
+
+    /// Store the calibration of the robot with the provided hardware, returning the previous calibration.
+    pub fn insert(
+        &mut self,
+        info: &HardwareInfo,
+        calibration: FsrCalibration,
+    ) -> Option<FsrCalibration> {
+        self.calibrations
+            .insert(info.body_id.trim().to_string(), calibration)
+    }
+}
+
+fn uniform(value: f32) -> Fsr {
+    Fsr {
+        left_foot: FsrFoot::fill(value),
+        right_foot: FsrFoot::fill(value),
+    }
+}
+
+fn mean(samples: &[Fsr]) -> Option<Fsr> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let sum = samples
+        .iter()
+        .cloned()
+        .fold(Fsr::default(), |sum, sample| sum + sample);
+    Some(sum / uniform(samples.len() as f32))
+}
+
+fn zip_foot(a: &FsrFoot, b: &FsrFoot, c: &FsrFoot, f: impl Fn(f32, f32, f32) -> f32) -> FsrFoot {
+    FsrFoot {
+        front_left: f(a.front_left, b.front_left, c.front_left),
+        front_right: f(a.front_right, b.front_right, c.front_right),
+        rear_left: f(a.rear_left, b.rear_left, c.rear_left),
+        rear_right: f(a.rear_right, b.rear_right, c.rear_right),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn foot(front_left: f32, front_right: f32, rear_left: f32, rear_right: f32) -> FsrFoot {
+        FsrFoot {
+            front_left,
+            front_right,
+            rear_left,
+            rear_right,
+        }
+    }
+
+    /// Deterministic noise in the range `[-0.02, 0.02]`.
+    fn noise(i: usize) -> f32 {
+        (i as f32 * 1.7).sin() * 0.02
+    }
+
+    fn noisy(base: &Fsr, samples: usize) -> Vec<Fsr> {
+        (0..samples)
+            .map(|i| base.clone() + uniform(noise(i)))
+            .collect()
+    }
+
+    fn assert_close(actual: &Fsr, expected: &Fsr) {
+        let difference = |a: f32, b: f32, _| (a - b).abs();
+        let error = zip_foot(
+            &actual.left_foot,
+            &expected.left_foot,
+            &FsrFoot::default(),
+            difference,
+        )
+        .sum()
+            + zip_foot(
+                &actual.right_foot,
+                &expected.right_foot,
+                &FsrFoot::default(),
+                difference,
+            )
+            .sum();
+        assert!(error < 1e-3, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn test_calibrate_zero_and_scale() {
+        let offsets = Fsr {
+            left_foot: foot(0.1, 0.25, 0.3, 0.15),
+            right_foot: foot(0.2, 0.1, 0.05, 0.3),
+        };
+        let unloaded = noisy(&offsets, 200);
+        let calibration = FsrCalibration::calibrate_zero(&unloaded);
+
+        // the noise averages out, and what is left over stays below the noise level
+        assert_close(&calibration.offsets, &offsets);
+        assert!(unloaded
+            .iter()
+            .all(|sample| calibration.apply(sample).sum() < 8.0 * 0.025));
+
+        // 1 kg on every sensor, which the sensors under-report with different gains
+        let gains = Fsr {
+            left_foot: foot(0.8, 0.9, 1.1, 1.0),
+            right_foot: foot(1.2, 0.95, 0.85, 1.05),
+        };
+        let expected = uniform(1.0);
+        let loaded = noisy(&(offsets.clone() + expected.clone() * gains.clone()), 200);
+        let calibration = calibration.calibrate_scales(&loaded, &expected);
+
+        let weighed = mean(
+            &loaded
+                .iter()
+                .map(|sample| calibration.apply(sample))
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_close(&weighed, &expected);
+
+        // a different weight is scaled correctly as well
+        let raw = offsets + uniform(2.5) * gains;
+        assert_close(&calibration.apply(&raw), &uniform(2.5));
+    }
+
+    #[test]
+    fn test_apply_clamps_to_zero() {
+        let calibration = FsrCalibration::calibrate_zero(&[uniform(0.3)]);
+        assert_eq!(calibration.apply(&uniform(0.1)), Fsr::default());
+        assert_eq!(FsrCalibration::default().apply(&uniform(0.1)), uniform(0.1));
+    }
+
+    #[test]
+    fn test_unloaded_sensors_keep_their_scale() {
+        let loaded = Fsr {
+            left_foot: foot(2.0, 0.0, 0.0, 0.0),
+            right_foot: FsrFoot::default(),
+        };
+        let calibration = FsrCalibration::default().calibrate_scales(&[loaded], &uniform(1.0));
+
+        assert_eq!(calibration.scales.left_foot.front_left, 0.5);
+        assert_eq!(calibration.scales.right_foot, FsrFoot::fill(1.0));
+        assert_eq!(calibration.offsets, Fsr::default());
+    }
+}
@@ -0,0 +1,9 @@
+//! High level interpretation of the buttons, touch sensors, foot pressure sensors and sonars of the robot.
+
+mod buttons;
+mod fsr_calibration;
+mod obstacle;
+
+pub use buttons::{ButtonEvent, ButtonInterface, ButtonInterfaceConfig, Side};
+pub use fsr_calibration::{FsrCalibration, FsrCalibrations};
+pub use obstacle::{ObstacleDetector, ObstacleDetectorConfig, ObstacleState};
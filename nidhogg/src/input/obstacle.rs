@@ -0,0 +1,341 @@
+//! Implements the [`ObstacleDetector`], which classifies the space in front of the robot using the sonars.
+
+use crate::types::SonarValues;
+
+/// The space in front of the robot, as classified by the [`ObstacleDetector`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ObstacleState {
+    /// Neither sonar detects an obstacle.
+    Clear,
+    /// Only the left sonar detects an obstacle.
+    ObstacleLeft,
+    /// Only the right sonar detects an obstacle.
+    ObstacleRight,
+    /// Both sonars detect an obstacle, so it is in front of the robot.
+    ObstacleCenter,
+    /// At least one sonar reports an error, or no state has been confirmed yet.
+    #[default]
+    Unknown,
+}
+
+impl ObstacleState {
+    /// Returns `true` if an obstacle is detected on any side.
+    pub fn is_obstacle(self) -> bool {
+        matches!(
+            self,
+            Self::ObstacleLeft | Self::ObstacleRight | Self::ObstacleCenter
+        )
+    }
+}
+
+/// Configuration for the [`ObstacleDetector`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObstacleDetectorConfig {
+    /// Distance in meters below which a sonar starts detecting an obstacle.
+    pub near: f32,
+    /// Distance in meters above which a sonar stops detecting an obstacle.
+    ///
+    /// Keeping this larger than [`Self::near`] prevents flicker when an obstacle is at the threshold.
+    pub far: f32,
+    /// Number of consecutive cycles a new state has to be measured before it is reported.
+    pub confirmation_frames: u32,
+    /// Maximum range of the sonars in meters, at which they report that no echo was received.
+    pub max_range: f32,
+}
+
+impl Default for ObstacleDetectorConfig {
+    fn default() -> Self {
+        Self {
+            near: 0.4,
+            far: 0.5,
+            confirmation_frames: 3,
+            max_range: 5.0,
+        }
+    }
+}
+
+/// What a single sonar measured in one cycle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Echo {
+    /// The sonar reported an error, by returning `0.0` or an invalid value.
+    Error,
+    /// The sonar received no echo within its range.
+    None,
+    /// The sonar received an echo from the provided distance.
+    At(f32),
+}
+
+/// State of a single sonar.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct SonarSide {
+    /// Whether the sonar currently detects an obstacle, including the hysteresis.
+    detected: bool,
+    /// The last distance at which an obstacle was detected.
+    distance: Option<f32>,
+}
+
+impl SonarSide {
+    fn update(&mut self, echo: Echo, config: &ObstacleDetectorConfig) -> Option<bool> {
+        match echo {
+            Echo::Error => return None,
+            Echo::None => self.detected = false,
+            Echo::At(distance) => {
+                let threshold = if self.detected {
+                    config.far
+                } else {
+                    config.near
+                };
+                self.detected = distance < threshold;
+            }
+        }
+
+        self.distance = match echo {
+            Echo::At(distance) if self.detected => Some(distance),
+            _ => None,
+        };
+        Some(self.detected)
+    }
+}
+
+/// Classifies the space in front of the robot into an [`ObstacleState`], using both sonars.
+///
+/// Each sonar detects an obstacle once it measures a distance below [`ObstacleDetectorConfig::near`],
+/// and keeps detecting it until the distance rises above [`ObstacleDetectorConfig::far`].
+/// The documented failure modes of the sonars are taken into account: a value of `0.0` is an error
+/// and results in [`ObstacleState::Unknown`], and a value at the maximum range means that no echo
+/// was received, which counts as clear.
+///
+/// A new state is only reported after it was measured for [`ObstacleDetectorConfig::confirmation_frames`]
+/// consecutive cycles, so single noisy measurements do not change the state.
+///
+/// The detector only consumes [`SonarValues`], so it works with any backend, and with values that
+/// were filtered beforehand.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{input::{ObstacleDetector, ObstacleState}, types::SonarValues};
+///
+/// let mut detector = ObstacleDetector::default();
+/// let close_left = SonarValues { left: 0.3, right: 5.0 };
+///
+/// for _ in 0..3 {
+///     detector.update(&close_left);
+/// }
+///
+/// assert_eq!(detector.state(), ObstacleState::ObstacleLeft);
+/// assert_eq!(detector.nearest_distance(), Some(0.3));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ObstacleDetector {
+    config: ObstacleDetectorConfig,
+    state: ObstacleState,
+    candidate: Option<(ObstacleState, u32)>,
+    left: SonarSide,
+    right: SonarSide,
+}
+
+impl ObstacleDetector {
+    /// Create a new [`ObstacleDetector`] using the provided configuration.
+    pub fn new(config: ObstacleDetectorConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// The configuration used by this detector.
+    pub fn config(&self) -> &ObstacleDetectorConfig {
+        &self.config
+    }
+
+    /// Update the detector with the sonar values of a single cycle, and return the confirmed state.
+    pub fn update(&mut self, sonar: &SonarValues) -> ObstacleState {
+        let measured = self.classify(sonar);
+
+        if measured == self.state {
+            self.candidate = None;
+            return self.state;
+        }
+
+        let confirmations = match self.candidate {
+            Some((candidate, confirmations)) if candidate == measured => confirmations + 1,
+            _ => 1,
+        };
+
+        if confirmations >= self.config.confirmation_frames {
+            self.state = measured;
+            self.candidate = None;
+        } else {
+            self.candidate = Some((measured, confirmations));
+        }
+
+        self.state
+    }
+
+    /// The confirmed state.
+    pub fn state(&self) -> ObstacleState {
+        self.state
+    }
+
+    /// The distance in meters to the nearest obstacle of the confirmed state, in the most recent cycle it was measured.
+    ///
+    /// Returns [`None`] if the confirmed state is not an obstacle.
+    pub fn nearest_distance(&self) -> Option<f32> {
+        match self.state {
+            ObstacleState::ObstacleLeft => self.left.distance,
+            ObstacleState::ObstacleRight => self.right.distance,
+            ObstacleState::ObstacleCenter => match (self.left.distance, self.right.distance) {
+                (Some(left), Some(right)) => Some(left.min(right)),
+                (left, right) => left.or(right),
+            },
+            ObstacleState::Clear | ObstacleState::Unknown => None,
+        }
+    }
+
+    /// Forget all measurements, so the state is [`ObstacleState::Unknown`] until a new state is confirmed.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config.clone());
+    }
+
+    fn echo(&self, distance: f32) -> Echo {
+        if !distance.is_finite() || distance <= 0.0 {
+            Echo::Error
+        } else if distance >= self.config.max_range {
+            Echo::None
+        } else {
+            Echo::At(distance)
+        }
+    }
+
+    fn classify(&mut self, sonar: &SonarValues) -> ObstacleState {
+        let left = self.left.update(self.echo(sonar.left), &self.config);
+        let right = self.right.update(self.echo(sonar.right), &self.config);
+
+        match (left, right) {
+            (None, _) | (_, None) => ObstacleState::Unknown,
+            (Some(true), Some(true)) => ObstacleState::ObstacleCenter,
+            (Some(true), Some(false)) => ObstacleState::ObstacleLeft,
+            (Some(false), Some(true)) => ObstacleState::ObstacleRight,
+            (Some(false), Some(false)) => ObstacleState::Clear,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sonar(left: f32, right: f32) -> SonarValues {
+        SonarValues { left, right }
+    }
+
+    /// Feed the same values `frames` times, returning the state after the last cycle.
+    fn feed(detector: &mut ObstacleDetector, values: SonarValues, frames: usize) -> ObstacleState {
+        (0..frames)
+            .map(|_| detector.update(&values))
+            .last()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_classification() {
+        let cases = [
+            (sonar(5.0, 5.0), ObstacleState::Clear),
+            (sonar(2.0, 1.0), ObstacleState::Clear),
+            (sonar(0.3, 5.0), ObstacleState::ObstacleLeft),
+            (sonar(5.0, 0.2), ObstacleState::ObstacleRight),
+            (sonar(0.3, 0.2), ObstacleState::ObstacleCenter),
+            (sonar(0.0, 0.2), ObstacleState::Unknown),
+            (sonar(1.0, f32::NAN), ObstacleState::Unknown),
+        ];
+
+        for (values, expected) in cases {
+            let mut detector = ObstacleDetector::default();
+            assert_eq!(
+                feed(&mut detector, values.clone(), 3),
+                expected,
+                "{values:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_nearest_distance() {
+        let mut detector = ObstacleDetector::default();
+        assert_eq!(detector.nearest_distance(), None);
+
+        feed(&mut detector, sonar(0.3, 0.2), 3);
+        assert_eq!(detector.nearest_distance(), Some(0.2));
+
+        feed(&mut detector, sonar(5.0, 5.0), 3);
+        assert_eq!(detector.state(), ObstacleState::Clear);
+        assert_eq!(detector.nearest_distance(), None);
+    }
+
+    #[test]
+    fn test_error_value() {
+        let mut detector = ObstacleDetector::default();
+        feed(&mut detector, sonar(0.3, 5.0), 3);
+
+        // a single error is suppressed, a persistent one is reported
+        assert_eq!(
+            detector.update(&sonar(0.0, 5.0)),
+            ObstacleState::ObstacleLeft
+        );
+        assert_eq!(
+            feed(&mut detector, sonar(0.0, 5.0), 2),
+            ObstacleState::Unknown
+        );
+        assert_eq!(detector.nearest_distance(), None);
+    }
+
+    #[test]
+    fn test_flicker_suppression() {
+        let mut detector = ObstacleDetector::default();
+        feed(&mut detector, sonar(5.0, 5.0), 3);
+
+        // alternating between an echo and none never confirms either state
+        for i in 0..10 {
+            let left = if i % 2 == 0 { 0.3 } else { 5.0 };
+            assert_eq!(detector.update(&sonar(left, 5.0)), ObstacleState::Clear);
+        }
+
+        // the confirmation only happens in the third consecutive frame
+        assert_eq!(
+            feed(&mut detector, sonar(0.3, 5.0), 2),
+            ObstacleState::Clear
+        );
+        assert_eq!(
+            detector.update(&sonar(0.3, 5.0)),
+            ObstacleState::ObstacleLeft
+        );
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let mut detector = ObstacleDetector::default();
+
+        // between the thresholds, an obstacle is neither detected nor lost
+        assert_eq!(
+            feed(&mut detector, sonar(0.45, 5.0), 3),
+            ObstacleState::Clear
+        );
+        assert_eq!(
+            feed(&mut detector, sonar(0.35, 5.0), 3),
+            ObstacleState::ObstacleLeft
+        );
+        assert_eq!(
+            feed(&mut detector, sonar(0.45, 5.0), 3),
+            ObstacleState::ObstacleLeft
+        );
+        assert_eq!(detector.nearest_distance(), Some(0.45));
+        assert_eq!(
+            feed(&mut detector, sonar(0.55, 5.0), 3),
+            ObstacleState::Clear
+        );
+
+        detector.reset();
+        assert_eq!(detector.state(), ObstacleState::Unknown);
+    }
+}
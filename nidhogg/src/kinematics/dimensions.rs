@@ -0,0 +1,48 @@
+//! Link lengths of the NAO V6 in meters, and angles in radians.
+//!
+//! The values are taken from the [NAO V6 documentation](http://doc.aldebaran.com/2-8/family/nao_technical/links_naov6.html).
+
+/// Distance between the torso and each hip joint along the y-axis.
+pub const HIP_OFFSET_Y: f32 = 0.05;
+
+/// Distance between the torso and the hip joints along the z-axis.
+pub const HIP_OFFSET_Z: f32 = 0.085;
+
+/// Distance between the hip pitch and knee pitch joints.
+pub const THIGH_LENGTH: f32 = 0.1;
+
+/// Distance between the knee pitch and ankle pitch joints.
+pub const TIBIA_LENGTH: f32 = 0.1029;
+
+/// Distance between the ankle roll joint and the sole of the foot.
+pub const FOOT_HEIGHT: f32 = 0.04519;
+
+/// Distance between the torso and each shoulder joint along the y-axis.
+pub const SHOULDER_OFFSET_Y: f32 = 0.098;
+
+/// Distance between the torso and the shoulder joints along the z-axis.
+pub const SHOULDER_OFFSET_Z: f32 = 0.1;
+
+/// Distance between the shoulder roll and elbow yaw joints along the x-axis.
+pub const UPPER_ARM_LENGTH: f32 = 0.105;
+
+/// Distance between the shoulder roll and elbow yaw joints along the y-axis.
+pub const ELBOW_OFFSET_Y: f32 = 0.015;
+
+/// Distance between the elbow roll and wrist yaw joints.
+pub const LOWER_ARM_LENGTH: f32 = 0.05595;
+
+/// Distance between the torso and the head yaw joint along the z-axis.
+pub const NECK_OFFSET_Z: f32 = 0.1265;
+
+/// Position of the top camera relative to the head pitch joint.
+pub const TOP_CAMERA_OFFSET: [f32; 3] = [0.05871, 0.0, 0.06364];
+
+/// Downward pitch of the top camera in radians.
+pub const TOP_CAMERA_PITCH: f32 = 0.0209;
+
+/// Position of the bottom camera relative to the head pitch joint.
+pub const BOTTOM_CAMERA_OFFSET: [f32; 3] = [0.05071, 0.0, 0.01774];
+
+/// Downward pitch of the bottom camera in radians.
+pub const BOTTOM_CAMERA_PITCH: f32 = 0.6929;
@@ -0,0 +1,389 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use nalgebra::{Isometry3, Translation3, Unit, UnitQuaternion, Vector3};
+
+use super::dimensions::{
+    BOTTOM_CAMERA_OFFSET, BOTTOM_CAMERA_PITCH, ELBOW_OFFSET_Y, FOOT_HEIGHT, HIP_OFFSET_Y,
+    HIP_OFFSET_Z, LOWER_ARM_LENGTH, NECK_OFFSET_Z, SHOULDER_OFFSET_Y, SHOULDER_OFFSET_Z,
+    THIGH_LENGTH, TIBIA_LENGTH, TOP_CAMERA_OFFSET, TOP_CAMERA_PITCH, UPPER_ARM_LENGTH,
+};
+use crate::types::{HeadJoints, JointArray, LeftLegJoints, RightLegJoints};
+
+/// Computes the pose of the left sole in the torso frame.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{kinematics::{dimensions, fk_left_leg}, types::LeftLegJoints};
+///
+/// let sole = fk_left_leg(&LeftLegJoints::default());
+///
+/// assert_eq!(sole.translation.y, dimensions::HIP_OFFSET_Y);
+/// ```
+pub fn fk_left_leg(joints: &LeftLegJoints<f32>) -> Isometry3<f32> {
+    leg_to_torso(
+        HIP_OFFSET_Y,
+        left_hip_yaw_pitch(joints.hip_yaw_pitch),
+        LegAngles {
+            hip_roll: joints.hip_roll,
+            hip_pitch: joints.hip_pitch,
+            knee_pitch: joints.knee_pitch,
+            ankle_pitch: joints.ankle_pitch,
+            ankle_roll: joints.ankle_roll,
+        },
+    )
+}
+
+/// Computes the pose of the right sole in the torso frame.
+///
+/// [`RightLegJoints`] does not contain a hip yaw pitch joint, since both legs share the
+/// same hip yaw pitch motor. The angle of the left hip yaw pitch joint should be provided instead.
+pub fn fk_right_leg(joints: &RightLegJoints<f32>, hip_yaw_pitch: f32) -> Isometry3<f32> {
+    leg_to_torso(
+        -HIP_OFFSET_Y,
+        right_hip_yaw_pitch(hip_yaw_pitch),
+        LegAngles {
+            hip_roll: joints.hip_roll,
+            hip_pitch: joints.hip_pitch,
+            knee_pitch: joints.knee_pitch,
+            ankle_pitch: joints.ankle_pitch,
+            ankle_roll: joints.ankle_roll,
+        },
+    )
+}
+
+/// Computes the pose of the left sole in the torso frame, see [`fk_left_leg`].
+pub fn left_sole_to_torso(joints: &JointArray<f32>) -> Isometry3<f32> {
+    fk_left_leg(&joints.left_leg_joints())
+}
+
+/// Computes the pose of the right sole in the torso frame, see [`fk_right_leg`].
+pub fn right_sole_to_torso(joints: &JointArray<f32>) -> Isometry3<f32> {
+    fk_right_leg(&joints.right_leg_joints(), joints.left_hip_yaw_pitch)
+}
+
+/// Computes the pose of the top or bottom camera in the torso frame.
+///
+/// The camera frame has the x-axis pointing along the optical axis.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{kinematics::fk_camera, types::HeadJoints};
+///
+/// let top = fk_camera(&HeadJoints::default(), true);
+/// let bottom = fk_camera(&HeadJoints::default(), false);
+///
+/// assert!(top.translation.z > bottom.translation.z);
+/// ```
+pub fn fk_camera(head: &HeadJoints<f32>, top: bool) -> Isometry3<f32> {
+    let (offset, pitch_angle) = if top {
+        (TOP_CAMERA_OFFSET, TOP_CAMERA_PITCH)
+    } else {
+        (BOTTOM_CAMERA_OFFSET, BOTTOM_CAMERA_PITCH)
+    };
+
+    let [_, head] = head_frames(head);
+
+    head * Isometry3::from(Translation3::new(offset[0], offset[1], offset[2])) * pitch(pitch_angle)
+}
+
+fn left_hip_yaw_pitch(angle: f32) -> UnitQuaternion<f32> {
+    // the hip yaw pitch joint rotates around the axis between the y and -z axis
+    let axis = Unit::new_unchecked(Vector3::new(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+    UnitQuaternion::from_axis_angle(&axis, angle)
+}
+
+fn right_hip_yaw_pitch(angle: f32) -> UnitQuaternion<f32> {
+    // mirrored version of the left hip yaw pitch axis
+    let axis = Unit::new_unchecked(Vector3::new(0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2));
+    UnitQuaternion::from_axis_angle(&axis, angle)
+}
+
+struct LegAngles {
+    hip_roll: f32,
+    hip_pitch: f32,
+    knee_pitch: f32,
+    ankle_pitch: f32,
+    ankle_roll: f32,
+}
+
+fn leg_to_torso(
+    hip_offset_y: f32,
+    hip_yaw_pitch: UnitQuaternion<f32>,
+    angles: LegAngles,
+) -> Isometry3<f32> {
+    let [.., foot] = leg_frames(hip_offset_y, hip_yaw_pitch, angles);
+
+    foot * down(FOOT_HEIGHT)
+}
+
+/// Computes the frames of the leg joints in the torso frame, from the hip yaw pitch to the ankle roll joint.
+fn leg_frames(
+    hip_offset_y: f32,
+    hip_yaw_pitch: UnitQuaternion<f32>,
+    angles: LegAngles,
+) -> [Isometry3<f32>; 6] {
+    let pelvis = Isometry3::from(Translation3::new(0.0, hip_offset_y, -HIP_OFFSET_Z))
+        * rotation(hip_yaw_pitch);
+    let hip = pelvis * roll(angles.hip_roll);
+    let thigh = hip * pitch(angles.hip_pitch);
+    let tibia = thigh * down(THIGH_LENGTH) * pitch(angles.knee_pitch);
+    let ankle = tibia * down(TIBIA_LENGTH) * pitch(angles.ankle_pitch);
+    let foot = ankle * roll(angles.ankle_roll);
+
+    [pelvis, hip, thigh, tibia, ankle, foot]
+}
+
+/// Computes the frames of the left leg joints in the torso frame, from the hip yaw pitch to the ankle roll joint.
+pub(super) fn left_leg_frames(joints: &JointArray<f32>) -> [Isometry3<f32>; 6] {
+    leg_frames(
+        HIP_OFFSET_Y,
+        left_hip_yaw_pitch(joints.left_hip_yaw_pitch),
+        LegAngles {
+            hip_roll: joints.left_hip_roll,
+            hip_pitch: joints.left_hip_pitch,
+            knee_pitch: joints.left_knee_pitch,
+            ankle_pitch: joints.left_ankle_pitch,
+            ankle_roll: joints.left_ankle_roll,
+        },
+    )
+}
+
+/// Computes the frames of the right leg joints in the torso frame, from the hip yaw pitch to the ankle roll joint.
+pub(super) fn right_leg_frames(joints: &JointArray<f32>) -> [Isometry3<f32>; 6] {
+    leg_frames(
+        -HIP_OFFSET_Y,
+        right_hip_yaw_pitch(joints.left_hip_yaw_pitch),
+        LegAngles {
+            hip_roll: joints.right_hip_roll,
+            hip_pitch: joints.right_hip_pitch,
+            knee_pitch: joints.right_knee_pitch,
+            ankle_pitch: joints.right_ankle_pitch,
+            ankle_roll: joints.right_ankle_roll,
+        },
+    )
+}
+
+/// Computes the frames of the head yaw and head pitch joints in the torso frame.
+pub(super) fn head_frames(head: &HeadJoints<f32>) -> [Isometry3<f32>; 2] {
+    let neck = Isometry3::from(Translation3::new(0.0, 0.0, NECK_OFFSET_Z)) * yaw(head.yaw);
+    let head = neck * pitch(head.pitch);
+
+    [neck, head]
+}
+
+/// Computes the frames of the left arm joints in the torso frame, from the shoulder pitch to the wrist yaw joint.
+pub(super) fn left_arm_frames(joints: &JointArray<f32>) -> [Isometry3<f32>; 5] {
+    arm_frames(
+        1.0,
+        [
+            joints.left_shoulder_pitch,
+            joints.left_shoulder_roll,
+            joints.left_elbow_yaw,
+            joints.left_elbow_roll,
+            joints.left_wrist_yaw,
+        ],
+    )
+}
+
+/// Computes the frames of the right arm joints in the torso frame, from the shoulder pitch to the wrist yaw joint.
+pub(super) fn right_arm_frames(joints: &JointArray<f32>) -> [Isometry3<f32>; 5] {
+    arm_frames(
+        -1.0,
+        [
+            joints.right_shoulder_pitch,
+            joints.right_shoulder_roll,
+            joints.right_elbow_yaw,
+            joints.right_elbow_roll,
+            joints.right_wrist_yaw,
+        ],
+    )
+}
+
+/// `side` is `1.0` for the left arm and `-1.0` for the right arm.
+fn arm_frames(side: f32, angles: [f32; 5]) -> [Isometry3<f32>; 5] {
+    let [shoulder_pitch, shoulder_roll, elbow_yaw, elbow_roll, wrist_yaw] = angles;
+
+    let shoulder = Isometry3::from(Translation3::new(
+        0.0,
+        side * SHOULDER_OFFSET_Y,
+        SHOULDER_OFFSET_Z,
+    )) * pitch(shoulder_pitch);
+    let bicep = shoulder * yaw(shoulder_roll);
+    let elbow = bicep
+        * Isometry3::from(Translation3::new(
+            UPPER_ARM_LENGTH,
+            side * ELBOW_OFFSET_Y,
+            0.0,
+        ))
+        * roll(elbow_yaw);
+    let forearm = elbow * yaw(elbow_roll);
+    let hand =
+        forearm * Isometry3::from(Translation3::new(LOWER_ARM_LENGTH, 0.0, 0.0)) * roll(wrist_yaw);
+
+    [shoulder, bicep, elbow, forearm, hand]
+}
+
+fn roll(angle: f32) -> Isometry3<f32> {
+    rotation(UnitQuaternion::from_axis_angle(&Vector3::x_axis(), angle))
+}
+
+fn pitch(angle: f32) -> Isometry3<f32> {
+    rotation(UnitQuaternion::from_axis_angle(&Vector3::y_axis(), angle))
+}
+
+fn yaw(angle: f32) -> Isometry3<f32> {
+    rotation(UnitQuaternion::from_axis_angle(&Vector3::z_axis(), angle))
+}
+
+fn down(length: f32) -> Isometry3<f32> {
+    Isometry3::from(Translation3::new(0.0, 0.0, -length))
+}
+
+fn rotation(rotation: UnitQuaternion<f32>) -> Isometry3<f32> {
+    Isometry3::from_parts(Translation3::identity(), rotation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEG_LENGTH: f32 = HIP_OFFSET_Z + THIGH_LENGTH + TIBIA_LENGTH + FOOT_HEIGHT;
+
+    fn assert_close(a: Vector3<f32>, b: Vector3<f32>) {
+        assert!((a - b).norm() < 1e-5, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn test_zero_position() {
+        let joints = JointArray::default();
+
+        assert_close(
+            left_sole_to_torso(&joints).translation.vector,
+            Vector3::new(0.0, HIP_OFFSET_Y, -LEG_LENGTH),
+        );
+        assert_close(
+            right_sole_to_torso(&joints).translation.vector,
+            Vector3::new(0.0, -HIP_OFFSET_Y, -LEG_LENGTH),
+        );
+    }
+
+    #[test]
+    fn test_knee_bend_raises_sole() {
+        let straight = fk_left_leg(&LeftLegJoints::default());
+        let bent = fk_left_leg(&LeftLegJoints {
+            knee_pitch: 0.5,
+            ..Default::default()
+        });
+
+        let lower_leg = TIBIA_LENGTH + FOOT_HEIGHT;
+        let raised = bent.translation.z - straight.translation.z;
+        assert!((raised - lower_leg * (1.0 - 0.5_f32.cos())).abs() < 1e-3);
+        // the lower leg rotates backwards
+        assert!((bent.translation.x + lower_leg * 0.5_f32.sin()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_right_leg_matches_joint_array() {
+        let joints = JointArray {
+            left_hip_yaw_pitch: -0.3,
+            right_hip_pitch: -0.4,
+            right_knee_pitch: 0.8,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            fk_right_leg(&joints.right_leg_joints(), joints.left_hip_yaw_pitch),
+            right_sole_to_torso(&joints)
+        );
+    }
+
+    #[test]
+    fn test_camera_zero_position() {
+        let head = HeadJoints::default();
+
+        let top = fk_camera(&head, true);
+        assert_close(
+            top.translation.vector,
+            Vector3::new(
+                TOP_CAMERA_OFFSET[0],
+                0.0,
+                NECK_OFFSET_Z + TOP_CAMERA_OFFSET[2],
+            ),
+        );
+        assert!((top.rotation.angle() - TOP_CAMERA_PITCH).abs() < 1e-5);
+
+        let bottom = fk_camera(&head, false);
+        assert_close(
+            bottom.translation.vector,
+            Vector3::new(
+                BOTTOM_CAMERA_OFFSET[0],
+                0.0,
+                NECK_OFFSET_Z + BOTTOM_CAMERA_OFFSET[2],
+            ),
+        );
+    }
+
+    #[test]
+    fn test_camera_follows_head_yaw() {
+        let head = HeadJoints {
+            yaw: std::f32::consts::FRAC_PI_2,
+            pitch: 0.0,
+        };
+
+        let top = fk_camera(&head, true);
+        assert_close(
+            top.translation.vector,
+            Vector3::new(
+                0.0,
+                TOP_CAMERA_OFFSET[0],
+                NECK_OFFSET_Z + TOP_CAMERA_OFFSET[2],
+            ),
+        );
+    }
+
+    #[test]
+    fn test_negative_hip_pitch_moves_foot_forward() {
+        let joints = JointArray {
+            left_hip_pitch: -0.3,
+            left_ankle_pitch: 0.3,
+            ..Default::default()
+        };
+
+        let sole = left_sole_to_torso(&joints);
+        let leg = THIGH_LENGTH + TIBIA_LENGTH;
+
+        assert_close(
+            sole.translation.vector,
+            Vector3::new(
+                leg * 0.3_f32.sin(),
+                HIP_OFFSET_Y,
+                -HIP_OFFSET_Z - leg * 0.3_f32.cos() - FOOT_HEIGHT,
+            ),
+        );
+        // the ankle compensates the hip, so the sole is parallel to the ground
+        assert!(sole.rotation.angle() < 1e-5);
+    }
+
+    #[test]
+    fn test_legs_are_mirrored() {
+        let joints = JointArray {
+            left_hip_yaw_pitch: -0.4,
+            left_hip_roll: 0.1,
+            right_hip_roll: -0.1,
+            left_hip_pitch: -0.5,
+            right_hip_pitch: -0.5,
+            left_knee_pitch: 0.9,
+            right_knee_pitch: 0.9,
+            left_ankle_roll: -0.05,
+            right_ankle_roll: 0.05,
+            ..Default::default()
+        };
+
+        let left = left_sole_to_torso(&joints).translation.vector;
+        let right = right_sole_to_torso(&joints).translation.vector;
+
+        assert_close(left, Vector3::new(right.x, -right.y, right.z));
+    }
+}
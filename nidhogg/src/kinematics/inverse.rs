@@ -0,0 +1,322 @@
+use std::f32::consts::FRAC_PI_4;
+
+use nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3};
+use thiserror::Error;
+
+use super::dimensions::{FOOT_HEIGHT, HIP_OFFSET_Y, HIP_OFFSET_Z, THIGH_LENGTH, TIBIA_LENGTH};
+use crate::{
+    safety::{JointLimit, JOINT_LIMITS},
+    types::{JointName, LeftLegJoints, RightLegJoints},
+};
+
+/// Error returned by [`ik_left_leg`] and [`ik_right_leg`].
+///
+/// Both variants contain the closest solution within the joint limits, so callers can
+/// choose to use it anyway.
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum IkError<J> {
+    /// The target is too far away from, or too close to, the hip.
+    #[error("target pose is out of reach of the leg")]
+    Unreachable { closest: J },
+    /// The target can be reached, but only by moving joints beyond their limits.
+    #[error("reaching the target pose violates the joint limits of {joints:?}")]
+    JointLimits { joints: Vec<JointName>, closest: J },
+}
+
+/// The right leg joints, together with the hip yaw pitch angle shared by both legs.
+pub type RightLegSolution = (RightLegJoints<f32>, f32);
+
+/// Computes the left leg joint positions that place the left sole at `target`, in the torso frame.
+///
+/// This is the inverse of [`fk_left_leg`](super::fk_left_leg).
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::kinematics::{fk_left_leg, ik_left_leg};
+/// use nidhogg::types::LeftLegJoints;
+///
+/// let joints = LeftLegJoints {
+///     hip_pitch: -0.4,
+///     knee_pitch: 0.8,
+///     ankle_pitch: -0.4,
+///     ..Default::default()
+/// };
+///
+/// let solution = ik_left_leg(&fk_left_leg(&joints)).unwrap();
+/// assert!((solution.knee_pitch - 0.8).abs() < 1e-3);
+/// ```
+pub fn ik_left_leg(
+    target: &Isometry3<f32>,
+) -> Result<LeftLegJoints<f32>, IkError<LeftLegJoints<f32>>> {
+    let (angles, reachable) = solve(target, HIP_OFFSET_Y, |thigh| {
+        // undo the tilt of the hip yaw pitch axis, so it aligns with the z-axis
+        let [yaw, roll, pitch] = decompose_zxy(
+            &(UnitQuaternion::from_axis_angle(&Vector3::x_axis(), -FRAC_PI_4) * thigh),
+        );
+        [-yaw, roll + FRAC_PI_4, pitch]
+    });
+
+    let joints = LeftLegJoints {
+        hip_yaw_pitch: angles[0],
+        hip_roll: angles[1],
+        hip_pitch: angles[2],
+        knee_pitch: angles[3],
+        ankle_pitch: angles[4],
+        ankle_roll: angles[5],
+    };
+    let limits = [
+        (JointName::LeftHipYawPitch, JOINT_LIMITS.left_hip_yaw_pitch),
+        (JointName::LeftHipRoll, JOINT_LIMITS.left_hip_roll),
+        (JointName::LeftHipPitch, JOINT_LIMITS.left_hip_pitch),
+        (JointName::LeftKneePitch, JOINT_LIMITS.left_knee_pitch),
+        (JointName::LeftAnklePitch, JOINT_LIMITS.left_ankle_pitch),
+        (JointName::LeftAnkleRoll, JOINT_LIMITS.left_ankle_roll),
+    ];
+
+    check_limits(angles, limits, reachable, |clamped| LeftLegJoints {
+        hip_yaw_pitch: clamped[0],
+        hip_roll: clamped[1],
+        hip_pitch: clamped[2],
+        knee_pitch: clamped[3],
+        ankle_pitch: clamped[4],
+        ankle_roll: clamped[5],
+    })
+    .map(|()| joints)
+}
+
+/// Computes the right leg joint positions that place the right sole at `target`, in the torso frame.
+///
+/// Returns the joints together with the required hip yaw pitch angle, since [`RightLegJoints`]
+/// does not contain the shared hip yaw pitch joint.
+/// This is the inverse of [`fk_right_leg`](super::fk_right_leg).
+pub fn ik_right_leg(
+    target: &Isometry3<f32>,
+) -> Result<RightLegSolution, IkError<RightLegSolution>> {
+    let (angles, reachable) = solve(target, -HIP_OFFSET_Y, |thigh| {
+        // undo the tilt of the hip yaw pitch axis, so it aligns with the z-axis
+        let [yaw, roll, pitch] = decompose_zxy(
+            &(UnitQuaternion::from_axis_angle(&Vector3::x_axis(), FRAC_PI_4) * thigh),
+        );
+        [yaw, roll - FRAC_PI_4, pitch]
+    });
+
+    let to_joints = |angles: [f32; 6]| {
+        (
+            RightLegJoints {
+                hip_roll: angles[1],
+                hip_pitch: angles[2],
+                knee_pitch: angles[3],
+                ankle_pitch: angles[4],
+                ankle_roll: angles[5],
+            },
+            angles[0],
+        )
+    };
+    let limits = [
+        // both hip yaw pitch joints share the same motor and limits
+        (JointName::LeftHipYawPitch, JOINT_LIMITS.left_hip_yaw_pitch),
+        (JointName::RightHipRoll, JOINT_LIMITS.right_hip_roll),
+        (JointName::RightHipPitch, JOINT_LIMITS.right_hip_pitch),
+        (JointName::RightKneePitch, JOINT_LIMITS.right_knee_pitch),
+        (JointName::RightAnklePitch, JOINT_LIMITS.right_ankle_pitch),
+        (JointName::RightAnkleRoll, JOINT_LIMITS.right_ankle_roll),
+    ];
+
+    check_limits(angles, limits, reachable, to_joints).map(|()| to_joints(angles))
+}
+
+/// Solves the leg angles `[hip_yaw_pitch, hip_roll, hip_pitch, knee_pitch, ankle_pitch, ankle_roll]`,
+/// and whether the target is reachable.
+///
+/// `decompose_hip` decomposes the rotation of the thigh into the hip yaw pitch, roll and pitch angles.
+fn solve(
+    target: &Isometry3<f32>,
+    hip_offset_y: f32,
+    decompose_hip: impl FnOnce(&UnitQuaternion<f32>) -> [f32; 3],
+) -> ([f32; 6], bool) {
+    let hip_to_torso = Isometry3::from(Translation3::new(0.0, hip_offset_y, -HIP_OFFSET_Z));
+    let ankle_to_hip = hip_to_torso.inverse() * target * Translation3::new(0.0, 0.0, FOOT_HEIGHT);
+
+    // the position of the hip, relative to the ankle
+    let hip = ankle_to_hip.inverse().translation.vector;
+
+    let max_distance = THIGH_LENGTH + TIBIA_LENGTH;
+    let min_distance = (THIGH_LENGTH - TIBIA_LENGTH).abs();
+    let distance = hip.norm();
+    let reachable = (min_distance..=max_distance).contains(&distance);
+    let distance = distance.clamp(min_distance, max_distance);
+
+    let cos_knee = (distance.powi(2) - THIGH_LENGTH.powi(2) - TIBIA_LENGTH.powi(2))
+        / (2.0 * THIGH_LENGTH * TIBIA_LENGTH);
+    let knee_pitch = cos_knee.clamp(-1.0, 1.0).acos();
+
+    // the hip position in the ankle frame, before applying the ankle rotation
+    let lower_leg_angle =
+        (-THIGH_LENGTH * knee_pitch.sin()).atan2(TIBIA_LENGTH + THIGH_LENGTH * knee_pitch.cos());
+    let ankle_roll = hip.y.atan2(hip.z);
+    let ankle_pitch = lower_leg_angle - hip.x.atan2(hip.y.hypot(hip.z));
+
+    // the remaining rotation is caused by the hip joints
+    let thigh = ankle_to_hip.rotation
+        * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), -ankle_roll)
+        * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), -(knee_pitch + ankle_pitch));
+    let [hip_yaw_pitch, hip_roll, hip_pitch] = decompose_hip(&thigh);
+
+    (
+        [
+            hip_yaw_pitch,
+            hip_roll,
+            hip_pitch,
+            knee_pitch,
+            ankle_pitch,
+            ankle_roll,
+        ],
+        reachable,
+    )
+}
+
+/// Decomposes a rotation into `[z, x, y]` angles, such that `rotation = Rz(z) * Rx(x) * Ry(y)`.
+fn decompose_zxy(rotation: &UnitQuaternion<f32>) -> [f32; 3] {
+    let m = rotation.to_rotation_matrix();
+    let m = m.matrix();
+
+    let x = m[(2, 1)].clamp(-1.0, 1.0).asin();
+    let z = (-m[(0, 1)]).atan2(m[(1, 1)]);
+    let y = (-m[(2, 0)]).atan2(m[(2, 2)]);
+
+    [z, x, y]
+}
+
+fn check_limits<J>(
+    angles: [f32; 6],
+    limits: [(JointName, JointLimit); 6],
+    reachable: bool,
+    to_joints: impl Fn([f32; 6]) -> J,
+) -> Result<(), IkError<J>> {
+    let clamped = std::array::from_fn(|i| limits[i].1.clamp(angles[i]));
+
+    if !reachable {
+        return Err(IkError::Unreachable {
+            closest: to_joints(clamped),
+        });
+    }
+
+    let joints: Vec<JointName> = limits
+        .iter()
+        .zip(angles)
+        .filter(|((_, limit), angle)| !limit.contains(*angle))
+        .map(|((name, _), _)| *name)
+        .collect();
+
+    if joints.is_empty() {
+        Ok(())
+    } else {
+        Err(IkError::JointLimits {
+            joints,
+            closest: to_joints(clamped),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinematics::{fk_left_leg, fk_right_leg};
+
+    fn assert_pose_close(a: &Isometry3<f32>, b: &Isometry3<f32>) {
+        let translation = (a.translation.vector - b.translation.vector).norm();
+        let rotation = a.rotation.angle_to(&b.rotation);
+        assert!(
+            translation < 1e-4 && rotation < 1e-3,
+            "{a:?} != {b:?} ({translation} m, {rotation} rad)"
+        );
+    }
+
+    /// Reachable sole poses, created from a grid of positions and orientations below the hip.
+    fn reachable_targets(side: f32) -> impl Iterator<Item = Isometry3<f32>> {
+        let offsets: [f32; 3] = [-0.03, 0.0, 0.03];
+        let heights = [-0.32, -0.3, -0.28];
+        let angles = [-0.05, 0.0, 0.05];
+
+        offsets.into_iter().flat_map(move |x| {
+            offsets.into_iter().flat_map(move |y| {
+                heights.into_iter().flat_map(move |z| {
+                    angles.into_iter().map(move |roll| {
+                        Isometry3::new(
+                            Vector3::new(x, side * (HIP_OFFSET_Y + y.abs()), z),
+                            Vector3::new(roll, -roll, 0.0),
+                        )
+                    })
+                })
+            })
+        })
+    }
+
+    #[test]
+    fn test_left_roundtrip() {
+        for target in reachable_targets(1.0) {
+            let joints = ik_left_leg(&target).unwrap();
+            assert_pose_close(&fk_left_leg(&joints), &target);
+        }
+    }
+
+    #[test]
+    fn test_right_roundtrip() {
+        for target in reachable_targets(-1.0) {
+            let (joints, hip_yaw_pitch) = ik_right_leg(&target).unwrap();
+            assert_pose_close(&fk_right_leg(&joints, hip_yaw_pitch), &target);
+        }
+    }
+
+    #[test]
+    fn test_hip_yaw_pitch_roundtrip() {
+        let joints = LeftLegJoints {
+            hip_yaw_pitch: -0.3,
+            hip_roll: 0.1,
+            hip_pitch: -0.5,
+            knee_pitch: 1.0,
+            ankle_pitch: -0.5,
+            ankle_roll: -0.1,
+        };
+
+        let solution = ik_left_leg(&fk_left_leg(&joints)).unwrap();
+        assert_pose_close(&fk_left_leg(&solution), &fk_left_leg(&joints));
+        assert!((solution.hip_yaw_pitch - joints.hip_yaw_pitch).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let target = Isometry3::translation(0.0, HIP_OFFSET_Y, -HIP_OFFSET_Z - 0.5);
+
+        let Err(IkError::Unreachable { closest }) = ik_left_leg(&target) else {
+            panic!("target should be unreachable");
+        };
+        // the closest solution is the fully extended leg
+        assert!(closest.knee_pitch.abs() < 1e-3);
+        assert!(matches!(
+            ik_right_leg(&Isometry3::translation(
+                0.0,
+                -HIP_OFFSET_Y,
+                -HIP_OFFSET_Z - 0.5
+            )),
+            Err(IkError::Unreachable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_joint_limits() {
+        // reachable, but requires the toes to point upwards while crouching
+        let target = Isometry3::new(
+            Vector3::new(0.0, HIP_OFFSET_Y, -0.26),
+            Vector3::new(0.0, -0.5, 0.0),
+        );
+
+        let result = ik_left_leg(&target);
+        let Err(IkError::JointLimits { joints, closest }) = result else {
+            panic!("target should violate the joint limits: {result:?}");
+        };
+        assert_eq!(joints, vec![JointName::LeftAnklePitch]);
+        assert!(JOINT_LIMITS.left_ankle_pitch.contains(closest.ankle_pitch));
+    }
+}
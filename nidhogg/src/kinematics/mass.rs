@@ -0,0 +1,200 @@
+use nalgebra::{Isometry3, Point3, Vector3};
+
+use super::forward::{
+    head_frames, left_arm_frames, left_leg_frames, left_sole_to_torso, right_arm_frames,
+    right_leg_frames, right_sole_to_torso,
+};
+use super::Foot;
+use crate::types::JointArray;
+
+/// Mass and center of mass of a single link of the robot.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkMass {
+    /// Mass of the link in kilograms.
+    pub mass: f32,
+    /// Center of mass of the link in meters, relative to the frame of the joint that moves the link.
+    pub center: [f32; 3],
+}
+
+impl LinkMass {
+    const fn new(mass: f32, center: [f32; 3]) -> Self {
+        Self { mass, center }
+    }
+}
+
+/// Mass of the torso, relative to the torso frame.
+pub const TORSO_MASS: LinkMass = LinkMass::new(1.0496, [-0.00413, 0.0, 0.04342]);
+
+/// Masses of the links of the NAO V6, indexed by the joint that moves the link, as documented by Aldebaran.
+///
+/// The mass of each hand is included in the wrist yaw link, so the hand joints have no mass.
+///
+/// See: <http://doc.aldebaran.com/2-8/family/nao_technical/masses_naov6.html>
+pub const LINK_MASSES: JointArray<LinkMass> = JointArray {
+    head_yaw: LinkMass::new(0.07842, [-0.00001, 0.0, -0.02742]),
+    head_pitch: LinkMass::new(0.65937, [0.00109, 0.00146, 0.05719]),
+
+    left_shoulder_pitch: LinkMass::new(0.09304, [-0.00165, -0.02663, 0.00014]),
+    left_shoulder_roll: LinkMass::new(0.15777, [0.02455, 0.00563, 0.0033]),
+    left_elbow_yaw: LinkMass::new(0.06483, [-0.02744, 0.0, -0.00014]),
+    left_elbow_roll: LinkMass::new(0.07761, [0.02556, 0.00281, 0.00076]),
+    left_wrist_yaw: LinkMass::new(0.18533, [0.03434, -0.00088, 0.00308]),
+
+    left_hip_yaw_pitch: LinkMass::new(0.06981, [-0.00781, -0.01114, 0.02661]),
+    left_hip_roll: LinkMass::new(0.14053, [-0.01549, 0.00029, -0.00515]),
+    left_hip_pitch: LinkMass::new(0.38968, [0.00138, 0.00221, -0.05373]),
+    left_knee_pitch: LinkMass::new(0.30142, [0.00453, 0.00225, -0.04936]),
+    left_ankle_pitch: LinkMass::new(0.13416, [0.00045, 0.00029, 0.00685]),
+    left_ankle_roll: LinkMass::new(0.17184, [0.02542, 0.0033, -0.03239]),
+
+    right_shoulder_pitch: LinkMass::new(0.09304, [-0.00165, 0.02663, 0.00014]),
+    right_shoulder_roll: LinkMass::new(0.15777, [0.02455, -0.00563, 0.0033]),
+    right_elbow_yaw: LinkMass::new(0.06483, [-0.02744, 0.0, -0.00014]),
+    right_elbow_roll: LinkMass::new(0.07761, [0.02556, -0.00281, 0.00076]),
+    right_wrist_yaw: LinkMass::new(0.18533, [0.03434, 0.00088, 0.00308]),
+
+    right_hip_roll: LinkMass::new(0.14053, [-0.01549, -0.00029, -0.00515]),
+    right_hip_pitch: LinkMass::new(0.38968, [0.00138, -0.00221, -0.05373]),
+    right_knee_pitch: LinkMass::new(0.30142, [0.00453, -0.00225, -0.04936]),
+    right_ankle_pitch: LinkMass::new(0.13416, [0.00045, -0.00029, 0.00685]),
+    right_ankle_roll: LinkMass::new(0.17184, [0.02542, -0.0033, -0.03239]),
+
+    left_hand: LinkMass::new(0.0, [0.0; 3]),
+    right_hand: LinkMass::new(0.0, [0.0; 3]),
+};
+
+/// Mass of the right pelvis.
+///
+/// Both pelvises are moved by the shared hip yaw pitch joint, so this link has no entry in [`LINK_MASSES`].
+const RIGHT_PELVIS_MASS: LinkMass = LinkMass::new(0.06981, [-0.00781, 0.01114, 0.02661]);
+
+/// Computes the center of mass of the robot in the torso frame.
+///
+/// This does not allocate, so it can be used every cycle.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{kinematics::center_of_mass, types::JointArray};
+///
+/// let com = center_of_mass(&JointArray::default());
+///
+/// // the center of mass lies close to the torso
+/// assert!(com.norm() < 0.05);
+/// ```
+pub fn center_of_mass(joints: &JointArray<f32>) -> Vector3<f32> {
+    let [neck, head] = head_frames(&joints.head_joints());
+    let [l_shoulder, l_bicep, l_elbow, l_forearm, l_hand] = left_arm_frames(joints);
+    let [r_shoulder, r_bicep, r_elbow, r_forearm, r_hand] = right_arm_frames(joints);
+    let [l_pelvis, l_hip, l_thigh, l_tibia, l_ankle, l_foot] = left_leg_frames(joints);
+    let [r_pelvis, r_hip, r_thigh, r_tibia, r_ankle, r_foot] = right_leg_frames(joints);
+
+    let links = [
+        (Isometry3::identity(), TORSO_MASS),
+        (neck, LINK_MASSES.head_yaw),
+        (head, LINK_MASSES.head_pitch),
+        (l_shoulder, LINK_MASSES.left_shoulder_pitch),
+        (l_bicep, LINK_MASSES.left_shoulder_roll),
+        (l_elbow, LINK_MASSES.left_elbow_yaw),
+        (l_forearm, LINK_MASSES.left_elbow_roll),
+        (l_hand, LINK_MASSES.left_wrist_yaw),
+        (r_shoulder, LINK_MASSES.right_shoulder_pitch),
+        (r_bicep, LINK_MASSES.right_shoulder_roll),
+        (r_elbow, LINK_MASSES.right_elbow_yaw),
+        (r_forearm, LINK_MASSES.right_elbow_roll),
+        (r_hand, LINK_MASSES.right_wrist_yaw),
+        (l_pelvis, LINK_MASSES.left_hip_yaw_pitch),
+        (l_hip, LINK_MASSES.left_hip_roll),
+        (l_thigh, LINK_MASSES.left_hip_pitch),
+        (l_tibia, LINK_MASSES.left_knee_pitch),
+        (l_ankle, LINK_MASSES.left_ankle_pitch),
+        (l_foot, LINK_MASSES.left_ankle_roll),
+        (r_pelvis, RIGHT_PELVIS_MASS),
+        (r_hip, LINK_MASSES.right_hip_roll),
+        (r_thigh, LINK_MASSES.right_hip_pitch),
+        (r_tibia, LINK_MASSES.right_knee_pitch),
+        (r_ankle, LINK_MASSES.right_ankle_pitch),
+        (r_foot, LINK_MASSES.right_ankle_roll),
+    ];
+
+    let (weighted, total) = links.iter().fold(
+        (Vector3::zeros(), 0.0),
+        |(weighted, total), (frame, link)| {
+            let center = frame * Point3::from(link.center);
+            (weighted + center.coords * link.mass, total + link.mass)
+        },
+    );
+
+    weighted / total
+}
+
+/// Computes the center of mass of the robot in the frame of the sole of the `support` foot.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{kinematics::{center_of_mass_in_sole_frame, Foot}, types::JointArray};
+///
+/// let com = center_of_mass_in_sole_frame(&JointArray::default(), Foot::Left);
+///
+/// // the center of mass lies above the sole, between both feet
+/// assert!(com.z > 0.3);
+/// assert!(com.y < 0.0);
+/// ```
+pub fn center_of_mass_in_sole_frame(joints: &JointArray<f32>, support: Foot) -> Vector3<f32> {
+    let sole_to_torso = match support {
+        Foot::Left => left_sole_to_torso(joints),
+        Foot::Right => right_sole_to_torso(joints),
+    };
+
+    sole_to_torso
+        .inverse_transform_point(&Point3::from(center_of_mass(joints)))
+        .coords
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Total mass of the NAO V6 as documented by Aldebaran, in kilograms.
+    const PUBLISHED_MASS: f32 = 5.48;
+
+    #[test]
+    fn test_total_mass() {
+        let total = TORSO_MASS.mass
+            + RIGHT_PELVIS_MASS.mass
+            + (&LINK_MASSES)
+                .into_iter()
+                .map(|link| link.mass)
+                .sum::<f32>();
+
+        assert!((total - PUBLISHED_MASS).abs() < 0.15, "{total}");
+    }
+
+    #[test]
+    fn test_zero_pose() {
+        let com = center_of_mass(&JointArray::default());
+
+        // the robot is symmetric, the arms point forward and the legs pull the center of mass below the torso
+        assert!(com.y.abs() < 1e-3, "{com:?}");
+        assert!((0.0..0.04).contains(&com.x), "{com:?}");
+        assert!((-0.06..0.0).contains(&com.z), "{com:?}");
+
+        let sole = center_of_mass_in_sole_frame(&JointArray::default(), Foot::Right);
+        assert!((sole.z - (0.3331 + com.z)).abs() < 0.01, "{sole:?}");
+        assert!((sole.y - super::super::dimensions::HIP_OFFSET_Y).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_leaning_forward_moves_com_forward() {
+        let joints = JointArray {
+            left_hip_pitch: -0.5,
+            right_hip_pitch: -0.5,
+            ..Default::default()
+        };
+
+        let com = center_of_mass_in_sole_frame(&joints, Foot::Left);
+        let upright = center_of_mass_in_sole_frame(&JointArray::default(), Foot::Left);
+        assert!(com.x > upright.x + 0.03, "{com:?}");
+    }
+}
@@ -0,0 +1,20 @@
+//! Kinematics of the NAO V6.
+//!
+//! All poses use the torso frame, which has its origin in the center of the torso
+//! with the x-axis pointing forward, the y-axis pointing left and the z-axis pointing up.
+
+pub mod dimensions;
+mod forward;
+mod inverse;
+mod mass;
+
+pub use forward::{fk_camera, fk_left_leg, fk_right_leg, left_sole_to_torso, right_sole_to_torso};
+pub use inverse::{ik_left_leg, ik_right_leg, IkError, RightLegSolution};
+pub use mass::{center_of_mass, center_of_mass_in_sole_frame, LinkMass, LINK_MASSES, TORSO_MASS};
+
+/// One of the feet of the robot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Foot {
+    Left,
+    Right,
+}
@@ -0,0 +1,209 @@
+//! Basic [`LedAnimation`] implementations.
+
+use std::time::Duration;
+
+use crate::types::{color::RgbF32, FillExt, LeftEar, LeftEye, RightEar, RightEye};
+
+use super::{LedAnimation, LedStateDelta};
+
+/// Blinks the chest and foot LEDs in the provided color.
+///
+/// The LEDs are on during the first half of every period, and off during the second half.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blink {
+    pub color: RgbF32,
+    pub period: Duration,
+}
+
+impl LedAnimation for Blink {
+    fn sample(&self, t: Duration) -> LedStateDelta {
+        let color = if phase(t, self.period) < 0.5 {
+            self.color
+        } else {
+            RgbF32::default()
+        };
+
+        LedStateDelta {
+            chest: Some(color),
+            left_foot: Some(color),
+            right_foot: Some(color),
+            ..Default::default()
+        }
+    }
+}
+
+/// Rotates the segments of the left eye pattern `base`, completing a full rotation every period.
+///
+/// The right eye shows the mirrored pattern, rotating in the same direction as the left eye.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rotate {
+    pub base: LeftEye,
+    pub period: Duration,
+}
+
+impl LedAnimation for Rotate {
+    fn sample(&self, t: Duration) -> LedStateDelta {
+        let steps = (phase(t, self.period) * 8.0) as i32;
+
+        LedStateDelta {
+            left_eye: Some(self.base.clone().rotated(steps)),
+            right_eye: Some(self.base.clone().mirror().rotated(steps)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Displays a fraction in the range `0.0..=1.0` on both ears.
+///
+/// See [`LeftEar::from_fraction`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct EarProgress {
+    pub fraction: f32,
+}
+
+impl LedAnimation for EarProgress {
+    fn sample(&self, _t: Duration) -> LedStateDelta {
+        LedStateDelta {
+            left_ear: Some(LeftEar::from_fraction(self.fraction)),
+            right_ear: Some(RightEar::from_fraction(self.fraction)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Fades both eyes from one color to another over the provided duration.
+///
+/// After the duration has passed, the eyes keep the `to` color.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fade {
+    pub from: RgbF32,
+    pub to: RgbF32,
+    pub duration: Duration,
+}
+
+impl LedAnimation for Fade {
+    fn sample(&self, t: Duration) -> LedStateDelta {
+        let progress = if self.duration.is_zero() {
+            1.0
+        } else {
+            (t.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+        };
+
+        let color = self.from.lerp(self.to, progress);
+
+        LedStateDelta {
+            left_eye: Some(LeftEye::fill(color)),
+            right_eye: Some(RightEye::fill(color)),
+            ..Default::default()
+        }
+    }
+}
+
+/// The position within the current period, in the range `0.0..1.0`.
+pub(super) fn phase(t: Duration, period: Duration) -> f32 {
+    if period.is_zero() {
+        return 0.0;
+    }
+
+    (t.as_nanos() % period.as_nanos()) as f32 / period.as_nanos() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::color;
+
+    fn marked_eye() -> LeftEye {
+        LeftEye {
+            led0: color::f32::RED,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rotate_wraps() {
+        let rotate = Rotate {
+            base: marked_eye(),
+            period: Duration::from_millis(800),
+        };
+
+        let at = |millis| {
+            rotate
+                .sample(Duration::from_millis(millis))
+                .left_eye
+                .unwrap()
+        };
+
+        assert_eq!(at(0).led0, color::f32::RED);
+        assert_eq!(at(100).led7, color::f32::RED);
+        assert_eq!(at(750).led1, color::f32::RED);
+        assert_eq!(at(750).led0, RgbF32::default());
+        // a full period wraps around to the start
+        assert_eq!(at(800), marked_eye());
+        assert_eq!(at(900).led7, color::f32::RED);
+    }
+
+    #[test]
+    fn test_rotate_right_eye_is_mirrored() {
+        let rotate = Rotate {
+            base: marked_eye(),
+            period: Duration::from_millis(800),
+        };
+
+        let delta = rotate.sample(Duration::from_millis(300));
+        assert_eq!(delta.right_eye.unwrap(), marked_eye().mirror().rotated(3));
+    }
+
+    #[test]
+    fn test_fade_interpolates_linearly() {
+        let fade = Fade {
+            from: RgbF32::new(0.0, 1.0, 0.5),
+            to: RgbF32::new(1.0, 0.0, 0.5),
+            duration: Duration::from_secs(2),
+        };
+
+        let at = |millis| {
+            fade.sample(Duration::from_millis(millis))
+                .left_eye
+                .unwrap()
+                .led0
+        };
+
+        assert_eq!(at(0), RgbF32::new(0.0, 1.0, 0.5));
+        assert_eq!(at(500), RgbF32::new(0.25, 0.75, 0.5));
+        assert_eq!(at(1000), RgbF32::new(0.5, 0.5, 0.5));
+        assert_eq!(at(2000), RgbF32::new(1.0, 0.0, 0.5));
+        assert_eq!(at(5000), RgbF32::new(1.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn test_blink() {
+        let blink = Blink {
+            color: color::f32::BLUE,
+            period: Duration::from_secs(1),
+        };
+
+        assert_eq!(blink.sample(Duration::ZERO).chest, Some(color::f32::BLUE));
+        assert_eq!(
+            blink.sample(Duration::from_millis(600)).chest,
+            Some(RgbF32::default())
+        );
+        assert_eq!(
+            blink.sample(Duration::from_millis(1200)).left_foot,
+            Some(color::f32::BLUE)
+        );
+        assert_eq!(blink.sample(Duration::ZERO).left_eye, None);
+    }
+
+    #[test]
+    fn test_ear_progress() {
+        let ears = EarProgress { fraction: 0.3 }.sample(Duration::ZERO);
+        let left = ears.left_ear.unwrap();
+
+        assert_eq!((left.led0, left.led2, left.led3), (1.0, 1.0, 0.0));
+        assert_eq!(ears.right_ear.unwrap().led2, 1.0);
+
+        let full = EarProgress { fraction: 3.0 }.sample(Duration::ZERO);
+        assert_eq!(full.left_ear, Some(LeftEar::fill(1.0)));
+    }
+}
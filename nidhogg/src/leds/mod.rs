@@ -0,0 +1,183 @@
+//! Animations for the LEDs of the robot.
+//!
+//! An [`LedAnimation`] produces an [`LedStateDelta`] for any point in time, containing only the
+//! LED groups it controls. Multiple animations can be composed using an [`AnimationPlayer`].
+//!
+//! [`StatusLeds`] shows the connection and battery state of the robot, on top of other LED output.
+
+mod animations;
+mod player;
+mod status;
+
+use std::time::Duration;
+
+use crate::{
+    types::{color::RgbF32, FillExt, LeftEar, LeftEye, RightEar, RightEye, Skull},
+    NaoControlMessage,
+};
+use nidhogg_derive::Builder;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bevy")]
+use bevy_ecs::prelude::Resource;
+
+pub use animations::{Blink, EarProgress, Fade, Rotate};
+pub use player::AnimationPlayer;
+pub use status::{ConnectionColors, ConnectionStatus, StatusLeds, StatusLedsConfig};
+
+/// An animation for (a subset of) the LEDs of the robot.
+pub trait LedAnimation: Send + Sync {
+    /// Sample the animation at time `t`, relative to the start of the animation.
+    fn sample(&self, t: Duration) -> LedStateDelta;
+}
+
+/// The state of all LEDs of the robot.
+///
+/// This contains the same LED fields as [`NaoControlMessage`], so code that only manages LEDs
+/// does not need the full control message. Use [`NaoControlMessage::leds`] and
+/// [`NaoControlMessage::set_leds`] to move between the two, or
+/// [`NaoControlMessageBuilder::leds`](crate::NaoControlMessageBuilder::leds) when building a message.
+///
+/// # Migrating
+///
+/// The LED fields of [`NaoControlMessage`] are unchanged, existing code that sets them directly
+/// keeps working. Code that copies all LED fields from one message to another can be replaced by:
+///
+/// ```
+/// use nidhogg::NaoControlMessage;
+///
+/// let previous = NaoControlMessage::default();
+/// let mut msg = NaoControlMessage::default();
+///
+/// msg.set_leds(previous.leds());
+/// ```
+#[derive(Builder, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "bevy", derive(Resource))]
+pub struct LedState {
+    pub left_ear: LeftEar,
+    pub right_ear: RightEar,
+    pub chest: RgbF32,
+    pub left_eye: LeftEye,
+    pub right_eye: RightEye,
+    pub left_foot: RgbF32,
+    pub right_foot: RgbF32,
+    pub skull: Skull,
+}
+
+impl LedState {
+    /// Returns a copy with every LED of both eyes set to `color`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{leds::LedState, types::color};
+    ///
+    /// let leds = LedState::default().fill_eyes(color::f32::BLUE);
+    ///
+    /// assert_eq!(leds.left_eye.led3, color::f32::BLUE);
+    /// assert_eq!(leds.right_eye.led7, color::f32::BLUE);
+    /// assert_eq!(leds.chest, Default::default());
+    /// ```
+    #[must_use]
+    pub fn fill_eyes(mut self, color: RgbF32) -> LedState {
+        self.left_eye = LeftEye::fill(color);
+        self.right_eye = RightEye::fill(color);
+        self
+    }
+
+    /// Returns a copy with every LED of both ears set to `intensity`.
+    #[must_use]
+    pub fn fill_ears(mut self, intensity: f32) -> LedState {
+        self.left_ear = LeftEar::fill(intensity);
+        self.right_ear = RightEar::fill(intensity);
+        self
+    }
+
+    /// Returns a copy with both feet set to `color`.
+    #[must_use]
+    pub fn fill_feet(mut self, color: RgbF32) -> LedState {
+        self.left_foot = color;
+        self.right_foot = color;
+        self
+    }
+}
+
+impl From<LedState> for LedStateDelta {
+    /// Create a delta that sets every LED group.
+    fn from(state: LedState) -> Self {
+        LedStateDelta {
+            left_ear: Some(state.left_ear),
+            right_ear: Some(state.right_ear),
+            chest: Some(state.chest),
+            left_eye: Some(state.left_eye),
+            right_eye: Some(state.right_eye),
+            left_foot: Some(state.left_foot),
+            right_foot: Some(state.right_foot),
+            skull: Some(state.skull),
+        }
+    }
+}
+
+/// The LED groups set by an [`LedAnimation`].
+///
+/// Groups that are [`None`] are not controlled by the animation and are left untouched.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LedStateDelta {
+    pub left_ear: Option<LeftEar>,
+    pub right_ear: Option<RightEar>,
+    pub chest: Option<RgbF32>,
+    pub left_eye: Option<LeftEye>,
+    pub right_eye: Option<RightEye>,
+    pub left_foot: Option<RgbF32>,
+    pub right_foot: Option<RgbF32>,
+    pub skull: Option<Skull>,
+}
+
+impl LedStateDelta {
+    /// Merge `other` on top of this delta, groups set in `other` take precedence.
+    #[must_use]
+    pub fn merge(self, other: LedStateDelta) -> LedStateDelta {
+        LedStateDelta {
+            left_ear: other.left_ear.or(self.left_ear),
+            right_ear: other.right_ear.or(self.right_ear),
+            chest: other.chest.or(self.chest),
+            left_eye: other.left_eye.or(self.left_eye),
+            right_eye: other.right_eye.or(self.right_eye),
+            left_foot: other.left_foot.or(self.left_foot),
+            right_foot: other.right_foot.or(self.right_foot),
+            skull: other.skull.or(self.skull),
+        }
+    }
+
+    /// Write the groups contained in this delta to the provided [`NaoControlMessage`].
+    pub fn apply(self, msg: &mut NaoControlMessage) {
+        if let Some(left_ear) = self.left_ear {
+            msg.left_ear = left_ear;
+        }
+        if let Some(right_ear) = self.right_ear {
+            msg.right_ear = right_ear;
+        }
+        if let Some(chest) = self.chest {
+            msg.chest = chest;
+        }
+        if let Some(left_eye) = self.left_eye {
+            msg.left_eye = left_eye;
+        }
+        if let Some(right_eye) = self.right_eye {
+            msg.right_eye = right_eye;
+        }
+        if let Some(left_foot) = self.left_foot {
+            msg.left_foot = left_foot;
+        }
+        if let Some(right_foot) = self.right_foot {
+            msg.right_foot = right_foot;
+        }
+        if let Some(skull) = self.skull {
+            msg.skull = skull;
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Implements the [`AnimationPlayer`], which composes multiple [`LedAnimation`]s.
+
+use std::{fmt, time::Duration};
+
+use crate::NaoControlMessage;
+
+use super::{LedAnimation, LedStateDelta};
+
+/// Plays multiple [`LedAnimation`]s at the same time.
+///
+/// Animations are layered in the order they are added, so when two animations control
+/// the same LED group the one added last wins.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use nidhogg::{
+///     leds::{AnimationPlayer, Blink, EarProgress},
+///     types::color,
+///     NaoControlMessage,
+/// };
+///
+/// let mut player = AnimationPlayer::default()
+///     .with(Blink { color: color::f32::GREEN, period: Duration::from_secs(1) })
+///     .with(EarProgress { fraction: 0.5 });
+///
+/// let mut msg = NaoControlMessage::default();
+/// player.update(Duration::from_millis(12)).apply(&mut msg);
+/// assert_eq!(msg.chest, color::f32::GREEN);
+/// ```
+#[derive(Default)]
+pub struct AnimationPlayer {
+    animations: Vec<Box<dyn LedAnimation>>,
+    time: Duration,
+}
+
+impl fmt::Debug for AnimationPlayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnimationPlayer")
+            .field("animations", &self.animations.len())
+            .field("time", &self.time)
+            .finish()
+    }
+}
+
+impl AnimationPlayer {
+    /// Add an animation on top of the current animations.
+    #[must_use]
+    pub fn with(mut self, animation: impl LedAnimation + 'static) -> Self {
+        self.push(animation);
+        self
+    }
+
+    /// Add an animation on top of the current animations.
+    pub fn push(&mut self, animation: impl LedAnimation + 'static) {
+        self.animations.push(Box::new(animation));
+    }
+
+    /// Remove all animations and reset the time.
+    pub fn clear(&mut self) {
+        self.animations.clear();
+        self.reset();
+    }
+
+    /// Restart all animations from the beginning.
+    pub fn reset(&mut self) {
+        self.time = Duration::ZERO;
+    }
+
+    /// The time since the animations were started.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Sample all animations at time `t`.
+    pub fn sample(&self, t: Duration) -> LedStateDelta {
+        self.animations
+            .iter()
+            .fold(LedStateDelta::default(), |delta, animation| {
+                delta.merge(animation.sample(t))
+            })
+    }
+
+    /// Advance the animations by `elapsed` and return the resulting LED state.
+    pub fn update(&mut self, elapsed: Duration) -> LedStateDelta {
+        self.time += elapsed;
+        self.sample(self.time)
+    }
+
+    /// Advance the animations by `elapsed` and write the resulting LED state to `msg`.
+    pub fn update_message(&mut self, elapsed: Duration, msg: &mut NaoControlMessage) {
+        self.update(elapsed).apply(msg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        leds::{Blink, Fade},
+        types::{color, FillExt, LeftEye},
+    };
+
+    #[test]
+    fn test_later_animations_take_precedence() {
+        let mut player = AnimationPlayer::default()
+            .with(Fade {
+                from: color::f32::RED,
+                to: color::f32::RED,
+                duration: Duration::from_secs(1),
+            })
+            .with(Blink {
+                color: color::f32::BLUE,
+                period: Duration::from_secs(1),
+            })
+            .with(Fade {
+                from: color::f32::GREEN,
+                to: color::f32::GREEN,
+                duration: Duration::from_secs(1),
+            });
+
+        let mut msg = NaoControlMessage::default();
+        player.update_message(Duration::from_millis(100), &mut msg);
+
+        assert_eq!(msg.left_eye, LeftEye::fill(color::f32::GREEN));
+        assert_eq!(msg.chest, color::f32::BLUE);
+        assert_eq!(msg.skull, Default::default());
+    }
+
+    #[test]
+    fn test_update_accumulates_time() {
+        let mut player = AnimationPlayer::default().with(Blink {
+            color: color::f32::BLUE,
+            period: Duration::from_secs(1),
+        });
+
+        assert_eq!(
+            player.update(Duration::from_millis(400)).chest,
+            Some(color::f32::BLUE)
+        );
+        assert_eq!(
+            player.update(Duration::from_millis(400)).chest,
+            Some(Default::default())
+        );
+        assert_eq!(player.time(), Duration::from_millis(800));
+
+        player.reset();
+        assert_eq!(player.time(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,256 @@
+//! Implements [`StatusLeds`], which shows the connection and battery state of the robot on its LEDs.
+
+use std::time::Duration;
+
+use crate::{
+    types::{color, color::RgbF32, LeftEar, RightEar},
+    NaoState,
+};
+
+use super::{animations::phase, LedStateDelta};
+
+/// State of the connection with the robot, or with whatever the robot is controlled by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ConnectionStatus {
+    /// The connection is healthy.
+    #[default]
+    Ok,
+    /// The connection was lost, and is being restored.
+    Reconnecting,
+    /// The connection was lost.
+    Lost,
+}
+
+#[cfg(feature = "bevy")]
+impl From<&crate::bevy::NaoConnectionStatus> for ConnectionStatus {
+    fn from(status: &crate::bevy::NaoConnectionStatus) -> Self {
+        match status {
+            crate::bevy::NaoConnectionStatus::Connected => ConnectionStatus::Ok,
+            crate::bevy::NaoConnectionStatus::Disconnected
+            | crate::bevy::NaoConnectionStatus::Failed(_) => ConnectionStatus::Lost,
+        }
+    }
+}
+
+/// Chest colors used by [`StatusLeds`] for each [`ConnectionStatus`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionColors {
+    pub ok: RgbF32,
+    pub reconnecting: RgbF32,
+    pub lost: RgbF32,
+}
+
+impl Default for ConnectionColors {
+    fn default() -> Self {
+        Self {
+            ok: color::f32::BLUE,
+            reconnecting: color::f32::YELLOW,
+            lost: color::f32::RED,
+        }
+    }
+}
+
+impl ConnectionColors {
+    /// The color for the provided connection status.
+    pub fn color(&self, status: ConnectionStatus) -> RgbF32 {
+        match status {
+            ConnectionStatus::Ok => self.ok,
+            ConnectionStatus::Reconnecting => self.reconnecting,
+            ConnectionStatus::Lost => self.lost,
+        }
+    }
+}
+
+/// Configuration for the [`StatusLeds`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusLedsConfig {
+    /// Chest colors for each connection status.
+    pub connection: ConnectionColors,
+    /// Battery charge in the range `0.0..=1.0` below which the feet blink.
+    pub battery_low_threshold: f32,
+    /// Amount the charge has to rise above [`StatusLedsConfig::battery_low_threshold`] before the feet stop blinking,
+    /// which prevents flickering when the charge is close to the threshold.
+    pub battery_hysteresis: f32,
+    /// Color the feet blink in while the battery is low.
+    pub battery_low_color: RgbF32,
+    /// Period of the blinking feet.
+    pub blink_period: Duration,
+    /// Show the battery charge on the ears.
+    pub battery_on_ears: bool,
+}
+
+impl Default for StatusLedsConfig {
+    fn default() -> Self {
+        Self {
+            connection: ConnectionColors::default(),
+            battery_low_threshold: 0.2,
+            battery_hysteresis: 0.02,
+            battery_low_color: color::f32::RED,
+            blink_period: Duration::from_secs(1),
+            battery_on_ears: true,
+        }
+    }
+}
+
+/// Shows the connection state on the chest, and the battery state on the ears and feet.
+///
+/// Only the LED groups owned by the status LEDs are set in the returned [`LedStateDelta`], so it can
+/// be applied on top of other LED output. The chest is always owned, the ears only if
+/// [`StatusLedsConfig::battery_on_ears`] is set, and the feet only while the battery is low.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use nidhogg::{leds::{ConnectionStatus, StatusLeds}, types::color, NaoControlMessage, NaoState};
+///
+/// let mut status = StatusLeds::default();
+/// let mut state = NaoState::default();
+/// state.battery.charge = 0.1;
+///
+/// // other LED output, the right foot is overridden while the battery is low
+/// let mut msg = NaoControlMessage::builder().right_foot(color::f32::GREEN).build();
+///
+/// status.update(&state, ConnectionStatus::Ok, Duration::ZERO).apply(&mut msg);
+/// assert_eq!(msg.chest, color::f32::BLUE);
+/// assert_eq!(msg.right_foot, color::f32::RED);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StatusLeds {
+    config: StatusLedsConfig,
+    battery_low: bool,
+}
+
+impl StatusLeds {
+    /// Create new [`StatusLeds`] using the provided configuration.
+    pub fn new(config: StatusLedsConfig) -> Self {
+        Self {
+            config,
+            battery_low: false,
+        }
+    }
+
+    /// The configuration used by these status LEDs.
+    pub fn config(&self) -> &StatusLedsConfig {
+        &self.config
+    }
+
+    /// Returns `true` if the battery was low during the last update.
+    pub fn battery_low(&self) -> bool {
+        self.battery_low
+    }
+
+    /// Update the status LEDs with the current state, returning the LED groups they own.
+    ///
+    /// `t` is used for blinking, and can be any steadily increasing time such as the time since startup.
+    pub fn update(
+        &mut self,
+        state: &NaoState,
+        connection: ConnectionStatus,
+        t: Duration,
+    ) -> LedStateDelta {
+        let charge = state.battery.charge;
+        self.battery_low = if self.battery_low {
+            charge < self.config.battery_low_threshold + self.config.battery_hysteresis
+        } else {
+            charge < self.config.battery_low_threshold
+        };
+
+        let feet = self.battery_low.then(|| {
+            if phase(t, self.config.blink_period) < 0.5 {
+                self.config.battery_low_color
+            } else {
+                RgbF32::default()
+            }
+        });
+
+        let (left_ear, right_ear) = if self.config.battery_on_ears {
+            (
+                Some(LeftEar::from_fraction(charge)),
+                Some(RightEar::from_fraction(charge)),
+            )
+        } else {
+            (None, None)
+        };
+
+        LedStateDelta {
+            chest: Some(self.config.connection.color(connection)),
+            left_foot: feet,
+            right_foot: feet,
+            left_ear,
+            right_ear,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(charge: f32) -> NaoState {
+        let mut state = NaoState::default();
+        state.battery.charge = charge;
+        state
+    }
+
+    #[test]
+    fn test_connection_colors() {
+        let mut status = StatusLeds::default();
+
+        for (connection, expected) in [
+            (ConnectionStatus::Ok, color::f32::BLUE),
+            (ConnectionStatus::Reconnecting, color::f32::YELLOW),
+            (ConnectionStatus::Lost, color::f32::RED),
+        ] {
+            let delta = status.update(&state(1.0), connection, Duration::ZERO);
+            assert_eq!(delta.chest, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_battery_threshold_crossings() {
+        let mut status = StatusLeds::default();
+        let mut feet_at = |charge| {
+            status
+                .update(&state(charge), ConnectionStatus::Ok, Duration::ZERO)
+                .left_foot
+        };
+
+        assert_eq!(feet_at(0.5), None);
+        assert_eq!(feet_at(0.21), None);
+        assert_eq!(feet_at(0.19), Some(color::f32::RED));
+        // within the hysteresis the battery stays low
+        assert_eq!(feet_at(0.21), Some(color::f32::RED));
+        assert_eq!(feet_at(0.23), None);
+    }
+
+    #[test]
+    fn test_blink_phase() {
+        let mut status = StatusLeds::new(StatusLedsConfig {
+            blink_period: Duration::from_millis(400),
+            battery_on_ears: false,
+            ..Default::default()
+        });
+        let mut feet_at = |millis| {
+            status
+                .update(
+                    &state(0.1),
+                    ConnectionStatus::Ok,
+                    Duration::from_millis(millis),
+                )
+                .right_foot
+                .unwrap()
+        };
+
+        assert_eq!(feet_at(0), color::f32::RED);
+        assert_eq!(feet_at(199), color::f32::RED);
+        assert_eq!(feet_at(200), RgbF32::default());
+        assert_eq!(feet_at(399), RgbF32::default());
+        assert_eq!(feet_at(400), color::f32::RED);
+
+        let delta = status.update(&state(0.1), ConnectionStatus::Ok, Duration::ZERO);
+        assert_eq!(delta.left_ear, None);
+        assert_eq!(delta.skull, None);
+    }
+}
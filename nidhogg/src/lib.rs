@@ -1,6 +1,9 @@
 // TODO: disallow missing docs
 #![deny(missing_debug_implementations, nonstandard_style)]
 #![warn(unreachable_pub, rust_2018_idioms)]
+// Log through `tracing` instead, so the output of applications is not polluted.
+#![deny(clippy::print_stdout, clippy::print_stderr)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 //! A high level abstraction layer for interfacing with NAO V6 robots.
 //!
@@ -19,7 +22,8 @@
 //! 🚧: Work in progress
 //!
 //! # Example
-//! ```no_run
+#![cfg_attr(feature = "lola", doc = "```no_run")]
+#![cfg_attr(not(feature = "lola"), doc = "```ignore")]
 //! use nidhogg::{
 //!     backend::LolaBackend,
 //!     NaoBackend,
@@ -32,17 +36,94 @@
 //! let state = nao.read_nao_state().expect("Failed to retrieve sensor data!");
 //! ```
 //!
+//! ## Public API
+//! The [`prelude`] re-exports the traits and types needed by most applications, use `nidhogg::prelude::*`
+//! instead of importing them one by one.
+//!
+//! Everything else is grouped by purpose: [`types`] contains the joint and LED types, [`backend`] the
+//! backends including the `LoLA` wire format, and modules such as [`safety`] and [`motion`] build on top of them.
+//!
+//! ## `no_std`
+//! Without the default `std` feature, only the [`types`] module is available, which then requires
+//! nothing but `core` and `alloc`. This allows tools without an operating system to share the joint,
+//! LED and sensor types with the robot. All other features require `std`.
+//!
+//! ```toml
+//! nidhogg = { version = "0.8", default-features = false }
+//! ```
+//!
+
+// The types use `alloc` directly, so they are the same with and without `std`.
+extern crate alloc;
 
+#[cfg(feature = "std")]
 pub mod backend;
+#[cfg(feature = "bevy")]
+pub mod bevy;
+#[cfg(feature = "std")]
+pub mod concurrent;
+#[cfg(feature = "serde")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod control;
+#[cfg(feature = "std")]
+pub mod diagnostics;
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(feature = "std")]
 mod error;
+#[cfg(feature = "std")]
+pub mod facade;
+#[cfg(feature = "std")]
+mod identity;
+#[cfg(feature = "std")]
+pub mod imu;
+#[cfg(feature = "std")]
+pub mod input;
+#[cfg(feature = "std")]
+pub mod kinematics;
+#[cfg(feature = "std")]
+pub mod leds;
+#[cfg(feature = "std")]
+pub mod motion;
+#[cfg(feature = "std")]
+pub mod odometry;
+#[cfg(feature = "std")]
+pub mod poses;
+#[cfg(feature = "std")]
+pub mod prelude;
+#[cfg(feature = "ros")]
+pub mod ros;
+#[cfg(feature = "std")]
+pub mod safety;
+#[cfg(feature = "std")]
+mod summary;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod types;
 
+#[cfg(feature = "std")]
+use backend::BackendCapabilities;
+#[cfg(feature = "std")]
+pub use diff::{ControlDiff, JointChange, StateDiff, TouchEdge};
+#[cfg(feature = "std")]
 pub use error::{Error, Result};
+#[cfg(feature = "std")]
+pub use identity::RobotIdentity;
+#[cfg(feature = "std")]
+use leds::LedState;
+#[cfg(feature = "std")]
 use nalgebra::{Vector2, Vector3};
+#[cfg(feature = "std")]
 use nidhogg_derive::Builder;
+#[cfg(feature = "std")]
+pub use summary::StateSummary;
+#[cfg(feature = "std")]
 use types::{
-    color::RgbF32, Battery, FillExt, Fsr, JointArray, LeftEar, LeftEye, RightEar, RightEye, Skull,
-    SonarEnabled, SonarValues, Touch,
+    color::RgbF32,
+    units::{Degrees, Radians},
+    Battery, FillExt, Fsr, JointArray, JointMask, LeftEar, LeftEye, RightEar, RightEye, Skull,
+    SonarMode, SonarValues, Touch,
 };
 
 #[cfg(feature = "serde")]
@@ -52,11 +133,13 @@ use serde::{Deserialize, Serialize};
 use bevy_ecs::prelude::Resource;
 
 /// Generic backend trait used for implementing a NAO interface.
+#[cfg(feature = "std")]
 pub trait NaoBackend: Sized {
     /// Connects to a NAO backend
     ///
     /// # Examples
-    /// ```no_run
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
     /// use nidhogg::{NaoBackend, backend::LolaBackend};
     ///
     /// // We connect to a real NAO using the LoLA backend
@@ -67,7 +150,8 @@ pub trait NaoBackend: Sized {
     /// Converts a control message to the format required by the backend and writes it to that backend.
     ///
     /// # Examples
-    /// ```no_run
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
     /// use nidhogg::{NaoBackend, NaoControlMessage, backend::LolaBackend, types::color};
     ///
     /// let mut nao = LolaBackend::connect().unwrap();
@@ -83,7 +167,8 @@ pub trait NaoBackend: Sized {
     /// Reads the current sensor data from the chosen backend
     ///
     /// # Examples
-    /// ```no_run
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
     /// use nidhogg::{NaoBackend, backend::LolaBackend};
     ///
     /// let mut nao = LolaBackend::connect().unwrap();
@@ -92,14 +177,33 @@ pub trait NaoBackend: Sized {
     /// let state = nao.read_nao_state().expect("Failed to retrieve sensor data!");
     /// ```
     fn read_nao_state(&mut self) -> Result<NaoState>;
+
+    /// The sensor data and timing guarantees this backend provides.
+    ///
+    /// The default implementation is conservative, and reports [`BackendCapabilities::NONE`].
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
+    /// use nidhogg::{NaoBackend, backend::LolaBackend};
+    ///
+    /// let nao = LolaBackend::connect().unwrap();
+    ///
+    /// assert!(nao.capabilities().has_fsr);
+    /// ```
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities::NONE
+    }
 }
 
 /// Generic backend extension to support disconnecting.
+#[cfg(feature = "std")]
 pub trait DisconnectExt {
     /// Disconnects a NAO backend
     ///
     /// # Examples
-    /// ```no_run
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
     /// use nidhogg::{DisconnectExt, NaoBackend, backend::LolaBackend};
     ///
     /// // We connect to a real NAO using the LoLA backend
@@ -112,7 +216,8 @@ pub trait DisconnectExt {
 }
 
 /// High level representation of the `LoLA` state message.
-#[derive(Debug, Clone, PartialEq)]
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Resource))]
 pub struct NaoState {
@@ -122,6 +227,7 @@ pub struct NaoState {
     ///
     /// The Accelerometer measures the proper acceleration along three axes (x, y, and z)
     /// in meters per second squared (m/s²). The Z axis is facing up.
+    /// See the [`imu`] module for the sign of each axis in different poses.
     ///
     /// Position relative to the torso frame: (-0.008, 0.00606, 0.027) in meters.
     pub accelerometer: Vector3<f32>,
@@ -129,6 +235,7 @@ pub struct NaoState {
     ///
     /// The Gyroscope provides direct measurements of the rotational speed along
     /// three axes (x, y and z) in radians per second (rad/s). The Z axis is facing up.
+    /// See the [`imu`] module for the sign conventions.
     ///
     /// Position relative to the torso frame: (-0.008, 0.006, 0.029) in meters.
     pub gyroscope: Vector3<f32>,
@@ -160,15 +267,43 @@ pub struct NaoState {
 }
 
 /// High level representation of the `LoLA` update message.
+///
+/// When deserializing, missing fields fall back to the values of [`NaoControlMessage::default`].
+///
+/// Use [`NaoControlMessage::holding`] to create a message that keeps all joints at their measured position,
+/// or leave joints out of [`position_mask`](NaoControlMessage::position_mask) to not command them at all.
+///
+/// The `position` and `stiffness` fields are required when using [`NaoControlMessageBuilder::try_build`].
+#[cfg(feature = "std")]
 #[derive(Builder, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[cfg_attr(feature = "bevy", derive(Resource))]
 pub struct NaoControlMessage {
+    /// The requested joint positions in radians.
+    ///
+    /// Only the joints selected by `position_mask` are commanded.
+    /// The default message fills this with `-1.0`, which is a valid position for several joints,
+    /// so an "empty" default message still moves those joints.
+    #[builder(required, skip_setter)]
     pub position: JointArray<f32>,
+    /// The joints whose `position` is commanded.
+    ///
+    /// Joints that are not selected keep their measured position: when the message is sent, the backend
+    /// replaces their position with the position it last read, see [`NaoControlMessage::hold_uncommanded`].
+    ///
+    /// The default message selects every joint. The builder only selects joints once a position is set,
+    /// so a message built without a position does not move any joint.
+    pub position_mask: JointMask,
+    #[builder(required)]
     pub stiffness: JointArray<f32>,
-    /// The sonar state for the left and right sonar.
+    /// The emitters used by the sonar sensors.
     ///
-    /// In the default state both sonars are disabled.
-    pub sonar: SonarEnabled,
+    /// In the default state both sonars are enabled, which matches `LoLA` after power-on.
+    /// Use [`SonarMode::Off`] to disable them.
+    /// The builder also accepts the deprecated [`SonarEnabled`](types::SonarEnabled).
+    #[builder(skip_setter)]
+    pub sonar: SonarMode,
 
     // LEDs
     pub left_ear: LeftEar,
@@ -181,12 +316,20 @@ pub struct NaoControlMessage {
     pub skull: Skull,
 }
 
+#[cfg(feature = "std")]
 impl Default for NaoControlMessage {
+    /// Creates a message with all positions set to `-1.0` and all stiffness values set to `0.0`.
+    ///
+    /// Relying on the `-1.0` position to mean "no change" is deprecated, as it is a valid position
+    /// for several joints, see [`NaoControlMessage::DEFAULT_POSITION`]. Use [`NaoControlMessage::holding`]
+    /// or [`position_mask`](NaoControlMessage::position_mask) instead.
     fn default() -> Self {
         Self {
-            position: JointArray::fill(-1.0),
+            #[allow(deprecated)]
+            position: JointArray::fill(Self::DEFAULT_POSITION),
+            position_mask: JointArray::fill(true),
             stiffness: JointArray::default(),
-            sonar: SonarEnabled::default(),
+            sonar: SonarMode::default(),
             left_ear: LeftEar::default(),
             right_ear: RightEar::default(),
             chest: RgbF32::default(),
@@ -199,8 +342,249 @@ impl Default for NaoControlMessage {
     }
 }
 
+#[cfg(feature = "std")]
+impl NaoControlMessage {
+    /// The position of every joint in [`NaoControlMessage::default`].
+    ///
+    /// `LoLA` does not treat this position specially, it moves the joints to `-1.0` radians.
+    #[deprecated(
+        note = "`-1.0` is a valid joint position, leave joints out of `position_mask` to not command them"
+    )]
+    pub const DEFAULT_POSITION: f32 = -1.0;
+
+    /// Replaces the position of every joint that is not selected by [`position_mask`](NaoControlMessage::position_mask)
+    /// with its `measured` position, and selects all joints.
+    ///
+    /// Backends call this when sending a message, with the position they last read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{types::{FillExt, JointArray, JointMask, JointRegion}, NaoControlMessage};
+    ///
+    /// let msg = NaoControlMessage {
+    ///     position: JointArray::fill(0.5),
+    ///     position_mask: JointMask::from_region(JointRegion::Head),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let msg = msg.hold_uncommanded(&JointArray::fill(0.1));
+    /// assert_eq!(msg.position.head_yaw, 0.5);
+    /// assert_eq!(msg.position.left_knee_pitch, 0.1);
+    /// assert_eq!(msg.position_mask, JointArray::fill(true));
+    /// ```
+    #[must_use]
+    pub fn hold_uncommanded(mut self, measured: &JointArray<f32>) -> Self {
+        self.position = self.position.select(&self.position_mask, measured);
+        self.position_mask = JointArray::fill(true);
+        self
+    }
+
+    /// Creates a message that holds all joints at the position and stiffness measured in `state`.
+    ///
+    /// All other values are set to their defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{types::JointArray, NaoControlMessage, NaoState};
+    ///
+    /// let state = NaoState {
+    ///     position: JointArray {
+    ///         left_shoulder_pitch: 1.4,
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let msg = NaoControlMessage::holding(&state);
+    /// assert_eq!(msg.position.left_shoulder_pitch, 1.4);
+    /// ```
+    #[must_use]
+    pub fn holding(state: &NaoState) -> Self {
+        Self {
+            position: state.position.clone(),
+            stiffness: state.stiffness.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a message that sets the stiffness of all joints to `0.0`, so the robot goes limp.
+    ///
+    /// The positions are set to `0.0` instead of the `-1.0` of [`NaoControlMessage::default`],
+    /// they have no effect as long as the stiffness is zero.
+    /// All other values are set to their defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{types::JointArray, NaoControlMessage};
+    ///
+    /// let msg = NaoControlMessage::unstiff();
+    /// assert_eq!(msg.stiffness, JointArray::default());
+    /// ```
+    #[must_use]
+    pub fn unstiff() -> Self {
+        Self {
+            position: JointArray::default(),
+            stiffness: JointArray::default(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a message that holds all joints at the position measured in `state`, using the same `stiffness` for every joint.
+    ///
+    /// All other values are set to their defaults.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{types::{FillExt, JointArray}, NaoControlMessage, NaoState};
+    ///
+    /// let state = NaoState {
+    ///     position: JointArray { head_yaw: 0.3, ..Default::default() },
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let msg = NaoControlMessage::stiff_hold(&state, 0.6);
+    /// assert_eq!(msg.position.head_yaw, 0.3);
+    /// assert_eq!(msg.stiffness, JointArray::fill(0.6));
+    /// ```
+    #[must_use]
+    pub fn stiff_hold(state: &NaoState, stiffness: f32) -> Self {
+        Self {
+            position: state.position.clone(),
+            stiffness: JointArray::fill(stiffness),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a message that commands the crouching posture of [`poses::penalized`],
+    /// with a low stiffness of `0.2` for every joint.
+    ///
+    /// This is meant for a robot that is already sitting, for example while it is penalized.
+    /// To move a standing robot into this posture, play [`motions::sit`](motion::motions::sit) instead.
+    /// All other values are set to their defaults.
+    #[must_use]
+    pub fn penalized() -> Self {
+        Self {
+            position: poses::penalized(),
+            stiffness: JointArray::fill(0.2),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the state of all LEDs in this message.
+    pub fn leds(&self) -> LedState {
+        LedState {
+            left_ear: self.left_ear.clone(),
+            right_ear: self.right_ear.clone(),
+            chest: self.chest,
+            left_eye: self.left_eye.clone(),
+            right_eye: self.right_eye.clone(),
+            left_foot: self.left_foot,
+            right_foot: self.right_foot,
+            skull: self.skull.clone(),
+        }
+    }
+
+    /// Sets all LEDs in this message to the provided [`LedState`].
+    pub fn set_leds(&mut self, leds: LedState) {
+        self.left_ear = leds.left_ear;
+        self.right_ear = leds.right_ear;
+        self.chest = leds.chest;
+        self.left_eye = leds.left_eye;
+        self.right_eye = leds.right_eye;
+        self.left_foot = leds.left_foot;
+        self.right_foot = leds.right_foot;
+        self.skull = leds.skull;
+    }
+}
+
+#[cfg(feature = "std")]
+impl NaoControlMessageBuilder {
+    /// Set the requested joint positions in radians, and select every joint in `position_mask`.
+    #[must_use = "builder methods take the builder by value, use the returned builder"]
+    pub fn position(mut self, position: JointArray<f32>) -> Self {
+        self.position = Some(position);
+        self.position_mask = Some(JointArray::fill(true));
+        self
+    }
+
+    /// Set the emitters used by the sonar sensors.
+    ///
+    /// Accepts a [`SonarMode`], or the deprecated [`SonarEnabled`](types::SonarEnabled).
+    #[must_use = "builder methods take the builder by value, use the returned builder"]
+    pub fn sonar(mut self, sonar: impl Into<SonarMode>) -> Self {
+        self.sonar = Some(sonar.into());
+        self
+    }
+
+    /// Set all LEDs to the values of the provided [`LedState`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{leds::LedState, types::color, NaoControlMessage};
+    ///
+    /// let leds = LedState::builder().chest(color::f32::RED).build();
+    /// let msg = NaoControlMessage::builder().leds(leds.clone()).build();
+    ///
+    /// assert_eq!(msg.leds(), leds);
+    /// ```
+    pub fn leds(self, leds: LedState) -> Self {
+        self.left_ear(leds.left_ear)
+            .right_ear(leds.right_ear)
+            .chest(leds.chest)
+            .left_eye(leds.left_eye)
+            .right_eye(leds.right_eye)
+            .left_foot(leds.left_foot)
+            .right_foot(leds.right_foot)
+            .skull(leds.skull)
+    }
+
+    /// Set the joint positions using typed [`Radians`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{types::{units::Radians, FillExt, JointArray}, NaoControlMessage};
+    ///
+    /// let msg = NaoControlMessage::builder()
+    ///     .position_radians(JointArray::fill(Radians(0.5)))
+    ///     .build();
+    /// assert_eq!(msg.position.head_yaw, 0.5);
+    /// ```
+    pub fn position_radians(self, position: JointArray<Radians>) -> Self {
+        self.position(position.into())
+    }
+
+    /// Set the joint positions using typed [`Degrees`], which are converted to radians.
+    pub fn position_degrees(self, position: JointArray<Degrees>) -> Self {
+        self.position(position.into())
+    }
+
+    /// Display the battery charge on both ears.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{types::{Battery, LeftEar}, NaoControlMessage};
+    ///
+    /// let battery = Battery { charge: 0.8, ..Default::default() };
+    /// let msg = NaoControlMessage::builder().battery_display(&battery).build();
+    /// assert_eq!(msg.left_ear, LeftEar::from_fraction(0.8));
+    /// ```
+    pub fn battery_display(self, battery: &Battery) -> Self {
+        self.left_ear(LeftEar::from_fraction(battery.charge))
+            .right_ear(RightEar::from_fraction(battery.charge))
+    }
+}
+
 /// Struct containing the hardware identifiers for the NAO V6 robot.
-#[derive(Debug)]
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Resource))]
 pub struct HardwareInfo {
     pub body_id: String,
@@ -208,3 +592,306 @@ pub struct HardwareInfo {
     pub head_id: String,
     pub head_version: String,
 }
+
+#[cfg(feature = "std")]
+impl HardwareInfo {
+    /// Parses the [`HardwareInfo::head_version`], e.g. `"6.0.0"`, into its major and minor version.
+    ///
+    /// Returns [`None`] if the version is not a dot separated list of numbers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::HardwareInfo;
+    ///
+    /// let info = HardwareInfo {
+    ///     body_id: "P0000074A04S94700023".to_string(),
+    ///     body_version: "6.0.0".to_string(),
+    ///     head_id: "P0000073A07S94500188".to_string(),
+    ///     head_version: "6.0.0".to_string(),
+    /// };
+    ///
+    /// assert_eq!(info.head_version_parsed(), Some((6, 0)));
+    /// assert!(info.is_v6());
+    /// ```
+    pub fn head_version_parsed(&self) -> Option<(u8, u8)> {
+        parse_version(&self.head_version)
+    }
+
+    /// Parses the [`HardwareInfo::body_version`], e.g. `"6.0.0"`, into its major and minor version.
+    ///
+    /// Returns [`None`] if the version is not a dot separated list of numbers.
+    pub fn body_version_parsed(&self) -> Option<(u8, u8)> {
+        parse_version(&self.body_version)
+    }
+
+    /// Returns `true` if both the head and the body are version 6.
+    pub fn is_v6(&self) -> bool {
+        matches!(self.head_version_parsed(), Some((6, _)))
+            && matches!(self.body_version_parsed(), Some((6, _)))
+    }
+}
+
+/// Parses a version such as `6.0.0` into its major and minor version, the minor version defaults to zero.
+#[cfg(feature = "std")]
+fn parse_version(version: &str) -> Option<(u8, u8)> {
+    let mut parts = version
+        .trim()
+        .split('.')
+        .map(|part| part.parse::<u8>().ok());
+
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    if parts.any(|part| part.is_none()) {
+        return None;
+    }
+
+    Some((major, minor))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use types::color;
+
+    #[test]
+    fn test_into_builder_preserves_untouched_fields() {
+        let msg = NaoControlMessage::builder()
+            .position(JointArray::fill(0.25))
+            .stiffness(JointArray::fill(0.8))
+            .left_eye(LeftEye::fill(color::f32::CYAN))
+            .build();
+
+        let updated = msg.clone().into_builder().chest(color::f32::RED).build();
+
+        assert_eq!(updated.chest, color::f32::RED);
+        assert_eq!(
+            updated,
+            NaoControlMessage {
+                chest: color::f32::RED,
+                ..msg
+            }
+        );
+    }
+
+    #[test]
+    fn test_holding_does_not_move_joints() {
+        let state = NaoState {
+            position: JointArray {
+                left_shoulder_pitch: 1.4,
+                right_shoulder_pitch: 1.3,
+                left_knee_pitch: 2.0,
+                ..Default::default()
+            },
+            stiffness: JointArray::fill(0.6),
+            ..Default::default()
+        };
+
+        let msg = NaoControlMessage::holding(&state);
+
+        assert_eq!(msg.position, state.position);
+        assert_eq!(msg.position_mask, JointArray::fill(true));
+        assert_eq!(msg.stiffness, state.stiffness);
+        assert_eq!(msg.chest, RgbF32::default());
+
+        // a message without positions does not command any joint, so they hold their measured position
+        let empty = NaoControlMessage::builder()
+            .stiffness(JointArray::fill(0.6))
+            .build();
+        assert_eq!(empty.position_mask, JointArray::fill(false));
+        assert_eq!(
+            empty.hold_uncommanded(&state.position).position,
+            state.position
+        );
+    }
+
+    #[test]
+    fn test_stiffness_presets() {
+        let unstiff = NaoControlMessage::unstiff();
+        assert_eq!(unstiff.position, JointArray::fill(0.0));
+        assert_eq!(unstiff.stiffness, JointArray::fill(0.0));
+
+        let state = NaoState {
+            position: JointArray {
+                head_pitch: -0.4,
+                left_knee_pitch: 1.2,
+                ..Default::default()
+            },
+            stiffness: JointArray::fill(1.0),
+            ..Default::default()
+        };
+        let hold = NaoControlMessage::stiff_hold(&state, 0.5);
+        assert_eq!(hold.position, state.position);
+        assert_eq!(hold.stiffness, JointArray::fill(0.5));
+
+        let penalized = NaoControlMessage::penalized();
+        assert_eq!(penalized.stiffness, JointArray::fill(0.2));
+        assert_eq!(penalized.position.left_knee_pitch, 2.1);
+        assert_eq!(penalized.position.right_ankle_pitch, -1.18);
+        assert_eq!(penalized.position.head_yaw, 0.0);
+        assert!(penalized
+            .position
+            .zip(safety::JOINT_LIMITS)
+            .all(|(position, limit)| limit.contains(position)));
+    }
+
+    #[test]
+    fn test_leds_roundtrip() {
+        let leds = LedState::builder()
+            .chest(color::f32::RED)
+            .left_eye(LeftEye::fill(color::f32::CYAN))
+            .skull(Skull::fill(0.5))
+            .build();
+
+        let mut msg = NaoControlMessage::default();
+        msg.set_leds(leds.clone());
+
+        assert_eq!(msg.leds(), leds);
+        assert_eq!(msg.position, NaoControlMessage::default().position);
+    }
+
+    #[test]
+    fn test_hardware_info_versions() {
+        let info = |body_version: &str, head_version: &str| HardwareInfo {
+            body_id: "P0000074A04S94700023".to_string(),
+            body_version: body_version.to_string(),
+            head_id: "P0000073A07S94500188".to_string(),
+            head_version: head_version.to_string(),
+        };
+
+        let v6 = info("6.0.0", "6.0.0");
+        assert_eq!(v6.body_version_parsed(), Some((6, 0)));
+        assert_eq!(v6.head_version_parsed(), Some((6, 0)));
+        assert!(v6.is_v6());
+
+        assert_eq!(info("6", "6.1").head_version_parsed(), Some((6, 1)));
+        assert_eq!(info("6", "6.1").body_version_parsed(), Some((6, 0)));
+        assert!(!info("5.0.0", "6.0.0").is_v6());
+        assert_eq!(info("", "6.x.0").body_version_parsed(), None);
+        assert_eq!(info("", "6.x.0").head_version_parsed(), None);
+        assert_eq!(info("6.0.0.beta", "").body_version_parsed(), None);
+    }
+
+    #[test]
+    fn test_try_build_requires_stiffness() {
+        let err = NaoControlMessage::builder()
+            .position(JointArray::fill(0.25))
+            .try_build()
+            .unwrap_err();
+        assert_eq!(err.missing_fields(), ["stiffness"]);
+
+        let msg = NaoControlMessage::builder()
+            .position(JointArray::fill(0.25))
+            .stiffness(JointArray::fill(0.8))
+            .try_build()
+            .unwrap();
+        assert_eq!(msg.stiffness, JointArray::fill(0.8));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use types::color;
+
+    fn control_msg() -> NaoControlMessage {
+        NaoControlMessage::builder()
+            .position(JointArray::fill(0.25))
+            .stiffness(JointArray::fill(0.8))
+            .chest(color::f32::MAGENTA)
+            .left_eye(LeftEye::fill(color::f32::CYAN))
+            .sonar(SonarMode::Alternating { period_frames: 3 })
+            .build()
+    }
+
+    fn hardware_info() -> HardwareInfo {
+        HardwareInfo {
+            body_id: "P0000074A04S94700023".to_string(),
+            body_version: "6.0.0".to_string(),
+            head_id: "P0000073A07S94500188".to_string(),
+            head_version: "6.0.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ear_json_has_no_marker() {
+        let ear = LeftEar::from_fraction(0.5);
+
+        let json = serde_json::to_string(&ear).unwrap();
+        assert!(!json.contains("_marker"));
+        assert_eq!(serde_json::from_str::<LeftEar>(&json).unwrap(), ear);
+    }
+
+    #[test]
+    fn test_control_msg_json_roundtrip() {
+        let msg = control_msg();
+
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(
+            serde_json::from_str::<NaoControlMessage>(&json).unwrap(),
+            msg
+        );
+    }
+
+    #[test]
+    fn test_hardware_info_json_roundtrip() {
+        let info = hardware_info();
+
+        let json = serde_json::to_string(&info).unwrap();
+        assert_eq!(serde_json::from_str::<HardwareInfo>(&json).unwrap(), info);
+    }
+
+    #[test]
+    fn test_fsr_calibrations_json_roundtrip() {
+        use input::{FsrCalibration, FsrCalibrations};
+
+        let mut calibrations = FsrCalibrations::default();
+        calibrations.insert(
+            &hardware_info(),
+            FsrCalibration::calibrate_zero(&[types::Fsr {
+                left_foot: types::FsrFoot::fill(0.2),
+                right_foot: types::FsrFoot::fill(0.1),
+            }]),
+        );
+
+        let json = serde_json::to_string(&calibrations).unwrap();
+        assert!(json.starts_with("{\"P0000074A04S94700023\":"));
+        assert_eq!(
+            serde_json::from_str::<FsrCalibrations>(&json).unwrap(),
+            calibrations
+        );
+    }
+
+    #[cfg(feature = "lola")]
+    #[test]
+    fn test_control_msg_msgpack_roundtrip() {
+        let msg = control_msg();
+
+        let bytes = rmp_serde::to_vec_named(&msg).unwrap();
+        assert_eq!(
+            rmp_serde::from_slice::<NaoControlMessage>(&bytes).unwrap(),
+            msg
+        );
+    }
+
+    #[cfg(feature = "lola")]
+    #[test]
+    fn test_hardware_info_msgpack_roundtrip() {
+        let info = hardware_info();
+
+        let bytes = rmp_serde::to_vec_named(&info).unwrap();
+        assert_eq!(rmp_serde::from_slice::<HardwareInfo>(&bytes).unwrap(), info);
+    }
+
+    #[test]
+    fn test_partial_control_msg_uses_defaults() {
+        let msg: NaoControlMessage =
+            serde_json::from_str(r#"{ "chest": { "red": 1.0, "green": 0.0, "blue": 0.0 } }"#)
+                .unwrap();
+
+        assert_eq!(msg.chest, color::f32::RED);
+        assert_eq!(msg.position, JointArray::fill(-1.0));
+        assert_eq!(msg.stiffness, JointArray::fill(0.0));
+        assert_eq!(msg.left_eye, LeftEye::default());
+    }
+}
@@ -0,0 +1,298 @@
+//! Implements the [`HoldPosition`] and [`DampedRelax`] whole-body behaviors.
+
+use std::time::Duration;
+
+use crate::{types::JointArray, NaoState};
+
+/// Keeps the robot in the position it was in when the behavior was activated.
+///
+/// The measured positions are latched by the first [`HoldPosition::update`] after activation,
+/// and commanded with the configured stiffness until the behavior is deactivated.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{motion::HoldPosition, types::{FillExt, JointArray}, NaoState};
+///
+/// let mut hold = HoldPosition::new(JointArray::fill(0.6));
+/// let mut state = NaoState::default();
+/// state.position.head_yaw = 0.3;
+///
+/// assert_eq!(hold.update(&state), None);
+///
+/// hold.activate();
+/// let (position, stiffness) = hold.update(&state).unwrap();
+/// assert_eq!(position.head_yaw, 0.3);
+/// assert_eq!(stiffness.head_yaw, 0.6);
+///
+/// // the latched position is kept, even if the robot is pushed
+/// state.position.head_yaw = 0.5;
+/// assert_eq!(hold.update(&state).unwrap().0.head_yaw, 0.3);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct HoldPosition {
+    stiffness: JointArray<f32>,
+    active: bool,
+    latched: Option<JointArray<f32>>,
+}
+
+impl HoldPosition {
+    /// Create an inactive [`HoldPosition`], which holds the joints with the provided stiffness.
+    pub fn new(stiffness: JointArray<f32>) -> Self {
+        Self {
+            stiffness,
+            active: false,
+            latched: None,
+        }
+    }
+
+    /// Activate the behavior, the positions are latched on the next update.
+    ///
+    /// Activating an already active behavior keeps the current latched positions.
+    pub fn activate(&mut self) {
+        self.active = true;
+    }
+
+    /// Activate the behavior, holding the provided positions instead of the measured ones.
+    ///
+    /// This is useful after a motion completes, to hold its final keyframe.
+    pub fn activate_at(&mut self, position: JointArray<f32>) {
+        self.active = true;
+        self.latched = Some(position);
+    }
+
+    /// Deactivate the behavior and forget the latched positions.
+    pub fn deactivate(&mut self) {
+        self.active = false;
+        self.latched = None;
+    }
+
+    /// Returns `true` if the behavior is active.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The positions that are being held, if they have been latched.
+    pub fn latched(&self) -> Option<&JointArray<f32>> {
+        self.latched.as_ref()
+    }
+
+    /// Returns the positions and stiffness to command, or [`None`] if the behavior is inactive.
+    pub fn update(&mut self, state: &NaoState) -> Option<(JointArray<f32>, JointArray<f32>)> {
+        if !self.active {
+            return None;
+        }
+
+        let position = self
+            .latched
+            .get_or_insert_with(|| state.position.clone())
+            .clone();
+        Some((position, self.stiffness.clone()))
+    }
+}
+
+/// Ramps the stiffness of all joints down to zero, while following the measured positions.
+///
+/// Commanding the measured positions means the joints never pull towards an old target,
+/// so the arms don't snap when the stiffness is released. The stiffness starts at the
+/// measured stiffness of the first [`DampedRelax::update`] after activation.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use nidhogg::{motion::DampedRelax, types::{FillExt, JointArray}, NaoState};
+///
+/// let mut relax = DampedRelax::new(Duration::from_millis(100));
+/// let state = NaoState {
+///     stiffness: JointArray::fill(0.8),
+///     ..Default::default()
+/// };
+///
+/// relax.activate();
+/// let (_, stiffness) = relax.update(&state, Duration::from_millis(50)).unwrap();
+/// assert!((stiffness.head_yaw - 0.4).abs() < 1e-6);
+///
+/// relax.update(&state, Duration::from_millis(50));
+/// assert!(relax.is_finished());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DampedRelax {
+    duration: Duration,
+    active: bool,
+    elapsed: Duration,
+    start: Option<JointArray<f32>>,
+}
+
+impl DampedRelax {
+    /// Create an inactive [`DampedRelax`] that releases the stiffness over the provided duration.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            ..Default::default()
+        }
+    }
+
+    /// Start relaxing from the stiffness measured on the next update.
+    ///
+    /// Activating an already active behavior restarts the ramp.
+    pub fn activate(&mut self) {
+        self.active = true;
+        self.elapsed = Duration::ZERO;
+        self.start = None;
+    }
+
+    /// Start relaxing from the provided stiffness, for instance the stiffness of the last command.
+    pub fn activate_from(&mut self, stiffness: JointArray<f32>) {
+        self.activate();
+        self.start = Some(stiffness);
+    }
+
+    /// Stop the behavior.
+    pub fn deactivate(&mut self) {
+        self.active = false;
+        self.start = None;
+    }
+
+    /// Returns `true` if the behavior is active.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Returns `true` if the behavior is active and the stiffness has reached zero.
+    pub fn is_finished(&self) -> bool {
+        self.active && self.elapsed >= self.duration
+    }
+
+    /// Advance the ramp by `dt`, and return the positions and stiffness to command.
+    ///
+    /// Returns [`None`] if the behavior is inactive. Once finished, this keeps returning
+    /// the measured positions with zero stiffness until the behavior is deactivated.
+    pub fn update(
+        &mut self,
+        state: &NaoState,
+        dt: Duration,
+    ) -> Option<(JointArray<f32>, JointArray<f32>)> {
+        if !self.active {
+            return None;
+        }
+
+        let start = self.start.get_or_insert_with(|| state.stiffness.clone());
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+
+        let remaining = if self.duration.is_zero() {
+            0.0
+        } else {
+            1.0 - self.elapsed.as_secs_f32() / self.duration.as_secs_f32()
+        };
+        let stiffness = start.clone().map(|stiffness| stiffness * remaining);
+
+        Some((state.position.clone(), stiffness))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        motion::{motions, MotionPlayer},
+        types::FillExt,
+    };
+
+    const DT: Duration = Duration::from_millis(12);
+
+    fn state(position: f32, stiffness: f32) -> NaoState {
+        NaoState {
+            position: JointArray::fill(position),
+            stiffness: JointArray::fill(stiffness),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_hold_latches_on_first_update() {
+        let mut hold = HoldPosition::new(JointArray::fill(0.5));
+
+        // the position is latched on the first update after activation, not before
+        assert_eq!(hold.update(&state(0.1, 0.0)), None);
+        hold.activate();
+        assert_eq!(hold.latched(), None);
+
+        let (position, stiffness) = hold.update(&state(0.2, 0.0)).unwrap();
+        assert_eq!(position, JointArray::fill(0.2));
+        assert_eq!(stiffness, JointArray::fill(0.5));
+
+        for measured in [0.3, 0.4, 0.5] {
+            assert_eq!(hold.update(&state(measured, 0.0)).unwrap().0, position);
+        }
+
+        // activating again does not re-latch
+        hold.activate();
+        assert_eq!(hold.update(&state(0.6, 0.0)).unwrap().0, position);
+
+        hold.deactivate();
+        assert_eq!(hold.update(&state(0.7, 0.0)), None);
+        hold.activate();
+        assert_eq!(
+            hold.update(&state(0.8, 0.0)).unwrap().0,
+            JointArray::fill(0.8)
+        );
+    }
+
+    #[test]
+    fn test_relax_decreases_monotonically() {
+        let mut relax = DampedRelax::new(Duration::from_millis(120));
+        relax.activate();
+
+        let mut previous = f32::INFINITY;
+        for i in 0..15 {
+            // the measured stiffness rises, which should not affect the ramp
+            let measured = state(i as f32 * 0.1, 0.8 + i as f32 * 0.01);
+            let (position, stiffness) = relax.update(&measured, DT).unwrap();
+
+            assert_eq!(position, measured.position);
+            assert!(stiffness.head_yaw <= previous);
+            assert!(stiffness.head_yaw >= 0.0);
+            previous = stiffness.head_yaw;
+        }
+
+        assert!(relax.is_finished());
+        assert_eq!(previous, 0.0);
+        assert_eq!(
+            relax.update(&state(0.0, 0.8), DT).unwrap().1,
+            JointArray::fill(0.0)
+        );
+    }
+
+    #[test]
+    fn test_relax_from_command() {
+        let mut relax = DampedRelax::new(Duration::from_millis(24));
+        relax.activate_from(JointArray::fill(1.0));
+
+        let (_, stiffness) = relax.update(&state(0.0, 0.2), DT).unwrap();
+        assert_eq!(stiffness, JointArray::fill(0.5));
+        assert!(!relax.is_finished());
+
+        relax.deactivate();
+        assert_eq!(relax.update(&state(0.0, 0.2), DT), None);
+        assert!(!relax.is_finished());
+    }
+
+    #[test]
+    fn test_hold_after_motion() {
+        let mut player = MotionPlayer::new(motions::stand_zero()).start_from(JointArray::fill(0.3));
+        let mut hold = HoldPosition::new(JointArray::fill(0.8));
+
+        let mut last = None;
+        while let Some(command) = player.tick(DT) {
+            last = Some(command);
+        }
+        let (final_position, _) = last.unwrap();
+        hold.activate_at(final_position.clone());
+
+        // the final keyframe is held, regardless of where the robot ended up
+        let (position, stiffness) = hold.update(&state(0.25, 0.8)).unwrap();
+        assert_eq!(position, final_position);
+        assert_eq!(stiffness, JointArray::fill(0.8));
+    }
+}
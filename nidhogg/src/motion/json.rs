@@ -0,0 +1,191 @@
+//! Loading motions from JSON files.
+
+use std::{collections::BTreeMap, io::Read, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{
+    types::{FillExt, JointArray, JointName},
+    Error, Result,
+};
+
+use super::{Interpolation, Keyframe, MotionPlayer};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyframeEntry {
+    duration_ms: u64,
+    #[serde(default)]
+    interpolation: Interpolation,
+    #[serde(default)]
+    positions: BTreeMap<String, f32>,
+    stiffness: Option<StiffnessEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StiffnessEntry {
+    Uniform(f32),
+    PerJoint(BTreeMap<String, f32>),
+}
+
+impl MotionPlayer {
+    /// Create a [`MotionPlayer`] from a JSON motion file.
+    ///
+    /// The file contains an array of keyframes:
+    ///
+    /// ```json
+    /// [
+    ///   {
+    ///     "duration_ms": 500,
+    ///     "interpolation": "smooth_step",
+    ///     "stiffness": 0.8,
+    ///     "positions": { "head_yaw": 0.5, "LShoulderPitch": 1.2 }
+    ///   },
+    ///   {
+    ///     "duration_ms": 250,
+    ///     "stiffness": { "head_yaw": 0.4 },
+    ///     "positions": { "head_yaw": -0.5 }
+    ///   }
+    /// ]
+    /// ```
+    ///
+    /// - `duration_ms`: the time it takes to move to this keyframe, in milliseconds.
+    /// - `interpolation` (optional): either `"linear"` (default) or `"smooth_step"`.
+    /// - `positions` (optional): the joint positions in radians.
+    /// - `stiffness` (optional): a single stiffness for all joints, or the stiffness per joint.
+    ///
+    /// Joints are named like the [`JointName`](crate::types::JointName) enum, either in snake case
+    /// (`left_shoulder_pitch`) or using the `LoLA` names (`LShoulderPitch`).
+    /// Joints missing from a keyframe hold their value from the previous keyframe, joints missing from
+    /// the first keyframe start at position `0.0` and stiffness `0.0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is not valid JSON, does not match the schema,
+    /// or contains an unknown joint name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::motion::MotionPlayer;
+    ///
+    /// let motion = r#"[{ "duration_ms": 500, "positions": { "head_yaw": 0.5 } }]"#;
+    /// let player = MotionPlayer::from_json(motion.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(player.keyframes()[0].positions.head_yaw, 0.5);
+    /// ```
+    pub fn from_json(reader: impl Read) -> Result<MotionPlayer> {
+        let entries: Vec<KeyframeEntry> = serde_json::from_reader(reader)?;
+
+        let mut positions = JointArray::fill(0.0);
+        let mut stiffness = JointArray::fill(0.0);
+        let mut keyframes = Vec::with_capacity(entries.len());
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            apply_joints(&mut positions, &entry.positions, index)?;
+
+            match entry.stiffness {
+                Some(StiffnessEntry::Uniform(value)) => stiffness = JointArray::fill(value),
+                Some(StiffnessEntry::PerJoint(values)) => {
+                    apply_joints(&mut stiffness, &values, index)?;
+                }
+                None => {}
+            }
+
+            keyframes.push(Keyframe {
+                positions: positions.clone(),
+                stiffness: stiffness.clone(),
+                duration: Duration::from_millis(entry.duration_ms),
+                interpolation: entry.interpolation,
+            });
+        }
+
+        Ok(MotionPlayer::new(keyframes))
+    }
+}
+
+fn apply_joints(
+    target: &mut JointArray<f32>,
+    values: &BTreeMap<String, f32>,
+    keyframe: usize,
+) -> Result<()> {
+    for (name, value) in values {
+        let joint: JointName = name
+            .parse()
+            .map_err(|source| Error::InvalidMotionJoint { keyframe, source })?;
+
+        target[joint] = *value;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ParseJointNameError;
+
+    #[test]
+    fn test_sample_file() {
+        let file = include_str!("../../examples/motions/wave.json");
+        let player = MotionPlayer::from_json(file.as_bytes()).unwrap();
+        let keyframes = player.keyframes();
+
+        assert_eq!(keyframes.len(), 5);
+        assert_eq!(player.duration(), Duration::from_millis(2800));
+        assert_eq!(keyframes[0].interpolation, Interpolation::SmoothStep);
+        assert_eq!(keyframes[1].interpolation, Interpolation::Linear);
+        assert_eq!(keyframes[0].positions.right_shoulder_pitch, -1.2);
+
+        // missing joints hold their previous value
+        assert_eq!(keyframes[1].positions.right_elbow_yaw, 1.5);
+        assert_eq!(keyframes[2].positions.head_yaw, -0.3);
+        assert_eq!(keyframes[3].stiffness, JointArray::fill(0.8));
+
+        assert_eq!(keyframes[4].stiffness.right_shoulder_roll, 0.5);
+        assert_eq!(keyframes[4].stiffness.right_elbow_roll, 0.8);
+    }
+
+    #[test]
+    fn test_unknown_joint() {
+        let file = r#"[
+            { "duration_ms": 100, "positions": { "head_yaw": 0.1 } },
+            { "duration_ms": 100, "positions": { "LeftTail": 0.1 } }
+        ]"#;
+
+        let Err(Error::InvalidMotionJoint { keyframe, source }) =
+            MotionPlayer::from_json(file.as_bytes())
+        else {
+            panic!("expected an invalid joint error");
+        };
+
+        assert_eq!(keyframe, 1);
+        assert_eq!(source, ParseJointNameError("LeftTail".to_string()));
+    }
+
+    #[test]
+    fn test_head_only() {
+        let file = r#"[
+            { "duration_ms": 200, "stiffness": 0.6, "positions": { "head_yaw": 0.5, "HeadPitch": -0.2 } }
+        ]"#;
+
+        let player = MotionPlayer::from_json(file.as_bytes()).unwrap();
+        let keyframe = &player.keyframes()[0];
+
+        assert_eq!(keyframe.positions.head_yaw, 0.5);
+        assert_eq!(keyframe.positions.head_pitch, -0.2);
+        assert_eq!(keyframe.positions.left_knee_pitch, 0.0);
+        assert_eq!(keyframe.stiffness, JointArray::fill(0.6));
+    }
+
+    #[test]
+    fn test_invalid_schema() {
+        let file = r#"[{ "positions": { "head_yaw": 0.5 } }]"#;
+
+        assert!(matches!(
+            MotionPlayer::from_json(file.as_bytes()),
+            Err(Error::MotionParseError(_))
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+//! Defines the [`Keyframe`] of a motion.
+
+use std::time::Duration;
+
+use crate::types::{FillExt, JointArray};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The interpolation used to move towards a [`Keyframe`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Interpolation {
+    /// Move with a constant velocity.
+    #[default]
+    Linear,
+    /// Accelerate at the start and decelerate at the end of the keyframe.
+    SmoothStep,
+}
+
+impl Interpolation {
+    /// Map the linear progress `t` in the range `0.0..=1.0` to the interpolated progress.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Interpolation::Linear => t,
+            Interpolation::SmoothStep => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// A target pose of a motion, which is reached after `duration` has passed since the previous keyframe.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyframe {
+    /// The joint positions of this keyframe.
+    pub positions: JointArray<f32>,
+    /// The joint stiffnesses of this keyframe.
+    pub stiffness: JointArray<f32>,
+    /// The time it takes to move from the previous keyframe to this one.
+    pub duration: Duration,
+    /// The interpolation used to move from the previous keyframe to this one.
+    pub interpolation: Interpolation,
+}
+
+impl Keyframe {
+    /// Create a new [`Keyframe`] with the same stiffness for all joints, using [`Interpolation::Linear`].
+    pub fn new(positions: JointArray<f32>, stiffness: f32, duration: Duration) -> Self {
+        Self {
+            positions,
+            stiffness: JointArray::fill(stiffness),
+            duration,
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    /// Set the stiffness of each joint individually.
+    #[must_use]
+    pub fn with_stiffness(mut self, stiffness: JointArray<f32>) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    /// Set the interpolation used to move towards this keyframe.
+    #[must_use]
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+}
+
+/// Interpolate each joint between `from` and `to`, where `t` is in the range `0.0..=1.0`.
+pub(super) fn interpolate(from: &JointArray<f32>, to: &JointArray<f32>, t: f32) -> JointArray<f32> {
+    from.clone()
+        .zip(to.clone())
+        .map(|(from, to)| from + (to - from) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolation_boundaries() {
+        for interpolation in [Interpolation::Linear, Interpolation::SmoothStep] {
+            assert_eq!(interpolation.apply(0.0), 0.0);
+            assert_eq!(interpolation.apply(0.5), 0.5);
+            assert_eq!(interpolation.apply(1.0), 1.0);
+            assert_eq!(interpolation.apply(-1.0), 0.0);
+            assert_eq!(interpolation.apply(2.0), 1.0);
+        }
+
+        assert!(Interpolation::SmoothStep.apply(0.25) < 0.25);
+        assert!(Interpolation::SmoothStep.apply(0.75) > 0.75);
+    }
+}
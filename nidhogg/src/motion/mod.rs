@@ -0,0 +1,21 @@
+//! Keyframe based motions, and estimation of joint velocities.
+//!
+//! A motion is a sequence of [`Keyframe`]s, which is played back by a [`MotionPlayer`].
+//! Some commonly used motions are provided in [`motions`].
+//!
+//! [`HoldPosition`] and [`DampedRelax`] are small whole-body behaviors, which can take over once a motion completes.
+//!
+//! The [`JointVelocityEstimator`] estimates the joint velocities, which `LoLA` does not report.
+
+mod behaviors;
+#[cfg(feature = "serde")]
+mod json;
+mod keyframe;
+pub mod motions;
+mod player;
+mod velocity;
+
+pub use behaviors::{DampedRelax, HoldPosition};
+pub use keyframe::{Interpolation, Keyframe};
+pub use player::MotionPlayer;
+pub use velocity::{JointVelocityEstimator, VelocityFilter};
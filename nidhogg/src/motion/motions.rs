@@ -0,0 +1,52 @@
+//! Built-in motions.
+
+use std::time::Duration;
+
+use crate::{
+    poses,
+    types::{FillExt, JointArray},
+};
+
+use super::{Interpolation, Keyframe};
+
+/// Move all joints to their zero position.
+pub fn stand_zero() -> Vec<Keyframe> {
+    vec![
+        Keyframe::new(JointArray::fill(0.0), 0.8, Duration::from_secs(2))
+            .with_interpolation(Interpolation::SmoothStep),
+    ]
+}
+
+/// The crouching posture used when sitting, and while the robot is penalized.
+///
+/// This is the same as [`poses::sit`].
+pub fn sit_pose() -> JointArray<f32> {
+    poses::sit()
+}
+
+/// Sit down by crouching, then lower the stiffness once the robot rests on the ground.
+pub fn sit() -> Vec<Keyframe> {
+    let crouch = sit_pose();
+
+    vec![
+        Keyframe::new(crouch.clone(), 0.8, Duration::from_millis(1500))
+            .with_interpolation(Interpolation::SmoothStep),
+        Keyframe::new(crouch, 0.2, Duration::from_millis(500)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::safety::JOINT_LIMITS;
+
+    #[test]
+    fn test_sit_within_joint_limits() {
+        for keyframe in sit() {
+            assert!(keyframe
+                .positions
+                .zip(JOINT_LIMITS)
+                .all(|(position, limit)| limit.contains(position)));
+        }
+    }
+}
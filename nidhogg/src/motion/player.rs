@@ -0,0 +1,284 @@
+//! Implements the [`MotionPlayer`], which plays back a sequence of [`Keyframe`]s.
+
+use std::time::Duration;
+
+use crate::types::JointArray;
+
+use super::keyframe::{interpolate, Keyframe};
+
+/// Plays back a sequence of [`Keyframe`]s.
+///
+/// Every call to [`MotionPlayer::tick`] advances the motion and returns the interpolated
+/// joint positions and stiffnesses, until the motion has completed.
+///
+/// By default the motion starts at the first keyframe, which means the first keyframe is held
+/// for its duration. Use [`MotionPlayer::start_from`] to start from the current measured
+/// position instead, which avoids a jump to the first keyframe.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use nidhogg::{motion::{motions, MotionPlayer}, NaoState};
+///
+/// let state = NaoState::default();
+/// let mut player = MotionPlayer::new(motions::stand_zero()).start_from(state.position);
+///
+/// while let Some((position, stiffness)) = player.tick(Duration::from_millis(12)) {
+///     // send the position and stiffness to the robot
+/// }
+/// assert!(player.is_finished());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MotionPlayer {
+    keyframes: Vec<Keyframe>,
+    start: Option<JointArray<f32>>,
+    elapsed: Duration,
+    paused: bool,
+    finished: bool,
+}
+
+impl MotionPlayer {
+    /// Create a new [`MotionPlayer`] for the provided keyframes.
+    pub fn new(keyframes: Vec<Keyframe>) -> Self {
+        Self {
+            finished: keyframes.is_empty(),
+            keyframes,
+            start: None,
+            elapsed: Duration::ZERO,
+            paused: false,
+        }
+    }
+
+    /// Start the motion from the provided joint positions, usually the current measured position of the robot.
+    ///
+    /// The player then moves from these positions to the first keyframe.
+    #[must_use]
+    pub fn start_from(mut self, positions: JointArray<f32>) -> Self {
+        self.start = Some(positions);
+        self
+    }
+
+    /// The keyframes of this motion.
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// The total duration of the motion.
+    pub fn duration(&self) -> Duration {
+        self.keyframes
+            .iter()
+            .map(|keyframe| keyframe.duration)
+            .sum()
+    }
+
+    /// The time that has been played back so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Pause the motion, [`MotionPlayer::tick`] keeps returning the current pose without advancing.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resume a paused motion.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns `true` if the motion is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Abort the motion, all subsequent calls to [`MotionPlayer::tick`] return [`None`].
+    pub fn abort(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns `true` if the motion has completed or was aborted.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Restart the motion from the beginning.
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+        self.paused = false;
+        self.finished = self.keyframes.is_empty();
+    }
+
+    /// Advance the motion by `dt` and return the resulting joint positions and stiffnesses.
+    ///
+    /// The final keyframe is returned once when the motion completes, after which this returns [`None`].
+    pub fn tick(&mut self, dt: Duration) -> Option<(JointArray<f32>, JointArray<f32>)> {
+        if self.finished {
+            return None;
+        }
+
+        if !self.paused {
+            self.elapsed += dt;
+        }
+
+        if self.elapsed >= self.duration() {
+            self.finished = true;
+            let last = self.keyframes.last()?;
+            return Some((last.positions.clone(), last.stiffness.clone()));
+        }
+
+        Some(self.sample(self.elapsed))
+    }
+
+    /// Sample the motion at time `t`, relative to the start of the motion.
+    pub fn sample(&self, t: Duration) -> (JointArray<f32>, JointArray<f32>) {
+        let Some(first) = self.keyframes.first() else {
+            return Default::default();
+        };
+
+        let mut from_positions = self.start.as_ref().unwrap_or(&first.positions);
+        let mut from_stiffness = &first.stiffness;
+        let mut segment_start = Duration::ZERO;
+
+        for keyframe in &self.keyframes {
+            let segment_end = segment_start + keyframe.duration;
+
+            if t < segment_end {
+                let progress = (t - segment_start).as_secs_f32() / keyframe.duration.as_secs_f32();
+                let t = keyframe.interpolation.apply(progress);
+
+                return (
+                    interpolate(from_positions, &keyframe.positions, t),
+                    interpolate(from_stiffness, &keyframe.stiffness, t),
+                );
+            }
+
+            from_positions = &keyframe.positions;
+            from_stiffness = &keyframe.stiffness;
+            segment_start = segment_end;
+        }
+
+        (from_positions.clone(), from_stiffness.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{motion::Interpolation, types::FillExt};
+
+    fn keyframe(position: f32, millis: u64) -> Keyframe {
+        Keyframe::new(
+            JointArray::fill(position),
+            1.0,
+            Duration::from_millis(millis),
+        )
+    }
+
+    fn head_yaw(player: &mut MotionPlayer, millis: u64) -> Option<f32> {
+        player
+            .tick(Duration::from_millis(millis))
+            .map(|(position, _)| position.head_yaw)
+    }
+
+    #[test]
+    fn test_linear_interpolation_timing() {
+        let mut player = MotionPlayer::new(vec![keyframe(1.0, 100), keyframe(2.0, 200)])
+            .start_from(JointArray::fill(0.0));
+
+        assert_eq!(player.duration(), Duration::from_millis(300));
+        assert_eq!(head_yaw(&mut player, 50), Some(0.5));
+        assert_eq!(head_yaw(&mut player, 50), Some(1.0));
+        assert_eq!(head_yaw(&mut player, 100), Some(1.5));
+        assert!(!player.is_finished());
+
+        // the final keyframe is reported exactly once
+        assert_eq!(head_yaw(&mut player, 150), Some(2.0));
+        assert!(player.is_finished());
+        assert_eq!(head_yaw(&mut player, 10), None);
+    }
+
+    #[test]
+    fn test_without_start_holds_first_keyframe() {
+        let mut player = MotionPlayer::new(vec![keyframe(1.0, 100), keyframe(2.0, 100)]);
+
+        assert_eq!(head_yaw(&mut player, 50), Some(1.0));
+        assert_eq!(head_yaw(&mut player, 100), Some(1.5));
+    }
+
+    #[test]
+    fn test_smooth_step() {
+        let mut player = MotionPlayer::new(vec![
+            keyframe(1.0, 100).with_interpolation(Interpolation::SmoothStep)
+        ])
+        .start_from(JointArray::fill(0.0));
+
+        assert!(head_yaw(&mut player, 25).unwrap() < 0.25);
+        assert_eq!(head_yaw(&mut player, 25), Some(0.5));
+    }
+
+    #[test]
+    fn test_zero_duration_keyframe_jumps() {
+        let mut player = MotionPlayer::new(vec![
+            keyframe(1.0, 100),
+            keyframe(3.0, 0),
+            keyframe(4.0, 100),
+        ])
+        .start_from(JointArray::fill(0.0));
+
+        assert_eq!(head_yaw(&mut player, 50), Some(0.5));
+        assert_eq!(head_yaw(&mut player, 100), Some(3.5));
+    }
+
+    #[test]
+    fn test_single_keyframe() {
+        let mut player = MotionPlayer::new(vec![keyframe(1.0, 100)]);
+        assert_eq!(head_yaw(&mut player, 10), Some(1.0));
+        assert_eq!(head_yaw(&mut player, 100), Some(1.0));
+        assert_eq!(head_yaw(&mut player, 10), None);
+
+        let mut instant = MotionPlayer::new(vec![keyframe(1.0, 0)]);
+        assert_eq!(head_yaw(&mut instant, 0), Some(1.0));
+        assert!(instant.is_finished());
+    }
+
+    #[test]
+    fn test_empty_motion() {
+        let mut player = MotionPlayer::new(Vec::new());
+
+        assert!(player.is_finished());
+        assert_eq!(head_yaw(&mut player, 10), None);
+    }
+
+    #[test]
+    fn test_pause_and_abort() {
+        let mut player =
+            MotionPlayer::new(vec![keyframe(1.0, 100)]).start_from(JointArray::fill(0.0));
+
+        assert_eq!(head_yaw(&mut player, 50), Some(0.5));
+        player.pause();
+        assert_eq!(head_yaw(&mut player, 50), Some(0.5));
+        assert_eq!(player.elapsed(), Duration::from_millis(50));
+
+        player.resume();
+        assert_eq!(head_yaw(&mut player, 25), Some(0.75));
+
+        player.abort();
+        assert!(player.is_finished());
+        assert_eq!(head_yaw(&mut player, 10), None);
+
+        player.restart();
+        assert_eq!(head_yaw(&mut player, 0), Some(0.0));
+    }
+
+    #[test]
+    fn test_stiffness_is_interpolated() {
+        let mut player = MotionPlayer::new(vec![
+            Keyframe::new(JointArray::fill(0.0), 0.0, Duration::ZERO),
+            Keyframe::new(JointArray::fill(0.0), 1.0, Duration::from_millis(100)),
+        ]);
+
+        let (_, stiffness) = player.tick(Duration::from_millis(25)).unwrap();
+        assert_eq!(stiffness, JointArray::fill(0.25));
+    }
+}
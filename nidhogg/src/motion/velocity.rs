@@ -0,0 +1,262 @@
+//! Implements the [`JointVelocityEstimator`], which estimates joint velocities from successive positions.
+
+use std::time::Duration;
+
+use crate::types::JointArray;
+
+/// Method used by the [`JointVelocityEstimator`] to compute velocities from positions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VelocityFilter {
+    /// The difference between the last two positions, divided by the time between them.
+    ///
+    /// This has no delay, but amplifies the measurement noise the most.
+    FiniteDifference,
+    /// Second order backward difference over the last three positions.
+    ///
+    /// This is more accurate than [`VelocityFilter::FiniteDifference`] for smooth trajectories,
+    /// and supports irregular time steps.
+    #[default]
+    BackwardDifference,
+    /// Finite differences, smoothed by a single-pole low-pass filter with the provided time constant.
+    ///
+    /// This suppresses noise at the cost of a delay of roughly the time constant.
+    LowPass {
+        /// Time constant of the filter, larger values smooth more.
+        time_constant: Duration,
+    },
+}
+
+/// Estimates joint velocities in radians per second, since `LoLA` does not report them.
+///
+/// The estimator is updated with the measured positions every cycle, together with the time
+/// that passed since the previous update. Irregular time steps, for instance because a frame
+/// was dropped, are taken into account.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use nidhogg::{motion::{JointVelocityEstimator, VelocityFilter}, types::{FillExt, JointArray}};
+///
+/// let mut estimator = JointVelocityEstimator::new(VelocityFilter::FiniteDifference);
+/// let dt = Duration::from_millis(10);
+///
+/// // the first frame has nothing to compare against
+/// assert_eq!(estimator.update(&JointArray::fill(0.0), dt), JointArray::fill(0.0));
+///
+/// let velocity = estimator.update(&JointArray::fill(0.01), dt);
+/// assert!((velocity.head_yaw - 1.0).abs() < 1e-4);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct JointVelocityEstimator {
+    filter: VelocityFilter,
+    /// The last two positions, with the time step that led up to each of them.
+    previous: Option<(JointArray<f32>, f32)>,
+    before_previous: Option<(JointArray<f32>, f32)>,
+    velocity: JointArray<f32>,
+}
+
+impl JointVelocityEstimator {
+    /// Create a new [`JointVelocityEstimator`] using the provided filter.
+    pub fn new(filter: VelocityFilter) -> Self {
+        Self {
+            filter,
+            ..Default::default()
+        }
+    }
+
+    /// The filter used by this estimator.
+    pub fn filter(&self) -> VelocityFilter {
+        self.filter
+    }
+
+    /// The most recently estimated velocities.
+    pub fn velocity(&self) -> &JointArray<f32> {
+        &self.velocity
+    }
+
+    /// Forget all previous positions, the next update is treated as the first frame.
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.before_previous = None;
+        self.velocity = JointArray::default();
+    }
+
+    /// Update the estimator with new positions, measured `dt` after the previous ones.
+    ///
+    /// Returns zero velocities for the first frame. Frames with a `dt` of zero are ignored,
+    /// and return the previous estimate.
+    pub fn update(&mut self, position: &JointArray<f32>, dt: Duration) -> JointArray<f32> {
+        let dt = dt.as_secs_f32();
+
+        let Some((previous, _)) = &self.previous else {
+            self.previous = Some((position.clone(), dt));
+            return self.velocity.clone();
+        };
+        if dt <= 0.0 {
+            return self.velocity.clone();
+        }
+
+        let difference = position
+            .clone()
+            .zip(previous.clone())
+            .map(|(position, previous)| (position - previous) / dt);
+
+        self.velocity = match (self.filter, &self.before_previous) {
+            (VelocityFilter::FiniteDifference, _) | (VelocityFilter::BackwardDifference, None) => {
+                difference
+            }
+            (VelocityFilter::BackwardDifference, Some((before_previous, _))) => {
+                // `previous` was measured `h1` ago, `before_previous` another `h2` before that
+                let h1 = dt;
+                let h2 = self.previous.as_ref().map_or(dt, |(_, dt)| *dt);
+                let c0 = (2.0 * h1 + h2) / (h1 * (h1 + h2));
+                let c1 = (h1 + h2) / (h1 * h2);
+                let c2 = h1 / (h2 * (h1 + h2));
+
+                position
+                    .clone()
+                    .zip(previous.clone())
+                    .zip(before_previous.clone())
+                    .map(|((p0, p1), p2)| c0 * p0 - c1 * p1 + c2 * p2)
+            }
+            (VelocityFilter::LowPass { time_constant }, _) => {
+                let alpha = dt / (time_constant.as_secs_f32() + dt);
+
+                self.velocity
+                    .clone()
+                    .zip(difference)
+                    .map(|(velocity, raw)| velocity + alpha * (raw - velocity))
+            }
+        };
+
+        self.before_previous = self.previous.replace((position.clone(), dt));
+        self.velocity.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::*;
+    use crate::types::FillExt;
+
+    const AMPLITUDE: f32 = 0.5;
+    const OMEGA: f32 = TAU;
+
+    /// Runs the estimator over a sine trajectory sampled at the provided time steps, and
+    /// returns the largest error of the head yaw velocity after the first `settle` frames.
+    fn max_error(
+        filter: VelocityFilter,
+        steps: impl IntoIterator<Item = f32>,
+        settle: usize,
+    ) -> f32 {
+        let mut estimator = JointVelocityEstimator::new(filter);
+        let mut t = 0.0;
+
+        let mut max_error: f32 = 0.0;
+        for (i, dt) in [0.0].into_iter().chain(steps).enumerate() {
+            t += dt;
+            let position = JointArray::fill(AMPLITUDE * (OMEGA * t).sin());
+            let velocity = estimator.update(&position, Duration::from_secs_f32(dt));
+
+            if i >= settle {
+                let expected = AMPLITUDE * OMEGA * (OMEGA * t).cos();
+                max_error = max_error.max((velocity.head_yaw - expected).abs());
+            }
+        }
+        max_error
+    }
+
+    fn regular() -> impl Iterator<Item = f32> {
+        std::iter::repeat_n(0.012, 200)
+    }
+
+    #[test]
+    fn test_first_frame_is_zero() {
+        let mut estimator = JointVelocityEstimator::default();
+        let dt = Duration::from_millis(12);
+
+        assert_eq!(
+            estimator.update(&JointArray::fill(1.0), dt),
+            JointArray::fill(0.0)
+        );
+        assert_eq!(
+            estimator.update(&JointArray::fill(1.0), dt),
+            JointArray::fill(0.0)
+        );
+
+        estimator.reset();
+        assert_eq!(
+            estimator.update(&JointArray::fill(2.0), dt),
+            JointArray::fill(0.0)
+        );
+    }
+
+    #[test]
+    fn test_sine_error_bounds() {
+        let peak = AMPLITUDE * OMEGA;
+
+        let finite = max_error(VelocityFilter::FiniteDifference, regular(), 1);
+        let backward = max_error(VelocityFilter::BackwardDifference, regular(), 2);
+        let low_pass = max_error(
+            VelocityFilter::LowPass {
+                time_constant: Duration::from_millis(10),
+            },
+            regular(),
+            20,
+        );
+
+        assert!(finite < 0.05 * peak, "finite difference error {finite}");
+        assert!(
+            backward < 0.005 * peak,
+            "backward difference error {backward}"
+        );
+        assert!(backward < finite);
+        assert!(low_pass < 0.15 * peak, "low-pass error {low_pass}");
+    }
+
+    #[test]
+    fn test_irregular_time_steps() {
+        let peak = AMPLITUDE * OMEGA;
+        let irregular = (0..200).map(|i| if i % 2 == 0 { 0.010 } else { 0.014 });
+
+        let backward = max_error(VelocityFilter::BackwardDifference, irregular, 2);
+        assert!(
+            backward < 0.01 * peak,
+            "backward difference error {backward}"
+        );
+    }
+
+    #[test]
+    fn test_dropped_frames_do_not_spike() {
+        let peak = AMPLITUDE * OMEGA;
+        // every 10th frame is dropped, so the time step doubles
+        let dropped = (0..200).map(|i| if i % 10 == 9 { 0.024 } else { 0.012 });
+
+        let low_pass = max_error(
+            VelocityFilter::LowPass {
+                time_constant: Duration::from_millis(10),
+            },
+            dropped,
+            20,
+        );
+        assert!(low_pass < 0.15 * peak, "low-pass error {low_pass}");
+    }
+
+    #[test]
+    fn test_zero_dt_is_ignored() {
+        let mut estimator = JointVelocityEstimator::new(VelocityFilter::FiniteDifference);
+        let dt = Duration::from_millis(10);
+
+        estimator.update(&JointArray::fill(0.0), dt);
+        let velocity = estimator.update(&JointArray::fill(0.01), dt);
+
+        assert_eq!(
+            estimator.update(&JointArray::fill(5.0), Duration::ZERO),
+            velocity
+        );
+        assert_eq!(estimator.velocity(), &velocity);
+    }
+}
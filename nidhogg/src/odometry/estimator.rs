@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use nalgebra::{Isometry2, Vector2};
+
+use crate::{
+    kinematics::{left_sole_to_torso, right_sole_to_torso, Foot},
+    NaoState,
+};
+
+/// Configuration for the [`OdometryEstimator`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct OdometryConfig {
+    /// Time between two consecutive states.
+    pub cycle_time: Duration,
+    /// Additional weight in kilograms the swing foot needs to carry before it becomes the support foot.
+    ///
+    /// This prevents the support foot from switching back and forth during double support.
+    pub support_hysteresis: f32,
+}
+
+impl Default for OdometryConfig {
+    fn default() -> Self {
+        Self {
+            cycle_time: Duration::from_millis(12),
+            support_hysteresis: 0.2,
+        }
+    }
+}
+
+/// Estimates the movement of the robot over the ground.
+///
+/// The translation is computed from the forward kinematics of the support foot, which is assumed
+/// to stand still on the ground. The support foot is detected using the FSRs.
+/// The rotation is computed by integrating the yaw rate measured by the gyroscope.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{odometry::OdometryEstimator, NaoState};
+///
+/// let mut odometry = OdometryEstimator::default();
+///
+/// let (translation, rotation) = odometry.update(&NaoState::default());
+/// println!("moved {translation:?} and turned {rotation} radians");
+/// println!("total: {:?}", odometry.pose());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OdometryEstimator {
+    config: OdometryConfig,
+    support: Option<Foot>,
+    /// The position of the left and right sole in the torso frame during the previous cycle.
+    previous_soles: Option<[Vector2<f32>; 2]>,
+    pose: Isometry2<f32>,
+}
+
+impl OdometryEstimator {
+    /// Create a new estimator using the provided configuration.
+    #[must_use]
+    pub fn new(config: OdometryConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Update the estimate using the state of the current cycle.
+    ///
+    /// Returns the translation in meters and rotation in radians of the robot since the previous cycle,
+    /// relative to the previous pose of the robot.
+    pub fn update(&mut self, state: &NaoState) -> (Vector2<f32>, f32) {
+        let soles = [
+            left_sole_to_torso(&state.position).translation.vector.xy(),
+            right_sole_to_torso(&state.position).translation.vector.xy(),
+        ];
+        let rotation = state.gyroscope.z * self.config.cycle_time.as_secs_f32();
+
+        // The support foot does not move over the ground, so the torso moves opposite to it.
+        // The previous support foot is used during a switch, as the new support foot might still
+        // have been moving during the previous cycle. The next cycle starts from the kinematic
+        // chain through the new support foot, so the switch itself does not cause a jump.
+        let support = self.support.unwrap_or_else(|| heaviest_foot(state));
+        let translation = match self.previous_soles {
+            Some(previous) => previous[support as usize] - soles[support as usize],
+            None => Vector2::zeros(),
+        };
+
+        self.support = Some(self.next_support(support, state));
+        self.previous_soles = Some(soles);
+        self.pose *= Isometry2::new(translation, rotation);
+
+        (translation, rotation)
+    }
+
+    /// The accumulated pose of the robot, relative to its pose when the estimator was created or reset.
+    pub fn pose(&self) -> Isometry2<f32> {
+        self.pose
+    }
+
+    /// The current support foot, or `None` if no state has been processed yet.
+    pub fn support_foot(&self) -> Option<Foot> {
+        self.support
+    }
+
+    /// Reset the accumulated pose to the origin.
+    pub fn reset(&mut self) {
+        self.pose = Isometry2::identity();
+    }
+
+    fn next_support(&self, support: Foot, state: &NaoState) -> Foot {
+        let left = state.fsr.left_foot.sum();
+        let right = state.fsr.right_foot.sum();
+        let hysteresis = self.config.support_hysteresis;
+
+        match support {
+            Foot::Left if right > left + hysteresis => Foot::Right,
+            Foot::Right if left > right + hysteresis => Foot::Left,
+            support => support,
+        }
+    }
+}
+
+fn heaviest_foot(state: &NaoState) -> Foot {
+    if state.fsr.right_foot.sum() > state.fsr.left_foot.sum() {
+        Foot::Right
+    } else {
+        Foot::Left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinematics::dimensions::{THIGH_LENGTH, TIBIA_LENGTH};
+    use crate::types::{FillExt, Fsr, FsrFoot, JointArray};
+
+    fn state(left_hip_pitch: f32, right_hip_pitch: f32, support: Foot) -> NaoState {
+        let (left, right) = match support {
+            Foot::Left => (1.0, 0.1),
+            Foot::Right => (0.1, 1.0),
+        };
+
+        NaoState {
+            position: JointArray {
+                left_hip_pitch,
+                left_ankle_pitch: -left_hip_pitch,
+                right_hip_pitch,
+                right_ankle_pitch: -right_hip_pitch,
+                ..Default::default()
+            },
+            fsr: Fsr {
+                left_foot: FsrFoot::fill(left),
+                right_foot: FsrFoot::fill(right),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_standing_still_does_not_drift() {
+        let mut odometry = OdometryEstimator::default();
+        let standing = state(-0.2, -0.2, Foot::Left);
+
+        for _ in 0..1000 {
+            odometry.update(&standing);
+        }
+
+        assert_eq!(odometry.pose(), Isometry2::identity());
+    }
+
+    #[test]
+    fn test_scripted_step() {
+        let mut odometry = OdometryEstimator::default();
+        const STEPS: usize = 20;
+        const SWING: f32 = 0.2;
+
+        // standing on the left foot, swing the right foot forward while the torso moves over the left foot
+        for i in 0..=STEPS {
+            let phase = i as f32 / STEPS as f32;
+            odometry.update(&state(SWING * phase, -SWING * phase, Foot::Left));
+        }
+        assert_eq!(odometry.support_foot(), Some(Foot::Left));
+
+        // the ankle keeps the foot flat, so only the thigh and tibia rotate around the hip
+        let expected = (THIGH_LENGTH + TIBIA_LENGTH) * SWING.sin();
+        let x = odometry.pose().translation.x;
+        assert!((x - expected).abs() < 1e-4, "{x} != {expected}");
+
+        // shift the weight to the right foot, without moving the joints
+        let (translation, _) = odometry.update(&state(SWING, -SWING, Foot::Right));
+        assert_eq!(translation, Vector2::zeros());
+        let (translation, _) = odometry.update(&state(SWING, -SWING, Foot::Right));
+        assert_eq!(translation, Vector2::zeros());
+        assert_eq!(odometry.support_foot(), Some(Foot::Right));
+
+        // move the torso over the right foot
+        for i in 0..=STEPS {
+            let phase = 1.0 - i as f32 / STEPS as f32;
+            odometry.update(&state(SWING * phase, -SWING * phase, Foot::Right));
+        }
+
+        let x = odometry.pose().translation.x;
+        assert!(
+            (x - 2.0 * expected).abs() < 1e-4,
+            "{x} != {}",
+            2.0 * expected
+        );
+        assert!(odometry.pose().translation.y.abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_gyro_rotation() {
+        let mut odometry = OdometryEstimator::default();
+        let mut turning = state(0.0, 0.0, Foot::Left);
+        turning.gyroscope.z = 1.0;
+
+        for _ in 0..100 {
+            odometry.update(&turning);
+        }
+
+        assert!((odometry.pose().rotation.angle() - 1.2).abs() < 1e-4);
+    }
+}
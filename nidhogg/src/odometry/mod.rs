@@ -0,0 +1,6 @@
+//! Odometry estimation from joint positions and the IMU.
+//!
+
+mod estimator;
+
+pub use estimator::{OdometryConfig, OdometryEstimator};
@@ -0,0 +1,208 @@
+//! Named full-body poses, and [`mirror`] to swap the left and right side of a pose.
+//!
+//! All poses except [`zero`] are within the [`JOINT_LIMITS`](crate::safety::JOINT_LIMITS).
+//!
+//! # Example
+//!
+//! ```
+//! use nidhogg::poses;
+//!
+//! let mut wave = poses::stand();
+//! wave.left_shoulder_pitch = -1.2;
+//! wave.left_shoulder_roll = 0.4;
+//!
+//! // wave with the right arm instead
+//! let wave_right = poses::mirror(&wave);
+//! assert_eq!(wave_right.right_shoulder_pitch, -1.2);
+//! assert_eq!(wave_right.right_shoulder_roll, -0.4);
+//! ```
+
+use crate::types::{FillExt, JointArray};
+
+/// All joints at their zero position, with the arms stretched forward and the legs straight.
+///
+/// The zero position of the elbow rolls is slightly outside of their joint limits.
+pub fn zero() -> JointArray<f32> {
+    JointArray::fill(0.0)
+}
+
+/// Standing with slightly bent knees and the arms hanging at the sides of the body.
+pub fn stand() -> JointArray<f32> {
+    JointArray::<f32>::builder()
+        .left_shoulder_pitch(1.5)
+        .left_shoulder_roll(0.15)
+        .left_elbow_yaw(-1.2)
+        .left_elbow_roll(-0.3)
+        .right_shoulder_pitch(1.5)
+        .right_shoulder_roll(-0.15)
+        .right_elbow_yaw(1.2)
+        .right_elbow_roll(0.3)
+        .left_hip_pitch(-0.45)
+        .left_knee_pitch(0.9)
+        .left_ankle_pitch(-0.45)
+        .right_hip_pitch(-0.45)
+        .right_knee_pitch(0.9)
+        .right_ankle_pitch(-0.45)
+        .build()
+}
+
+/// The crouching posture used when sitting down.
+pub fn sit() -> JointArray<f32> {
+    JointArray::<f32>::builder()
+        .left_shoulder_pitch(1.5)
+        .left_shoulder_roll(0.1)
+        .left_elbow_yaw(-1.2)
+        .left_elbow_roll(-0.5)
+        .right_shoulder_pitch(1.5)
+        .right_shoulder_roll(-0.1)
+        .right_elbow_yaw(1.2)
+        .right_elbow_roll(0.5)
+        .left_hip_pitch(-0.85)
+        .left_knee_pitch(2.1)
+        .left_ankle_pitch(-1.18)
+        .right_hip_pitch(-0.85)
+        .right_knee_pitch(2.1)
+        .right_ankle_pitch(-1.18)
+        .build()
+}
+
+/// The posture held while the robot is penalized, which is the crouch of [`sit`].
+///
+/// See [`NaoControlMessage::penalized`](crate::NaoControlMessage::penalized) for a message holding this pose.
+pub fn penalized() -> JointArray<f32> {
+    sit()
+}
+
+/// Standing ready to walk, with the arms held behind the back so they do not collide with other robots.
+pub fn ready_arms_back() -> JointArray<f32> {
+    JointArray {
+        left_shoulder_pitch: 2.0,
+        left_shoulder_roll: 0.2,
+        left_elbow_yaw: 1.5,
+        left_elbow_roll: -0.05,
+        left_wrist_yaw: -1.5,
+        right_shoulder_pitch: 2.0,
+        right_shoulder_roll: -0.2,
+        right_elbow_yaw: -1.5,
+        right_elbow_roll: 0.05,
+        right_wrist_yaw: 1.5,
+        ..stand()
+    }
+}
+
+/// Mirror `pose` in the sagittal plane, so the left side of the robot does what the right side did and vice versa.
+///
+/// The left and right joints are swapped, and the joints that rotate sideways (the rolls and yaws)
+/// are negated, because their axes are mirrored between both sides. Pitch joints and hands keep their value.
+///
+/// The head yaw is negated, so the robot looks to the other side. The hip yaw pitch is a single
+/// joint that moves both legs symmetrically, so it keeps its value.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{poses, types::JointArray};
+///
+/// let pose = JointArray { head_yaw: 0.5, left_ankle_roll: 0.1, ..poses::stand() };
+///
+/// let mirrored = poses::mirror(&pose);
+/// assert_eq!(mirrored.head_yaw, -0.5);
+/// assert_eq!(mirrored.right_ankle_roll, -0.1);
+/// assert_eq!(poses::mirror(&mirrored), pose);
+/// ```
+pub fn mirror(pose: &JointArray<f32>) -> JointArray<f32> {
+    JointArray {
+        head_yaw: -pose.head_yaw,
+        head_pitch: pose.head_pitch,
+
+        left_shoulder_pitch: pose.right_shoulder_pitch,
+        left_shoulder_roll: -pose.right_shoulder_roll,
+        left_elbow_yaw: -pose.right_elbow_yaw,
+        left_elbow_roll: -pose.right_elbow_roll,
+        left_wrist_yaw: -pose.right_wrist_yaw,
+
+        left_hip_yaw_pitch: pose.left_hip_yaw_pitch,
+        left_hip_roll: -pose.right_hip_roll,
+        left_hip_pitch: pose.right_hip_pitch,
+        left_knee_pitch: pose.right_knee_pitch,
+        left_ankle_pitch: pose.right_ankle_pitch,
+        left_ankle_roll: -pose.right_ankle_roll,
+
+        right_shoulder_pitch: pose.left_shoulder_pitch,
+        right_shoulder_roll: -pose.left_shoulder_roll,
+        right_elbow_yaw: -pose.left_elbow_yaw,
+        right_elbow_roll: -pose.left_elbow_roll,
+        right_wrist_yaw: -pose.left_wrist_yaw,
+
+        right_hip_roll: -pose.left_hip_roll,
+        right_hip_pitch: pose.left_hip_pitch,
+        right_knee_pitch: pose.left_knee_pitch,
+        right_ankle_pitch: pose.left_ankle_pitch,
+        right_ankle_roll: -pose.left_ankle_roll,
+
+        left_hand: pose.right_hand,
+        right_hand: pose.left_hand,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::safety::JOINT_LIMITS;
+
+    /// Every joint has a different value, so any joint that is mirrored to the wrong place shows up.
+    fn asymmetric() -> JointArray<f32> {
+        JointArray::try_from_iter((0..25).map(|i| i as f32 * 0.1 - 1.2)).unwrap()
+    }
+
+    #[test]
+    fn test_poses_within_joint_limits() {
+        for pose in [stand(), sit(), penalized(), ready_arms_back()] {
+            assert!(pose
+                .zip(JOINT_LIMITS)
+                .all(|(position, limit)| limit.contains(position)));
+        }
+    }
+
+    #[test]
+    fn test_mirror_twice_is_identity() {
+        assert_eq!(mirror(&mirror(&asymmetric())), asymmetric());
+    }
+
+    #[test]
+    fn test_symmetric_poses_mirror_to_themselves() {
+        for pose in [zero(), stand(), sit(), ready_arms_back()] {
+            assert_eq!(mirror(&pose), pose);
+        }
+    }
+
+    #[test]
+    fn test_mirror_raised_left_arm() {
+        let raised = JointArray {
+            left_shoulder_pitch: -1.5,
+            left_shoulder_roll: 0.5,
+            left_elbow_yaw: -1.0,
+            left_elbow_roll: -0.3,
+            left_wrist_yaw: 0.7,
+            left_hand: 1.0,
+            ..stand()
+        };
+
+        let mirrored = mirror(&raised);
+        assert_eq!(
+            mirrored,
+            JointArray {
+                right_shoulder_pitch: -1.5,
+                right_shoulder_roll: -0.5,
+                right_elbow_yaw: 1.0,
+                right_elbow_roll: 0.3,
+                right_wrist_yaw: -0.7,
+                right_hand: 1.0,
+                ..stand()
+            }
+        );
+        assert!(mirrored
+            .zip(JOINT_LIMITS)
+            .all(|(position, limit)| limit.contains(position)));
+    }
+}
@@ -0,0 +1,94 @@
+//! Re-exports the traits and types needed by most applications.
+//!
+//! Forgetting to import a trait such as [`FillExt`] results in confusing "method not found" errors,
+//! importing the prelude brings all of them into scope at once.
+//!
+//! The prelude does not contain [`Result`](crate::Result), so it does not shadow the [`Result`]
+//! of the standard library. The `LoLA` wire format types, such as [`LolaControlMsg`](crate::backend::LolaControlMsg),
+//! are meant for tooling that inspects raw frames and are only available from [`backend`](crate::backend).
+//!
+//! # Example
+//!
+#![cfg_attr(feature = "lola", doc = "```no_run")]
+#![cfg_attr(not(feature = "lola"), doc = "```ignore")]
+//! use nidhogg::prelude::*;
+//!
+//! let mut nao = LolaBackend::connect().unwrap();
+//!
+//! let state = nao.read_nao_state().unwrap();
+//! let msg = NaoControlMessage::builder()
+//!     .position(state.position)
+//!     .stiffness(JointArray::fill(0.5))
+//!     .chest(color::f32::CYAN)
+//!     .build();
+//!
+//! nao.send_control_msg(msg).unwrap();
+//! ```
+
+#[cfg(feature = "lola")]
+pub use crate::backend::LolaBackend;
+pub use crate::{
+    backend::{ConnectWithPath, ConnectWithRetry, ReadHardwareInfo, RetryPolicy},
+    types::{
+        color, ArmJoints, FillExt, HeadJoints, JointArray, JointMask, JointName, JointRegion,
+        JointRegionOps, LeftArmJoints, LeftEar, LeftEye, LeftLegJoints, LegJoints, Rgb, RgbF32,
+        RgbU8, RightArmJoints, RightEar, RightEye, RightLegJoints, SingleArmJoints, Skull,
+        SonarMode,
+    },
+    DisconnectExt, Error, HardwareInfo, NaoBackend, NaoControlMessage, NaoState,
+};
+
+#[cfg(test)]
+mod tests {
+    // `tests/public_api.rs` lists the items of the prelude, this checks that they can be used together.
+    #[test]
+    #[allow(clippy::type_complexity)]
+    fn test_prelude_items_are_usable() {
+        use super::*;
+
+        fn region<R: JointRegionOps<f32> + FillExt<f32>>() {}
+        region::<JointArray<f32>>();
+
+        #[cfg(feature = "lola")]
+        {
+            fn backend<B: NaoBackend + ConnectWithRetry + ReadHardwareInfo + DisconnectExt>() {}
+            fn path<B: ConnectWithPath>() {}
+
+            backend::<LolaBackend>();
+            path::<LolaBackend>();
+        }
+
+        let _: Option<(
+            NaoState,
+            NaoControlMessage,
+            HardwareInfo,
+            Error,
+            RetryPolicy,
+            JointMask,
+            JointName,
+            JointRegion,
+            SonarMode,
+        )> = None;
+        let _: Option<(
+            HeadJoints<f32>,
+            SingleArmJoints<f32>,
+            LeftArmJoints<f32>,
+            RightArmJoints<f32>,
+            ArmJoints<f32>,
+            LeftLegJoints<f32>,
+            RightLegJoints<f32>,
+            LegJoints<f32>,
+        )> = None;
+        let _: Option<(LeftEar, RightEar, LeftEye, RightEye, Skull)> = None;
+        let _: (Rgb<u8>, RgbU8, RgbF32) = (color::u8::RED, color::u8::RED, color::f32::RED);
+    }
+
+    #[test]
+    fn test_prelude_does_not_shadow_std() {
+        use super::*;
+
+        // `Result` still refers to the standard library type, with its error type parameter
+        let result: Result<NaoState, Error> = Ok(NaoState::default());
+        assert!(result.is_ok());
+    }
+}
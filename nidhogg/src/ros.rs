@@ -0,0 +1,214 @@
+//! Conversions between nidhogg types and the structure of ROS 2 messages.
+//!
+//! The [`JointStateMsg`] mirrors [`sensor_msgs/JointState`](https://docs.ros2.org/latest/api/sensor_msgs/msg/JointState.html),
+//! without the header. Joints are named after the canonical ROS names of the NAO joints, such as `HeadYaw`
+//! and `LHipYawPitch`, which are the same as the names used by `LoLA`.
+//!
+//! # Example
+//!
+//! ```
+//! use nidhogg::NaoState;
+//!
+//! let mut state = NaoState::default();
+//! state.position.left_knee_pitch = 0.5;
+//!
+//! let msg = state.to_joint_state();
+//! assert_eq!(msg.name[10], "LKneePitch");
+//! assert_eq!(msg.position[10], 0.5);
+//!
+//! let (position, unknown) = msg.joint_positions();
+//! assert_eq!(position, state.position);
+//! assert!(unknown.is_empty());
+//! ```
+
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    types::{JointArray, JointName},
+    NaoState,
+};
+
+/// Joint state of the robot, structured like a ROS 2 `sensor_msgs/JointState` message.
+///
+/// All vectors are either empty or have the same length as [`JointStateMsg::name`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JointStateMsg {
+    /// ROS names of the joints, e.g. `HeadYaw` or `LShoulderPitch`.
+    pub name: Vec<String>,
+    /// Position of each joint in radians.
+    pub position: Vec<f64>,
+    /// Velocity of each joint in radians per second.
+    pub velocity: Vec<f64>,
+    /// Effort of each joint.
+    ///
+    /// The NAO does not measure torque, so this contains the current drawn by each joint in amperes.
+    pub effort: Vec<f64>,
+}
+
+impl JointStateMsg {
+    /// Build a [`JointArray`] from the names and positions in this message.
+    ///
+    /// See [`joint_positions_from_names`] for the handling of missing and unknown joints.
+    pub fn joint_positions(&self) -> (JointArray<f32>, Vec<String>) {
+        joint_positions_from_names(
+            self.name
+                .iter()
+                .map(String::as_str)
+                .zip(self.position.iter().map(|&position| position as f32)),
+        )
+    }
+}
+
+impl NaoState {
+    /// Convert the joint positions and currents to a [`JointStateMsg`], in `LoLA` joint order.
+    ///
+    /// The velocity is left empty, use [`NaoState::to_joint_state_with_velocity`] to fill it in.
+    pub fn to_joint_state(&self) -> JointStateMsg {
+        let to_f64 = |values: &JointArray<f32>| {
+            values
+                .as_array_ref()
+                .into_iter()
+                .map(|&value| f64::from(value))
+                .collect()
+        };
+
+        JointStateMsg {
+            name: JointName::ALL
+                .iter()
+                .map(|name| name.lola_name().to_string())
+                .collect(),
+            position: to_f64(&self.position),
+            velocity: Vec::new(),
+            effort: to_f64(&self.current),
+        }
+    }
+
+    /// Convert to a [`JointStateMsg`] like [`NaoState::to_joint_state`], with the velocity
+    /// computed as the finite difference from the `previous` state, `dt` earlier.
+    ///
+    /// If `dt` is zero, the velocity is left empty.
+    pub fn to_joint_state_with_velocity(&self, previous: &NaoState, dt: Duration) -> JointStateMsg {
+        let mut msg = self.to_joint_state();
+        if dt.is_zero() {
+            return msg;
+        }
+
+        let dt = dt.as_secs_f64();
+        msg.velocity = self
+            .position
+            .as_array_ref()
+            .into_iter()
+            .zip(previous.position.as_array_ref())
+            .map(|(&current, &previous)| (f64::from(current) - f64::from(previous)) / dt)
+            .collect();
+        msg
+    }
+}
+
+/// Build a [`JointArray`] from pairs of joint names and positions.
+///
+/// Names are matched against the ROS names (`HeadYaw`) and the field names (`head_yaw`) of the joints.
+/// Joints that are not in `pairs` are set to `0.0`.
+/// The names that do not belong to a NAO joint are ignored, and returned in the order they were encountered.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::ros::joint_positions_from_names;
+///
+/// let (position, unknown) = joint_positions_from_names([("HeadYaw", 0.5), ("RHipYawPitch", 0.1)]);
+///
+/// assert_eq!(position.head_yaw, 0.5);
+/// assert_eq!(unknown, ["RHipYawPitch"]);
+/// ```
+pub fn joint_positions_from_names<'a>(
+    pairs: impl IntoIterator<Item = (&'a str, f32)>,
+) -> (JointArray<f32>, Vec<String>) {
+    let mut position = JointArray::default();
+    let mut unknown = Vec::new();
+
+    for (name, value) in pairs {
+        match name.parse::<JointName>() {
+            Ok(joint) => position[joint] = value,
+            Err(_) => unknown.push(name.to_string()),
+        }
+    }
+
+    (position, unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_names_are_complete() {
+        let msg = NaoState::default().to_joint_state();
+
+        assert_eq!(msg.name.len(), 25);
+        assert_eq!(msg.name.iter().collect::<HashSet<_>>().len(), 25);
+        assert_eq!(msg.position.len(), 25);
+        assert_eq!(msg.effort.len(), 25);
+        assert!(msg.velocity.is_empty());
+
+        assert_eq!(msg.name[0], "HeadYaw");
+        assert_eq!(msg.name[7], "LHipYawPitch");
+        assert_eq!(msg.name[24], "RHand");
+        for (name, joint) in msg.name.iter().zip(JointName::ALL) {
+            assert_eq!(name.parse::<JointName>(), Ok(joint));
+        }
+    }
+
+    #[test]
+    fn test_values_follow_joint_order() {
+        let mut state = NaoState::default();
+        state.position.right_ankle_roll = 0.25;
+        state.current.left_hand = 0.5;
+
+        let msg = state.to_joint_state();
+        let index = |joint: JointName| msg.name.iter().position(|name| name == joint.lola_name());
+
+        assert_eq!(
+            msg.position[index(JointName::RightAnkleRoll).unwrap()],
+            0.25
+        );
+        assert_eq!(msg.effort[index(JointName::LeftHand).unwrap()], 0.5);
+        assert_eq!(msg.joint_positions(), (state.position, Vec::new()));
+    }
+
+    #[test]
+    fn test_velocity_from_finite_differences() {
+        let previous = NaoState::default();
+        let mut state = NaoState::default();
+        state.position.head_pitch = 0.024;
+
+        let msg = state.to_joint_state_with_velocity(&previous, Duration::from_millis(12));
+        assert_eq!(msg.velocity.len(), 25);
+        assert!((msg.velocity[1] - 2.0).abs() < 1e-6);
+        assert_eq!(msg.velocity[0], 0.0);
+
+        let msg = state.to_joint_state_with_velocity(&previous, Duration::ZERO);
+        assert!(msg.velocity.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_names_are_returned() {
+        let (position, unknown) = joint_positions_from_names([
+            ("LShoulderPitch", 1.0),
+            ("RHipYawPitch", 0.3),
+            ("right_knee_pitch", 0.5),
+            ("Tail", 2.0),
+        ]);
+
+        assert_eq!(position.left_shoulder_pitch, 1.0);
+        assert_eq!(position.right_knee_pitch, 0.5);
+        assert_eq!(position.head_yaw, 0.0);
+        assert_eq!(unknown, ["RHipYawPitch", "Tail"]);
+    }
+}
@@ -0,0 +1,415 @@
+//! Implements the [`FallDetector`], which tracks whether the robot is upright, falling or lying on the ground.
+
+use crate::{backend::BackendCapabilities, input::FsrCalibration, NaoState};
+
+/// The direction in which the robot is falling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Falling towards the front of the robot.
+    Forward,
+    /// Falling towards the back of the robot.
+    Backward,
+    /// Falling towards the left side of the robot.
+    Left,
+    /// Falling towards the right side of the robot.
+    Right,
+}
+
+/// The fall state of the robot, as estimated by the [`FallDetector`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FallState {
+    /// The robot is standing (or being held) upright.
+    #[default]
+    Upright,
+    /// The robot is tilting beyond the falling threshold in the provided direction.
+    Falling(Direction),
+    /// The robot is lying on its front.
+    FallenFront,
+    /// The robot is lying on its back.
+    FallenBack,
+    /// The robot is lying on one of its sides.
+    FallenSide,
+}
+
+impl FallState {
+    /// Returns `true` if the robot is lying on the ground.
+    pub fn is_fallen(&self) -> bool {
+        matches!(
+            self,
+            FallState::FallenFront | FallState::FallenBack | FallState::FallenSide
+        )
+    }
+}
+
+/// Configuration for the [`FallDetector`].
+///
+/// All angles are in radians, accelerations in m/s² and FSR values in (approximate) kilograms.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FallDetectorConfig {
+    /// Torso inclination above which the robot is considered to be falling.
+    pub falling_angle: f32,
+    /// Torso inclination above which the robot is considered to be lying on the ground.
+    pub fallen_angle: f32,
+    /// Margin below [`Self::falling_angle`] the inclination has to drop before the robot
+    /// is considered upright again.
+    ///
+    /// This prevents the state from oscillating when the robot tilts right around the threshold.
+    pub hysteresis: f32,
+    /// Magnitude of the measured acceleration below which the robot is considered to be in free fall.
+    pub free_fall_acceleration: f32,
+    /// Total FSR value above which the feet are considered to be in contact with the ground.
+    ///
+    /// When the detector has a [`FsrCalibration`], this threshold applies to the calibrated values.
+    pub ground_contact_threshold: f32,
+    /// Number of consecutive frames the fallen conditions have to hold before switching to a fallen state.
+    pub min_fallen_frames: u32,
+}
+
+impl Default for FallDetectorConfig {
+    fn default() -> Self {
+        Self {
+            falling_angle: 0.6,
+            fallen_angle: 1.2,
+            hysteresis: 0.15,
+            free_fall_acceleration: 3.0,
+            ground_contact_threshold: 0.5,
+            min_fallen_frames: 10,
+        }
+    }
+}
+
+/// Detects whether the robot is falling or has fallen, based on the torso angles,
+/// the accelerometer and the force sensitive resistors in the feet.
+///
+/// The detector should be updated with every new [`NaoState`].
+///
+/// # Example
+///
+/// ```
+/// use nalgebra::{Vector2, Vector3};
+/// use nidhogg::{NaoState, safety::{Direction, FallDetector, FallState}};
+///
+/// let mut detector = FallDetector::default();
+///
+/// let mut state = NaoState {
+///     accelerometer: Vector3::new(0.0, 0.0, 9.81),
+///     ..Default::default()
+/// };
+/// assert_eq!(detector.update(&state), FallState::Upright);
+///
+/// // The torso pitches forward beyond the falling threshold
+/// state.angles = Vector2::new(0.0, 0.8);
+/// assert_eq!(detector.update(&state), FallState::Falling(Direction::Forward));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FallDetector {
+    config: FallDetectorConfig,
+    state: FallState,
+    fallen_frames: u32,
+    fsr_calibration: Option<FsrCalibration>,
+    ignore_fsr: bool,
+}
+
+impl FallDetector {
+    /// Create a new [`FallDetector`] using the provided configuration.
+    pub fn new(config: FallDetectorConfig) -> Self {
+        Self {
+            config,
+            state: FallState::Upright,
+            fallen_frames: 0,
+            fsr_calibration: None,
+            ignore_fsr: false,
+        }
+    }
+
+    /// Adapt the detector to the data provided by a backend with the provided capabilities.
+    ///
+    /// Backends without FSR report zeros, which look like the feet never touch the ground.
+    /// On those backends, the fall state is based on the torso angles and the accelerometer only.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(feature = "lola", doc = "```no_run")]
+    #[cfg_attr(not(feature = "lola"), doc = "```ignore")]
+    /// use nidhogg::{NaoBackend, backend::LolaBackend, safety::FallDetector};
+    ///
+    /// let nao = LolaBackend::connect().unwrap();
+    /// let detector = FallDetector::default().with_capabilities(nao.capabilities());
+    /// ```
+    #[must_use]
+    pub fn with_capabilities(mut self, capabilities: BackendCapabilities) -> Self {
+        self.ignore_fsr = !capabilities.has_fsr;
+        self
+    }
+
+    /// Use the provided [`FsrCalibration`] for the FSR values before checking for ground contact.
+    ///
+    /// Uncalibrated sensors that drift upwards can otherwise report ground contact while the robot is lying down.
+    #[must_use]
+    pub fn with_fsr_calibration(mut self, calibration: FsrCalibration) -> Self {
+        self.fsr_calibration = Some(calibration);
+        self
+    }
+
+    /// The [`FsrCalibration`] used by this detector, if any.
+    pub fn fsr_calibration(&self) -> Option<&FsrCalibration> {
+        self.fsr_calibration.as_ref()
+    }
+
+    /// The configuration used by this detector.
+    pub fn config(&self) -> &FallDetectorConfig {
+        &self.config
+    }
+
+    /// The most recently computed [`FallState`].
+    pub fn state(&self) -> FallState {
+        self.state
+    }
+
+    /// Reset the detector to the [`FallState::Upright`] state.
+    pub fn reset(&mut self) {
+        self.state = FallState::Upright;
+        self.fallen_frames = 0;
+    }
+
+    /// Update the detector with a new [`NaoState`] and return the resulting [`FallState`].
+    pub fn update(&mut self, nao_state: &NaoState) -> FallState {
+        let roll = nao_state.angles.x;
+        let pitch = nao_state.angles.y;
+        let tilt = roll.abs().max(pitch.abs());
+
+        let fsr = match &self.fsr_calibration {
+            Some(calibration) => calibration.apply(&nao_state.fsr).sum(),
+            None => nao_state.fsr.sum(),
+        };
+        let free_fall = nao_state.accelerometer.norm() < self.config.free_fall_acceleration;
+        // without FSR, only the torso angles and accelerometer are used
+        let ground_contact = !self.ignore_fsr && fsr > self.config.ground_contact_threshold;
+        let recovered = tilt < self.config.falling_angle - self.config.hysteresis;
+
+        let lying = tilt > self.config.fallen_angle && !ground_contact;
+        self.fallen_frames = if lying { self.fallen_frames + 1 } else { 0 };
+
+        self.state = match self.state {
+            FallState::Upright => {
+                if tilt > self.config.falling_angle || (free_fall && !ground_contact) {
+                    FallState::Falling(fall_direction(roll, pitch))
+                } else {
+                    FallState::Upright
+                }
+            }
+            FallState::Falling(direction) => {
+                if self.fallen_frames >= self.config.min_fallen_frames {
+                    fallen_state(roll, pitch)
+                } else if recovered && !free_fall {
+                    FallState::Upright
+                } else if tilt > self.config.falling_angle {
+                    FallState::Falling(fall_direction(roll, pitch))
+                } else {
+                    FallState::Falling(direction)
+                }
+            }
+            fallen => {
+                if recovered {
+                    FallState::Upright
+                } else if lying {
+                    fallen_state(roll, pitch)
+                } else {
+                    fallen
+                }
+            }
+        };
+
+        self.state
+    }
+}
+
+/// Positive pitch tilts the torso forward, positive roll tilts it to the right.
+fn fall_direction(roll: f32, pitch: f32) -> Direction {
+    if pitch.abs() >= roll.abs() {
+        if pitch >= 0.0 {
+            Direction::Forward
+        } else {
+            Direction::Backward
+        }
+    } else if roll >= 0.0 {
+        Direction::Right
+    } else {
+        Direction::Left
+    }
+}
+
+fn fallen_state(roll: f32, pitch: f32) -> FallState {
+    match fall_direction(roll, pitch) {
+        Direction::Forward => FallState::FallenFront,
+        Direction::Backward => FallState::FallenBack,
+        Direction::Left | Direction::Right => FallState::FallenSide,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Vector2, Vector3};
+
+    use super::*;
+    use crate::types::{FillExt, Fsr, FsrFoot};
+
+    const GRAVITY: f32 = 9.81;
+
+    fn frame(roll: f32, pitch: f32, fsr: f32, acceleration: f32) -> NaoState {
+        NaoState {
+            angles: Vector2::new(roll, pitch),
+            accelerometer: Vector3::new(0.0, 0.0, acceleration),
+            fsr: Fsr {
+                left_foot: FsrFoot::fill(fsr),
+                right_foot: FsrFoot::fill(fsr),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn run(detector: &mut FallDetector, frames: impl IntoIterator<Item = NaoState>) -> FallState {
+        frames
+            .into_iter()
+            .fold(FallState::Upright, |_, state| detector.update(&state))
+    }
+
+    #[test]
+    fn test_standing_stays_upright() {
+        let mut detector = FallDetector::default();
+        let frames = (0..100).map(|_| frame(0.02, -0.03, 0.6, GRAVITY));
+
+        assert_eq!(run(&mut detector, frames), FallState::Upright);
+    }
+
+    #[test]
+    fn test_front_fall() {
+        let mut detector = FallDetector::default();
+
+        // standing, tipping over forwards and finally lying on the ground without foot contact.
+        let standing = (0..10).map(|_| frame(0.0, 0.0, 0.6, GRAVITY));
+        let tipping = (0..10).map(|i| frame(0.0, 0.1 * i as f32, 0.3, GRAVITY));
+        run(&mut detector, standing.chain(tipping));
+        assert_eq!(detector.state(), FallState::Falling(Direction::Forward));
+
+        let lying: Vec<_> = (0..10).map(|_| frame(0.05, 1.5, 0.0, GRAVITY)).collect();
+        run(&mut detector, lying[..9].iter().cloned());
+        assert_eq!(detector.state(), FallState::Falling(Direction::Forward));
+
+        detector.update(&lying[9]);
+        assert_eq!(detector.state(), FallState::FallenFront);
+    }
+
+    #[test]
+    fn test_back_fall() {
+        let mut detector = FallDetector::default();
+
+        let tipping = (0..10).map(|i| frame(0.0, -0.1 * i as f32, 0.3, GRAVITY));
+        let lying = (0..20).map(|_| frame(-0.1, -1.5, 0.0, GRAVITY));
+
+        assert_eq!(
+            run(&mut detector, tipping.chain(lying)),
+            FallState::FallenBack
+        );
+    }
+
+    #[test]
+    fn test_side_fall() {
+        let mut detector = FallDetector::default();
+
+        let tipping = (0..10).map(|i| frame(-0.1 * i as f32, 0.0, 0.3, GRAVITY));
+        run(&mut detector, tipping);
+        assert_eq!(detector.state(), FallState::Falling(Direction::Left));
+
+        let lying = (0..20).map(|_| frame(-1.5, 0.1, 0.0, GRAVITY));
+        assert_eq!(run(&mut detector, lying), FallState::FallenSide);
+    }
+
+    #[test]
+    fn test_pick_up_is_not_a_fall() {
+        let mut detector = FallDetector::default();
+
+        // Robot is lifted: no foot contact, but the torso stays upright.
+        let standing = (0..10).map(|_| frame(0.0, 0.0, 0.6, GRAVITY));
+        let lifted = (0..50).map(|i| frame(0.05, 0.1, 0.0, GRAVITY + (i % 3) as f32));
+
+        assert_eq!(
+            run(&mut detector, standing.chain(lifted)),
+            FallState::Upright
+        );
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let mut detector = FallDetector::default();
+        let threshold = detector.config().falling_angle;
+
+        detector.update(&frame(0.0, threshold + 0.01, 0.6, GRAVITY));
+        assert_eq!(detector.state(), FallState::Falling(Direction::Forward));
+
+        // Hovering right below the threshold should not flip back to upright.
+        for i in 0..20 {
+            let offset = if i % 2 == 0 { -0.05 } else { 0.01 };
+            detector.update(&frame(0.0, threshold + offset, 0.6, GRAVITY));
+            assert_eq!(detector.state(), FallState::Falling(Direction::Forward));
+        }
+
+        detector.update(&frame(0.0, 0.1, 0.6, GRAVITY));
+        assert_eq!(detector.state(), FallState::Upright);
+    }
+
+    #[test]
+    fn test_free_fall_without_contact() {
+        let mut detector = FallDetector::default();
+
+        detector.update(&frame(0.0, 0.1, 0.0, 0.5));
+        assert_eq!(detector.state(), FallState::Falling(Direction::Forward));
+    }
+
+    #[test]
+    fn test_get_up_after_fall() {
+        let mut detector = FallDetector::default();
+
+        let lying = (0..20).map(|_| frame(0.0, 1.5, 0.0, GRAVITY));
+        assert_eq!(run(&mut detector, lying), FallState::FallenFront);
+
+        detector.update(&frame(0.0, 0.0, 0.6, GRAVITY));
+        assert_eq!(detector.state(), FallState::Upright);
+    }
+
+    #[test]
+    fn test_backend_without_fsr() {
+        let capabilities = BackendCapabilities {
+            has_fsr: false,
+            ..BackendCapabilities::ALL
+        };
+        let mut detector = FallDetector::default().with_capabilities(capabilities);
+
+        // a simulator that reports foot contact that is not there
+        let lying = (0..20).map(|_| frame(0.0, 1.5, 10.0, GRAVITY));
+        assert_eq!(run(&mut detector, lying), FallState::FallenFront);
+
+        let mut detector = FallDetector::default().with_capabilities(BackendCapabilities::ALL);
+        let lying = (0..20).map(|_| frame(0.0, 1.5, 10.0, GRAVITY));
+        assert_eq!(
+            run(&mut detector, lying),
+            FallState::Falling(Direction::Forward)
+        );
+    }
+
+    #[test]
+    fn test_calibrated_ground_contact() {
+        // drifting sensors report 0.1 kg each while the robot is lying down
+        let lying = (0..20).map(|_| frame(0.0, 1.5, 0.1, GRAVITY));
+
+        let mut detector = FallDetector::default();
+        assert_eq!(
+            run(&mut detector, lying.clone()),
+            FallState::Falling(Direction::Forward)
+        );
+
+        let calibration = FsrCalibration::calibrate_zero(&[frame(0.0, 0.0, 0.1, GRAVITY).fsr]);
+        let mut detector = FallDetector::default().with_fsr_calibration(calibration);
+        assert_eq!(run(&mut detector, lying), FallState::FallenFront);
+    }
+}
@@ -0,0 +1,93 @@
+//! Hardware joint limits of the NAO V6 robot.
+
+use crate::types::JointArray;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The range of positions a joint can physically reach, in radians.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JointLimit {
+    /// The minimum position of the joint.
+    pub min: f32,
+    /// The maximum position of the joint.
+    pub max: f32,
+}
+
+impl JointLimit {
+    /// Create a new [`JointLimit`] with the provided minimum and maximum position.
+    #[must_use]
+    pub const fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+
+    /// Clamp the provided position to this limit.
+    pub fn clamp(&self, position: f32) -> f32 {
+        position.clamp(self.min, self.max)
+    }
+
+    /// Returns `true` if the provided position lies within this limit.
+    pub fn contains(&self, position: f32) -> bool {
+        (self.min..=self.max).contains(&position)
+    }
+}
+
+/// Joint limits of the NAO V6 robot, as documented by Aldebaran.
+///
+/// The hand joints are in the range `0.0` (closed) to `1.0` (open).
+///
+/// See: <http://doc.aldebaran.com/2-8/family/nao_technical/joints_naov6.html>
+pub const JOINT_LIMITS: JointArray<JointLimit> = JointArray {
+    head_yaw: JointLimit::new(-2.0857, 2.0857),
+    head_pitch: JointLimit::new(-0.6720, 0.5149),
+
+    left_shoulder_pitch: JointLimit::new(-2.0857, 2.0857),
+    left_shoulder_roll: JointLimit::new(-0.3142, 1.3265),
+    left_elbow_yaw: JointLimit::new(-2.0857, 2.0857),
+    left_elbow_roll: JointLimit::new(-1.5446, -0.0349),
+    left_wrist_yaw: JointLimit::new(-1.8238, 1.8238),
+
+    left_hip_yaw_pitch: JointLimit::new(-1.145303, 0.740810),
+    left_hip_roll: JointLimit::new(-0.379472, 0.790477),
+    left_hip_pitch: JointLimit::new(-1.535889, 0.484090),
+    left_knee_pitch: JointLimit::new(-0.092346, 2.112528),
+    left_ankle_pitch: JointLimit::new(-1.189516, 0.922747),
+    left_ankle_roll: JointLimit::new(-0.397880, 0.769001),
+
+    right_shoulder_pitch: JointLimit::new(-2.0857, 2.0857),
+    right_shoulder_roll: JointLimit::new(-1.3265, 0.3142),
+    right_elbow_yaw: JointLimit::new(-2.0857, 2.0857),
+    right_elbow_roll: JointLimit::new(0.0349, 1.5446),
+    right_wrist_yaw: JointLimit::new(-1.8238, 1.8238),
+
+    right_hip_roll: JointLimit::new(-0.790477, 0.379472),
+    right_hip_pitch: JointLimit::new(-1.535889, 0.484090),
+    right_knee_pitch: JointLimit::new(-0.103083, 2.120198),
+    right_ankle_pitch: JointLimit::new(-1.186448, 0.932056),
+    right_ankle_roll: JointLimit::new(-0.768992, 0.397935),
+
+    left_hand: JointLimit::new(0.0, 1.0),
+    right_hand: JointLimit::new(0.0, 1.0),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_are_ordered() {
+        assert!(JOINT_LIMITS.all(|limit| limit.min < limit.max));
+    }
+
+    #[test]
+    fn test_clamp() {
+        let limit = JOINT_LIMITS.head_pitch;
+
+        assert_eq!(limit.clamp(1.0), 0.5149);
+        assert_eq!(limit.clamp(-1.0), -0.6720);
+        assert_eq!(limit.clamp(0.1), 0.1);
+        assert!(limit.contains(0.0));
+        assert!(!limit.contains(0.6));
+    }
+}
@@ -0,0 +1,13 @@
+//! Utilities that help keep the robot (and the people around it) safe.
+
+mod fall_detection;
+mod limits;
+mod safe_backend;
+mod thermal;
+mod watchdog;
+
+pub use fall_detection::{Direction, FallDetector, FallDetectorConfig, FallState};
+pub use limits::{JointLimit, JOINT_LIMITS};
+pub use safe_backend::{SafeBackend, SafetyPolicy, Violation, ViolationCounters};
+pub use thermal::{DeratingCurve, ThermalDerating, ThermalDeratingConfig};
+pub use watchdog::{install_panic_hook, SafetyWatchdog};
@@ -0,0 +1,528 @@
+//! Implements the [`SafeBackend`], a wrapper that enforces safety limits on every control message.
+
+use std::fmt;
+
+use crate::{
+    backend::BackendCapabilities,
+    control::BacklashCompensator,
+    types::{JointArray, JointMask},
+    DisconnectExt, NaoBackend, NaoControlMessage, NaoState, Result,
+};
+
+use super::{JointLimit, ThermalDerating, JOINT_LIMITS};
+
+/// Policy enforced by the [`SafeBackend`] on every control message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SafetyPolicy {
+    /// Joint limits the requested positions are clamped to.
+    pub joint_limits: JointArray<JointLimit>,
+    /// Maximum change in position of a single joint per control message, in radians.
+    ///
+    /// The delta is computed relative to the last message sent through the [`SafeBackend`].
+    pub max_position_delta: f32,
+    /// Maximum stiffness of any joint.
+    pub max_stiffness: f32,
+    /// Temperature in degrees Celsius above which the stiffness of a joint is forced to zero.
+    ///
+    /// Set to [`None`] to disable the temperature check.
+    pub max_temperature: Option<f32>,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self {
+            joint_limits: JOINT_LIMITS,
+            max_position_delta: 0.1,
+            max_stiffness: 1.0,
+            max_temperature: Some(75.0),
+        }
+    }
+}
+
+/// A violation of the [`SafetyPolicy`] that was corrected by the [`SafeBackend`].
+///
+/// Each variant contains a mask of the joints that were affected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The requested position was outside of the joint limits.
+    PositionLimit(JointArray<bool>),
+    /// The requested position change exceeded the maximum delta per cycle.
+    RateLimit(JointArray<bool>),
+    /// The requested stiffness exceeded the maximum stiffness.
+    StiffnessLimit(JointArray<bool>),
+    /// The joint temperature exceeded the maximum temperature, so the stiffness was set to zero.
+    OverTemperature(JointArray<bool>),
+}
+
+/// Counters for the number of joints affected by each kind of [`Violation`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ViolationCounters {
+    /// Number of joints whose requested position was clamped to the joint limits.
+    pub position_limit: u64,
+    /// Number of joints whose requested position change was limited to [`SafetyPolicy::max_position_delta`].
+    pub rate_limit: u64,
+    /// Number of joints whose requested stiffness was limited to [`SafetyPolicy::max_stiffness`].
+    pub stiffness_limit: u64,
+    /// Number of joints whose stiffness was set to zero because they exceeded [`SafetyPolicy::max_temperature`].
+    pub over_temperature: u64,
+}
+
+impl ViolationCounters {
+    /// The total number of violations.
+    pub fn total(&self) -> u64 {
+        self.position_limit + self.rate_limit + self.stiffness_limit + self.over_temperature
+    }
+}
+
+type ViolationCallback = Box<dyn FnMut(&Violation) + Send>;
+
+/// Backend wrapper that enforces a [`SafetyPolicy`] before writing to the wrapped backend.
+///
+/// Every control message sent through this backend is modified such that:
+/// - joint positions are compensated for backlash, if a [`BacklashCompensator`] was added using [`SafeBackend::with_backlash_compensation`],
+/// - joint positions are clamped to the hardware limits,
+/// - joint positions change at most [`SafetyPolicy::max_position_delta`] per message,
+/// - stiffness is clamped to [`SafetyPolicy::max_stiffness`],
+/// - stiffness of joints above [`SafetyPolicy::max_temperature`] is set to zero,
+/// - stiffness of hot joints is lowered, if a [`ThermalDerating`] was added using [`SafeBackend::with_thermal_derating`].
+///
+/// Violations are never silently dropped, they are counted and can be observed using
+/// [`SafeBackend::on_violation`].
+///
+/// # Example
+#[cfg_attr(feature = "lola", doc = "```no_run")]
+#[cfg_attr(not(feature = "lola"), doc = "```ignore")]
+/// use nidhogg::{NaoBackend, backend::LolaBackend, safety::SafeBackend};
+///
+/// let mut nao = SafeBackend::new(LolaBackend::connect().unwrap())
+///     .on_violation(|violation| eprintln!("safety violation: {violation:?}"));
+///
+/// let state = nao.read_nao_state().unwrap();
+/// ```
+pub struct SafeBackend<B: NaoBackend> {
+    backend: B,
+    policy: SafetyPolicy,
+    last_position: Option<JointArray<f32>>,
+    last_temperature: Option<JointArray<f32>>,
+    counters: ViolationCounters,
+    callback: Option<ViolationCallback>,
+    thermal_derating: Option<ThermalDerating>,
+    backlash: Option<BacklashCompensator>,
+}
+
+impl<B: NaoBackend + fmt::Debug> fmt::Debug for SafeBackend<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SafeBackend")
+            .field("backend", &self.backend)
+            .field("policy", &self.policy)
+            .field("last_position", &self.last_position)
+            .field("last_temperature", &self.last_temperature)
+            .field("counters", &self.counters)
+            .field("thermal_derating", &self.thermal_derating)
+            .field("backlash", &self.backlash)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B: NaoBackend> SafeBackend<B> {
+    /// Wrap the provided backend, using the default [`SafetyPolicy`].
+    pub fn new(backend: B) -> Self {
+        Self::with_policy(backend, SafetyPolicy::default())
+    }
+
+    /// Wrap the provided backend, using the provided [`SafetyPolicy`].
+    pub fn with_policy(backend: B, policy: SafetyPolicy) -> Self {
+        Self {
+            backend,
+            policy,
+            last_position: None,
+            last_temperature: None,
+            counters: ViolationCounters::default(),
+            callback: None,
+            thermal_derating: None,
+            backlash: None,
+        }
+    }
+
+    /// Register a callback that is called for every [`Violation`] of the policy.
+    #[must_use]
+    pub fn on_violation(mut self, callback: impl FnMut(&Violation) + Send + 'static) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Lower the stiffness of hot joints using the provided [`ThermalDerating`].
+    ///
+    /// The derating is applied after the [`SafetyPolicy`], using the temperatures of the most recently read state.
+    #[must_use]
+    pub fn with_thermal_derating(mut self, derating: ThermalDerating) -> Self {
+        self.thermal_derating = Some(derating);
+        self
+    }
+
+    /// Compensate the backlash of the joints using the provided [`BacklashCompensator`].
+    ///
+    /// The compensation is applied to the requested positions before the [`SafetyPolicy`], so the
+    /// compensated positions are still clamped to the joint limits.
+    #[must_use]
+    pub fn with_backlash_compensation(mut self, compensator: BacklashCompensator) -> Self {
+        self.backlash = Some(compensator);
+        self
+    }
+
+    /// Mask of the joints whose stiffness is currently lowered by the [`ThermalDerating`].
+    ///
+    /// Returns [`None`] if no thermal derating was added.
+    pub fn derated_joints(&self) -> Option<JointMask> {
+        self.thermal_derating.as_ref().map(ThermalDerating::derated)
+    }
+
+    /// The policy enforced by this backend.
+    pub fn policy(&self) -> &SafetyPolicy {
+        &self.policy
+    }
+
+    /// The number of joints affected by each kind of violation so far.
+    pub fn counters(&self) -> &ViolationCounters {
+        &self.counters
+    }
+
+    /// Reference to the wrapped backend.
+    pub fn inner(&self) -> &B {
+        &self.backend
+    }
+
+    /// Mutable reference to the wrapped backend.
+    ///
+    /// # Note
+    ///
+    /// Messages sent directly to the wrapped backend bypass the safety policy!
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Unwrap this backend, returning the wrapped backend.
+    pub fn into_inner(self) -> B {
+        self.backend
+    }
+
+    /// Apply the safety policy to a control message, without sending it.
+    ///
+    /// Joints that are not selected by the `position_mask` of the message are not counted as position or rate violations,
+    /// as the backend replaces their position with the measured one.
+    pub fn enforce(&mut self, mut msg: NaoControlMessage) -> NaoControlMessage {
+        if let Some(backlash) = self.backlash.as_mut() {
+            msg.position = backlash.compensate(msg.position);
+        }
+
+        let limits = &self.policy.joint_limits;
+        let limited = msg.position.clone().zip(limits.clone());
+        let position_violations =
+            limited.clone().map(|(pos, limit)| !limit.contains(pos)) & msg.position_mask.clone();
+        msg.position = limited.map(|(pos, limit)| limit.clamp(pos));
+        self.report(position_violations, Violation::PositionLimit);
+
+        if let Some(previous) = self.last_position.as_ref() {
+            let max_delta = self.policy.max_position_delta;
+            let deltas = msg.position.clone().zip(previous.clone());
+            let rate_violations = deltas
+                .clone()
+                .map(|(pos, prev)| (pos - prev).abs() > max_delta)
+                & msg.position_mask.clone();
+            msg.position =
+                deltas.map(|(pos, prev)| prev + (pos - prev).clamp(-max_delta, max_delta));
+            self.report(rate_violations, Violation::RateLimit);
+        }
+
+        let max_stiffness = self.policy.max_stiffness;
+        let stiffness_violations = msg.stiffness.clone().map(|s| s > max_stiffness);
+        msg.stiffness = msg.stiffness.map(|s| s.min(max_stiffness));
+        self.report(stiffness_violations, Violation::StiffnessLimit);
+
+        if let (Some(max_temperature), Some(temperature)) =
+            (self.policy.max_temperature, self.last_temperature.as_ref())
+        {
+            let hot = temperature.clone().map(|t| t > max_temperature);
+            msg.stiffness = msg
+                .stiffness
+                .zip(hot.clone())
+                .map(|(s, hot)| if hot { 0.0 } else { s });
+            self.report(hot, Violation::OverTemperature);
+        }
+
+        if let (Some(derating), Some(temperature)) = (
+            self.thermal_derating.as_mut(),
+            self.last_temperature.as_ref(),
+        ) {
+            msg.stiffness = derating.derate(msg.stiffness, temperature);
+        }
+
+        self.last_position = Some(msg.position.clone());
+        msg
+    }
+
+    fn report(&mut self, mask: JointArray<bool>, violation: fn(JointArray<bool>) -> Violation) {
+        let count = mask.as_array_ref().iter().filter(|&&&v| v).count() as u64;
+        if count == 0 {
+            return;
+        }
+
+        let violation = violation(mask);
+        let counter = match violation {
+            Violation::PositionLimit(_) => &mut self.counters.position_limit,
+            Violation::RateLimit(_) => &mut self.counters.rate_limit,
+            Violation::StiffnessLimit(_) => &mut self.counters.stiffness_limit,
+            Violation::OverTemperature(_) => &mut self.counters.over_temperature,
+        };
+        *counter += count;
+
+        if let Some(callback) = self.callback.as_mut() {
+            callback(&violation);
+        }
+    }
+}
+
+impl<B: NaoBackend> NaoBackend for SafeBackend<B> {
+    /// Connects to the wrapped backend, using the default [`SafetyPolicy`].
+    fn connect() -> Result<Self> {
+        B::connect().map(Self::new)
+    }
+
+    /// Applies the [`SafetyPolicy`] to the control message and sends it to the wrapped backend.
+    fn send_control_msg(&mut self, update: NaoControlMessage) -> Result<()> {
+        let msg = self.enforce(update);
+        self.backend.send_control_msg(msg)
+    }
+
+    /// Reads the current state from the wrapped backend.
+    ///
+    /// The joint temperatures are stored, to be checked when sending the next control message.
+    /// If no message has been sent yet, the measured positions are used as the base for rate limiting.
+    fn read_nao_state(&mut self) -> Result<NaoState> {
+        let state = self.backend.read_nao_state()?;
+
+        self.last_temperature = Some(state.temperature.clone());
+        if self.last_position.is_none() {
+            self.last_position = Some(state.position.clone());
+        }
+
+        Ok(state)
+    }
+
+    /// The capabilities of the wrapped backend.
+    fn capabilities(&self) -> BackendCapabilities {
+        self.backend.capabilities()
+    }
+}
+
+impl<B: NaoBackend + DisconnectExt> DisconnectExt for SafeBackend<B> {
+    fn disconnect(self) -> Result<()> {
+        self.backend.disconnect()
+    }
+}
+
+impl<B: NaoBackend> From<B> for SafeBackend<B> {
+    fn from(backend: B) -> Self {
+        Self::new(backend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use crate::types::{FillExt, JointMask, JointRegion};
+
+    fn uniform_msg(position: f32, stiffness: f32) -> NaoControlMessage {
+        NaoControlMessage {
+            position: JointArray::fill(position),
+            stiffness: JointArray::fill(stiffness),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_large_jump_is_split_across_cycles() {
+        let policy = SafetyPolicy {
+            max_position_delta: 0.125,
+            ..Default::default()
+        };
+        let mut nao = SafeBackend::with_policy(MockBackend::default(), policy);
+
+        nao.send_control_msg(uniform_msg(0.0, 1.0)).unwrap();
+        for _ in 0..5 {
+            let mut msg = uniform_msg(0.0, 1.0);
+            msg.position.head_yaw = 0.5;
+            nao.send_control_msg(msg).unwrap();
+        }
+
+        let sent: Vec<f32> = nao
+            .inner()
+            .sent
+            .iter()
+            .map(|msg| msg.position.head_yaw)
+            .collect();
+
+        assert_eq!(sent, [0.0, 0.125, 0.25, 0.375, 0.5, 0.5]);
+        assert_eq!(nao.counters().rate_limit, 3);
+        assert_eq!(nao.inner().sent[5].position.head_pitch, 0.0);
+    }
+
+    #[test]
+    fn test_rate_limit_starts_from_measured_position() {
+        let state = NaoState {
+            position: JointArray::fill(0.2),
+            ..Default::default()
+        };
+        let mut nao = SafeBackend::new(MockBackend::with_states([state]));
+
+        nao.read_nao_state().unwrap();
+        nao.send_control_msg(uniform_msg(-0.5, 0.5)).unwrap();
+
+        let sent = &nao.inner().sent[0];
+        assert!((sent.position.left_shoulder_pitch - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_backlash_compensated_before_limits() {
+        let policy = SafetyPolicy {
+            max_position_delta: f32::INFINITY,
+            ..Default::default()
+        };
+        let compensator = BacklashCompensator::new(crate::control::BacklashConfig {
+            play: JointArray::fill(0.5),
+            dead_band: 0.1,
+        });
+        let mut nao = SafeBackend::with_policy(MockBackend::default(), policy)
+            .with_backlash_compensation(compensator);
+
+        nao.send_control_msg(uniform_msg(0.0, 1.0)).unwrap();
+        let violations = nao.counters().position_limit;
+
+        let mut msg = uniform_msg(0.0, 1.0);
+        msg.position.head_yaw = 1.0;
+        msg.position.head_pitch = JOINT_LIMITS.head_pitch.max;
+        nao.send_control_msg(msg).unwrap();
+
+        let sent = &nao.inner().sent[1];
+        assert_eq!(sent.position.head_yaw, 1.25);
+        assert_eq!(sent.position.head_pitch, JOINT_LIMITS.head_pitch.max);
+        // joints such as the elbow rolls can not reach zero, so they are clamped in both messages,
+        // while the compensated head pitch only exceeds its limit in the second one
+        assert_eq!(nao.counters().position_limit, 2 * violations + 1);
+    }
+
+    #[test]
+    fn test_positions_clamped_to_limits() {
+        let policy = SafetyPolicy {
+            max_position_delta: f32::INFINITY,
+            ..Default::default()
+        };
+        let mut nao = SafeBackend::with_policy(MockBackend::default(), policy);
+
+        nao.send_control_msg(uniform_msg(10.0, 1.0)).unwrap();
+
+        let sent = &nao.inner().sent[0];
+        assert_eq!(sent.position.head_pitch, JOINT_LIMITS.head_pitch.max);
+        assert_eq!(sent.position.left_hand, 1.0);
+        assert_eq!(nao.counters().position_limit, 25);
+    }
+
+    #[test]
+    fn test_uncommanded_joints_are_not_violations() {
+        let mut nao = SafeBackend::new(MockBackend::default());
+
+        nao.send_control_msg(NaoControlMessage {
+            position_mask: JointMask::from_region(JointRegion::Head),
+            ..uniform_msg(10.0, 1.0)
+        })
+        .unwrap();
+
+        assert_eq!(nao.counters().position_limit, 2);
+    }
+
+    #[test]
+    fn test_stiffness_clamped() {
+        let policy = SafetyPolicy {
+            max_stiffness: 0.6,
+            ..Default::default()
+        };
+        let mut nao = SafeBackend::with_policy(MockBackend::default(), policy);
+
+        let mut msg = uniform_msg(0.0, 0.5);
+        msg.stiffness.right_knee_pitch = 0.9;
+        nao.send_control_msg(msg).unwrap();
+
+        let sent = &nao.inner().sent[0];
+        assert_eq!(sent.stiffness.right_knee_pitch, 0.6);
+        assert_eq!(sent.stiffness.left_knee_pitch, 0.5);
+        assert_eq!(nao.counters().stiffness_limit, 1);
+    }
+
+    #[test]
+    fn test_hot_joints_are_unstiffened() {
+        let mut state = NaoState::default();
+        state.temperature.left_knee_pitch = 80.0;
+
+        let violations = Arc::new(Mutex::new(Vec::new()));
+        let recorded = violations.clone();
+        let mut nao = SafeBackend::new(MockBackend::with_states([state]))
+            .on_violation(move |v| recorded.lock().unwrap().push(v.clone()));
+
+        nao.read_nao_state().unwrap();
+        nao.send_control_msg(uniform_msg(0.0, 1.0)).unwrap();
+
+        let sent = &nao.inner().sent[0];
+        assert_eq!(sent.stiffness.left_knee_pitch, 0.0);
+        assert_eq!(sent.stiffness.right_knee_pitch, 1.0);
+
+        let expected = JointArray::<bool> {
+            left_knee_pitch: true,
+            ..Default::default()
+        };
+        assert!(violations
+            .lock()
+            .unwrap()
+            .contains(&Violation::OverTemperature(expected)));
+        assert_eq!(nao.counters().over_temperature, 1);
+    }
+
+    #[test]
+    fn test_capabilities_of_wrapped_backend() {
+        let nao = SafeBackend::new(MockBackend::default());
+
+        assert!(nao.capabilities().has_fsr);
+        assert!(!nao.capabilities().realtime);
+    }
+
+    #[test]
+    fn test_thermal_derating() {
+        let warm = |temperature| {
+            let mut state = NaoState::default();
+            state.temperature.right_hip_pitch = temperature;
+            state
+        };
+        let states = [warm(70.0), warm(74.0), warm(80.0)];
+        let mut nao = SafeBackend::new(MockBackend::with_states(states))
+            .with_thermal_derating(ThermalDerating::default());
+        assert_eq!(nao.derated_joints(), Some(JointMask::default()));
+
+        let mut sent = Vec::new();
+        for _ in 0..3 {
+            nao.read_nao_state().unwrap();
+            nao.send_control_msg(uniform_msg(0.0, 1.0)).unwrap();
+            sent.push(nao.inner().sent.last().unwrap().stiffness.right_hip_pitch);
+        }
+
+        // the temperature limit still unstiffens joints that are too hot
+        assert!((sent[0] - 0.65).abs() < 1e-6);
+        assert!((sent[1] - 0.37).abs() < 1e-6);
+        assert_eq!(sent[2], 0.0);
+
+        let derated = nao.derated_joints().unwrap();
+        assert!(derated.right_hip_pitch);
+        assert_eq!(derated.count(|derated| *derated), 1);
+    }
+}
@@ -0,0 +1,220 @@
+//! Implements [`ThermalDerating`], which lowers the stiffness of joints as they heat up.
+
+use crate::types::{FillExt, JointArray, JointMask};
+
+/// Curve that maps the temperature of a joint to a factor for its stiffness.
+///
+/// The factor is 1.0 up to [`Self::start`], and decreases linearly to [`Self::min_factor`] at [`Self::end`].
+/// All temperatures are in degrees Celsius.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeratingCurve {
+    /// Temperature at which the derating starts.
+    pub start: f32,
+    /// Temperature at which the stiffness reaches [`Self::min_factor`].
+    pub end: f32,
+    /// Factor applied to the stiffness at and above [`Self::end`].
+    pub min_factor: f32,
+}
+
+impl Default for DeratingCurve {
+    fn default() -> Self {
+        Self {
+            start: 65.0,
+            end: 75.0,
+            min_factor: 0.3,
+        }
+    }
+}
+
+impl DeratingCurve {
+    /// The factor for the stiffness of a joint at `temperature`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::safety::DeratingCurve;
+    ///
+    /// let curve = DeratingCurve::default();
+    /// assert_eq!(curve.factor(60.0), 1.0);
+    /// assert!((curve.factor(70.0) - 0.65).abs() < 1e-6);
+    /// assert_eq!(curve.factor(80.0), 0.3);
+    /// ```
+    pub fn factor(&self, temperature: f32) -> f32 {
+        if temperature <= self.start {
+            return 1.0;
+        }
+
+        let progress = ((temperature - self.start) / (self.end - self.start)).min(1.0);
+        1.0 - progress * (1.0 - self.min_factor)
+    }
+}
+
+/// Configuration for [`ThermalDerating`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThermalDeratingConfig {
+    /// The derating curve of every joint.
+    pub curves: JointArray<DeratingCurve>,
+    /// Number of degrees the temperature of a joint has to drop before its stiffness is raised again.
+    ///
+    /// This prevents the stiffness from oscillating when the temperature hovers around a value.
+    pub hysteresis: f32,
+}
+
+impl Default for ThermalDeratingConfig {
+    fn default() -> Self {
+        Self {
+            curves: JointArray::fill(DeratingCurve::default()),
+            hysteresis: 1.0,
+        }
+    }
+}
+
+/// Lowers the stiffness of hot joints, following a [`DeratingCurve`] per joint.
+///
+/// Rising temperatures are followed immediately, but the stiffness of a joint is only raised again
+/// once its temperature dropped by more than [`ThermalDeratingConfig::hysteresis`].
+///
+/// Use [`SafeBackend::with_thermal_derating`](super::SafeBackend::with_thermal_derating) to derate
+/// every control message.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{safety::ThermalDerating, types::{FillExt, JointArray}};
+///
+/// let mut derating = ThermalDerating::default();
+///
+/// let mut temperature = JointArray::fill(40.0);
+/// temperature.left_knee_pitch = 70.0;
+///
+/// let stiffness = derating.derate(JointArray::fill(1.0), &temperature);
+/// assert!((stiffness.left_knee_pitch - 0.65).abs() < 1e-6);
+/// assert_eq!(stiffness.right_knee_pitch, 1.0);
+/// assert!(derating.derated().left_knee_pitch);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ThermalDerating {
+    config: ThermalDeratingConfig,
+    /// Temperature used for the curve of every joint, which lags behind falling temperatures.
+    effective: Option<JointArray<f32>>,
+}
+
+impl ThermalDerating {
+    /// Create a new [`ThermalDerating`] using the provided configuration.
+    pub fn new(config: ThermalDeratingConfig) -> Self {
+        Self {
+            config,
+            effective: None,
+        }
+    }
+
+    /// The configuration used by this derating.
+    pub fn config(&self) -> &ThermalDeratingConfig {
+        &self.config
+    }
+
+    /// Update the temperatures and return the derated `stiffness`.
+    pub fn derate(
+        &mut self,
+        stiffness: JointArray<f32>,
+        temperature: &JointArray<f32>,
+    ) -> JointArray<f32> {
+        let hysteresis = self.config.hysteresis;
+        let effective = match self.effective.take() {
+            Some(effective) => effective.zip(temperature.clone()).map(|(effective, t)| {
+                if t > effective {
+                    t
+                } else {
+                    effective.min(t + hysteresis)
+                }
+            }),
+            None => temperature.clone(),
+        };
+
+        let factors = self.factors_for(&effective);
+        self.effective = Some(effective);
+
+        stiffness
+            .zip(factors)
+            .map(|(stiffness, factor)| stiffness * factor)
+    }
+
+    /// The factor currently applied to the stiffness of every joint.
+    pub fn factors(&self) -> JointArray<f32> {
+        match &self.effective {
+            Some(effective) => self.factors_for(effective),
+            None => JointArray::fill(1.0),
+        }
+    }
+
+    /// Mask of the joints whose stiffness is currently lowered.
+    pub fn derated(&self) -> JointMask {
+        self.factors().map(|factor| factor < 1.0)
+    }
+
+    /// Forget the temperatures, so no joints are derated until the next update.
+    pub fn reset(&mut self) {
+        self.effective = None;
+    }
+
+    fn factors_for(&self, temperature: &JointArray<f32>) -> JointArray<f32> {
+        self.config
+            .curves
+            .clone()
+            .zip(temperature.clone())
+            .map(|(curve, temperature)| curve.factor(temperature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn knee_stiffness(derating: &mut ThermalDerating, temperature: f32) -> f32 {
+        let temperatures = JointArray {
+            left_knee_pitch: temperature,
+            ..JointArray::fill(40.0)
+        };
+        let stiffness = derating
+            .derate(JointArray::fill(1.0), &temperatures)
+            .left_knee_pitch;
+
+        // round to two decimals, to compare with the expected values
+        (stiffness * 100.0).round() / 100.0
+    }
+
+    #[test]
+    fn test_curve() {
+        let mut derating = ThermalDerating::default();
+
+        let ramp: Vec<f32> = (60..=80)
+            .step_by(5)
+            .map(|t| knee_stiffness(&mut derating, t as f32))
+            .collect();
+        assert_eq!(ramp, [1.0, 1.0, 0.65, 0.3, 0.3]);
+        assert_eq!(derating.derated().count(|derated| *derated), 1);
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let mut derating = ThermalDerating::default();
+
+        assert_eq!(knee_stiffness(&mut derating, 70.0), 0.65);
+
+        // hovering below the highest temperature keeps the stiffness
+        for t in [69.5, 70.0, 69.2, 70.0] {
+            assert_eq!(knee_stiffness(&mut derating, t), 0.65);
+        }
+
+        // the stiffness follows the temperature one degree behind while cooling down
+        let ramp: Vec<f32> = [68.0, 67.0, 64.0, 63.5]
+            .into_iter()
+            .map(|t| knee_stiffness(&mut derating, t))
+            .collect();
+        assert_eq!(ramp, [0.72, 0.79, 1.0, 1.0]);
+        assert!(!derating.derated().left_knee_pitch);
+
+        // rising temperatures are followed immediately
+        assert_eq!(knee_stiffness(&mut derating, 75.0), 0.3);
+    }
+}
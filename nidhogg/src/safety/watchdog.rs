@@ -0,0 +1,270 @@
+//! Implements the [`SafetyWatchdog`], a wrapper that removes the stiffness when the client stops sending.
+
+use std::panic;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+use crate::{
+    backend::BackendCapabilities,
+    types::{FillExt, JointArray},
+    NaoBackend, NaoControlMessage, NaoState, Result,
+};
+
+/// Number of panics in any thread, counted by the hook installed by [`install_panic_hook`].
+static PANICS: AtomicU64 = AtomicU64::new(0);
+
+/// Install a panic hook that trips every existing [`SafetyWatchdog`] when any thread panics.
+///
+/// Watchdogs that are created or reset after the panic are not tripped by it.
+/// The hook calls the previously installed hook, and is only installed once.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            PANICS.fetch_add(1, Ordering::SeqCst);
+            previous(info);
+        }));
+    });
+}
+
+/// Backend wrapper that unstiffens the robot when the client stops sending control messages.
+///
+/// A stiff robot that no longer receives new commands stands rigid and overheats, for example
+/// when the control loop deadlocks. Every control message sent through the watchdog feeds it.
+/// If the watchdog was not fed within the timeout, the next message is sent without stiffness
+/// and the watchdog trips. Once tripped, all messages are sent without stiffness until
+/// [`SafetyWatchdog::reset`] is called.
+///
+/// Only one client can write to `LoLA`, so the watchdog can only act through its own connection:
+/// - dropping the watchdog, which also happens while unwinding from a panic, sends a final message without stiffness,
+/// - after a panic in any thread, the watchdog trips if [`install_panic_hook`] was called.
+///
+/// # Example
+///
+#[cfg_attr(feature = "lola", doc = "```no_run")]
+#[cfg_attr(not(feature = "lola"), doc = "```ignore")]
+/// use std::time::Duration;
+/// use nidhogg::{backend::LolaBackend, facade::Nao, safety::{self, SafetyWatchdog}, NaoBackend};
+///
+/// safety::install_panic_hook();
+///
+/// let backend = SafetyWatchdog::new(LolaBackend::connect().unwrap(), Duration::from_millis(100));
+/// let mut nao = Nao::with_backend(backend);
+///
+/// loop {
+///     // every tick sends a control message, which feeds the watchdog
+///     nao.tick().unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SafetyWatchdog<B: NaoBackend> {
+    backend: B,
+    timeout: Duration,
+    last_fed: Instant,
+    last_position: JointArray<f32>,
+    tripped: bool,
+    /// The value of [`PANICS`] when the watchdog was created or last reset.
+    panics_seen: u64,
+}
+
+impl<B: NaoBackend> SafetyWatchdog<B> {
+    /// Wrap the provided backend, tripping when it is not fed for longer than `timeout`.
+    ///
+    /// The watchdog starts out fed.
+    pub fn new(backend: B, timeout: Duration) -> Self {
+        Self {
+            backend,
+            timeout,
+            last_fed: Instant::now(),
+            last_position: JointArray::default(),
+            tripped: false,
+            panics_seen: PANICS.load(Ordering::SeqCst),
+        }
+    }
+
+    /// The timeout after which the watchdog trips.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Feed the watchdog, without sending a control message.
+    pub fn feed(&mut self) {
+        self.last_fed = Instant::now();
+    }
+
+    /// Returns `true` if the watchdog tripped, and unstiffens the robot.
+    pub fn is_tripped(&self) -> bool {
+        self.tripped
+    }
+
+    /// Reset a tripped watchdog, so control messages are sent unchanged again.
+    ///
+    /// Panics that happened before the reset no longer trip the watchdog.
+    pub fn reset(&mut self) {
+        self.tripped = false;
+        self.panics_seen = PANICS.load(Ordering::SeqCst);
+        self.feed();
+    }
+
+    /// Reference to the wrapped backend.
+    pub fn inner(&self) -> &B {
+        &self.backend
+    }
+
+    /// Mutable reference to the wrapped backend.
+    ///
+    /// # Note
+    ///
+    /// Messages sent directly to the wrapped backend do not feed the watchdog!
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Trip the watchdog if it was not fed within the timeout, or if a thread panicked.
+    fn check(&mut self) {
+        if self.tripped {
+            return;
+        }
+
+        let starved = self.last_fed.elapsed() > self.timeout;
+        if starved || PANICS.load(Ordering::SeqCst) > self.panics_seen {
+            warn!(starved, "Safety watchdog tripped, removing the stiffness");
+            self.tripped = true;
+        }
+    }
+
+    /// Message without stiffness, that holds the last sent position.
+    fn unstiffened(&self) -> NaoControlMessage {
+        NaoControlMessage {
+            position: self.last_position.clone(),
+            stiffness: JointArray::fill(0.0),
+            ..Default::default()
+        }
+    }
+}
+
+impl<B: NaoBackend> NaoBackend for SafetyWatchdog<B> {
+    fn connect() -> Result<Self> {
+        B::connect().map(|backend| Self::new(backend, Duration::from_millis(100)))
+    }
+
+    fn send_control_msg(&mut self, mut update: NaoControlMessage) -> Result<()> {
+        self.check();
+        if self.tripped {
+            update.stiffness = JointArray::fill(0.0);
+        }
+
+        self.last_position = update.position.clone();
+        self.backend.send_control_msg(update)?;
+        self.feed();
+
+        Ok(())
+    }
+
+    fn read_nao_state(&mut self) -> Result<NaoState> {
+        self.backend.read_nao_state()
+    }
+
+    /// The capabilities of the wrapped backend.
+    fn capabilities(&self) -> BackendCapabilities {
+        self.backend.capabilities()
+    }
+}
+
+impl<B: NaoBackend> Drop for SafetyWatchdog<B> {
+    fn drop(&mut self) {
+        let msg = self.unstiffened();
+        if let Err(error) = self.backend.send_control_msg(msg) {
+            warn!(%error, "Safety watchdog failed to remove the stiffness");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use super::*;
+    use crate::{backend::mock::MockBackend, facade::Nao};
+
+    /// Backend that records sent messages in a log that outlives it.
+    #[derive(Debug, Default)]
+    struct SharedBackend {
+        sent: Arc<Mutex<Vec<NaoControlMessage>>>,
+    }
+
+    impl NaoBackend for SharedBackend {
+        fn connect() -> Result<Self> {
+            Ok(Self::default())
+        }
+
+        fn send_control_msg(&mut self, update: NaoControlMessage) -> Result<()> {
+            self.sent.lock().unwrap().push(update);
+            Ok(())
+        }
+
+        fn read_nao_state(&mut self) -> Result<NaoState> {
+            Ok(NaoState::default())
+        }
+    }
+
+    fn stiff() -> NaoControlMessage {
+        NaoControlMessage {
+            position: JointArray::fill(0.1),
+            stiffness: JointArray::fill(1.0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_trips_when_starved() {
+        let mut watchdog = SafetyWatchdog::new(MockBackend::default(), Duration::from_millis(20));
+
+        watchdog.send_control_msg(stiff()).unwrap();
+        assert!(!watchdog.is_tripped());
+
+        thread::sleep(Duration::from_millis(30));
+        watchdog.send_control_msg(stiff()).unwrap();
+        watchdog.send_control_msg(stiff()).unwrap();
+        assert!(watchdog.is_tripped());
+
+        let sent = &watchdog.inner().sent;
+        assert_eq!(sent[0].stiffness, JointArray::fill(1.0));
+        assert!(sent[1..]
+            .iter()
+            .all(|msg| msg.stiffness == JointArray::fill(0.0)));
+        assert_eq!(sent[1].position, JointArray::fill(0.1));
+
+        watchdog.reset();
+        watchdog.send_control_msg(stiff()).unwrap();
+        assert_eq!(watchdog.inner().sent[3].stiffness, JointArray::fill(1.0));
+    }
+
+    #[test]
+    fn test_panic_in_control_loop_unstiffens() {
+        let backend = SharedBackend::default();
+        let sent = backend.sent.clone();
+        let mut nao = Nao::with_backend(SafetyWatchdog::new(backend, Duration::from_secs(1)));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(move || {
+            nao.set_joints(JointArray::fill(0.0), JointArray::fill(1.0));
+            for cycle in 0.. {
+                nao.tick().unwrap();
+                assert!(cycle < 3, "control closure panicked");
+            }
+        }));
+        assert!(result.is_err());
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 5);
+        assert_eq!(sent[3].stiffness, JointArray::fill(1.0));
+        assert_eq!(sent.last().unwrap().stiffness, JointArray::fill(0.0));
+    }
+}
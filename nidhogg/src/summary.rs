@@ -0,0 +1,178 @@
+//! Human readable formatting of the state and control messages.
+
+use std::fmt;
+
+use nalgebra::Vector2;
+
+use crate::{
+    diagnostics::JointDiagnostics,
+    diff::changed_led_groups,
+    types::{JointArray, JointName},
+    NaoControlMessage, NaoState,
+};
+
+/// Short summary of a [`NaoState`], containing the values shown by its [`Display`](fmt::Display) implementation.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::{types::JointName, NaoState};
+///
+/// let mut state = NaoState::default();
+/// state.battery.charge = 0.75;
+/// state.temperature.right_knee_pitch = 61.0;
+///
+/// let summary = state.summary();
+/// assert_eq!(summary.battery_percentage, 75.0);
+/// assert_eq!(summary.hottest_joint, (JointName::RightKneePitch, 61.0));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateSummary {
+    /// Battery charge in percent.
+    pub battery_percentage: f32,
+    /// The joint with the highest temperature, together with that temperature in degrees Celsius.
+    pub hottest_joint: (JointName, f32),
+    /// Inclination angles of the torso in radians.
+    pub angles: Vector2<f32>,
+    /// Sum of all FSR values of both feet, which approximates the weight on the feet in kilograms.
+    pub fsr_total: f32,
+}
+
+impl NaoState {
+    /// Returns a [`StateSummary`] of this state.
+    pub fn summary(&self) -> StateSummary {
+        StateSummary {
+            battery_percentage: self.battery.charge * 100.0,
+            hottest_joint: JointDiagnostics::from(self).hottest_joint(),
+            angles: self.angles,
+            fsr_total: self.fsr.sum(),
+        }
+    }
+}
+
+impl fmt::Display for StateSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (joint, temperature) = self.hottest_joint;
+
+        write!(
+            f,
+            "battery {:.1}%, hottest joint {joint} at {temperature:.1}°C, angles ({:.3}, {:.3}) rad, fsr total {:.3} kg",
+            self.battery_percentage, self.angles.x, self.angles.y, self.fsr_total
+        )
+    }
+}
+
+/// Displays a one line summary of the state, see [`StateSummary`].
+impl fmt::Display for NaoState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.summary().fmt(f)
+    }
+}
+
+/// Displays only the fields that differ from [`NaoControlMessage::default`].
+///
+/// For joints, only the values that differ from the default are shown.
+/// For LEDs, only the names of the changed LED groups are shown.
+impl fmt::Display for NaoControlMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let default = NaoControlMessage::default();
+        let mut parts = Vec::new();
+
+        if let Some(position) = changed_joints(&self.position, &default.position) {
+            parts.push(format!("position {position}"));
+        }
+        if let Some(stiffness) = changed_joints(&self.stiffness, &default.stiffness) {
+            parts.push(format!("stiffness {stiffness}"));
+        }
+        if self.sonar != default.sonar {
+            parts.push(format!("sonar {:?}", self.sonar));
+        }
+
+        let leds = changed_led_groups(&default, self);
+        if !leds.is_empty() {
+            parts.push(format!("leds [{}]", leds.join(", ")));
+        }
+
+        if parts.is_empty() {
+            f.write_str("default control message")
+        } else {
+            f.write_str(&parts.join(", "))
+        }
+    }
+}
+
+/// Formats the joints of `values` that differ from `default`, or returns [`None`] if none differ.
+///
+/// If every joint differs and has the same value, this is shortened to `all: <value>`.
+fn changed_joints(values: &JointArray<f32>, default: &JointArray<f32>) -> Option<String> {
+    let changed = values
+        .iter_named()
+        .zip(default)
+        .filter(|((_, value), default)| value != default)
+        .map(|((name, value), _)| (name, *value))
+        .collect::<Vec<_>>();
+
+    match changed.as_slice() {
+        [] => None,
+        [(_, first), ..] if changed.len() == 25 && changed.iter().all(|(_, v)| v == first) => {
+            Some(format!("{{all: {first:.3}}}"))
+        }
+        changed => Some(format!(
+            "{{{}}}",
+            changed
+                .iter()
+                .map(|(name, value)| format!("{name}: {value:.3}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{color, FillExt};
+
+    #[test]
+    fn test_state_display() {
+        let mut state = NaoState::default();
+        state.battery.charge = 0.9;
+        state.temperature = JointArray {
+            right_knee_pitch: 61.0,
+            ..JointArray::fill(35.0)
+        };
+        state.angles = Vector2::new(0.01, -0.02);
+        state.fsr.left_foot.front_left = 1.5;
+        state.fsr.right_foot.rear_right = 0.9;
+
+        assert_eq!(
+            state.to_string(),
+            "battery 90.0%, hottest joint right_knee_pitch at 61.0°C, angles (0.010, -0.020) rad, fsr total 2.400 kg"
+        );
+    }
+
+    #[test]
+    fn test_control_message_display() {
+        assert_eq!(
+            NaoControlMessage::default().to_string(),
+            "default control message"
+        );
+
+        let msg = NaoControlMessage {
+            position: JointArray {
+                head_yaw: 0.5,
+                left_knee_pitch: 1.25,
+                ..JointArray::fill(-1.0)
+            },
+            stiffness: JointArray::fill(0.8),
+            chest: color::f32::RED,
+            skull: crate::types::Skull::fill(1.0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            msg.to_string(),
+            "position {head_yaw: 0.500, left_knee_pitch: 1.250}, stiffness {all: 0.800}, leds [chest, skull]"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Canned states and frames, so tests don't have to build a [`NaoState`] field by field.
+//!
+//! All fixtures are deterministic, and can be modified using struct update syntax.
+//!
+//! # Example
+//!
+//! ```
+//! use nidhogg::{safety::{FallDetector, FallState}, testing::fixtures};
+//!
+//! let mut detector = FallDetector::default();
+//! assert_eq!(detector.update(&fixtures::standing_state()), FallState::Upright);
+//!
+//! let fallen = fixtures::fallen_front_state();
+//! let state = (0..20).map(|_| detector.update(&fallen)).last();
+//! assert_eq!(state, Some(FallState::FallenFront));
+//! ```
+
+use nalgebra::{Vector2, Vector3};
+
+use crate::{
+    backend::encode_state_frame,
+    imu::GRAVITY,
+    types::{Battery, FillExt, Fsr, FsrFoot, JointArray, SonarValues},
+    HardwareInfo, NaoState,
+};
+
+use super::LOLA_FRAME_SIZE;
+
+/// Hardware info of a fake robot, which is also sent by a [`FakeLola`](super::FakeLola) by default.
+pub fn hardware_info() -> HardwareInfo {
+    HardwareInfo {
+        body_id: "fake-body".to_string(),
+        body_version: "6.0.0".to_string(),
+        head_id: "fake-head".to_string(),
+        head_version: "6.0.0".to_string(),
+    }
+}
+
+/// The joint positions of a robot standing with slightly bent knees and its arms at its sides.
+pub fn standing_pose() -> JointArray<f32> {
+    JointArray::<f32>::builder()
+        .left_shoulder_pitch(1.5)
+        .left_shoulder_roll(0.15)
+        .left_elbow_yaw(-1.2)
+        .left_elbow_roll(-0.4)
+        .right_shoulder_pitch(1.5)
+        .right_shoulder_roll(-0.15)
+        .right_elbow_yaw(1.2)
+        .right_elbow_roll(0.4)
+        .left_hip_pitch(-0.4)
+        .left_knee_pitch(0.8)
+        .left_ankle_pitch(-0.4)
+        .right_hip_pitch(-0.4)
+        .right_knee_pitch(0.8)
+        .right_ankle_pitch(-0.4)
+        .build()
+}
+
+/// A robot that stands upright in the [`standing_pose`], with its weight evenly spread over both feet.
+pub fn standing_state() -> NaoState {
+    NaoState {
+        position: standing_pose(),
+        stiffness: JointArray::fill(1.0),
+        accelerometer: Vector3::new(0.0, 0.0, GRAVITY),
+        sonar: SonarValues {
+            left: 5.0,
+            right: 5.0,
+        },
+        fsr: Fsr {
+            left_foot: FsrFoot::fill(0.7),
+            right_foot: FsrFoot::fill(0.7),
+        },
+        battery: Battery {
+            charge: 0.9,
+            current: -1.0,
+            status: 0.0,
+            temperature: 30.0,
+        },
+        temperature: JointArray::fill(35.0),
+        current: JointArray::fill(0.05),
+        ..Default::default()
+    }
+}
+
+/// A robot that fell forward and is lying on its front, without stiffness and without ground contact of the feet.
+pub fn fallen_front_state() -> NaoState {
+    let standing = standing_state();
+
+    NaoState {
+        stiffness: JointArray::fill(0.0),
+        accelerometer: Vector3::new(-GRAVITY, 0.0, 0.0),
+        angles: Vector2::new(0.0, std::f32::consts::FRAC_PI_2),
+        fsr: Fsr::default(),
+        current: JointArray::fill(0.0),
+        ..standing
+    }
+}
+
+/// A robot that stands upright, with a battery charge of 5%.
+pub fn low_battery_state() -> NaoState {
+    let standing = standing_state();
+
+    NaoState {
+        battery: Battery {
+            charge: 0.05,
+            current: -2.0,
+            ..standing.battery
+        },
+        ..standing
+    }
+}
+
+/// Encodes `state` into a frame exactly as `LoLA` sends it, with `hardware_info` as the robot config.
+///
+/// The frame is padded to the 896 bytes of a `LoLA` frame, this is the frame a [`FakeLola`](super::FakeLola) sends.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::testing::fixtures;
+///
+/// let frame = fixtures::lola_frame_bytes(&fixtures::standing_state(), &fixtures::hardware_info());
+/// assert_eq!(frame.len(), 896);
+/// ```
+pub fn lola_frame_bytes(state: &NaoState, hardware_info: &HardwareInfo) -> Vec<u8> {
+    encode_state_frame(state, hardware_info, LOLA_FRAME_SIZE)
+        .expect("state frames can always be encoded")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        backend::{LolaBackend, ReadHardwareInfo},
+        leds::{ConnectionStatus, StatusLeds, StatusLedsConfig},
+        NaoBackend,
+    };
+
+    #[test]
+    fn test_frame_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("robocup");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let states = [standing_state(), fallen_front_state(), low_battery_state()];
+        let server = {
+            let states = states.clone();
+            thread::spawn(move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                for state in &states {
+                    stream
+                        .write_all(&lola_frame_bytes(state, &hardware_info()))
+                        .unwrap();
+                }
+            })
+        };
+
+        let mut nao = LolaBackend::connect_with_path(&path).unwrap();
+        assert_eq!(nao.read_hardware_info().unwrap(), hardware_info());
+        for state in states {
+            assert_eq!(nao.read_nao_state().unwrap(), state);
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_low_battery() {
+        let mut leds = StatusLeds::new(StatusLedsConfig::default());
+
+        leds.update(&standing_state(), ConnectionStatus::Ok, Duration::ZERO);
+        assert!(!leds.battery_low());
+
+        leds.update(&low_battery_state(), ConnectionStatus::Ok, Duration::ZERO);
+        assert!(leds.battery_low());
+    }
+}
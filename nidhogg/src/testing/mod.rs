@@ -0,0 +1,313 @@
+//! Utilities for testing code that talks to `LoLA`, without a robot.
+//!
+//! The [`FakeLola`] server binds a Unix socket, sends [`NaoState`] frames in the `LoLA` wire format
+//! and records the control messages it receives.
+//!
+//! # Example
+//!
+//! ```
+//! use nidhogg::{testing::FakeLola, types::color, NaoBackend, NaoControlMessage};
+//!
+//! let lola = FakeLola::start().unwrap();
+//! let mut nao = lola.connect().unwrap();
+//!
+//! let state = nao.read_nao_state().unwrap();
+//! assert_eq!(state, lola.state());
+//!
+//! nao.send_control_msg(NaoControlMessage::builder().chest(color::f32::RED).build())
+//!     .unwrap();
+//! lola.expect_chest_color(color::f32::RED);
+//! ```
+//!
+//! Canned states and frames are provided by the [`fixtures`] module.
+
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Write};
+use std::net::Shutdown;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use tempfile::TempDir;
+
+use crate::{
+    backend::{encode_state_frame, LolaBackend, LolaControlMsg},
+    types::color::RgbF32,
+    HardwareInfo, NaoControlMessage, NaoState, Result,
+};
+
+pub mod fixtures;
+
+/// Size of the frames sent by `LoLA` on a NAO V6.
+const LOLA_FRAME_SIZE: usize = 896;
+/// Time [`FakeLola::expect_chest_color`] waits for a control message.
+const EXPECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Configuration of a [`FakeLola`] server.
+#[derive(Clone, Debug)]
+pub struct FakeLolaConfig {
+    /// Time between two state frames.
+    pub frame_interval: Duration,
+    /// State that is sent in every frame, until it is replaced using [`FakeLola::set_state`].
+    pub state: NaoState,
+    /// Hardware info that is sent in every frame.
+    pub hardware_info: HardwareInfo,
+    /// Size frames are padded to with zeros, which is the size of a `LoLA` frame by default.
+    ///
+    /// Frames are sent unpadded if the encoded state is larger, so frames of other sizes
+    /// can be emulated with a size of zero, or long strings in the hardware info.
+    pub frame_size: usize,
+}
+
+impl Default for FakeLolaConfig {
+    fn default() -> Self {
+        Self {
+            frame_interval: Duration::from_millis(12),
+            state: NaoState::default(),
+            hardware_info: fixtures::hardware_info(),
+            frame_size: LOLA_FRAME_SIZE,
+        }
+    }
+}
+
+/// State shared between the [`FakeLola`] handle and its server thread.
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<NaoState>,
+    queue: Mutex<VecDeque<NaoState>>,
+    received: Mutex<Vec<LolaControlMsg>>,
+    message_received: Condvar,
+    paused: AtomicBool,
+    stopped: AtomicBool,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // a panicking client thread cannot leave the recorded values inconsistent
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// A fake `LoLA` server, which serves one connection at a time on a Unix socket.
+///
+/// The server stops when it is dropped.
+#[derive(Debug)]
+pub struct FakeLola {
+    path: PathBuf,
+    shared: Arc<Shared>,
+    server: Option<JoinHandle<()>>,
+    // removed when the server is dropped
+    _dir: Option<TempDir>,
+}
+
+impl FakeLola {
+    /// Starts a server with the default [`FakeLolaConfig`] on a socket in a new temporary directory.
+    pub fn start() -> Result<Self> {
+        Self::new(FakeLolaConfig::default())
+    }
+
+    /// Starts a server with the provided configuration on a socket in a new temporary directory.
+    pub fn new(config: FakeLolaConfig) -> Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let mut lola = Self::bind(dir.path().join("robocup"), config)?;
+        lola._dir = Some(dir);
+
+        Ok(lola)
+    }
+
+    /// Starts a server with the provided configuration on a socket at `path`.
+    pub fn bind(path: impl AsRef<Path>, config: FakeLolaConfig) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let listener = UnixListener::bind(&path)?;
+        // poll for new connections, so the server notices when it is stopped
+        listener.set_nonblocking(true)?;
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(config.state.clone()),
+            queue: Mutex::default(),
+            received: Mutex::default(),
+            message_received: Condvar::new(),
+            paused: AtomicBool::new(false),
+            stopped: AtomicBool::new(false),
+        });
+
+        let server = {
+            let shared = shared.clone();
+            thread::spawn(move || serve(&listener, &config, &shared))
+        };
+
+        Ok(Self {
+            path,
+            shared,
+            server: Some(server),
+            _dir: None,
+        })
+    }
+
+    /// The path of the socket of the server.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Connects a [`LolaBackend`] to this server.
+    pub fn connect(&self) -> Result<LolaBackend> {
+        LolaBackend::connect_with_path(&self.path)
+    }
+
+    /// Returns the state that is sent in every frame.
+    pub fn state(&self) -> NaoState {
+        lock(&self.shared.state).clone()
+    }
+
+    /// Replaces the state that is sent in every frame.
+    pub fn set_state(&self, state: NaoState) {
+        *lock(&self.shared.state) = state;
+    }
+
+    /// Queues states that are sent once each, one per frame, before the regular state is sent again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::{testing::FakeLola, NaoBackend, NaoState};
+    ///
+    /// let lola = FakeLola::start().unwrap();
+    /// let states: Vec<_> = (0..3)
+    ///     .map(|i| {
+    ///         let mut state = NaoState::default();
+    ///         state.position.head_yaw = i as f32;
+    ///         state
+    ///     })
+    ///     .collect();
+    /// lola.queue_states(states);
+    ///
+    /// let mut nao = lola.connect().unwrap();
+    /// assert_eq!(nao.read_nao_state().unwrap().position.head_yaw, 0.0);
+    /// assert_eq!(nao.read_nao_state().unwrap().position.head_yaw, 1.0);
+    /// ```
+    pub fn queue_states(&self, states: impl IntoIterator<Item = NaoState>) {
+        lock(&self.shared.queue).extend(states);
+    }
+
+    /// Stops sending frames, while keeping the connection open.
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::Release);
+    }
+
+    /// Continues sending frames after [`FakeLola::pause`].
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::Release);
+    }
+
+    /// Returns all control messages received so far, in the order they were received.
+    pub fn received(&self) -> Vec<LolaControlMsg> {
+        lock(&self.shared.received).clone()
+    }
+
+    /// Returns the last received control message.
+    pub fn last_message(&self) -> Option<NaoControlMessage> {
+        lock(&self.shared.received)
+            .last()
+            .cloned()
+            .map(NaoControlMessage::from)
+    }
+
+    /// Blocks until at least `count` control messages were received, or until `timeout` has passed.
+    ///
+    /// Returns `true` if the messages were received in time.
+    pub fn wait_for_messages(&self, count: usize, timeout: Duration) -> bool {
+        let (received, _) = self
+            .shared
+            .message_received
+            .wait_timeout_while(lock(&self.shared.received), timeout, |received| {
+                received.len() < count
+            })
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        received.len() >= count
+    }
+
+    /// Asserts that the last received control message sets the chest to `color`.
+    ///
+    /// Waits up to a second for the first control message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no message was received, or if the chest of the last message has a different color.
+    #[track_caller]
+    pub fn expect_chest_color(&self, color: RgbF32) {
+        assert!(
+            self.wait_for_messages(1, EXPECT_TIMEOUT),
+            "no control message was received"
+        );
+
+        let msg = self.last_message().expect("a message was received");
+        assert_eq!(msg.chest, color, "unexpected chest color");
+    }
+}
+
+impl Drop for FakeLola {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::Release);
+        if let Some(server) = self.server.take() {
+            // a panic in the server thread is reported by the test that caused it
+            let _ = server.join();
+        }
+    }
+}
+
+fn serve(listener: &UnixListener, config: &FakeLolaConfig, shared: &Arc<Shared>) {
+    while !shared.stopped.load(Ordering::Acquire) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if stream.set_nonblocking(false).is_ok() {
+                    serve_connection(stream, config, shared);
+                }
+            }
+            Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(1))
+            }
+            Err(_) => return,
+        }
+    }
+}
+
+/// Sends frames until the client disconnects or the server is stopped.
+fn serve_connection(mut stream: UnixStream, config: &FakeLolaConfig, shared: &Arc<Shared>) {
+    let Ok(mut reader) = stream.try_clone() else {
+        return;
+    };
+    let receiver = {
+        let shared = shared.clone();
+        thread::spawn(move || {
+            while let Ok(msg) = rmp_serde::from_read::<_, LolaControlMsg>(&mut reader) {
+                lock(&shared.received).push(msg);
+                shared.message_received.notify_all();
+            }
+        })
+    };
+
+    let mut next_frame = Instant::now();
+    // the receiver stops when the client disconnects
+    while !shared.stopped.load(Ordering::Acquire) && !receiver.is_finished() {
+        if !shared.paused.load(Ordering::Acquire) {
+            let queued = lock(&shared.queue).pop_front();
+            let state = queued.unwrap_or_else(|| lock(&shared.state).clone());
+            let frame = encode_state_frame(&state, &config.hardware_info, config.frame_size)
+                .expect("state frames can always be encoded");
+            if stream.write_all(&frame).is_err() {
+                break;
+            }
+        }
+
+        next_frame += config.frame_interval;
+        thread::sleep(next_frame.saturating_duration_since(Instant::now()));
+    }
+
+    // unblocks the receiver thread
+    let _ = stream.shutdown(Shutdown::Both);
+    let _ = receiver.join();
+}
@@ -1,4 +1,15 @@
+use alloc::{borrow::ToOwned, string::String};
+use core::{fmt, str::FromStr};
+
 use nidhogg_derive::Builder;
+
+// Float rounding is only inherent to `f32` with `std`. The import is unused when another crate of the
+// build links `std`, as the test harness does.
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num::traits::float::FloatCore;
+
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 pub type RgbU8 = Rgb<u8>;
@@ -19,6 +30,149 @@ impl<T> Rgb<T> {
     }
 }
 
+impl RgbF32 {
+    /// Scale the brightness of the color by the provided factor.
+    #[must_use]
+    pub fn scaled(self, brightness: f32) -> RgbF32 {
+        RgbF32 {
+            red: self.red * brightness,
+            green: self.green * brightness,
+            blue: self.blue * brightness,
+        }
+    }
+
+    /// Linearly interpolate between this color and `other`.
+    ///
+    /// A `t` of `0.0` results in this color, a `t` of `1.0` results in `other`.
+    #[must_use]
+    pub fn lerp(self, other: RgbF32, t: f32) -> RgbF32 {
+        RgbF32 {
+            red: self.red + (other.red - self.red) * t,
+            green: self.green + (other.green - self.green) * t,
+            blue: self.blue + (other.blue - self.blue) * t,
+        }
+    }
+
+    /// Clamp all channels of the color to the range `0.0..=1.0`.
+    #[must_use]
+    pub fn clamped(self) -> RgbF32 {
+        RgbF32 {
+            red: self.red.clamp(0.0, 1.0),
+            green: self.green.clamp(0.0, 1.0),
+            blue: self.blue.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Create a color from its hue (in degrees), saturation and value.
+    ///
+    /// The hue wraps around at 360 degrees, saturation and value are clamped to the range `0.0..=1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{color, RgbF32};
+    ///
+    /// assert_eq!(RgbF32::from_hsv(120.0, 1.0, 1.0), color::f32::LIME);
+    /// ```
+    #[must_use]
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> RgbF32 {
+        let hue = super::rem_euclid(hue, 360.0) / 60.0;
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (red, green, blue) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+
+        RgbF32 {
+            red: red + m,
+            green: green + m,
+            blue: blue + m,
+        }
+    }
+
+    /// Convert the color to its hue (in degrees), saturation and value.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let max = self.red.max(self.green).max(self.blue);
+        let min = self.red.min(self.green).min(self.blue);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == self.red {
+            60.0 * super::rem_euclid((self.green - self.blue) / delta, 6.0)
+        } else if max == self.green {
+            60.0 * ((self.blue - self.red) / delta + 2.0)
+        } else {
+            60.0 * ((self.red - self.green) / delta + 4.0)
+        };
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+        (hue, saturation, max)
+    }
+}
+
+/// Error returned when parsing a color from a `"#RRGGBB"` hex string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseColorError {
+    MissingHash(String),
+    InvalidLength(String),
+    InvalidDigit(String),
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingHash(s) => write!(f, "hex color `{s}` must start with `#`"),
+            Self::InvalidLength(s) => write!(f, "hex color `{s}` must have exactly 6 digits"),
+            Self::InvalidDigit(s) => write!(f, "hex color `{s}` contains an invalid hex digit"),
+        }
+    }
+}
+
+impl core::error::Error for ParseColorError {}
+
+impl FromStr for RgbU8 {
+    type Err = ParseColorError;
+
+    /// Parse a color from a `"#RRGGBB"` hex string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .strip_prefix('#')
+            .ok_or_else(|| ParseColorError::MissingHash(s.to_owned()))?;
+
+        if digits.len() != 6 {
+            return Err(ParseColorError::InvalidLength(s.to_owned()));
+        }
+
+        // `from_str_radix` accepts a leading sign, which is not a valid hex digit.
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseColorError::InvalidDigit(s.to_owned()));
+        }
+
+        u32::from_str_radix(digits, 16)
+            .map(RgbU8::from)
+            .map_err(|_| ParseColorError::InvalidDigit(s.to_owned()))
+    }
+}
+
+impl FromStr for RgbF32 {
+    type Err = ParseColorError;
+
+    /// Parse a color from a `"#RRGGBB"` hex string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<RgbU8>().map(RgbF32::from)
+    }
+}
+
 impl From<u32> for RgbU8 {
     fn from(color: u32) -> Self {
         RgbU8 {
@@ -40,11 +194,14 @@ impl From<RgbU8> for RgbF32 {
 }
 
 impl From<RgbF32> for RgbU8 {
+    /// Converts the color to 8 bits per channel, values outside of `0.0..=1.0` are clamped.
     fn from(value: RgbF32) -> Self {
+        let to_u8 = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+
         RgbU8 {
-            red: (value.red * 255.0) as u8,
-            green: (value.green * 255.0) as u8,
-            blue: (value.blue * 255.0) as u8,
+            red: to_u8(value.red),
+            green: to_u8(value.green),
+            blue: to_u8(value.blue),
         }
     }
 }
@@ -119,3 +276,95 @@ impl_consts! {
     YELLOW (1.0, 1.0, 0.0),
     ORANGE (1.0, 0.25, 0.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!("#FF8000".parse(), Ok(RgbU8::new(255, 128, 0)));
+        assert_eq!("#00ff00".parse(), Ok(f32::LIME));
+    }
+
+    #[test]
+    fn test_parse_hex_failures() {
+        assert_eq!(
+            "FF8000".parse::<RgbU8>(),
+            Err(ParseColorError::MissingHash("FF8000".to_owned()))
+        );
+        assert_eq!(
+            "#FF80".parse::<RgbU8>(),
+            Err(ParseColorError::InvalidLength("#FF80".to_owned()))
+        );
+        assert_eq!(
+            "#FF80001".parse::<RgbU8>(),
+            Err(ParseColorError::InvalidLength("#FF80001".to_owned()))
+        );
+        assert_eq!(
+            "#GG8000".parse::<RgbU8>(),
+            Err(ParseColorError::InvalidDigit("#GG8000".to_owned()))
+        );
+        assert_eq!(
+            "#+F8000".parse::<RgbF32>(),
+            Err(ParseColorError::InvalidDigit("#+F8000".to_owned()))
+        );
+        assert_eq!(
+            "#ÿÿÿ".parse::<RgbU8>(),
+            Err(ParseColorError::InvalidDigit("#ÿÿÿ".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_u8_f32_conversion() {
+        assert_eq!(
+            RgbU8::from(RgbF32::new(0.5, 1.0, 0.0)),
+            RgbU8::new(128, 255, 0)
+        );
+        assert_eq!(
+            RgbU8::from(RgbF32::new(2.0, -1.0, 0.999)),
+            RgbU8::new(255, 0, 255)
+        );
+
+        for value in [0, 1, 127, 128, 254, 255] {
+            let color = RgbU8::new(value, value, value);
+            assert_eq!(RgbU8::from(RgbF32::from(color)), color);
+        }
+    }
+
+    #[test]
+    fn test_hsv_primaries() {
+        let primaries = [
+            (0.0, f32::RED),
+            (60.0, f32::YELLOW),
+            (120.0, f32::LIME),
+            (180.0, f32::CYAN),
+            (240.0, f32::BLUE),
+            (300.0, f32::MAGENTA),
+        ];
+
+        for (hue, color) in primaries {
+            assert_eq!(RgbF32::from_hsv(hue, 1.0, 1.0), color);
+            assert_eq!(color.to_hsv(), (hue, 1.0, 1.0));
+        }
+
+        assert_eq!(RgbF32::from_hsv(360.0, 1.0, 1.0), f32::RED);
+        assert_eq!(RgbF32::from_hsv(0.0, 0.0, 0.5), f32::GRAY);
+    }
+
+    #[test]
+    fn test_scaled_and_lerp() {
+        assert_eq!(f32::WHITE.scaled(0.5), f32::GRAY);
+        assert_eq!(f32::RED.lerp(f32::BLUE, 0.5), RgbF32::new(0.5, 0.0, 0.5));
+        assert_eq!(f32::RED.lerp(f32::BLUE, 0.0), f32::RED);
+        assert_eq!(f32::RED.lerp(f32::BLUE, 1.0), f32::BLUE);
+    }
+
+    #[test]
+    fn test_clamped() {
+        assert_eq!(
+            RgbF32::new(1.5, -0.5, 0.5).clamped(),
+            RgbF32::new(1.0, 0.0, 0.5)
+        );
+    }
+}
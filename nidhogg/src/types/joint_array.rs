@@ -1,16 +1,98 @@
 //! Implements [`JointArray`] type and associated functions, for manipulating joint values.
 
-use std::ops::Sub;
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, BitAnd, BitOr, Index, IndexMut, Not, Sub};
 
 use crate::types::{
-    ArmJoints, FillExt, HeadJoints, LeftArmJoints, LeftLegJoints, LegJoints, RightArmJoints,
-    RightLegJoints,
+    ArmJoints, FillExt, HeadJoints, JointName, JointRegion, LeftArmJoints, LeftLegJoints,
+    LegJoints, RightArmJoints, RightLegJoints,
 };
+use nalgebra::{SVector, Scalar};
 use nidhogg_derive::Builder;
 use num::Signed;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+/// Expands `$callback!` with the field name, index and `LoLA` index of every joint, in the order of [`JointName::ALL`].
+///
+/// The tokens passed to `$callback` are forwarded in parentheses before the joints, so
+/// `for_each_joint!(callback!(a, b))` expands to
+/// `callback!((a, b) head_yaw 0 0, head_pitch 1 1, ..., right_hand 24 24)`.
+///
+/// This is the only place that defines the order of the joints, which is used by everything
+/// that converts a [`JointArray`] to or from an array.
+macro_rules! for_each_joint {
+    ($callback:ident!($($args:tt)*)) => {
+        $callback!(
+            ($($args)*)
+            head_yaw 0 0,
+            head_pitch 1 1,
+            left_shoulder_pitch 2 2,
+            left_shoulder_roll 3 3,
+            left_elbow_yaw 4 4,
+            left_elbow_roll 5 5,
+            left_wrist_yaw 6 6,
+            left_hip_yaw_pitch 7 7,
+            left_hip_roll 8 8,
+            left_hip_pitch 9 9,
+            left_knee_pitch 10 10,
+            left_ankle_pitch 11 11,
+            left_ankle_roll 12 12,
+            right_shoulder_pitch 13 18,
+            right_shoulder_roll 14 19,
+            right_elbow_yaw 15 20,
+            right_elbow_roll 16 21,
+            right_wrist_yaw 17 22,
+            right_hip_roll 18 13,
+            right_hip_pitch 19 14,
+            right_knee_pitch 20 15,
+            right_ankle_pitch 21 16,
+            right_ankle_roll 22 17,
+            left_hand 23 23,
+            right_hand 24 24
+        )
+    };
+}
+#[cfg(feature = "lola")]
+pub(crate) use for_each_joint;
+
+/// Error returned by [`JointArray::try_from_iter`] when the iterator does not yield exactly 25 elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WrongLength {
+    /// The number of elements required to create a [`JointArray`].
+    pub expected: usize,
+    /// The number of elements yielded by the iterator.
+    pub actual: usize,
+}
+
+impl fmt::Display for WrongLength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} elements to create a JointArray, got {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl core::error::Error for WrongLength {}
+
+/// Selects a subset of the joints, with `true` for every selected joint.
+///
+/// Masks can be combined using `&`, `|` and `!`.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::types::{JointMask, JointRegion};
+///
+/// let left_arm = JointMask::from_region(JointRegion::Arms) & !JointMask::from_region(JointRegion::RightArm);
+/// assert_eq!(left_arm, JointMask::from_region(JointRegion::LeftArm));
+/// ```
+pub type JointMask = JointArray<bool>;
+
 /// Struct containing values of type `T` for all the joints
 #[derive(Builder, Clone, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -92,8 +174,31 @@ pub struct JointArray<T> {
 }
 
 impl<T> JointArray<T> {
+    /// The names of the fields of a [`JointArray`], in the order of [`JointName::ALL`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{JointArray, JointName};
+    ///
+    /// assert_eq!(JointArray::<f32>::NAMES[0], "head_yaw");
+    /// assert_eq!(JointArray::<f32>::NAMES[24], JointName::RightHand.as_str());
+    /// ```
+    pub const NAMES: [&'static str; 25] = {
+        macro_rules! names {
+            (() $($field:ident $i:literal $lola:literal),*) => {
+                [$(stringify!($field)),*]
+            };
+        }
+
+        for_each_joint!(names!())
+    };
+
     /// Returns a reference to the joint value at the specified index.
     ///
+    /// The index of a joint is the position of its [`JointName`] in [`JointName::ALL`],
+    /// prefer indexing with a [`JointName`] directly.
+    ///
     /// # Example
     ///
     /// ```
@@ -105,38 +210,23 @@ impl<T> JointArray<T> {
     /// assert!(joints.get(25).is_none()); // out of bounds
     /// ```
     pub fn get(&self, index: usize) -> Option<&T> {
-        match index {
-            0 => Some(&self.head_yaw),
-            1 => Some(&self.head_pitch),
-            2 => Some(&self.left_shoulder_pitch),
-            3 => Some(&self.left_shoulder_roll),
-            4 => Some(&self.left_elbow_yaw),
-            5 => Some(&self.left_elbow_roll),
-            6 => Some(&self.left_wrist_yaw),
-            7 => Some(&self.left_hip_yaw_pitch),
-            8 => Some(&self.left_hip_roll),
-            9 => Some(&self.left_hip_pitch),
-            10 => Some(&self.left_knee_pitch),
-            11 => Some(&self.left_ankle_pitch),
-            12 => Some(&self.left_ankle_roll),
-            13 => Some(&self.right_shoulder_pitch),
-            14 => Some(&self.right_shoulder_roll),
-            15 => Some(&self.right_elbow_yaw),
-            16 => Some(&self.right_elbow_roll),
-            17 => Some(&self.right_wrist_yaw),
-            18 => Some(&self.right_hip_roll),
-            19 => Some(&self.right_hip_pitch),
-            20 => Some(&self.right_knee_pitch),
-            21 => Some(&self.right_ankle_pitch),
-            22 => Some(&self.right_ankle_roll),
-            23 => Some(&self.left_hand),
-            24 => Some(&self.right_hand),
-            _ => None,
+        macro_rules! get {
+            (($joints:ident, $index:ident) $($field:ident $i:literal $lola:literal),*) => {
+                match $index {
+                    $($i => Some(&$joints.$field),)*
+                    _ => None,
+                }
+            };
         }
+
+        for_each_joint!(get!(self, index))
     }
 
     /// Returns a mutable reference to the joint value at the specified index.
     ///
+    /// The index of a joint is the position of its [`JointName`] in [`JointName::ALL`],
+    /// prefer indexing with a [`JointName`] directly.
+    ///
     /// # Example
     ///
     /// ```
@@ -152,34 +242,16 @@ impl<T> JointArray<T> {
     /// assert!(joints.get_mut(25).is_none()); // out of bounds
     /// ```
     pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
-        match index {
-            0 => Some(&mut self.head_yaw),
-            1 => Some(&mut self.head_pitch),
-            2 => Some(&mut self.left_shoulder_pitch),
-            3 => Some(&mut self.left_shoulder_roll),
-            4 => Some(&mut self.left_elbow_yaw),
-            5 => Some(&mut self.left_elbow_roll),
-            6 => Some(&mut self.left_wrist_yaw),
-            7 => Some(&mut self.left_hip_yaw_pitch),
-            8 => Some(&mut self.left_hip_roll),
-            9 => Some(&mut self.left_hip_pitch),
-            10 => Some(&mut self.left_knee_pitch),
-            11 => Some(&mut self.left_ankle_pitch),
-            12 => Some(&mut self.left_ankle_roll),
-            13 => Some(&mut self.right_shoulder_pitch),
-            14 => Some(&mut self.right_shoulder_roll),
-            15 => Some(&mut self.right_elbow_yaw),
-            16 => Some(&mut self.right_elbow_roll),
-            17 => Some(&mut self.right_wrist_yaw),
-            18 => Some(&mut self.right_hip_roll),
-            19 => Some(&mut self.right_hip_pitch),
-            20 => Some(&mut self.right_knee_pitch),
-            21 => Some(&mut self.right_ankle_pitch),
-            22 => Some(&mut self.right_ankle_roll),
-            23 => Some(&mut self.left_hand),
-            24 => Some(&mut self.right_hand),
-            _ => None,
+        macro_rules! get_mut {
+            (($joints:ident, $index:ident) $($field:ident $i:literal $lola:literal),*) => {
+                match $index {
+                    $($i => Some(&mut $joints.$field),)*
+                    _ => None,
+                }
+            };
         }
+
+        for_each_joint!(get_mut!(self, index))
     }
 
     /// Returns all joint values as a fixed-size array.
@@ -195,33 +267,13 @@ impl<T> JointArray<T> {
     /// assert!(values.iter().all(|&v| v == 0));
     /// ```
     pub fn as_array(self) -> [T; 25] {
-        [
-            self.head_yaw,
-            self.head_pitch,
-            self.left_shoulder_pitch,
-            self.left_shoulder_roll,
-            self.left_elbow_yaw,
-            self.left_elbow_roll,
-            self.left_wrist_yaw,
-            self.left_hip_yaw_pitch,
-            self.left_hip_roll,
-            self.left_hip_pitch,
-            self.left_knee_pitch,
-            self.left_ankle_pitch,
-            self.left_ankle_roll,
-            self.right_shoulder_pitch,
-            self.right_shoulder_roll,
-            self.right_elbow_yaw,
-            self.right_elbow_roll,
-            self.right_wrist_yaw,
-            self.right_hip_roll,
-            self.right_hip_pitch,
-            self.right_knee_pitch,
-            self.right_ankle_pitch,
-            self.right_ankle_roll,
-            self.left_hand,
-            self.right_hand,
-        ]
+        macro_rules! as_array {
+            (($joints:ident) $($field:ident $i:literal $lola:literal),*) => {
+                [$($joints.$field),*]
+            };
+        }
+
+        for_each_joint!(as_array!(self))
     }
 
     /// Returns a reference to all joint values as a fixed-size array.
@@ -234,36 +286,16 @@ impl<T> JointArray<T> {
     /// let joints = JointArray::<i32>::default();
     /// let values = joints.as_array_ref();
     /// assert_eq!(values.len(), 25);
-    /// assert!(values.iter().all(|&v| v == 0));
+    /// assert!(values.iter().all(|&&v| v == 0));
     /// ```
     pub fn as_array_ref(&self) -> [&T; 25] {
-        [
-            &self.head_yaw,
-            &self.head_pitch,
-            &self.left_shoulder_pitch,
-            &self.left_shoulder_roll,
-            &self.left_elbow_yaw,
-            &self.left_elbow_roll,
-            &self.left_wrist_yaw,
-            &self.left_hip_yaw_pitch,
-            &self.left_hip_roll,
-            &self.left_hip_pitch,
-            &self.left_knee_pitch,
-            &self.left_ankle_pitch,
-            &self.left_ankle_roll,
-            &self.right_shoulder_pitch,
-            &self.right_shoulder_roll,
-            &self.right_elbow_yaw,
-            &self.right_elbow_roll,
-            &self.right_wrist_yaw,
-            &self.right_hip_roll,
-            &self.right_hip_pitch,
-            &self.right_knee_pitch,
-            &self.right_ankle_pitch,
-            &self.right_ankle_roll,
-            &self.left_hand,
-            &self.right_hand,
-        ]
+        macro_rules! as_array_ref {
+            (($joints:ident) $($field:ident $i:literal $lola:literal),*) => {
+                [$(&$joints.$field),*]
+            };
+        }
+
+        for_each_joint!(as_array_ref!(self))
     }
 
     /// Returns a mutable reference to all joint values as a fixed-size array.
@@ -274,39 +306,19 @@ impl<T> JointArray<T> {
     /// use nidhogg::types::JointArray;
     ///
     /// let mut joints = JointArray::<i32>::default();
-    /// for joint in joints.as_mut() {
+    /// for joint in joints.as_array_mut() {
     ///     *joint = 42;
     /// }
-    /// assert!(joints.as_ref().iter().all(|&v| v == 42));
+    /// assert!(joints.as_array_ref().iter().all(|&&v| v == 42));
     /// ```
     pub fn as_array_mut(&mut self) -> [&mut T; 25] {
-        [
-            &mut self.head_yaw,
-            &mut self.head_pitch,
-            &mut self.left_shoulder_pitch,
-            &mut self.left_shoulder_roll,
-            &mut self.left_elbow_yaw,
-            &mut self.left_elbow_roll,
-            &mut self.left_wrist_yaw,
-            &mut self.left_hip_yaw_pitch,
-            &mut self.left_hip_roll,
-            &mut self.left_hip_pitch,
-            &mut self.left_knee_pitch,
-            &mut self.left_ankle_pitch,
-            &mut self.left_ankle_roll,
-            &mut self.right_shoulder_pitch,
-            &mut self.right_shoulder_roll,
-            &mut self.right_elbow_yaw,
-            &mut self.right_elbow_roll,
-            &mut self.right_wrist_yaw,
-            &mut self.right_hip_roll,
-            &mut self.right_hip_pitch,
-            &mut self.right_knee_pitch,
-            &mut self.right_ankle_pitch,
-            &mut self.right_ankle_roll,
-            &mut self.left_hand,
-            &mut self.right_hand,
-        ]
+        macro_rules! as_array_mut {
+            (($joints:ident) $($field:ident $i:literal $lola:literal),*) => {
+                [$(&mut $joints.$field),*]
+            };
+        }
+
+        for_each_joint!(as_array_mut!(self))
     }
 
     /// Transforms each element in the [`JointArray`] using the provided closure `f`,
@@ -327,32 +339,64 @@ impl<T> JointArray<T> {
     where
         F: FnMut(T) -> U,
     {
-        JointArray {
-            head_yaw: f(self.head_yaw),
-            head_pitch: f(self.head_pitch),
-            left_shoulder_pitch: f(self.left_shoulder_pitch),
-            left_shoulder_roll: f(self.left_shoulder_roll),
-            left_elbow_yaw: f(self.left_elbow_yaw),
-            left_elbow_roll: f(self.left_elbow_roll),
-            left_wrist_yaw: f(self.left_wrist_yaw),
-            left_hip_yaw_pitch: f(self.left_hip_yaw_pitch),
-            left_hip_roll: f(self.left_hip_roll),
-            left_hip_pitch: f(self.left_hip_pitch),
-            left_knee_pitch: f(self.left_knee_pitch),
-            left_ankle_pitch: f(self.left_ankle_pitch),
-            left_ankle_roll: f(self.left_ankle_roll),
-            right_shoulder_pitch: f(self.right_shoulder_pitch),
-            right_shoulder_roll: f(self.right_shoulder_roll),
-            right_elbow_yaw: f(self.right_elbow_yaw),
-            right_elbow_roll: f(self.right_elbow_roll),
-            right_wrist_yaw: f(self.right_wrist_yaw),
-            right_hip_roll: f(self.right_hip_roll),
-            right_hip_pitch: f(self.right_hip_pitch),
-            right_knee_pitch: f(self.right_knee_pitch),
-            right_ankle_pitch: f(self.right_ankle_pitch),
-            right_ankle_roll: f(self.right_ankle_roll),
-            left_hand: f(self.left_hand),
-            right_hand: f(self.right_hand),
+        macro_rules! map {
+            (($joints:ident, $f:ident) $($field:ident $i:literal $lola:literal),*) => {
+                // the fields are evaluated in the order of `JointName::ALL`
+                JointArray {
+                    $($field: $f($joints.$field)),*
+                }
+            };
+        }
+
+        for_each_joint!(map!(self, f))
+    }
+
+    /// Transforms each element in the [`JointArray`] using the provided closure `f`,
+    /// which also receives the [`JointName`] of the element.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{JointArray, JointName, JointRegion};
+    ///
+    /// let gains = JointArray::<f32>::default().map_with_name(|name, _| {
+    ///     if JointRegion::Legs.contains(name) { 0.8 } else { 0.3 }
+    /// });
+    ///
+    /// assert_eq!(gains.left_knee_pitch, 0.8);
+    /// assert_eq!(gains.head_yaw, 0.3);
+    /// ```
+    pub fn map_with_name<F, U>(self, mut f: F) -> JointArray<U>
+    where
+        F: FnMut(JointName, T) -> U,
+    {
+        // `map` visits the joints in the order of `JointName::ALL`
+        let mut names = JointName::ALL.into_iter();
+        self.map(|value| f(names.next().expect("JointName::ALL has 25 names"), value))
+    }
+
+    /// Applies `f` to every joint in the provided [`JointRegion`], leaving the other joints untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{FillExt, JointArray, JointRegion};
+    ///
+    /// let mut stiffness = JointArray::fill(1.0);
+    /// stiffness.update_region(JointRegion::Arms, |value| *value = 0.2);
+    ///
+    /// assert_eq!(stiffness.left_elbow_roll, 0.2);
+    /// assert_eq!(stiffness.right_hand, 0.2);
+    /// assert_eq!(stiffness.head_yaw, 1.0);
+    /// ```
+    pub fn update_region<F>(&mut self, region: JointRegion, mut f: F)
+    where
+        F: FnMut(&mut T),
+    {
+        for (name, value) in JointName::ALL.into_iter().zip(self.as_array_mut()) {
+            if region.contains(name) {
+                f(value);
+            }
         }
     }
 
@@ -369,33 +413,15 @@ impl<T> JointArray<T> {
     /// assert_eq!(zipped.head_yaw, (0_u32, 0_f32));
     /// ```
     pub fn zip<U>(self, other: JointArray<U>) -> JointArray<(T, U)> {
-        JointArray {
-            head_yaw: (self.head_yaw, other.head_yaw),
-            head_pitch: (self.head_pitch, other.head_pitch),
-            left_shoulder_pitch: (self.left_shoulder_pitch, other.left_shoulder_pitch),
-            left_shoulder_roll: (self.left_shoulder_roll, other.left_shoulder_roll),
-            left_elbow_yaw: (self.left_elbow_yaw, other.left_elbow_yaw),
-            left_elbow_roll: (self.left_elbow_roll, other.left_elbow_roll),
-            left_wrist_yaw: (self.left_wrist_yaw, other.left_wrist_yaw),
-            left_hip_yaw_pitch: (self.left_hip_yaw_pitch, other.left_hip_yaw_pitch),
-            left_hip_roll: (self.left_hip_roll, other.left_hip_roll),
-            left_hip_pitch: (self.left_hip_pitch, other.left_hip_pitch),
-            left_knee_pitch: (self.left_knee_pitch, other.left_knee_pitch),
-            left_ankle_pitch: (self.left_ankle_pitch, other.left_ankle_pitch),
-            left_ankle_roll: (self.left_ankle_roll, other.left_ankle_roll),
-            right_shoulder_pitch: (self.right_shoulder_pitch, other.right_shoulder_pitch),
-            right_shoulder_roll: (self.right_shoulder_roll, other.right_shoulder_roll),
-            right_elbow_yaw: (self.right_elbow_yaw, other.right_elbow_yaw),
-            right_elbow_roll: (self.right_elbow_roll, other.right_elbow_roll),
-            right_wrist_yaw: (self.right_wrist_yaw, other.right_wrist_yaw),
-            right_hip_roll: (self.right_hip_roll, other.right_hip_roll),
-            right_hip_pitch: (self.right_hip_pitch, other.right_hip_pitch),
-            right_knee_pitch: (self.right_knee_pitch, other.right_knee_pitch),
-            right_ankle_pitch: (self.right_ankle_pitch, other.right_ankle_pitch),
-            right_ankle_roll: (self.right_ankle_roll, other.right_ankle_roll),
-            left_hand: (self.left_hand, other.left_hand),
-            right_hand: (self.right_hand, other.right_hand),
+        macro_rules! zip {
+            (($joints:ident, $other:ident) $($field:ident $i:literal $lola:literal),*) => {
+                JointArray {
+                    $($field: ($joints.$field, $other.$field)),*
+                }
+            };
         }
+
+        for_each_joint!(zip!(self, other))
     }
 
     /// Checks if all elements of a joint array satisfy a certain condition.
@@ -617,6 +643,305 @@ impl<T> JointArray<T> {
             self.right_hand,
         ]
     }
+
+    /// Creates a [`JointArray`] from an iterator that yields exactly 25 elements, in the order of [`JointName::ALL`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{JointArray, WrongLength};
+    ///
+    /// let joints = JointArray::try_from_iter(0..25).unwrap();
+    /// assert_eq!(joints.right_hand, 24);
+    ///
+    /// let error = JointArray::try_from_iter(0..3).unwrap_err();
+    /// assert_eq!(error, WrongLength { expected: 25, actual: 3 });
+    /// ```
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, WrongLength>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let values: Vec<T> = iter.into_iter().collect();
+        let actual = values.len();
+
+        let values: [T; 25] = values.try_into().map_err(|_| WrongLength {
+            expected: 25,
+            actual,
+        })?;
+        let [head_yaw, head_pitch, left_shoulder_pitch, left_shoulder_roll, left_elbow_yaw, left_elbow_roll, left_wrist_yaw, left_hip_yaw_pitch, left_hip_roll, left_hip_pitch, left_knee_pitch, left_ankle_pitch, left_ankle_roll, right_shoulder_pitch, right_shoulder_roll, right_elbow_yaw, right_elbow_roll, right_wrist_yaw, right_hip_roll, right_hip_pitch, right_knee_pitch, right_ankle_pitch, right_ankle_roll, left_hand, right_hand] =
+            values;
+
+        Ok(JointArray {
+            head_yaw,
+            head_pitch,
+            left_shoulder_pitch,
+            left_shoulder_roll,
+            left_elbow_yaw,
+            left_elbow_roll,
+            left_wrist_yaw,
+            left_hip_yaw_pitch,
+            left_hip_roll,
+            left_hip_pitch,
+            left_knee_pitch,
+            left_ankle_pitch,
+            left_ankle_roll,
+            right_shoulder_pitch,
+            right_shoulder_roll,
+            right_elbow_yaw,
+            right_elbow_roll,
+            right_wrist_yaw,
+            right_hip_roll,
+            right_hip_pitch,
+            right_knee_pitch,
+            right_ankle_pitch,
+            right_ankle_roll,
+            left_hand,
+            right_hand,
+        })
+    }
+
+    /// Returns an iterator over the joint values, together with their [`JointName`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{JointArray, JointName};
+    ///
+    /// let joints = JointArray::<i32> {
+    ///     head_pitch: 3,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let (name, value) = joints.iter_named().nth(1).unwrap();
+    /// assert_eq!(name, JointName::HeadPitch);
+    /// assert_eq!(*value, 3);
+    /// ```
+    pub fn iter_named(&self) -> impl Iterator<Item = (JointName, &T)> {
+        JointName::ALL.into_iter().zip(self.as_array_ref())
+    }
+
+    /// Folds every joint value into an accumulator, visiting the joints in the order of [`JointName::ALL`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{FillExt, JointArray};
+    ///
+    /// let stiffness = JointArray::fill(0.5_f32);
+    /// let max = stiffness.fold(0.0, |max, &value| f32::max(max, value));
+    ///
+    /// assert_eq!(max, 0.5);
+    /// ```
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &T) -> B,
+    {
+        self.as_array_ref().into_iter().fold(init, f)
+    }
+
+    /// Returns the sum of all joint values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{FillExt, JointArray};
+    ///
+    /// assert_eq!(JointArray::fill(2).sum(), 50);
+    /// ```
+    pub fn sum(&self) -> T
+    where
+        T: Add<Output = T> + Default + Clone,
+    {
+        self.fold(T::default(), |sum, value| sum + value.clone())
+    }
+
+    /// Returns the joint with the maximum value with respect to the provided comparison function.
+    ///
+    /// If multiple joints are equally maximum, the first one in [`JointName::ALL`] is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{JointArray, JointName};
+    ///
+    /// let temperature = JointArray::<f32> {
+    ///     left_knee_pitch: 60.0,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let (name, &value) = temperature.max_by(f32::total_cmp);
+    /// assert_eq!((name, value), (JointName::LeftKneePitch, 60.0));
+    /// ```
+    pub fn max_by<F>(&self, mut compare: F) -> (JointName, &T)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.select_by(|value, best| compare(value, best) == Ordering::Greater)
+    }
+
+    /// Returns the joint with the minimum value with respect to the provided comparison function.
+    ///
+    /// If multiple joints are equally minimum, the first one in [`JointName::ALL`] is returned.
+    pub fn min_by<F>(&self, mut compare: F) -> (JointName, &T)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.select_by(|value, best| compare(value, best) == Ordering::Less)
+    }
+
+    /// Returns the first joint for which no later joint is `better`.
+    fn select_by<F>(&self, mut better: F) -> (JointName, &T)
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut joints = self.iter_named();
+        let first = joints.next().expect("a joint array is never empty");
+
+        joints.fold(
+            first,
+            |best, joint| {
+                if better(joint.1, best.1) {
+                    joint
+                } else {
+                    best
+                }
+            },
+        )
+    }
+
+    /// Returns the amount of joints for which `predicate` returns `true`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::JointArray;
+    ///
+    /// let stiffness = JointArray::<f32> {
+    ///     head_yaw: 1.0,
+    ///     head_pitch: 1.0,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(stiffness.count(|&value| value > 0.0), 2);
+    /// ```
+    pub fn count<F>(&self, mut predicate: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.fold(0, |count, value| count + usize::from(predicate(value)))
+    }
+}
+
+impl JointArray<f32> {
+    /// Returns the mean of all joint values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{FillExt, JointArray};
+    ///
+    /// assert_eq!(JointArray::fill(0.25).mean(), 0.25);
+    /// ```
+    pub fn mean(&self) -> f32 {
+        self.sum() / JointName::ALL.len() as f32
+    }
+}
+
+/// Displays one joint per line, with the joint names aligned and values rounded to three decimals.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::types::JointArray;
+///
+/// let joints = JointArray::<f32> { head_pitch: 0.25, ..Default::default() };
+/// let table = joints.to_string();
+///
+/// assert_eq!(table.lines().nth(1), Some("head_pitch             0.250"));
+/// ```
+impl fmt::Display for JointArray<f32> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = JointName::ALL
+            .iter()
+            .map(|name| name.as_str().len())
+            .max()
+            .unwrap_or_default();
+
+        for (i, (name, value)) in self.iter_named().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:<width$} {value:>7.3}", name.as_str())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Index<JointName> for JointArray<T> {
+    type Output = T;
+
+    fn index(&self, name: JointName) -> &Self::Output {
+        match name {
+            JointName::HeadYaw => &self.head_yaw,
+            JointName::HeadPitch => &self.head_pitch,
+            JointName::LeftShoulderPitch => &self.left_shoulder_pitch,
+            JointName::LeftShoulderRoll => &self.left_shoulder_roll,
+            JointName::LeftElbowYaw => &self.left_elbow_yaw,
+            JointName::LeftElbowRoll => &self.left_elbow_roll,
+            JointName::LeftWristYaw => &self.left_wrist_yaw,
+            JointName::LeftHipYawPitch => &self.left_hip_yaw_pitch,
+            JointName::LeftHipRoll => &self.left_hip_roll,
+            JointName::LeftHipPitch => &self.left_hip_pitch,
+            JointName::LeftKneePitch => &self.left_knee_pitch,
+            JointName::LeftAnklePitch => &self.left_ankle_pitch,
+            JointName::LeftAnkleRoll => &self.left_ankle_roll,
+            JointName::RightShoulderPitch => &self.right_shoulder_pitch,
+            JointName::RightShoulderRoll => &self.right_shoulder_roll,
+            JointName::RightElbowYaw => &self.right_elbow_yaw,
+            JointName::RightElbowRoll => &self.right_elbow_roll,
+            JointName::RightWristYaw => &self.right_wrist_yaw,
+            JointName::RightHipRoll => &self.right_hip_roll,
+            JointName::RightHipPitch => &self.right_hip_pitch,
+            JointName::RightKneePitch => &self.right_knee_pitch,
+            JointName::RightAnklePitch => &self.right_ankle_pitch,
+            JointName::RightAnkleRoll => &self.right_ankle_roll,
+            JointName::LeftHand => &self.left_hand,
+            JointName::RightHand => &self.right_hand,
+        }
+    }
+}
+
+impl<T> IndexMut<JointName> for JointArray<T> {
+    fn index_mut(&mut self, name: JointName) -> &mut Self::Output {
+        match name {
+            JointName::HeadYaw => &mut self.head_yaw,
+            JointName::HeadPitch => &mut self.head_pitch,
+            JointName::LeftShoulderPitch => &mut self.left_shoulder_pitch,
+            JointName::LeftShoulderRoll => &mut self.left_shoulder_roll,
+            JointName::LeftElbowYaw => &mut self.left_elbow_yaw,
+            JointName::LeftElbowRoll => &mut self.left_elbow_roll,
+            JointName::LeftWristYaw => &mut self.left_wrist_yaw,
+            JointName::LeftHipYawPitch => &mut self.left_hip_yaw_pitch,
+            JointName::LeftHipRoll => &mut self.left_hip_roll,
+            JointName::LeftHipPitch => &mut self.left_hip_pitch,
+            JointName::LeftKneePitch => &mut self.left_knee_pitch,
+            JointName::LeftAnklePitch => &mut self.left_ankle_pitch,
+            JointName::LeftAnkleRoll => &mut self.left_ankle_roll,
+            JointName::RightShoulderPitch => &mut self.right_shoulder_pitch,
+            JointName::RightShoulderRoll => &mut self.right_shoulder_roll,
+            JointName::RightElbowYaw => &mut self.right_elbow_yaw,
+            JointName::RightElbowRoll => &mut self.right_elbow_roll,
+            JointName::RightWristYaw => &mut self.right_wrist_yaw,
+            JointName::RightHipRoll => &mut self.right_hip_roll,
+            JointName::RightHipPitch => &mut self.right_hip_pitch,
+            JointName::RightKneePitch => &mut self.right_knee_pitch,
+            JointName::RightAnklePitch => &mut self.right_ankle_pitch,
+            JointName::RightAnkleRoll => &mut self.right_ankle_roll,
+            JointName::LeftHand => &mut self.left_hand,
+            JointName::RightHand => &mut self.right_hand,
+        }
+    }
 }
 
 impl<'a, T> From<&'a JointArray<T>> for JointArray<&'a T> {
@@ -704,6 +1029,286 @@ impl<T: Clone> JointArray<T> {
     }
 }
 
+impl<T> JointArray<T> {
+    /// Sets the `head_pitch` and `head_yaw` values to the corresponding values from the provided [`HeadJoints`].
+    pub fn set_head_joints(&mut self, joints: HeadJoints<T>) {
+        self.head_yaw = joints.yaw;
+        self.head_pitch = joints.pitch;
+    }
+
+    /// Sets the left leg joints to the corresponding values from the provided [`LeftLegJoints`].
+    pub fn set_left_leg_joints(&mut self, joints: LeftLegJoints<T>) {
+        self.left_hip_yaw_pitch = joints.hip_yaw_pitch;
+        self.left_hip_roll = joints.hip_roll;
+        self.left_hip_pitch = joints.hip_pitch;
+        self.left_knee_pitch = joints.knee_pitch;
+        self.left_ankle_pitch = joints.ankle_pitch;
+        self.left_ankle_roll = joints.ankle_roll;
+    }
+
+    /// Sets the right leg joints to the corresponding values from the provided [`RightLegJoints`].
+    pub fn set_right_leg_joints(&mut self, joints: RightLegJoints<T>) {
+        self.right_hip_roll = joints.hip_roll;
+        self.right_hip_pitch = joints.hip_pitch;
+        self.right_knee_pitch = joints.knee_pitch;
+        self.right_ankle_pitch = joints.ankle_pitch;
+        self.right_ankle_roll = joints.ankle_roll;
+    }
+
+    /// Sets the leg joints to the corresponding values from the provided [`LegJoints`].
+    pub fn set_leg_joints(&mut self, joints: LegJoints<T>) {
+        self.set_left_leg_joints(joints.left_leg);
+        self.set_right_leg_joints(joints.right_leg);
+    }
+
+    /// Sets the left arm joints to the corresponding values from the provided [`LeftArmJoints`].
+    pub fn set_left_arm_joints(&mut self, joints: LeftArmJoints<T>) {
+        self.left_shoulder_pitch = joints.shoulder_pitch;
+        self.left_shoulder_roll = joints.shoulder_roll;
+        self.left_elbow_yaw = joints.elbow_yaw;
+        self.left_elbow_roll = joints.elbow_roll;
+        self.left_wrist_yaw = joints.wrist_yaw;
+        self.left_hand = joints.hand;
+    }
+
+    /// Sets the right arm joints to the corresponding values from the provided [`RightArmJoints`].
+    pub fn set_right_arm_joints(&mut self, joints: RightArmJoints<T>) {
+        self.right_shoulder_pitch = joints.shoulder_pitch;
+        self.right_shoulder_roll = joints.shoulder_roll;
+        self.right_elbow_yaw = joints.elbow_yaw;
+        self.right_elbow_roll = joints.elbow_roll;
+        self.right_wrist_yaw = joints.wrist_yaw;
+        self.right_hand = joints.hand;
+    }
+
+    /// Sets the arm joints to the corresponding values from the provided [`ArmJoints`].
+    pub fn set_arm_joints(&mut self, joints: ArmJoints<T>) {
+        self.set_left_arm_joints(joints.left_arm);
+        self.set_right_arm_joints(joints.right_arm);
+    }
+
+    /// Returns the [`JointArray`] with the head joints replaced by the provided [`HeadJoints`].
+    #[must_use]
+    pub fn with_head_joints(mut self, joints: HeadJoints<T>) -> Self {
+        self.set_head_joints(joints);
+        self
+    }
+
+    /// Returns the [`JointArray`] with the leg joints replaced by the provided [`LegJoints`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{FillExt, JointArray, LegJoints};
+    ///
+    /// let joints = JointArray::<f32>::default().with_leg_joints(LegJoints::fill(0.5));
+    ///
+    /// assert_eq!(joints.left_knee_pitch, 0.5);
+    /// assert_eq!(joints.head_yaw, 0.0);
+    /// ```
+    #[must_use]
+    pub fn with_leg_joints(mut self, joints: LegJoints<T>) -> Self {
+        self.set_leg_joints(joints);
+        self
+    }
+
+    /// Returns the [`JointArray`] with the arm joints replaced by the provided [`ArmJoints`].
+    #[must_use]
+    pub fn with_arm_joints(mut self, joints: ArmJoints<T>) -> Self {
+        self.set_arm_joints(joints);
+        self
+    }
+}
+
+impl<T: Clone> JointArray<Option<T>> {
+    /// Returns the contained values, using the corresponding value from `fallback` for joints that are `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{FillExt, JointArray};
+    ///
+    /// let partial = JointArray {
+    ///     head_yaw: Some(1.0),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let joints = partial.unwrap_or(&JointArray::fill(0.5));
+    ///
+    /// assert_eq!(joints.head_yaw, 1.0);
+    /// assert_eq!(joints.head_pitch, 0.5);
+    /// ```
+    pub fn unwrap_or(&self, fallback: &JointArray<T>) -> JointArray<T> {
+        self.as_ref()
+            .zip(fallback.as_ref())
+            .map(|(value, fallback)| value.as_ref().unwrap_or(fallback).clone())
+    }
+}
+
+impl JointMask {
+    /// Create a mask that selects the joints in `region`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{JointMask, JointRegion};
+    ///
+    /// let mask = JointMask::from_region(JointRegion::Head);
+    /// assert!(mask.head_yaw);
+    /// assert!(!mask.left_hand);
+    /// ```
+    pub fn from_region(region: JointRegion) -> Self {
+        JointArray::default().map_with_name(|name, _: bool| region.contains(name))
+    }
+}
+
+impl<T: Clone> JointArray<T> {
+    /// Returns the values of `self` for the joints selected by `mask`, and the values of `other` for the other joints.
+    ///
+    /// # Example
+    ///
+    /// Freeze the arms, but let the walk control the legs:
+    ///
+    /// ```
+    /// use nidhogg::types::{FillExt, JointArray, JointMask, JointRegion};
+    ///
+    /// let walk = JointArray::fill(0.5);
+    /// let frozen = JointArray::fill(0.0);
+    ///
+    /// let position = walk.select(&!JointMask::from_region(JointRegion::Arms), &frozen);
+    /// assert_eq!(position.left_knee_pitch, 0.5);
+    /// assert_eq!(position.right_elbow_roll, 0.0);
+    /// ```
+    pub fn select(&self, mask: &JointMask, other: &JointArray<T>) -> JointArray<T> {
+        self.as_ref()
+            .zip(other.as_ref())
+            .zip(mask.clone())
+            .map(|((value, other), selected)| if selected { value } else { other }.clone())
+    }
+
+    /// Returns a copy with the joints in `region` set to `value`, and all other joints unchanged.
+    ///
+    /// The shared hip yaw pitch joint is only part of [`JointRegion::LeftLeg`], see [`JointRegion`].
+    ///
+    /// # Example
+    ///
+    /// Stiffness presets per region, in one expression:
+    ///
+    /// ```
+    /// use nidhogg::types::{FillExt, JointArray, JointRegion};
+    ///
+    /// let stiffness = JointArray::fill(0.8)
+    ///     .fill_region(JointRegion::Arms, 0.3)
+    ///     .fill_region(JointRegion::Head, 0.5);
+    ///
+    /// assert_eq!(stiffness.left_hand, 0.3);
+    /// assert_eq!(stiffness.head_pitch, 0.5);
+    /// assert_eq!(stiffness.right_knee_pitch, 0.8);
+    /// ```
+    #[must_use]
+    pub fn fill_region(mut self, region: JointRegion, value: T) -> JointArray<T> {
+        self.filled_region(region, value);
+        self
+    }
+
+    /// Sets the joints in `region` to `value`, leaving all other joints unchanged.
+    ///
+    /// This is the in-place version of [`JointArray::fill_region`].
+    pub fn filled_region(&mut self, region: JointRegion, value: T) {
+        self.apply_masked(&JointMask::from_region(region), JointArray::fill(value));
+    }
+}
+
+impl<T> JointArray<T> {
+    /// Replaces the values of the joints selected by `mask` with the corresponding `values`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{FillExt, JointArray, JointMask, JointRegion};
+    ///
+    /// let mut stiffness = JointArray::fill(1.0);
+    /// stiffness.apply_masked(&JointMask::from_region(JointRegion::Head), JointArray::fill(0.0));
+    ///
+    /// assert_eq!(stiffness.head_pitch, 0.0);
+    /// assert_eq!(stiffness.left_hand, 1.0);
+    /// ```
+    pub fn apply_masked(&mut self, mask: &JointMask, values: JointArray<T>) {
+        for ((value, new), selected) in self.as_array_mut().into_iter().zip(values).zip(mask) {
+            if *selected {
+                *value = new;
+            }
+        }
+    }
+}
+
+impl BitAnd for JointMask {
+    type Output = JointMask;
+
+    fn bitand(self, rhs: JointMask) -> JointMask {
+        self.zip(rhs).map(|(lhs, rhs)| lhs && rhs)
+    }
+}
+
+impl BitOr for JointMask {
+    type Output = JointMask;
+
+    fn bitor(self, rhs: JointMask) -> JointMask {
+        self.zip(rhs).map(|(lhs, rhs)| lhs || rhs)
+    }
+}
+
+impl Not for JointMask {
+    type Output = JointMask;
+
+    fn not(self) -> JointMask {
+        self.map(|selected| !selected)
+    }
+}
+
+impl<T: Scalar> JointArray<T> {
+    /// Converts the [`JointArray`] into a [`SVector`], in the order of [`JointName::ALL`].
+    ///
+    /// # Example
+    ///
+    /// Computing the distance between two joint configurations in joint space:
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// use nidhogg::types::JointArray;
+    ///
+    /// let current = JointArray::<f32>::default();
+    /// let target = JointArray {
+    ///     head_yaw: 3.0,
+    ///     head_pitch: 4.0,
+    ///     ..JointArray::default()
+    /// };
+    ///
+    /// let distance = (target.to_svector() - current.to_svector()).norm();
+    /// assert_eq!(distance, 5.0);
+    /// ```
+    pub fn to_svector(&self) -> SVector<T, 25> {
+        SVector::from_iterator(self.as_array_ref().into_iter().cloned())
+    }
+
+    /// Creates a [`JointArray`] from a [`SVector`], in the order of [`JointName::ALL`].
+    pub fn from_svector(vector: SVector<T, 25>) -> Self {
+        vector.iter().cloned().collect()
+    }
+}
+
+impl<T: Scalar> From<JointArray<T>> for SVector<T, 25> {
+    fn from(value: JointArray<T>) -> Self {
+        value.to_svector()
+    }
+}
+
+impl<T: Scalar> From<SVector<T, 25>> for JointArray<T> {
+    fn from(value: SVector<T, 25>) -> Self {
+        JointArray::from_svector(value)
+    }
+}
+
 impl<T: Clone> FillExt<T> for JointArray<T> {
     fn fill(value: T) -> JointArray<T> {
         JointArray {
@@ -896,7 +1501,7 @@ impl<T> JointArrayBuilder<T> {
 
 impl<T> IntoIterator for JointArray<T> {
     type Item = T;
-    type IntoIter = std::array::IntoIter<T, 25>;
+    type IntoIter = core::array::IntoIter<T, 25>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.as_array().into_iter()
@@ -905,17 +1510,51 @@ impl<T> IntoIterator for JointArray<T> {
 
 impl<'a, T> IntoIterator for &'a JointArray<T> {
     type Item = &'a T;
-    type IntoIter = std::array::IntoIter<&'a T, 25>;
+    type IntoIter = core::array::IntoIter<&'a T, 25>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.as_array_ref().into_iter()
     }
 }
 
+impl<'a, T> IntoIterator for &'a mut JointArray<T> {
+    type Item = &'a mut T;
+    type IntoIter = core::array::IntoIter<&'a mut T, 25>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_array_mut().into_iter()
+    }
+}
+
+impl<T> FromIterator<T> for JointArray<T> {
+    /// Creates a [`JointArray`] from an iterator, in the order of [`JointName::ALL`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator does not yield exactly 25 elements,
+    /// use [`JointArray::try_from_iter`] to handle this case instead.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        match JointArray::try_from_iter(iter) {
+            Ok(joints) => joints,
+            Err(error) => panic!("failed to collect into JointArray: {error}"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::{string::ToString, vec, vec::Vec};
+    use core::cmp::Ordering;
+
     use crate::types::FillExt;
     use crate::types::JointArray;
+    use crate::types::JointMask;
+    use crate::types::JointName;
+    use crate::types::JointRegion;
+    use crate::types::WrongLength;
+    use crate::types::{
+        ArmJoints, HeadJoints, LeftLegJoints, LegJoints, RightLegJoints, SingleArmJoints,
+    };
 
     #[test]
     fn test_joint_array_map() {
@@ -1057,4 +1696,379 @@ mod tests {
             assert_eq!(original.get(i), reconstructed.get(i));
         }
     }
+
+    #[test]
+    fn test_index_by_name_matches_fields() {
+        let joints = JointArray::<usize>::try_from(&(0..25).collect::<Vec<_>>()[..]).unwrap();
+
+        assert_eq!(joints[JointName::HeadYaw], joints.head_yaw);
+        assert_eq!(joints[JointName::HeadPitch], joints.head_pitch);
+        assert_eq!(
+            joints[JointName::LeftShoulderPitch],
+            joints.left_shoulder_pitch
+        );
+        assert_eq!(
+            joints[JointName::LeftShoulderRoll],
+            joints.left_shoulder_roll
+        );
+        assert_eq!(joints[JointName::LeftElbowYaw], joints.left_elbow_yaw);
+        assert_eq!(joints[JointName::LeftElbowRoll], joints.left_elbow_roll);
+        assert_eq!(joints[JointName::LeftWristYaw], joints.left_wrist_yaw);
+        assert_eq!(
+            joints[JointName::LeftHipYawPitch],
+            joints.left_hip_yaw_pitch
+        );
+        assert_eq!(joints[JointName::LeftHipRoll], joints.left_hip_roll);
+        assert_eq!(joints[JointName::LeftHipPitch], joints.left_hip_pitch);
+        assert_eq!(joints[JointName::LeftKneePitch], joints.left_knee_pitch);
+        assert_eq!(joints[JointName::LeftAnklePitch], joints.left_ankle_pitch);
+        assert_eq!(joints[JointName::LeftAnkleRoll], joints.left_ankle_roll);
+        assert_eq!(
+            joints[JointName::RightShoulderPitch],
+            joints.right_shoulder_pitch
+        );
+        assert_eq!(
+            joints[JointName::RightShoulderRoll],
+            joints.right_shoulder_roll
+        );
+        assert_eq!(joints[JointName::RightElbowYaw], joints.right_elbow_yaw);
+        assert_eq!(joints[JointName::RightElbowRoll], joints.right_elbow_roll);
+        assert_eq!(joints[JointName::RightWristYaw], joints.right_wrist_yaw);
+        assert_eq!(joints[JointName::RightHipRoll], joints.right_hip_roll);
+        assert_eq!(joints[JointName::RightHipPitch], joints.right_hip_pitch);
+        assert_eq!(joints[JointName::RightKneePitch], joints.right_knee_pitch);
+        assert_eq!(joints[JointName::RightAnklePitch], joints.right_ankle_pitch);
+        assert_eq!(joints[JointName::RightAnkleRoll], joints.right_ankle_roll);
+        assert_eq!(joints[JointName::LeftHand], joints.left_hand);
+        assert_eq!(joints[JointName::RightHand], joints.right_hand);
+
+        for name in JointName::ALL {
+            assert_eq!(joints[name], name.index());
+            assert_eq!(joints.get(name.index()), Some(&joints[name]));
+        }
+    }
+
+    #[test]
+    fn test_index_mut_by_name() {
+        let mut joints = JointArray::<i32>::default();
+
+        for (value, name) in JointName::ALL.into_iter().enumerate() {
+            joints[name] = value as i32;
+        }
+
+        assert_eq!(joints.clone().to_vec(), (0..25).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_iter_named() {
+        let joints = JointArray::<i32> {
+            right_knee_pitch: 7,
+            ..Default::default()
+        };
+
+        let named: Vec<_> = joints.iter_named().filter(|(_, &v)| v != 0).collect();
+        assert_eq!(named, vec![(JointName::RightKneePitch, &7)]);
+    }
+
+    #[test]
+    fn test_collect_exact() {
+        let joints: JointArray<usize> = (0..25).collect();
+        assert_eq!(joints.clone().to_vec(), (0..25).collect::<Vec<_>>());
+
+        let doubled: JointArray<usize> = joints.iter_named().map(|(_, value)| value * 2).collect();
+        assert_eq!(doubled.right_hand, 48);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 25 elements to create a JointArray, got 24")]
+    fn test_collect_too_few() {
+        let _: JointArray<usize> = (0..24).collect();
+    }
+
+    #[test]
+    #[should_panic(expected = "expected 25 elements to create a JointArray, got 26")]
+    fn test_collect_too_many() {
+        let _: JointArray<usize> = (0..26).collect();
+    }
+
+    #[test]
+    fn test_try_from_iter_wrong_length() {
+        assert_eq!(
+            JointArray::try_from_iter(0..10),
+            Err(WrongLength {
+                expected: 25,
+                actual: 10
+            })
+        );
+        assert_eq!(
+            JointArray::try_from_iter(0..30),
+            Err(WrongLength {
+                expected: 25,
+                actual: 30
+            })
+        );
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut joints = JointArray::<i32>::fill(1);
+        for value in &mut joints {
+            *value += 1;
+        }
+        assert_eq!(joints, JointArray::fill(2));
+    }
+
+    #[test]
+    fn test_set_joints_touch_only_region() {
+        let mut joints = JointArray::<i32>::default();
+        joints.set_left_leg_joints(LeftLegJoints::fill(1));
+        assert_eq!(joints.left_leg_joints(), LeftLegJoints::fill(1));
+        assert_eq!(joints.clone().into_iter().filter(|&v| v == 1).count(), 6);
+
+        let mut joints = JointArray::<i32>::default();
+        joints.set_right_leg_joints(RightLegJoints::fill(1));
+        assert_eq!(joints.right_leg_joints(), RightLegJoints::fill(1));
+        assert_eq!(joints.clone().into_iter().filter(|&v| v == 1).count(), 5);
+
+        let mut joints = JointArray::<i32>::default();
+        joints.set_head_joints(HeadJoints { yaw: 1, pitch: 2 });
+        assert_eq!((joints.head_yaw, joints.head_pitch), (1, 2));
+        assert_eq!(joints.clone().into_iter().filter(|&v| v != 0).count(), 2);
+
+        let mut joints = JointArray::<i32>::default();
+        joints.set_arm_joints(ArmJoints {
+            left_arm: SingleArmJoints::fill(1),
+            right_arm: SingleArmJoints::fill(2),
+        });
+        assert_eq!(joints.left_arm_joints(), SingleArmJoints::fill(1));
+        assert_eq!(joints.right_arm_joints(), SingleArmJoints::fill(2));
+        assert_eq!(joints.leg_joints(), LegJoints::default());
+        assert_eq!(joints.head_joints(), HeadJoints::default());
+    }
+
+    #[test]
+    fn test_with_leg_joints() {
+        let legs = JointArray::<i32>::fill(3).leg_joints();
+        let joints = JointArray::<i32>::default().with_leg_joints(legs.clone());
+
+        assert_eq!(joints.leg_joints(), legs);
+        assert_eq!(joints.arm_joints(), ArmJoints::default());
+        assert_eq!(joints.head_joints(), HeadJoints::default());
+    }
+
+    #[test]
+    fn test_svector_roundtrip() {
+        let joints: JointArray<f32> = (0..25).map(|i| i as f32).collect();
+        let vector = joints.to_svector();
+
+        assert_eq!(
+            vector[JointName::LeftKneePitch as usize],
+            joints.left_knee_pitch
+        );
+        assert_eq!(vector[24], joints.right_hand);
+        assert_eq!(JointArray::from_svector(vector), joints);
+        assert_eq!(
+            JointArray::from(nalgebra::SVector::from(joints.clone())),
+            joints
+        );
+    }
+
+    #[test]
+    fn test_map_with_name() {
+        let names = JointArray::fill(()).map_with_name(|name, ()| name);
+        for (name, value) in names.iter_named() {
+            assert_eq!(name, *value);
+        }
+
+        let gains = JointArray::fill(1.0).map_with_name(|name, value| match name {
+            JointName::HeadYaw | JointName::HeadPitch => value * 0.5,
+            name if JointRegion::Legs.contains(name) => value * 2.0,
+            _ => value,
+        });
+        assert_eq!(gains.head_pitch, 0.5);
+        assert_eq!(gains.right_ankle_roll, 2.0);
+        assert_eq!(gains.left_hip_yaw_pitch, 2.0);
+        assert_eq!(gains.left_shoulder_roll, 1.0);
+    }
+
+    #[test]
+    fn test_update_region() {
+        let mut joints = JointArray::fill(0);
+        joints.update_region(JointRegion::LeftLeg, |value| *value += 1);
+        joints.update_region(JointRegion::Legs, |value| *value += 1);
+
+        for (name, value) in joints.iter_named() {
+            let expected = match name {
+                name if JointRegion::LeftLeg.contains(name) => 2,
+                name if JointRegion::RightLeg.contains(name) => 1,
+                _ => 0,
+            };
+            assert_eq!(*value, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_mask_ops() {
+        let legs = JointMask::from_region(JointRegion::Legs);
+        let left_leg = JointMask::from_region(JointRegion::LeftLeg);
+
+        assert_eq!(legs.count(|selected| *selected), 11);
+        assert_eq!(
+            legs.clone() & !left_leg.clone(),
+            JointMask::from_region(JointRegion::RightLeg)
+        );
+        assert_eq!(
+            !(legs.clone() | JointMask::from_region(JointRegion::Arms)),
+            JointMask::from_region(JointRegion::Head)
+        );
+        assert_eq!(legs | !left_leg, JointMask::fill(true));
+    }
+
+    #[test]
+    fn test_select_and_apply_masked() {
+        let joints = JointArray::try_from_iter(0..25).unwrap();
+        let other = JointArray::fill(-1);
+
+        let head = JointMask::from_region(JointRegion::Head);
+        let selected = joints.select(&head, &other);
+        let mut expected = JointArray::fill(-1);
+        expected.head_yaw = 0;
+        expected.head_pitch = 1;
+        assert_eq!(selected, expected);
+
+        let mask = JointMask {
+            left_hand: true,
+            right_ankle_roll: true,
+            ..Default::default()
+        };
+        let mut applied = joints.clone();
+        applied.apply_masked(&mask, other.clone());
+        for (name, value) in applied.iter_named() {
+            let expected = match name {
+                JointName::LeftHand | JointName::RightAnkleRoll => -1,
+                name => name.index() as i32,
+            };
+            assert_eq!(*value, expected, "{name}");
+        }
+
+        // selecting from the values to apply gives the same result
+        assert_eq!(other.select(&mask, &joints), applied);
+    }
+
+    #[test]
+    fn test_fill_region_coverage() {
+        let sizes = [
+            (JointRegion::Head, 2),
+            (JointRegion::LeftArm, 6),
+            (JointRegion::RightArm, 6),
+            (JointRegion::LeftLeg, 6),
+            (JointRegion::RightLeg, 5),
+            (JointRegion::Arms, 12),
+            (JointRegion::Legs, 11),
+            (JointRegion::All, 25),
+        ];
+
+        for (region, size) in sizes {
+            let filled = JointArray::fill(0).fill_region(region, 1);
+            assert_eq!(filled.count(|value| *value == 1), size, "{region:?}");
+            for (name, value) in filled.iter_named() {
+                assert_eq!(*value == 1, region.contains(name), "{region:?} {name}");
+            }
+        }
+
+        // the shared hip yaw pitch joint is only filled with the left leg
+        let mut joints = JointArray::fill(0);
+        joints.filled_region(JointRegion::RightLeg, 1);
+        assert_eq!(joints.left_hip_yaw_pitch, 0);
+        joints.filled_region(JointRegion::LeftLeg, 2);
+        assert_eq!(joints.left_hip_yaw_pitch, 2);
+        assert_eq!(joints.right_hip_roll, 1);
+    }
+
+    #[test]
+    fn test_display_table() {
+        let joints = JointArray {
+            head_yaw: -0.5,
+            right_shoulder_pitch: 1.2346,
+            right_hand: 10.0,
+            ..Default::default()
+        };
+        let table = joints.to_string();
+        let lines = table.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 25);
+        assert_eq!(lines[0], "head_yaw              -0.500");
+        assert_eq!(lines[13], "right_shoulder_pitch   1.235");
+        assert_eq!(lines[24], "right_hand            10.000");
+    }
+
+    fn sample() -> JointArray<f32> {
+        JointArray::try_from_iter((0..25).map(|i| ((i * 7) % 11) as f32 - 3.5)).unwrap()
+    }
+
+    #[test]
+    fn test_reductions_match_vec() {
+        let joints = sample();
+        let values = joints.clone().to_vec();
+
+        assert_eq!(joints.sum(), values.iter().sum::<f32>());
+        assert_eq!(joints.mean(), values.iter().sum::<f32>() / 25.0);
+        assert_eq!(
+            joints.fold(1.0, |product, value| product * value.abs()),
+            values
+                .iter()
+                .fold(1.0, |product, value| product * value.abs())
+        );
+        assert_eq!(
+            joints.count(|&value| value > 0.0),
+            values.iter().filter(|&&value| value > 0.0).count()
+        );
+    }
+
+    #[test]
+    fn test_max_and_min_by() {
+        let joints = sample();
+        let values = joints.clone().to_vec();
+
+        let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+        let first_max = values.iter().position(|&value| value == max).unwrap();
+        let first_min = values.iter().position(|&value| value == min).unwrap();
+
+        assert_eq!(
+            joints.max_by(f32::total_cmp),
+            (JointName::ALL[first_max], &max)
+        );
+        assert_eq!(
+            joints.min_by(f32::total_cmp),
+            (JointName::ALL[first_min], &min)
+        );
+
+        // ties resolve to the first joint
+        let (name, _) = JointArray::fill(1).max_by(Ord::cmp);
+        assert_eq!(name, JointName::HeadYaw);
+        let (name, _) = JointArray::fill(1).min_by(|_, _| Ordering::Equal);
+        assert_eq!(name, JointName::HeadYaw);
+    }
+
+    #[test]
+    fn test_names_match_joint_names() {
+        for (name, joint) in JointArray::<f32>::NAMES.into_iter().zip(JointName::ALL) {
+            assert_eq!(name, joint.as_str());
+        }
+    }
+
+    #[test]
+    fn test_get_matches_as_array() {
+        let joints = JointArray::<usize>::try_from_iter(0..25).unwrap();
+
+        for (index, value) in joints.clone().as_array().into_iter().enumerate() {
+            assert_eq!(joints.get(index), Some(&value));
+            assert_eq!(value, index);
+        }
+        assert_eq!(
+            joints.zip(JointArray::fill(1)).map(|(a, b)| a + b).get(24),
+            Some(&25)
+        );
+    }
 }
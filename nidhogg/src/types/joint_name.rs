@@ -0,0 +1,313 @@
+//! Implements the [`JointName`] enum, naming each of the joints in a [`JointArray`](crate::types::JointArray).
+
+use alloc::string::{String, ToString};
+use core::{fmt, str::FromStr};
+
+/// Name of one of the 25 joints of the NAO V6 robot.
+///
+/// The variants are ordered the same as the fields of [`JointArray`](crate::types::JointArray).
+///
+/// A [`JointName`] is displayed as the name of the corresponding [`JointArray`](crate::types::JointArray) field,
+/// and can be parsed from either that name or the name used by `LoLA`.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::types::JointName;
+///
+/// let name: JointName = "LShoulderPitch".parse().unwrap();
+///
+/// assert_eq!(name, JointName::LeftShoulderPitch);
+/// assert_eq!(name.to_string(), "left_shoulder_pitch");
+/// assert_eq!("left_shoulder_pitch".parse::<JointName>().unwrap(), name);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JointName {
+    HeadYaw,
+    HeadPitch,
+    LeftShoulderPitch,
+    LeftShoulderRoll,
+    LeftElbowYaw,
+    LeftElbowRoll,
+    LeftWristYaw,
+    LeftHipYawPitch,
+    LeftHipRoll,
+    LeftHipPitch,
+    LeftKneePitch,
+    LeftAnklePitch,
+    LeftAnkleRoll,
+    RightShoulderPitch,
+    RightShoulderRoll,
+    RightElbowYaw,
+    RightElbowRoll,
+    RightWristYaw,
+    RightHipRoll,
+    RightHipPitch,
+    RightKneePitch,
+    RightAnklePitch,
+    RightAnkleRoll,
+    LeftHand,
+    RightHand,
+}
+
+impl JointName {
+    /// All joint names, in the same order as the fields of [`JointArray`](crate::types::JointArray).
+    pub const ALL: [JointName; 25] = [
+        JointName::HeadYaw,
+        JointName::HeadPitch,
+        JointName::LeftShoulderPitch,
+        JointName::LeftShoulderRoll,
+        JointName::LeftElbowYaw,
+        JointName::LeftElbowRoll,
+        JointName::LeftWristYaw,
+        JointName::LeftHipYawPitch,
+        JointName::LeftHipRoll,
+        JointName::LeftHipPitch,
+        JointName::LeftKneePitch,
+        JointName::LeftAnklePitch,
+        JointName::LeftAnkleRoll,
+        JointName::RightShoulderPitch,
+        JointName::RightShoulderRoll,
+        JointName::RightElbowYaw,
+        JointName::RightElbowRoll,
+        JointName::RightWristYaw,
+        JointName::RightHipRoll,
+        JointName::RightHipPitch,
+        JointName::RightKneePitch,
+        JointName::RightAnklePitch,
+        JointName::RightAnkleRoll,
+        JointName::LeftHand,
+        JointName::RightHand,
+    ];
+
+    /// The index of this joint, as used by [`JointArray::get`](crate::types::JointArray::get).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::JointName;
+    ///
+    /// assert_eq!(JointName::HeadYaw.index(), 0);
+    /// assert_eq!(JointName::RightHand.index(), 24);
+    /// ```
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The name of the [`JointArray`](crate::types::JointArray) field corresponding to this joint, e.g. `head_yaw`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            JointName::HeadYaw => "head_yaw",
+            JointName::HeadPitch => "head_pitch",
+            JointName::LeftShoulderPitch => "left_shoulder_pitch",
+            JointName::LeftShoulderRoll => "left_shoulder_roll",
+            JointName::LeftElbowYaw => "left_elbow_yaw",
+            JointName::LeftElbowRoll => "left_elbow_roll",
+            JointName::LeftWristYaw => "left_wrist_yaw",
+            JointName::LeftHipYawPitch => "left_hip_yaw_pitch",
+            JointName::LeftHipRoll => "left_hip_roll",
+            JointName::LeftHipPitch => "left_hip_pitch",
+            JointName::LeftKneePitch => "left_knee_pitch",
+            JointName::LeftAnklePitch => "left_ankle_pitch",
+            JointName::LeftAnkleRoll => "left_ankle_roll",
+            JointName::RightShoulderPitch => "right_shoulder_pitch",
+            JointName::RightShoulderRoll => "right_shoulder_roll",
+            JointName::RightElbowYaw => "right_elbow_yaw",
+            JointName::RightElbowRoll => "right_elbow_roll",
+            JointName::RightWristYaw => "right_wrist_yaw",
+            JointName::RightHipRoll => "right_hip_roll",
+            JointName::RightHipPitch => "right_hip_pitch",
+            JointName::RightKneePitch => "right_knee_pitch",
+            JointName::RightAnklePitch => "right_ankle_pitch",
+            JointName::RightAnkleRoll => "right_ankle_roll",
+            JointName::LeftHand => "left_hand",
+            JointName::RightHand => "right_hand",
+        }
+    }
+
+    /// The name `LoLA` uses for this joint, e.g. `HeadYaw` or `LShoulderPitch`.
+    pub const fn lola_name(self) -> &'static str {
+        match self {
+            JointName::HeadYaw => "HeadYaw",
+            JointName::HeadPitch => "HeadPitch",
+            JointName::LeftShoulderPitch => "LShoulderPitch",
+            JointName::LeftShoulderRoll => "LShoulderRoll",
+            JointName::LeftElbowYaw => "LElbowYaw",
+            JointName::LeftElbowRoll => "LElbowRoll",
+            JointName::LeftWristYaw => "LWristYaw",
+            JointName::LeftHipYawPitch => "LHipYawPitch",
+            JointName::LeftHipRoll => "LHipRoll",
+            JointName::LeftHipPitch => "LHipPitch",
+            JointName::LeftKneePitch => "LKneePitch",
+            JointName::LeftAnklePitch => "LAnklePitch",
+            JointName::LeftAnkleRoll => "LAnkleRoll",
+            JointName::RightShoulderPitch => "RShoulderPitch",
+            JointName::RightShoulderRoll => "RShoulderRoll",
+            JointName::RightElbowYaw => "RElbowYaw",
+            JointName::RightElbowRoll => "RElbowRoll",
+            JointName::RightWristYaw => "RWristYaw",
+            JointName::RightHipRoll => "RHipRoll",
+            JointName::RightHipPitch => "RHipPitch",
+            JointName::RightKneePitch => "RKneePitch",
+            JointName::RightAnklePitch => "RAnklePitch",
+            JointName::RightAnkleRoll => "RAnkleRoll",
+            JointName::LeftHand => "LHand",
+            JointName::RightHand => "RHand",
+        }
+    }
+}
+
+/// A group of joints, used to select part of a [`JointArray`](crate::types::JointArray).
+///
+/// The hands are part of their arm, and the shared hip yaw pitch joint is part of the left leg.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::types::{JointName, JointRegion};
+///
+/// assert!(JointRegion::Arms.contains(JointName::RightHand));
+/// assert!(JointRegion::LeftLeg.contains(JointName::LeftHipYawPitch));
+/// assert!(!JointRegion::RightLeg.contains(JointName::LeftHipYawPitch));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JointRegion {
+    Head,
+    LeftArm,
+    RightArm,
+    LeftLeg,
+    RightLeg,
+    Arms,
+    Legs,
+    All,
+}
+
+impl JointRegion {
+    /// Returns `true` if the joint is part of this region.
+    pub const fn contains(self, name: JointName) -> bool {
+        use JointName::*;
+
+        match self {
+            JointRegion::Head => matches!(name, HeadYaw | HeadPitch),
+            JointRegion::LeftArm => matches!(
+                name,
+                LeftShoulderPitch
+                    | LeftShoulderRoll
+                    | LeftElbowYaw
+                    | LeftElbowRoll
+                    | LeftWristYaw
+                    | LeftHand
+            ),
+            JointRegion::RightArm => matches!(
+                name,
+                RightShoulderPitch
+                    | RightShoulderRoll
+                    | RightElbowYaw
+                    | RightElbowRoll
+                    | RightWristYaw
+                    | RightHand
+            ),
+            JointRegion::LeftLeg => matches!(
+                name,
+                LeftHipYawPitch
+                    | LeftHipRoll
+                    | LeftHipPitch
+                    | LeftKneePitch
+                    | LeftAnklePitch
+                    | LeftAnkleRoll
+            ),
+            JointRegion::RightLeg => matches!(
+                name,
+                RightHipRoll | RightHipPitch | RightKneePitch | RightAnklePitch | RightAnkleRoll
+            ),
+            JointRegion::Arms => {
+                JointRegion::LeftArm.contains(name) || JointRegion::RightArm.contains(name)
+            }
+            JointRegion::Legs => {
+                JointRegion::LeftLeg.contains(name) || JointRegion::RightLeg.contains(name)
+            }
+            JointRegion::All => true,
+        }
+    }
+}
+
+impl fmt::Display for JointName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when parsing an unknown [`JointName`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseJointNameError(pub String);
+
+impl fmt::Display for ParseJointNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown joint name `{}`", self.0)
+    }
+}
+
+impl core::error::Error for ParseJointNameError {}
+
+impl FromStr for JointName {
+    type Err = ParseJointNameError;
+
+    /// Parses a joint name, accepting both the field name (`head_yaw`) and the `LoLA` name (`HeadYaw`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        JointName::ALL
+            .into_iter()
+            .find(|name| name.as_str() == s || name.lola_name() == s)
+            .ok_or_else(|| ParseJointNameError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::{JointName, JointRegion, ParseJointNameError};
+
+    #[test]
+    fn test_all_is_ordered_by_index() {
+        for (index, name) in JointName::ALL.iter().enumerate() {
+            assert_eq!(name.index(), index);
+        }
+    }
+
+    #[test]
+    fn test_display_from_str_roundtrip() {
+        for name in JointName::ALL {
+            assert_eq!(name.to_string().parse::<JointName>(), Ok(name));
+            assert_eq!(name.lola_name().parse::<JointName>(), Ok(name));
+        }
+    }
+
+    #[test]
+    fn test_from_str_unknown() {
+        assert_eq!(
+            "LeftTail".parse::<JointName>(),
+            Err(ParseJointNameError("LeftTail".to_string()))
+        );
+        assert!("RHipYawPitch".parse::<JointName>().is_err());
+    }
+
+    #[test]
+    fn test_regions_partition_joints() {
+        let regions = [
+            JointRegion::Head,
+            JointRegion::LeftArm,
+            JointRegion::RightArm,
+            JointRegion::LeftLeg,
+            JointRegion::RightLeg,
+        ];
+
+        for name in JointName::ALL {
+            let count = regions
+                .iter()
+                .filter(|region| region.contains(name))
+                .count();
+            assert_eq!(count, 1, "{name} is in {count} regions");
+            assert!(JointRegion::All.contains(name));
+        }
+    }
+}
@@ -1,10 +1,17 @@
 //! Convenience types used to make interacting with the NAO more convenient.
 //!
 
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use core::marker::PhantomData;
+use core::ops::{Add, Div, Mul, Neg, Sub};
 
 use nidhogg_derive::{Builder, Filler};
 
+// Float rounding is only inherent to `f32` with `std`. The import is unused when another crate of the
+// build links `std`, as the test harness does.
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num::traits::float::FloatCore;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -13,9 +20,20 @@ use bevy_ecs::prelude::Resource;
 
 pub mod color;
 mod joint_array;
+mod joint_name;
+#[cfg(feature = "serde")]
+pub mod named_joints;
+mod region;
+pub mod units;
 
 pub use color::{Rgb, RgbF32, RgbU8};
-pub use joint_array::JointArray;
+#[cfg(feature = "lola")]
+pub(crate) use joint_array::for_each_joint;
+pub use joint_array::{JointArray, JointMask, WrongLength};
+pub use joint_name::{JointName, JointRegion, ParseJointNameError};
+#[cfg(feature = "serde")]
+pub use named_joints::NamedJoints;
+pub use region::JointRegionOps;
 
 /// Trait that introduces the [`fill`](`FillExt::fill`) method for a type, which allows filling in all fields with the same value.
 pub trait FillExt<T> {
@@ -23,6 +41,34 @@ pub trait FillExt<T> {
     fn fill(value: T) -> Self;
 }
 
+/// Fills every region of a tuple, for example to create a preset for the arms and legs at once.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::types::{ArmJoints, FillExt, LegJoints};
+///
+/// let (arms, legs): (ArmJoints<f32>, LegJoints<f32>) = FillExt::fill(0.3);
+///
+/// assert_eq!(arms, ArmJoints::fill(0.3));
+/// assert_eq!(legs, LegJoints::fill(0.3));
+/// ```
+impl<T: Clone, A: FillExt<T>, B: FillExt<T>> FillExt<T> for (A, B) {
+    fn fill(value: T) -> (A, B) {
+        (A::fill(value.clone()), B::fill(value))
+    }
+}
+
+impl<T: Clone, A: FillExt<T>, B: FillExt<T>, C: FillExt<T>> FillExt<T> for (A, B, C) {
+    fn fill(value: T) -> (A, B, C) {
+        (
+            A::fill(value.clone()),
+            B::fill(value.clone()),
+            C::fill(value),
+        )
+    }
+}
+
 /// Struct representing the LEDs on top of the NAO robot's head.
 ///
 /// Each value represents the intensity of a white LED.
@@ -45,48 +91,365 @@ pub struct Skull {
     pub right_rear_2: f32,
 }
 
-/// Struct representing the LED intensities in the left ear of the robot.
+impl Skull {
+    /// Display a fraction in the range `0.0..=1.0` as a progress bar going from the front to the back of the head.
+    ///
+    /// Both sides of the head show the same progress, out-of-range values are clamped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::Skull;
+    ///
+    /// let skull = Skull::from_fraction(0.5);
+    /// assert_eq!(skull.left_middle_0, 1.0);
+    /// assert_eq!(skull.left_rear_0, 0.0);
+    /// ```
+    pub fn from_fraction(fraction: f32) -> Self {
+        let [front_0, front_1, middle_0, rear_0, rear_1, rear_2] = fraction_levels(fraction);
+
+        Skull {
+            left_front_0: front_0,
+            left_front_1: front_1,
+            left_middle_0: middle_0,
+            left_rear_0: rear_0,
+            left_rear_1: rear_1,
+            left_rear_2: rear_2,
+            right_front_0: front_0,
+            right_front_1: front_1,
+            right_middle_0: middle_0,
+            right_rear_0: rear_0,
+            right_rear_1: rear_1,
+            right_rear_2: rear_2,
+        }
+    }
+
+    /// Create a [`Skull`] with all LEDs on the left side of the head set to `intensity`.
+    pub fn left_side(intensity: f32) -> Self {
+        Skull {
+            left_front_0: intensity,
+            left_front_1: intensity,
+            left_middle_0: intensity,
+            left_rear_0: intensity,
+            left_rear_1: intensity,
+            left_rear_2: intensity,
+            ..Default::default()
+        }
+    }
+
+    /// Create a [`Skull`] with all LEDs on the right side of the head set to `intensity`.
+    pub fn right_side(intensity: f32) -> Self {
+        Skull {
+            right_front_0: intensity,
+            right_front_1: intensity,
+            right_middle_0: intensity,
+            right_rear_0: intensity,
+            right_rear_1: intensity,
+            right_rear_2: intensity,
+            ..Default::default()
+        }
+    }
+
+    /// Create a [`Skull`] with the front LEDs on both sides of the head set to `intensity`.
+    pub fn front(intensity: f32) -> Self {
+        Skull {
+            left_front_0: intensity,
+            left_front_1: intensity,
+            right_front_0: intensity,
+            right_front_1: intensity,
+            ..Default::default()
+        }
+    }
+
+    /// Create a [`Skull`] with the rear LEDs on both sides of the head set to `intensity`.
+    pub fn rear(intensity: f32) -> Self {
+        Skull {
+            left_rear_0: intensity,
+            left_rear_1: intensity,
+            left_rear_2: intensity,
+            right_rear_0: intensity,
+            right_rear_1: intensity,
+            right_rear_2: intensity,
+            ..Default::default()
+        }
+    }
+
+    /// Get the LED intensities as an array, in the order used by `LoLA`.
+    ///
+    /// The left side is ordered from front to back, followed by the right side from back to front.
+    pub fn as_array(&self) -> [f32; 12] {
+        [
+            self.left_front_0,
+            self.left_front_1,
+            self.left_middle_0,
+            self.left_rear_0,
+            self.left_rear_1,
+            self.left_rear_2,
+            self.right_rear_2,
+            self.right_rear_1,
+            self.right_rear_0,
+            self.right_middle_0,
+            self.right_front_0,
+            self.right_front_1,
+        ]
+    }
+
+    /// Create a [`Skull`] from an array of intensities, in the order used by `LoLA`.
+    ///
+    /// See [`Skull::as_array`] for the order of the LEDs.
+    pub fn from_array(array: [f32; 12]) -> Self {
+        let [left_front_0, left_front_1, left_middle_0, left_rear_0, left_rear_1, left_rear_2, right_rear_2, right_rear_1, right_rear_0, right_middle_0, right_front_0, right_front_1] =
+            array;
+
+        Skull {
+            left_front_0,
+            left_front_1,
+            left_middle_0,
+            left_rear_0,
+            left_rear_1,
+            left_rear_2,
+            right_front_0,
+            right_front_1,
+            right_middle_0,
+            right_rear_0,
+            right_rear_1,
+            right_rear_2,
+        }
+    }
+
+    /// A dot that moves from the front to the back of the head and back again, on both sides.
+    ///
+    /// `phase` is the position in the cycle, in the range `0.0..1.0`, values outside of this range wrap around.
+    /// In between two LEDs, the intensity is spread over both of them, so the dot moves smoothly.
+    /// This is useful to show that the robot is busy, by increasing the phase over time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::Skull;
+    ///
+    /// assert_eq!(Skull::sweep(0.0).left_front_0, 1.0);
+    /// assert_eq!(Skull::sweep(0.5).right_rear_2, 1.0);
+    /// assert_eq!(Skull::sweep(1.0), Skull::sweep(0.0));
+    /// ```
+    pub fn sweep(phase: f32) -> Self {
+        let phase = rem_euclid(phase, 1.0);
+        // position of the dot, from 0.0 at the front to 5.0 at the back
+        let position = 5.0 * (1.0 - (2.0 * phase - 1.0).abs());
+        let [front_0, front_1, middle_0, rear_0, rear_1, rear_2]: [f32; 6] =
+            core::array::from_fn(|i| (1.0 - (i as f32 - position).abs()).max(0.0));
+
+        Skull {
+            left_front_0: front_0,
+            left_front_1: front_1,
+            left_middle_0: middle_0,
+            left_rear_0: rear_0,
+            left_rear_1: rear_1,
+            left_rear_2: rear_2,
+            right_front_0: front_0,
+            right_front_1: front_1,
+            right_middle_0: middle_0,
+            right_rear_0: rear_0,
+            right_rear_1: rear_1,
+            right_rear_2: rear_2,
+        }
+    }
+}
+
+/// Marker trait for the side of the head an [`Ear`] or [`Eye`] is on.
 ///
-/// ## LED order:
-/// These LEDs are placed in the following order:
+/// This is implemented by [`Left`] and [`Right`], and contains the side-specific order
+/// in which `LoLA` expects the LEDs.
+pub trait Side:
+    Clone + Copy + core::fmt::Debug + Default + PartialEq + Send + Sync + 'static
+{
+    /// The side on the other half of the head.
+    type Mirror: Side<Mirror = Self>;
+
+    /// For every `LoLA` index of an ear, the index of the LED that is sent at that position.
+    const EAR_LOLA_ORDER: [usize; 10];
+
+    /// For every `LoLA` index of an eye color channel, the index of the LED that is sent at that position.
+    const EYE_LOLA_ORDER: [usize; 8];
+}
+
+/// Marker type for the left side of the head.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Left;
+
+/// Marker type for the right side of the head.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Right;
+
+impl Side for Left {
+    type Mirror = Right;
+
+    const EAR_LOLA_ORDER: [usize; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+    const EYE_LOLA_ORDER: [usize; 8] = [7, 0, 1, 2, 3, 4, 5, 6];
+}
+
+impl Side for Right {
+    type Mirror = Left;
+
+    const EAR_LOLA_ORDER: [usize; 10] = [9, 8, 7, 6, 5, 4, 3, 2, 1, 0];
+    const EYE_LOLA_ORDER: [usize; 8] = [0, 7, 6, 5, 4, 3, 2, 1];
+}
+
+/// Struct representing the LED intensities in an ear of the robot.
 ///
-/// ![Left Ear](https://cdn.dutchnao.team/nidhogg/hardware_led_left_ear.png)
+/// Use the [`LeftEar`] and [`RightEar`] aliases to refer to a specific ear.
 #[derive(Builder, Clone, Debug, Default, Filler, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Resource))]
-pub struct LeftEar {
-    pub l0: f32,
-    pub l1: f32,
-    pub l2: f32,
-    pub l3: f32,
-    pub l4: f32,
-    pub l5: f32,
-    pub l6: f32,
-    pub l7: f32,
-    pub l8: f32,
-    pub l9: f32,
+pub struct Ear<S: Side> {
+    pub led0: f32,
+    pub led1: f32,
+    pub led2: f32,
+    pub led3: f32,
+    pub led4: f32,
+    pub led5: f32,
+    pub led6: f32,
+    pub led7: f32,
+    pub led8: f32,
+    pub led9: f32,
+    /// Marker for the side of the head, this is always [`PhantomData`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _marker: PhantomData<S>,
 }
 
+/// Struct representing the LED intensities in the left ear of the robot.
+///
+/// ## LED order:
+/// These LEDs are placed in the following order:
+///
+/// ![Left Ear](https://cdn.dutchnao.team/nidhogg/hardware_led_left_ear.png)
+pub type LeftEar = Ear<Left>;
+
 /// Struct representing the LED intensities in the right ear of the robot.
 ///
 /// ## LED order:
 /// These LEDs are placed in the following order:
 ///
 /// ![Right Ear](https://cdn.dutchnao.team/nidhogg/hardware_led_right_ear.png)
+pub type RightEar = Ear<Right>;
+
+impl<S: Side> Ear<S> {
+    /// Display a fraction in the range `0.0..=1.0` on the ear, starting at `led0`.
+    ///
+    /// The LEDs are lit fully up to the fraction, the next LED is lit partially
+    /// with the remainder. Out-of-range values are clamped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::LeftEar;
+    ///
+    /// let ear = LeftEar::from_fraction(0.25);
+    /// assert_eq!(ear.led1, 1.0);
+    /// assert_eq!(ear.led2, 0.5);
+    /// assert_eq!(ear.led3, 0.0);
+    /// ```
+    pub fn from_fraction(fraction: f32) -> Self {
+        Self::from_array(fraction_levels(fraction))
+    }
+
+    /// Get the LED intensities as an array, ordered from `led0` to `led9`.
+    pub fn as_array(&self) -> [f32; 10] {
+        [
+            self.led0, self.led1, self.led2, self.led3, self.led4, self.led5, self.led6, self.led7,
+            self.led8, self.led9,
+        ]
+    }
+
+    /// Create an [`Ear`] from an array of intensities, ordered from `led0` to `led9`.
+    pub fn from_array(array: [f32; 10]) -> Self {
+        let [led0, led1, led2, led3, led4, led5, led6, led7, led8, led9] = array;
+
+        Ear {
+            led0,
+            led1,
+            led2,
+            led3,
+            led4,
+            led5,
+            led6,
+            led7,
+            led8,
+            led9,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Rotate the pattern by the provided number of LEDs.
+    ///
+    /// Positive steps rotate clockwise as seen from outside the robot, negative steps counter-clockwise.
+    #[must_use]
+    pub fn rotated(self, steps: i32) -> Self {
+        Self::from_array(rotate_segments(self.as_array(), steps))
+    }
+
+    /// Convert this pattern into the mirrored pattern on the other ear.
+    ///
+    /// Each LED maps to the LED of the other ear at the same `LoLA` index.
+    pub fn mirror(self) -> Ear<S::Mirror> {
+        let mut array = self.as_array();
+        array.reverse();
+        Ear::from_array(array)
+    }
+}
+
+/// Spread a fraction over `N` LEDs, lighting the last LED partially with the remainder.
+fn fraction_levels<const N: usize>(fraction: f32) -> [f32; N] {
+    let level = fraction.clamp(0.0, 1.0) * N as f32;
+
+    core::array::from_fn(|i| (level - i as f32).clamp(0.0, 1.0))
+}
+
+/// The least non-negative remainder of `value / rhs`, like [`f32::rem_euclid`] which requires `std`.
+fn rem_euclid(value: f32, rhs: f32) -> f32 {
+    let remainder = value % rhs;
+    if remainder < 0.0 {
+        remainder + rhs.abs()
+    } else {
+        remainder
+    }
+}
+
+/// Struct representing the RGB LEDs in an eye of the robot.
+///
+/// Use the [`LeftEye`] and [`RightEye`] aliases to refer to a specific eye.
+///
+/// ## Segment angles:
+/// The NAO documentation names the segments by their angle, in steps of 45 degrees from `0°` at the top.
+/// The eyes are mirror images, so on both eyes the angle increases towards the nose.
+/// The methods [`Eye::get_at_angle`], [`Eye::set_at_angle`] and [`Eye::iter_segments`] address
+/// the segments by angle, using the following layout:
+///
+/// | Angle  | [`LeftEye`] | [`RightEye`] |
+/// |--------|-------------|--------------|
+/// | `0°`   | `led7`      | `led0`       |
+/// | `45°`  | `led0`      | `led7`       |
+/// | `90°`  | `led1`      | `led6`       |
+/// | `135°` | `led2`      | `led5`       |
+/// | `180°` | `led3`      | `led4`       |
+/// | `225°` | `led4`      | `led3`       |
+/// | `270°` | `led5`      | `led2`       |
+/// | `315°` | `led6`      | `led1`       |
 #[derive(Builder, Clone, Debug, Default, Filler, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Resource))]
-pub struct RightEar {
-    pub r0: f32,
-    pub r1: f32,
-    pub r2: f32,
-    pub r3: f32,
-    pub r4: f32,
-    pub r5: f32,
-    pub r6: f32,
-    pub r7: f32,
-    pub r8: f32,
-    pub r9: f32,
+pub struct Eye<S: Side> {
+    pub led0: RgbF32,
+    pub led1: RgbF32,
+    pub led2: RgbF32,
+    pub led3: RgbF32,
+    pub led4: RgbF32,
+    pub led5: RgbF32,
+    pub led6: RgbF32,
+    pub led7: RgbF32,
+    /// Marker for the side of the head, this is always [`PhantomData`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _marker: PhantomData<S>,
 }
 
 /// Struct representing the RGB LEDs in the left eye of the robot.
@@ -94,37 +457,164 @@ pub struct RightEar {
 /// These LEDs are placed in the following order:
 ///
 /// ![Left Eye](https://cdn.dutchnao.team/nidhogg/hardware_led_left_eye.png)
-#[derive(Builder, Clone, Debug, Default, Filler, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "bevy", derive(Resource))]
-pub struct LeftEye {
-    pub l0: RgbF32,
-    pub l1: RgbF32,
-    pub l2: RgbF32,
-    pub l3: RgbF32,
-    pub l4: RgbF32,
-    pub l5: RgbF32,
-    pub l6: RgbF32,
-    pub l7: RgbF32,
-}
+pub type LeftEye = Eye<Left>;
 
-/// Struct representing the RGB LEDs in the left eye of the robot.
+/// Struct representing the RGB LEDs in the right eye of the robot.
 /// ## LED order:
 /// These LEDs are placed in the following order:
 ///
 /// ![Right Eye](https://cdn.dutchnao.team/nidhogg/hardware_led_right_eye.png)
-#[derive(Builder, Clone, Debug, Default, Filler, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[cfg_attr(feature = "bevy", derive(Resource))]
-pub struct RightEye {
-    pub r0: RgbF32,
-    pub r1: RgbF32,
-    pub r2: RgbF32,
-    pub r3: RgbF32,
-    pub r4: RgbF32,
-    pub r5: RgbF32,
-    pub r6: RgbF32,
-    pub r7: RgbF32,
+pub type RightEye = Eye<Right>;
+
+impl<S: Side> Eye<S> {
+    /// Get the LED colors as an array, ordered from `led0` to `led7`.
+    pub fn as_array(&self) -> [RgbF32; 8] {
+        [
+            self.led0, self.led1, self.led2, self.led3, self.led4, self.led5, self.led6, self.led7,
+        ]
+    }
+
+    /// Create an [`Eye`] from an array of colors, ordered from `led0` to `led7`.
+    pub fn from_array(array: [RgbF32; 8]) -> Self {
+        let [led0, led1, led2, led3, led4, led5, led6, led7] = array;
+
+        Eye {
+            led0,
+            led1,
+            led2,
+            led3,
+            led4,
+            led5,
+            led6,
+            led7,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Rotate the pattern by the provided number of LEDs.
+    ///
+    /// Positive steps rotate clockwise as seen from outside the robot, negative steps counter-clockwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{color, LeftEye};
+    ///
+    /// let eye = LeftEye { led0: color::f32::RED, ..Default::default() };
+    /// assert_eq!(eye.clone().rotated(1).led7, color::f32::RED);
+    /// assert_eq!(eye.clone().rotated(8), eye);
+    /// ```
+    #[must_use]
+    pub fn rotated(self, steps: i32) -> Self {
+        Self::from_array(rotate_segments(self.as_array(), steps))
+    }
+
+    /// Convert this pattern into the mirrored pattern on the other eye.
+    ///
+    /// Each LED maps to the LED of the other eye at the same `LoLA` index.
+    pub fn mirror(self) -> Eye<S::Mirror> {
+        let mut array = self.as_array();
+        array.reverse();
+        Eye::from_array(array)
+    }
+
+    /// Get the color of the segment nearest to the provided angle in degrees.
+    ///
+    /// See the [segment angles](Eye#segment-angles) for the layout, angles outside of `0.0..360.0` wrap around.
+    pub fn get_at_angle(&self, degrees: f32) -> RgbF32 {
+        *self.led(segment_at_angle::<S>(degrees))
+    }
+
+    /// Set the color of the segment nearest to the provided angle in degrees.
+    ///
+    /// See the [segment angles](Eye#segment-angles) for the layout, angles outside of `0.0..360.0` wrap around.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{color, LeftEye, RightEye};
+    ///
+    /// // light the segments pointing to the left of the robot, towards a ball
+    /// let mut left = LeftEye::default();
+    /// left.set_at_angle(280.0, color::f32::ORANGE);
+    /// let mut right = RightEye::default();
+    /// right.set_at_angle(80.0, color::f32::ORANGE);
+    ///
+    /// assert_eq!(left.led5, color::f32::ORANGE);
+    /// assert_eq!(right.led6, color::f32::ORANGE);
+    /// ```
+    pub fn set_at_angle(&mut self, degrees: f32, color: RgbF32) {
+        *self.led_mut(segment_at_angle::<S>(degrees)) = color;
+    }
+
+    /// Iterate over the segments with their angle in degrees, starting at `0°` at the top.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::{color, LeftEye};
+    ///
+    /// let mut eye = LeftEye::default();
+    /// eye.led0 = color::f32::BLUE;
+    ///
+    /// let lit: Vec<f32> = eye
+    ///     .iter_segments()
+    ///     .filter(|(_, color)| **color == color::f32::BLUE)
+    ///     .map(|(degrees, _)| degrees)
+    ///     .collect();
+    /// assert_eq!(lit, [45.0]);
+    /// ```
+    pub fn iter_segments(&self) -> impl Iterator<Item = (f32, &RgbF32)> {
+        S::EYE_LOLA_ORDER
+            .iter()
+            .enumerate()
+            .map(|(segment, led)| (segment as f32 * EYE_SEGMENT_DEGREES, self.led(*led)))
+    }
+
+    fn led(&self, index: usize) -> &RgbF32 {
+        match index {
+            0 => &self.led0,
+            1 => &self.led1,
+            2 => &self.led2,
+            3 => &self.led3,
+            4 => &self.led4,
+            5 => &self.led5,
+            6 => &self.led6,
+            _ => &self.led7,
+        }
+    }
+
+    fn led_mut(&mut self, index: usize) -> &mut RgbF32 {
+        match index {
+            0 => &mut self.led0,
+            1 => &mut self.led1,
+            2 => &mut self.led2,
+            3 => &mut self.led3,
+            4 => &mut self.led4,
+            5 => &mut self.led5,
+            6 => &mut self.led6,
+            _ => &mut self.led7,
+        }
+    }
+}
+
+/// Angle between two segments of an eye.
+const EYE_SEGMENT_DEGREES: f32 = 45.0;
+
+/// Index of the LED of the segment nearest to `degrees`.
+///
+/// The segments are numbered by angle in the same order as `LoLA` expects them.
+fn segment_at_angle<S: Side>(degrees: f32) -> usize {
+    let segment = rem_euclid((degrees / EYE_SEGMENT_DEGREES).round(), 8.0) as usize;
+    S::EYE_LOLA_ORDER[segment % 8]
+}
+
+/// Rotate the segments of an eye or ear clockwise by `steps`.
+///
+/// The segment indices of both eyes and ears increase counter-clockwise, as seen from outside the robot.
+fn rotate_segments<T, const N: usize>(mut segments: [T; N], steps: i32) -> [T; N] {
+    segments.rotate_left(steps.rem_euclid(N as i32) as usize);
+    segments
 }
 
 /// Struct representing the battery status of the robot.
@@ -440,45 +930,384 @@ pub struct SonarValues {
     pub right: f32,
 }
 
-/// Enabled state of the left and right sonar sensors.
-#[derive(Builder, Clone, Default, Debug, PartialEq, Eq)]
+#[allow(deprecated)]
+pub use sonar_enabled::{SonarEnabled, SonarEnabledBuilder};
+
+// the derives use the deprecated struct, which is allowed in this module only
+#[allow(deprecated)]
+mod sonar_enabled {
+    use super::*;
+
+    /// Enabled state of the left and right sonar sensors.
+    ///
+    /// This only covers a subset of the [`SonarMode`]s, and converts into the equivalent mode.
+    ///
+    /// The default is [`SonarEnabled::BOTH_ON`], like the default [`SonarMode::Both`].
+    #[deprecated(note = "use `SonarMode` instead")]
+    #[derive(Builder, Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "bevy", derive(Resource))]
+    pub struct SonarEnabled {
+        pub left: bool,
+        pub right: bool,
+    }
+
+    impl SonarEnabled {
+        /// Both sonar sensors are enabled.
+        pub const BOTH_ON: Self = Self {
+            left: true,
+            right: true,
+        };
+
+        /// Both sonar sensors are disabled.
+        pub const BOTH_OFF: Self = Self {
+            left: false,
+            right: false,
+        };
+    }
+
+    impl Default for SonarEnabled {
+        fn default() -> Self {
+            Self::BOTH_ON
+        }
+    }
+}
+
+/// The emitters used by the sonar sensors.
+///
+/// `LoLA` has one actuator value per emitter, which enables the left and the right emitter:
+///
+/// | Mode | Left | Right |
+/// |-|-|-|
+/// | [`SonarMode::Off`] | `false` | `false` |
+/// | [`SonarMode::LeftOnly`] | `true` | `false` |
+/// | [`SonarMode::RightOnly`] | `false` | `true` |
+/// | [`SonarMode::Both`] | `true` | `true` |
+/// | [`SonarMode::Alternating`] | alternating | alternating |
+///
+/// Firing one emitter at a time avoids the two sonars picking up each other's echo.
+///
+/// The default is [`SonarMode::Both`], which matches `LoLA` after power-on.
+/// With the emitters disabled, the [`SonarValues`] are not updated and should not be used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Resource))]
-pub struct SonarEnabled {
-    pub left: bool,
-    pub right: bool,
+pub enum SonarMode {
+    /// Both emitters are disabled.
+    Off,
+    /// Only the left emitter is enabled.
+    LeftOnly,
+    /// Only the right emitter is enabled.
+    RightOnly,
+    /// Both emitters are enabled.
+    #[default]
+    Both,
+    /// The emitters take turns, starting with the left emitter and switching every `period_frames` frames.
+    ///
+    /// A period of `0` switches every frame, like a period of `1`.
+    Alternating { period_frames: u32 },
+}
+
+impl SonarMode {
+    /// Returns whether the left and right emitter are enabled in the provided control frame.
+    ///
+    /// Only [`SonarMode::Alternating`] depends on the frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::SonarMode;
+    ///
+    /// let mode = SonarMode::Alternating { period_frames: 2 };
+    /// assert_eq!(mode.emitters(1), [true, false]);
+    /// assert_eq!(mode.emitters(2), [false, true]);
+    /// ```
+    pub fn emitters(&self, frame: u64) -> [bool; 2] {
+        match *self {
+            SonarMode::Off => [false, false],
+            SonarMode::LeftOnly => [true, false],
+            SonarMode::RightOnly => [false, true],
+            SonarMode::Both => [true, true],
+            SonarMode::Alternating { period_frames } => {
+                let left = frame / u64::from(period_frames.max(1)) % 2 == 0;
+                [left, !left]
+            }
+        }
+    }
+
+    /// Returns the mode that enables the provided emitters, which never is [`SonarMode::Alternating`].
+    pub fn from_emitters(left: bool, right: bool) -> Self {
+        match (left, right) {
+            (false, false) => SonarMode::Off,
+            (true, false) => SonarMode::LeftOnly,
+            (false, true) => SonarMode::RightOnly,
+            (true, true) => SonarMode::Both,
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl From<SonarEnabled> for SonarMode {
+    fn from(value: SonarEnabled) -> Self {
+        SonarMode::from_emitters(value.left, value.right)
+    }
 }
 
 /// Struct containing the touch activation value for each touch sensor on the robot.
 #[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "bevy", derive(Resource))]
-pub struct Touch {
-    pub chest_board: f32,
-    pub head_front: f32,
-    pub head_middle: f32,
-    pub head_rear: f32,
-    pub left_foot_left: f32,
-    pub left_foot_right: f32,
-    pub left_hand_back: f32,
-    pub left_hand_left: f32,
-    pub left_hand_right: f32,
-    pub right_foot_left: f32,
-    pub right_foot_right: f32,
-    pub right_hand_back: f32,
-    pub right_hand_left: f32,
-    pub right_hand_right: f32,
+pub struct Touch<T = f32> {
+    pub chest_board: T,
+    pub head_front: T,
+    pub head_middle: T,
+    pub head_rear: T,
+    pub left_foot_left: T,
+    pub left_foot_right: T,
+    pub left_hand_back: T,
+    pub left_hand_left: T,
+    pub left_hand_right: T,
+    pub right_foot_left: T,
+    pub right_foot_right: T,
+    pub right_hand_back: T,
+    pub right_hand_left: T,
+    pub right_hand_right: T,
+}
+
+impl<T: Copy> Touch<T> {
+    /// The touch values of the bumpers on the left foot.
+    pub fn left_foot(&self) -> FootBumper<T> {
+        FootBumper {
+            left: self.left_foot_left,
+            right: self.left_foot_right,
+        }
+    }
+
+    /// The touch values of the bumpers on the right foot.
+    pub fn right_foot(&self) -> FootBumper<T> {
+        FootBumper {
+            left: self.right_foot_left,
+            right: self.right_foot_right,
+        }
+    }
+
+    /// The touch values of the sensors on the left hand.
+    pub fn left_hand(&self) -> HandTouch<T> {
+        HandTouch {
+            back: self.left_hand_back,
+            left: self.left_hand_left,
+            right: self.left_hand_right,
+        }
+    }
+
+    /// The touch values of the sensors on the right hand.
+    pub fn right_hand(&self) -> HandTouch<T> {
+        HandTouch {
+            back: self.right_hand_back,
+            left: self.right_hand_left,
+            right: self.right_hand_right,
+        }
+    }
+
+    /// The touch values of the sensors on the head.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::Touch;
+    ///
+    /// let touch = Touch { head_middle: 1.0, ..Default::default() };
+    ///
+    /// assert!(touch.head().any_pressed(0.5));
+    /// assert!(!touch.left_foot().any_pressed(0.5));
+    /// ```
+    pub fn head(&self) -> HeadTouch<T> {
+        HeadTouch {
+            front: self.head_front,
+            middle: self.head_middle,
+            rear: self.head_rear,
+        }
+    }
+}
+
+impl<T> Touch<T> {
+    /// Transforms each element in the [`Touch`] using the provided closure `f`,
+    /// producing a new [`Touch`] with the transformed values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::Touch;
+    ///
+    /// let touch = Touch { chest_board: 0.8, ..Default::default() };
+    ///
+    /// let pressed = touch.map(|value| value > 0.5);
+    ///
+    /// assert!(pressed.chest_board);
+    /// assert!(!pressed.head_front);
+    /// ```
+    pub fn map<F, U>(self, mut f: F) -> Touch<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Touch {
+            chest_board: f(self.chest_board),
+            head_front: f(self.head_front),
+            head_middle: f(self.head_middle),
+            head_rear: f(self.head_rear),
+            left_foot_left: f(self.left_foot_left),
+            left_foot_right: f(self.left_foot_right),
+            left_hand_back: f(self.left_hand_back),
+            left_hand_left: f(self.left_hand_left),
+            left_hand_right: f(self.left_hand_right),
+            right_foot_left: f(self.right_foot_left),
+            right_foot_right: f(self.right_foot_right),
+            right_hand_back: f(self.right_hand_back),
+            right_hand_left: f(self.right_hand_left),
+            right_hand_right: f(self.right_hand_right),
+        }
+    }
+
+    /// Zips two [`Touch`] instances element-wise, creating a new [`Touch`]
+    /// containing tuples of corresponding elements from the two instances.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::Touch;
+    ///
+    /// let previous = Touch::<f32>::default();
+    /// let current = Touch { chest_board: 1.0, ..Default::default() };
+    ///
+    /// let rising = previous.zip(current).map(|(previous, current)| previous < 0.5 && current >= 0.5);
+    ///
+    /// assert!(rising.chest_board);
+    /// ```
+    pub fn zip<U>(self, other: Touch<U>) -> Touch<(T, U)> {
+        Touch {
+            chest_board: (self.chest_board, other.chest_board),
+            head_front: (self.head_front, other.head_front),
+            head_middle: (self.head_middle, other.head_middle),
+            head_rear: (self.head_rear, other.head_rear),
+            left_foot_left: (self.left_foot_left, other.left_foot_left),
+            left_foot_right: (self.left_foot_right, other.left_foot_right),
+            left_hand_back: (self.left_hand_back, other.left_hand_back),
+            left_hand_left: (self.left_hand_left, other.left_hand_left),
+            left_hand_right: (self.left_hand_right, other.left_hand_right),
+            right_foot_left: (self.right_foot_left, other.right_foot_left),
+            right_foot_right: (self.right_foot_right, other.right_foot_right),
+            right_hand_back: (self.right_hand_back, other.right_hand_back),
+            right_hand_left: (self.right_hand_left, other.right_hand_left),
+            right_hand_right: (self.right_hand_right, other.right_hand_right),
+        }
+    }
+}
+
+/// Touch values of the two bumpers on the front of a foot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FootBumper<T = f32> {
+    pub left: T,
+    pub right: T,
+}
+
+impl<T: PartialOrd> FootBumper<T> {
+    /// Returns `true` if any of the bumpers is above the provided `threshold`.
+    pub fn any_pressed(&self, threshold: T) -> bool {
+        self.left > threshold || self.right > threshold
+    }
+}
+
+/// Touch values of the three tactile sensors on a hand.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HandTouch<T = f32> {
+    pub back: T,
+    pub left: T,
+    pub right: T,
+}
+
+impl<T: PartialOrd> HandTouch<T> {
+    /// Returns `true` if any of the hand sensors is above the provided `threshold`.
+    pub fn any_pressed(&self, threshold: T) -> bool {
+        self.back > threshold || self.left > threshold || self.right > threshold
+    }
+}
+
+/// Touch values of the three tactile sensors on the head.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HeadTouch<T = f32> {
+    pub front: T,
+    pub middle: T,
+    pub rear: T,
+}
+
+impl<T: PartialOrd> HeadTouch<T> {
+    /// Returns `true` if any of the head sensors is above the provided `threshold`.
+    pub fn any_pressed(&self, threshold: T) -> bool {
+        self.front > threshold || self.middle > threshold || self.rear > threshold
+    }
 }
 
 /// Wrapper struct containing the head joints of the robot.
 #[derive(Builder, Clone, Debug, Default, Filler, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct HeadJoints<T> {
     pub yaw: T,
     pub pitch: T,
 }
 
+impl<T> HeadJoints<T> {
+    /// Transforms each element in the [`HeadJoints`] using the provided closure `f`,
+    /// producing a new [`HeadJoints`] with the transformed values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::HeadJoints;
+    /// use nidhogg::types::FillExt;
+    ///
+    /// let joints = HeadJoints::<u32>::default();
+    ///
+    /// let transformed = joints.map(|x| x + 1);
+    ///
+    /// assert_eq!(transformed, HeadJoints::fill(1));
+    /// ```
+    pub fn map<F, U>(self, mut f: F) -> HeadJoints<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        HeadJoints {
+            yaw: f(self.yaw),
+            pitch: f(self.pitch),
+        }
+    }
+
+    /// Zips two [`HeadJoints`] instances element-wise, creating a new [`HeadJoints`]
+    /// containing tuples of corresponding elements from the two arrays.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nidhogg::types::HeadJoints;
+    /// use nidhogg::types::FillExt;
+    ///
+    /// let zipped = HeadJoints::<u32>::default().zip(HeadJoints::<u32>::default());
+    ///
+    /// assert_eq!(zipped, HeadJoints::<(u32, u32)>::fill((0_u32, 0_u32)));
+    /// ```
+    pub fn zip<U>(self, other: HeadJoints<U>) -> HeadJoints<(T, U)> {
+        HeadJoints {
+            yaw: (self.yaw, other.yaw),
+            pitch: (self.pitch, other.pitch),
+        }
+    }
+}
+
 /// Wrapper struct containing the left leg joints of the robot.
 #[derive(Builder, Clone, Debug, Default, Filler, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LeftLegJoints<T> {
     pub hip_yaw_pitch: T,
     pub hip_roll: T,
@@ -545,6 +1374,7 @@ impl<T> LeftLegJoints<T> {
 
 /// Wrapper struct containing right left leg joints of the robot.
 #[derive(Builder, Clone, Debug, Default, Filler, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RightLegJoints<T> {
     // This value does not exist
     // pub hip_yaw_pitch: T,
@@ -610,6 +1440,7 @@ impl<T> RightLegJoints<T> {
 
 /// Wrapper struct containing joint values for both legs of the robot.
 #[derive(Builder, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LegJoints<T> {
     pub left_leg: LeftLegJoints<T>,
     pub right_leg: RightLegJoints<T>,
@@ -666,13 +1497,14 @@ impl<T: Clone> FillExt<T> for LegJoints<T> {
     fn fill(value: T) -> LegJoints<T> {
         LegJoints {
             left_leg: LeftLegJoints::fill(value.clone()),
-            right_leg: RightLegJoints::fill(value.clone()),
+            right_leg: RightLegJoints::fill(value),
         }
     }
 }
 
 /// Wrapper struct containing the joints for a single arm of the robot.
 #[derive(Builder, Clone, Debug, Default, Filler, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SingleArmJoints<T> {
     pub shoulder_pitch: T,
     pub shoulder_roll: T,
@@ -690,10 +1522,13 @@ impl<T> SingleArmJoints<T> {
     ///
     /// ```
     /// use nidhogg::types::SingleArmJoints;
+    /// use nidhogg::types::FillExt;
     ///
     /// let joints = SingleArmJoints::<u32>::default();
     ///
     /// let transformed = joints.map(|x| x + 1);
+    ///
+    /// assert_eq!(transformed, SingleArmJoints::fill(1));
     /// ```
     pub fn map<F, U>(self, mut f: F) -> SingleArmJoints<U>
     where
@@ -744,6 +1579,7 @@ pub type RightArmJoints<T> = SingleArmJoints<T>;
 
 /// Wrapper struct containing the arm joints of the robot.
 #[derive(Builder, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ArmJoints<T> {
     pub left_arm: SingleArmJoints<T>,
     pub right_arm: SingleArmJoints<T>,
@@ -757,10 +1593,13 @@ impl<T> ArmJoints<T> {
     ///
     /// ```
     /// use nidhogg::types::ArmJoints;
+    /// use nidhogg::types::FillExt;
     ///
     /// let joints = ArmJoints::<u32>::default();
     ///
     /// let transformed = joints.map(|x| x + 1);
+    ///
+    /// assert_eq!(transformed, ArmJoints::fill(1));
     /// ```
     pub fn map<F, U>(self, mut f: F) -> ArmJoints<U>
     where
@@ -797,15 +1636,131 @@ impl<T: Clone> FillExt<T> for ArmJoints<T> {
     fn fill(value: T) -> ArmJoints<T> {
         ArmJoints {
             left_arm: LeftArmJoints::fill(value.clone()),
-            right_arm: RightArmJoints::fill(value.clone()),
+            right_arm: RightArmJoints::fill(value),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::{vec, vec::Vec};
+
     use super::*;
-    use crate::types::{FillExt, LeftEye, RgbF32};
+    use crate::types::{FillExt, LeftEar, LeftEye, RgbF32, RightEar, Skull};
+
+    fn indexed_touch() -> Touch {
+        Touch {
+            chest_board: 0.0,
+            head_front: 1.0,
+            head_middle: 2.0,
+            head_rear: 3.0,
+            left_foot_left: 4.0,
+            left_foot_right: 5.0,
+            left_hand_back: 6.0,
+            left_hand_left: 7.0,
+            left_hand_right: 8.0,
+            right_foot_left: 9.0,
+            right_foot_right: 10.0,
+            right_hand_back: 11.0,
+            right_hand_left: 12.0,
+            right_hand_right: 13.0,
+        }
+    }
+
+    #[test]
+    fn test_touch_groups() {
+        let touch = indexed_touch();
+
+        assert_eq!(
+            touch.left_foot(),
+            FootBumper {
+                left: 4.0,
+                right: 5.0
+            }
+        );
+        assert_eq!(
+            touch.right_foot(),
+            FootBumper {
+                left: 9.0,
+                right: 10.0
+            }
+        );
+        assert_eq!(
+            touch.left_hand(),
+            HandTouch {
+                back: 6.0,
+                left: 7.0,
+                right: 8.0
+            }
+        );
+        assert_eq!(
+            touch.right_hand(),
+            HandTouch {
+                back: 11.0,
+                left: 12.0,
+                right: 13.0
+            }
+        );
+        assert_eq!(
+            touch.head(),
+            HeadTouch {
+                front: 1.0,
+                middle: 2.0,
+                rear: 3.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_touch_any_pressed() {
+        let touch = Touch {
+            right_foot_right: 1.0,
+            left_hand_back: 0.6,
+            ..Default::default()
+        };
+
+        assert!(!touch.left_foot().any_pressed(0.5));
+        assert!(touch.right_foot().any_pressed(0.5));
+        assert!(touch.left_hand().any_pressed(0.5));
+        assert!(!touch.left_hand().any_pressed(0.7));
+        assert!(!touch.right_hand().any_pressed(0.5));
+        assert!(!touch.head().any_pressed(0.5));
+    }
+
+    #[test]
+    fn test_touch_map_zip() {
+        let touch = indexed_touch();
+
+        let doubled = touch.clone().map(|value| value * 2.0);
+        assert_eq!(doubled.left_hand_right, 16.0);
+
+        let zipped = touch.zip(doubled);
+        assert_eq!(
+            zipped.right_foot(),
+            FootBumper {
+                left: (9.0, 18.0),
+                right: (10.0, 20.0)
+            }
+        );
+        assert_eq!(zipped.chest_board, (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_nested_map_shares_closure() {
+        // a stateful closure is passed by value, and sees the left leg before the right leg
+        let mut count = 0;
+        let legs = LegJoints::fill(()).map(|()| {
+            count += 1;
+            count
+        });
+
+        assert_eq!(legs.left_leg.hip_yaw_pitch, 1);
+        assert_eq!(legs.left_leg.ankle_roll, 6);
+        assert_eq!(legs.right_leg.ankle_roll, 11);
+
+        let arms = ArmJoints::fill(1.0).map(f32::to_radians);
+        assert_eq!(arms.right_arm.hand, 1.0_f32.to_radians());
+    }
 
     #[test]
     fn test_average_force_feet() {
@@ -863,11 +1818,216 @@ mod tests {
         assert_eq!(color.blue, 255);
     }
 
+    fn lit_left_ear(ear: &LeftEar) -> Vec<f32> {
+        [
+            ear.led0, ear.led1, ear.led2, ear.led3, ear.led4, ear.led5, ear.led6, ear.led7,
+            ear.led8, ear.led9,
+        ]
+        .into_iter()
+        .filter(|&x| x > 0.0)
+        .collect()
+    }
+
+    #[test]
+    fn test_ear_from_fraction() {
+        assert_eq!(LeftEar::from_fraction(0.0), LeftEar::default());
+        assert_eq!(LeftEar::from_fraction(1.0), LeftEar::fill(1.0));
+        assert_eq!(RightEar::from_fraction(1.0), RightEar::fill(1.0));
+
+        let half = LeftEar::from_fraction(0.5);
+        assert_eq!(lit_left_ear(&half), vec![1.0; 5]);
+        assert_eq!(half.led4, 1.0);
+
+        let right = RightEar::from_fraction(0.5);
+        assert_eq!((right.led4, right.led5), (1.0, 0.0));
+
+        let partial = LeftEar::from_fraction(0.35);
+        assert_eq!(lit_left_ear(&partial).len(), 4);
+        assert!((partial.led3 - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_ear_from_fraction_out_of_range() {
+        assert_eq!(LeftEar::from_fraction(-0.5), LeftEar::default());
+        assert_eq!(
+            LeftEar::from_fraction(f32::NEG_INFINITY),
+            LeftEar::default()
+        );
+        assert_eq!(LeftEar::from_fraction(3.0), LeftEar::fill(1.0));
+        assert_eq!(RightEar::from_fraction(1.5), RightEar::fill(1.0));
+    }
+
+    #[test]
+    fn test_skull_from_fraction() {
+        assert_eq!(Skull::from_fraction(0.0), Skull::default());
+        assert_eq!(Skull::from_fraction(1.0), Skull::fill(1.0));
+        assert_eq!(Skull::from_fraction(2.0), Skull::fill(1.0));
+
+        let half = Skull::from_fraction(0.5);
+        assert_eq!(
+            half,
+            Skull {
+                left_front_0: 1.0,
+                left_front_1: 1.0,
+                left_middle_0: 1.0,
+                right_front_0: 1.0,
+                right_front_1: 1.0,
+                right_middle_0: 1.0,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_skull_regions() {
+        let left = Skull::left_side(0.5);
+        let right = Skull::right_side(0.5);
+        assert_eq!(left.as_array()[..6], [0.5; 6]);
+        assert_eq!(left.as_array()[6..], [0.0; 6]);
+        assert_eq!(right.as_array()[6..], [0.5; 6]);
+
+        let front = Skull::front(1.0);
+        let rear = Skull::rear(1.0);
+        assert_eq!(front.left_front_1, 1.0);
+        assert_eq!(front.right_front_0, 1.0);
+        assert_eq!(front.left_middle_0, 0.0);
+        assert_eq!(rear.right_rear_2, 1.0);
+        assert_eq!(rear.left_front_0, 0.0);
+
+        let skull = Skull::from_array(core::array::from_fn(|i| i as f32));
+        assert_eq!(Skull::from_array(skull.as_array()), skull);
+        assert_eq!(skull.right_front_1, 11.0);
+    }
+
+    #[test]
+    fn test_skull_sweep() {
+        let lit = |skull: &Skull| {
+            skull.as_array()[..6]
+                .iter()
+                .position(|&intensity| intensity > 0.99)
+        };
+
+        // the dot moves front to back in the first half, and back to front in the second half
+        let positions: Vec<_> = (0..=10)
+            .map(|i| lit(&Skull::sweep(i as f32 / 10.0)))
+            .collect();
+        assert_eq!(positions, [0, 1, 2, 3, 4, 5, 4, 3, 2, 1, 0].map(Some));
+
+        // in between, the intensity is spread over two neighbouring LEDs
+        let between = Skull::sweep(0.05);
+        assert!((between.left_front_0 - 0.5).abs() < 1e-5);
+        assert!((between.left_front_1 - 0.5).abs() < 1e-5);
+        assert!((between.as_array().iter().sum::<f32>() - 2.0).abs() < 1e-5);
+
+        // both sides show the same dot, and the phase wraps around
+        assert_eq!(between.left_front_0, between.right_front_0);
+        assert_eq!(Skull::sweep(1.25), Skull::sweep(0.25));
+        assert_eq!(Skull::sweep(-0.75), Skull::sweep(0.25));
+    }
+
+    fn numbered_left_eye() -> LeftEye {
+        LeftEye::from_array(core::array::from_fn(|i| RgbF32::new(i as f32, 0.0, 0.0)))
+    }
+
+    fn numbered_left_ear() -> LeftEar {
+        LeftEar::from_array(core::array::from_fn(|i| i as f32))
+    }
+
+    #[test]
+    fn test_led_array_round_trip() {
+        let eye = numbered_left_eye();
+        assert_eq!(LeftEye::from_array(eye.as_array()), eye);
+        assert_eq!(eye.as_array()[3], eye.led3);
+
+        let ear = numbered_left_ear();
+        assert_eq!(LeftEar::from_array(ear.as_array()), ear);
+        assert_eq!(ear.as_array()[9], ear.led9);
+    }
+
+    #[test]
+    fn test_full_rotation_is_identity() {
+        let eye = numbered_left_eye();
+        assert_eq!(eye.clone().rotated(8), eye);
+        assert_eq!(eye.clone().rotated(-8), eye);
+        assert_eq!(eye.clone().rotated(3).rotated(-3), eye);
+        assert_eq!(eye.clone().rotated(11), eye.clone().rotated(3));
+        assert_eq!(eye.clone().mirror().rotated(8), eye.clone().mirror());
+
+        let ear = numbered_left_ear();
+        assert_eq!(ear.clone().rotated(10), ear);
+        assert_eq!(ear.clone().rotated(-13), ear.clone().rotated(7));
+        assert_eq!(ear.clone().mirror().rotated(10), ear.clone().mirror());
+    }
+
+    #[test]
+    fn test_mirror_twice_is_identity() {
+        let eye = numbered_left_eye();
+        assert_eq!(eye.clone().mirror().mirror(), eye);
+
+        let ear = numbered_left_ear();
+        assert_eq!(ear.clone().mirror().mirror(), ear);
+    }
+
+    #[test]
+    fn test_mirror_reverses_rotation() {
+        let eye = numbered_left_eye();
+        assert_eq!(
+            eye.clone().rotated(3).mirror(),
+            eye.clone().mirror().rotated(-3)
+        );
+
+        let ear = numbered_left_ear();
+        assert_eq!(
+            ear.clone().rotated(4).mirror(),
+            ear.clone().mirror().rotated(-4)
+        );
+    }
+
+    #[test]
+    fn test_eye_segment_angles() {
+        let left = numbered_left_eye();
+        let right = RightEye::from_array(left.as_array());
+        let led = |color: RgbF32| color.red as usize;
+
+        let angles = [0.0, 45.0, 90.0, 135.0, 180.0, 225.0, 270.0, 315.0];
+        assert_eq!(
+            angles.map(|degrees| led(left.get_at_angle(degrees))),
+            [7, 0, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(
+            angles.map(|degrees| led(right.get_at_angle(degrees))),
+            [0, 7, 6, 5, 4, 3, 2, 1]
+        );
+
+        // the nearest segment is used, and angles wrap around
+        assert_eq!(led(left.get_at_angle(22.0)), 7);
+        assert_eq!(led(left.get_at_angle(23.0)), 0);
+        assert_eq!(led(left.get_at_angle(350.0)), 7);
+        assert_eq!(led(left.get_at_angle(-45.0)), 6);
+        assert_eq!(led(left.get_at_angle(405.0)), 0);
+
+        // mirrored patterns light the segments at the same angles
+        let mirrored = left.clone().mirror();
+        for (degrees, color) in left.iter_segments() {
+            assert_eq!(mirrored.get_at_angle(degrees), *color);
+        }
+
+        let mut eye = RightEye::default();
+        eye.set_at_angle(100.0, RgbF32::new(1.0, 0.0, 0.0));
+        assert_eq!(eye.led6.red, 1.0);
+        assert_eq!(
+            eye.iter_segments()
+                .map(|(degrees, _)| degrees)
+                .collect::<Vec<_>>(),
+            angles
+        );
+    }
+
     #[test]
     fn test_color_fill() {
         let color = LeftEye::fill(RgbF32::new(0.5, 0.5, 0.5));
-        assert_eq!(color.l0.red, 0.5);
-        assert_eq!(color.l0.green, 0.5);
-        assert_eq!(color.l0.blue, 0.5);
+        assert_eq!(color.led0.red, 0.5);
+        assert_eq!(color.led0.green, 0.5);
+        assert_eq!(color.led0.blue, 0.5);
     }
 }
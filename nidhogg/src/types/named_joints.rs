@@ -0,0 +1,241 @@
+//! Serialize a [`JointArray`] as a map keyed by joint name, for configuration files written by hand.
+//!
+//! The derived serde implementation of [`JointArray`] requires every joint, using the field names
+//! such as `head_yaw`. The map form produced by this module uses the `LoLA` names such as `HeadYaw`,
+//! accepts both naming conventions when deserializing, and allows joints to be omitted.
+//! Unknown joint names are rejected with an error listing the valid names.
+//!
+//! Use [`NamedJoints`] as a wrapper type, or this module with `#[serde(with = "...")]`:
+//!
+//! ```
+//! use nidhogg::types::JointArray;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Pose {
+//!     #[serde(with = "nidhogg::types::named_joints")]
+//!     position: JointArray<f32>,
+//! }
+//!
+//! let pose: Pose = serde_json::from_str(r#"{ "position": { "HeadYaw": 0.5, "left_knee_pitch": 1.0 } }"#).unwrap();
+//! assert_eq!(pose.position.head_yaw, 0.5);
+//! assert_eq!(pose.position.left_knee_pitch, 1.0);
+//! assert_eq!(pose.position.head_pitch, 0.0);
+//! ```
+//!
+//! Omitted joints are filled with their [`Default`] value, use [`NamedJointsSeed`] to fill them
+//! from a fallback instead.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, Error, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{JointArray, JointName};
+
+/// The `LoLA` names of all joints, listed in the error for unknown joint names.
+const LOLA_NAMES: [&str; 25] = {
+    let mut names = [""; 25];
+    let mut i = 0;
+    while i < names.len() {
+        names[i] = JointName::ALL[i].lola_name();
+        i += 1;
+    }
+    names
+};
+
+/// Wrapper around a [`JointArray`] that is serialized as a map keyed by joint name.
+///
+/// See the [module documentation](self) for the accepted format.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::types::{JointArray, NamedJoints};
+///
+/// let joints: NamedJoints<f32> = serde_json::from_str(r#"{ "RKneePitch": 0.8 }"#).unwrap();
+/// assert_eq!(joints.0.right_knee_pitch, 0.8);
+///
+/// let json = serde_json::to_string(&joints).unwrap();
+/// assert!(json.starts_with(r#"{"HeadYaw":0.0,"HeadPitch":0.0,"#));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NamedJoints<T>(pub JointArray<T>);
+
+impl<T> NamedJoints<T> {
+    /// Unwrap the [`JointArray`].
+    pub fn into_inner(self) -> JointArray<T> {
+        self.0
+    }
+}
+
+impl<T> From<JointArray<T>> for NamedJoints<T> {
+    fn from(joints: JointArray<T>) -> Self {
+        Self(joints)
+    }
+}
+
+impl<T> From<NamedJoints<T>> for JointArray<T> {
+    fn from(joints: NamedJoints<T>) -> Self {
+        joints.0
+    }
+}
+
+impl<T: Serialize> Serialize for NamedJoints<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Default> Deserialize<'de> for NamedJoints<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(NamedJoints)
+    }
+}
+
+/// Deserializes a [`JointArray`] from a map keyed by joint name, filling omitted joints from a fallback.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::types::{named_joints::NamedJointsSeed, FillExt, JointArray};
+/// use serde::de::DeserializeSeed;
+///
+/// let mut deserializer = serde_json::Deserializer::from_str(r#"{ "HeadYaw": 0.1 }"#);
+/// let gains = NamedJointsSeed::new(JointArray::fill(0.8))
+///     .deserialize(&mut deserializer)
+///     .unwrap();
+///
+/// assert_eq!(gains.head_yaw, 0.1);
+/// assert_eq!(gains.head_pitch, 0.8);
+/// ```
+#[derive(Clone, Debug)]
+pub struct NamedJointsSeed<T> {
+    fallback: JointArray<T>,
+}
+
+impl<T> NamedJointsSeed<T> {
+    /// Create a seed that fills joints that are not in the map from `fallback`.
+    pub fn new(fallback: JointArray<T>) -> Self {
+        Self { fallback }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for NamedJointsSeed<T> {
+    type Value = JointArray<T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(NamedJointsVisitor {
+            fallback: self.fallback,
+            marker: PhantomData,
+        })
+    }
+}
+
+struct NamedJointsVisitor<'de, T> {
+    fallback: JointArray<T>,
+    marker: PhantomData<&'de ()>,
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for NamedJointsVisitor<'de, T> {
+    type Value = JointArray<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of joint names to values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut joints = self.fallback;
+        let mut seen = [false; 25];
+
+        while let Some(key) = map.next_key::<std::borrow::Cow<'de, str>>()? {
+            let joint = key
+                .parse::<JointName>()
+                .map_err(|_| A::Error::unknown_field(&key, &LOLA_NAMES))?;
+            if core::mem::replace(&mut seen[joint.index()], true) {
+                return Err(A::Error::duplicate_field(joint.lola_name()));
+            }
+
+            joints[joint] = map.next_value()?;
+        }
+
+        Ok(joints)
+    }
+}
+
+/// Serialize a [`JointArray`] as a map from the `LoLA` name of each joint to its value.
+///
+/// The joints are written in `LoLA` order.
+pub fn serialize<T: Serialize, S: Serializer>(
+    joints: &JointArray<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(JointName::ALL.len()))?;
+    for joint in JointName::ALL {
+        map.serialize_entry(joint.lola_name(), &joints[joint])?;
+    }
+    map.end()
+}
+
+/// Deserialize a [`JointArray`] from a map keyed by joint name, filling omitted joints with their [`Default`] value.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<JointArray<T>, D::Error>
+where
+    T: Deserialize<'de> + Default,
+    D: Deserializer<'de>,
+{
+    NamedJointsSeed::new(JointArray::default()).deserialize(deserializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FillExt;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut joints = JointArray::fill(0.25);
+        joints.right_hand = 0.75;
+
+        let json = serde_json::to_string(&NamedJoints(joints.clone())).unwrap();
+        assert!(json.contains(r#""RHand":0.75"#));
+        assert_eq!(
+            serde_json::from_str::<NamedJoints<f32>>(&json).unwrap(),
+            NamedJoints(joints)
+        );
+    }
+
+    #[test]
+    fn test_partial_map() {
+        let json = r#"{ "LShoulderPitch": 1.5, "right_shoulder_pitch": 1.4 }"#;
+
+        let joints = serde_json::from_str::<NamedJoints<f32>>(json)
+            .unwrap()
+            .into_inner();
+        assert_eq!(joints.left_shoulder_pitch, 1.5);
+        assert_eq!(joints.right_shoulder_pitch, 1.4);
+        assert_eq!(joints.head_yaw, 0.0);
+
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let joints = NamedJointsSeed::new(JointArray::fill(-1.0))
+            .deserialize(&mut deserializer)
+            .unwrap();
+        assert_eq!(joints.left_shoulder_pitch, 1.5);
+        assert_eq!(joints.left_hand, -1.0);
+    }
+
+    #[test]
+    fn test_unknown_and_duplicate_names() {
+        let error = serde_json::from_str::<NamedJoints<f32>>(r#"{ "Tail": 1.0 }"#).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("unknown field `Tail`"), "{message}");
+        assert!(message.contains("`HeadYaw`"), "{message}");
+        assert!(message.contains("`RHand`"), "{message}");
+
+        let error =
+            serde_json::from_str::<NamedJoints<f32>>(r#"{ "HeadYaw": 1.0, "head_yaw": 2.0 }"#)
+                .unwrap_err();
+        assert!(error.to_string().contains("duplicate field `HeadYaw`"));
+    }
+}
@@ -0,0 +1,243 @@
+//! Implements [`JointRegionOps`], which allows writing generic code for all joint region types.
+
+use alloc::{vec, vec::Vec};
+
+use super::{
+    ArmJoints, FillExt, HeadJoints, JointArray, LeftLegJoints, LegJoints, RightLegJoints,
+    SingleArmJoints,
+};
+
+/// Operations shared by [`JointArray`] and the region types that contain a subset of its joints.
+///
+/// The region types are [`HeadJoints`], [`SingleArmJoints`], [`ArmJoints`], [`LeftLegJoints`],
+/// [`RightLegJoints`] and [`LegJoints`]. Filling a region with a single value is provided by the
+/// [`FillExt`] supertrait.
+///
+/// # Example
+///
+/// ```
+/// use nidhogg::types::{ArmJoints, FillExt, HeadJoints, JointRegionOps};
+///
+/// // scales every value of a region by the gain of the corresponding joint
+/// fn apply_gains<R: JointRegionOps<f32, Mapped<f32> = R>>(values: R, gains: R) -> R {
+///     values.zip_with::<f32, f32>(gains, |value, gain| value * gain)
+/// }
+///
+/// let head = apply_gains(HeadJoints::fill(2.0), HeadJoints::fill(0.5));
+/// assert_eq!(head, HeadJoints::fill(1.0));
+///
+/// let arms = apply_gains(ArmJoints::fill(4.0), ArmJoints::fill(0.25));
+/// assert_eq!(arms.len(), 12);
+/// assert!(arms.as_slice_refs().iter().all(|&&value| value == 1.0));
+/// ```
+pub trait JointRegionOps<T>: FillExt<T> + Sized {
+    /// The same region type, containing values of type `U`.
+    type Mapped<U>;
+
+    /// The number of joints in the region.
+    const LEN: usize;
+
+    /// Transforms every value in the region using the provided closure `f`.
+    fn map<U>(self, f: impl FnMut(T) -> U) -> Self::Mapped<U>;
+
+    /// Zips two regions element-wise into a region of tuples.
+    fn zip<U>(self, other: Self::Mapped<U>) -> Self::Mapped<(T, U)>;
+
+    /// Combines two regions element-wise using the provided closure `f`.
+    ///
+    /// Generic code has to name `U` and `V` explicitly, as they can not be inferred from [`Self::Mapped`].
+    fn zip_with<U, V>(self, other: Self::Mapped<U>, f: impl FnMut(T, U) -> V) -> Self::Mapped<V>;
+
+    /// References to all values in the region, in the order of [`JointArray`].
+    fn as_slice_refs(&self) -> Vec<&T>;
+
+    /// The number of joints in the region, which is [`JointRegionOps::LEN`].
+    fn len(&self) -> usize {
+        Self::LEN
+    }
+
+    /// Returns `true` if the region contains no joints, which is never the case.
+    fn is_empty(&self) -> bool {
+        Self::LEN == 0
+    }
+}
+
+/// Implements [`JointRegionOps`] by forwarding to the inherent `map` and `zip` methods of the type.
+macro_rules! impl_region_ops {
+    ($region:ident, $len:literal, |$value:ident| [$($field:expr),*]) => {
+        impl<T: Clone> JointRegionOps<T> for $region<T> {
+            type Mapped<U> = $region<U>;
+
+            const LEN: usize = $len;
+
+            fn map<U>(self, f: impl FnMut(T) -> U) -> $region<U> {
+                $region::map(self, f)
+            }
+
+            fn zip<U>(self, other: $region<U>) -> $region<(T, U)> {
+                $region::zip(self, other)
+            }
+
+            fn zip_with<U, V>(self, other: $region<U>, mut f: impl FnMut(T, U) -> V) -> $region<V> {
+                $region::zip(self, other).map(|(a, b)| f(a, b))
+            }
+
+            fn as_slice_refs(&self) -> Vec<&T> {
+                let $value = self;
+                vec![$($field),*]
+            }
+        }
+    };
+}
+
+impl_region_ops!(HeadJoints, 2, |joints| [&joints.yaw, &joints.pitch]);
+impl_region_ops!(SingleArmJoints, 6, |joints| [
+    &joints.shoulder_pitch,
+    &joints.shoulder_roll,
+    &joints.elbow_yaw,
+    &joints.elbow_roll,
+    &joints.wrist_yaw,
+    &joints.hand
+]);
+impl_region_ops!(LeftLegJoints, 6, |joints| [
+    &joints.hip_yaw_pitch,
+    &joints.hip_roll,
+    &joints.hip_pitch,
+    &joints.knee_pitch,
+    &joints.ankle_pitch,
+    &joints.ankle_roll
+]);
+impl_region_ops!(RightLegJoints, 5, |joints| [
+    &joints.hip_roll,
+    &joints.hip_pitch,
+    &joints.knee_pitch,
+    &joints.ankle_pitch,
+    &joints.ankle_roll
+]);
+
+impl<T: Clone> JointRegionOps<T> for ArmJoints<T> {
+    type Mapped<U> = ArmJoints<U>;
+
+    const LEN: usize = 12;
+
+    fn map<U>(self, f: impl FnMut(T) -> U) -> ArmJoints<U> {
+        ArmJoints::map(self, f)
+    }
+
+    fn zip<U>(self, other: ArmJoints<U>) -> ArmJoints<(T, U)> {
+        ArmJoints::zip(self, other)
+    }
+
+    fn zip_with<U, V>(self, other: ArmJoints<U>, mut f: impl FnMut(T, U) -> V) -> ArmJoints<V> {
+        self.zip(other).map(|(a, b)| f(a, b))
+    }
+
+    fn as_slice_refs(&self) -> Vec<&T> {
+        let mut refs = self.left_arm.as_slice_refs();
+        refs.extend(self.right_arm.as_slice_refs());
+        refs
+    }
+}
+
+impl<T: Clone> JointRegionOps<T> for LegJoints<T> {
+    type Mapped<U> = LegJoints<U>;
+
+    const LEN: usize = 11;
+
+    fn map<U>(self, f: impl FnMut(T) -> U) -> LegJoints<U> {
+        LegJoints::map(self, f)
+    }
+
+    fn zip<U>(self, other: LegJoints<U>) -> LegJoints<(T, U)> {
+        LegJoints::zip(self, other)
+    }
+
+    fn zip_with<U, V>(self, other: LegJoints<U>, mut f: impl FnMut(T, U) -> V) -> LegJoints<V> {
+        self.zip(other).map(|(a, b)| f(a, b))
+    }
+
+    fn as_slice_refs(&self) -> Vec<&T> {
+        let mut refs = self.left_leg.as_slice_refs();
+        refs.extend(self.right_leg.as_slice_refs());
+        refs
+    }
+}
+
+impl<T: Clone> JointRegionOps<T> for JointArray<T> {
+    type Mapped<U> = JointArray<U>;
+
+    const LEN: usize = 25;
+
+    fn map<U>(self, f: impl FnMut(T) -> U) -> JointArray<U> {
+        JointArray::map(self, f)
+    }
+
+    fn zip<U>(self, other: JointArray<U>) -> JointArray<(T, U)> {
+        JointArray::zip(self, other)
+    }
+
+    fn zip_with<U, V>(self, other: JointArray<U>, mut f: impl FnMut(T, U) -> V) -> JointArray<V> {
+        self.zip(other).map(|(a, b)| f(a, b))
+    }
+
+    fn as_slice_refs(&self) -> Vec<&T> {
+        self.as_array_ref().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gain schedule that is written once, for every region type.
+    fn schedule<R>(region: R, gain: f32) -> R
+    where
+        R: JointRegionOps<f32, Mapped<f32> = R> + Clone,
+    {
+        let scaled = region.clone().map(|value| value * gain);
+        region.zip_with::<f32, f32>(scaled, |value, scaled| value + scaled)
+    }
+
+    fn check<R>(expected_len: usize)
+    where
+        R: JointRegionOps<f32, Mapped<f32> = R> + Clone + PartialEq + core::fmt::Debug,
+    {
+        let region = schedule(R::fill(2.0), 0.5);
+
+        assert_eq!(region, R::fill(3.0));
+        assert_eq!(region.len(), expected_len);
+        assert_eq!(region.as_slice_refs().len(), expected_len);
+        assert!(!region.is_empty());
+    }
+
+    #[test]
+    fn test_every_region() {
+        check::<HeadJoints<f32>>(2);
+        check::<SingleArmJoints<f32>>(6);
+        check::<ArmJoints<f32>>(12);
+        check::<LeftLegJoints<f32>>(6);
+        check::<RightLegJoints<f32>>(5);
+        check::<LegJoints<f32>>(11);
+        check::<JointArray<f32>>(25);
+    }
+
+    #[test]
+    fn test_slice_refs_match_joint_array_order() {
+        let joints = JointArray::try_from_iter(0..25_u8).unwrap();
+
+        let legs: Vec<u8> = joints
+            .leg_joints()
+            .as_slice_refs()
+            .into_iter()
+            .copied()
+            .collect();
+        let expected: Vec<u8> = joints
+            .as_slice_refs()
+            .into_iter()
+            .copied()
+            .filter(|&index| legs.contains(&index))
+            .collect();
+
+        assert_eq!(legs, expected);
+    }
+}
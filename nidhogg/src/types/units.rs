@@ -0,0 +1,257 @@
+//! Angle newtypes, which make the unit of a joint position explicit.
+//!
+//! The `f32` based APIs of nidhogg interpret angles as radians, which is also what `LoLA` expects.
+//! [`Radians`] and [`Degrees`] make it impossible to accidentally mix up the two,
+//! and convert to plain radians when passed to the existing APIs.
+//!
+//! # Example
+//!
+#![cfg_attr(feature = "std", doc = "```")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
+//! use nidhogg::{
+//!     types::{units::{Degrees, Radians}, FillExt, JointArray},
+//!     NaoControlMessage,
+//! };
+//!
+//! let mut position = JointArray::fill(Degrees(0.0));
+//! position.head_yaw = Degrees(90.0);
+//!
+//! let msg = NaoControlMessage::builder()
+//!     .position_degrees(position)
+//!     .stiffness(JointArray::fill(0.8))
+//!     .build();
+//!
+//! assert_eq!(msg.position.head_yaw, core::f32::consts::FRAC_PI_2);
+//! assert_eq!(Radians::from(Degrees(180.0)), Radians(core::f32::consts::PI));
+//! ```
+
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::JointArray;
+
+/// An angle in radians.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Radians(pub f32);
+
+/// An angle in degrees.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Degrees(pub f32);
+
+impl Radians {
+    /// Convert this angle to degrees.
+    pub fn to_degrees(self) -> Degrees {
+        Degrees(self.0.to_degrees())
+    }
+}
+
+impl Degrees {
+    /// Convert this angle to radians.
+    pub fn to_radians(self) -> Radians {
+        Radians(self.0.to_radians())
+    }
+}
+
+impl From<Degrees> for Radians {
+    fn from(degrees: Degrees) -> Self {
+        degrees.to_radians()
+    }
+}
+
+impl From<Radians> for Degrees {
+    fn from(radians: Radians) -> Self {
+        radians.to_degrees()
+    }
+}
+
+impl From<Radians> for f32 {
+    fn from(radians: Radians) -> Self {
+        radians.0
+    }
+}
+
+impl From<Degrees> for f32 {
+    /// Converts to radians, which is the unit of all `f32` angles in nidhogg.
+    fn from(degrees: Degrees) -> Self {
+        degrees.to_radians().0
+    }
+}
+
+impl fmt::Display for Radians {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)?;
+        f.write_str(" rad")
+    }
+}
+
+impl fmt::Display for Degrees {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)?;
+        f.write_str("°")
+    }
+}
+
+macro_rules! angle_ops {
+    ($unit:ident) => {
+        impl Add for $unit {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                $unit(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $unit {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                $unit(self.0 - rhs.0)
+            }
+        }
+
+        impl Neg for $unit {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                $unit(-self.0)
+            }
+        }
+
+        impl Mul<f32> for $unit {
+            type Output = Self;
+
+            fn mul(self, rhs: f32) -> Self::Output {
+                $unit(self.0 * rhs)
+            }
+        }
+
+        impl Div<f32> for $unit {
+            type Output = Self;
+
+            fn div(self, rhs: f32) -> Self::Output {
+                $unit(self.0 / rhs)
+            }
+        }
+
+        impl AddAssign for $unit {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl SubAssign for $unit {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+    };
+}
+
+angle_ops!(Radians);
+angle_ops!(Degrees);
+
+impl JointArray<Radians> {
+    /// Convert all joint angles to degrees.
+    pub fn to_degrees(self) -> JointArray<Degrees> {
+        self.map(Radians::to_degrees)
+    }
+}
+
+impl JointArray<Degrees> {
+    /// Convert all joint angles to radians.
+    pub fn to_radians(self) -> JointArray<Radians> {
+        self.map(Degrees::to_radians)
+    }
+}
+
+impl From<JointArray<f32>> for JointArray<Radians> {
+    fn from(radians: JointArray<f32>) -> Self {
+        radians.map(Radians)
+    }
+}
+
+impl From<JointArray<Radians>> for JointArray<f32> {
+    fn from(radians: JointArray<Radians>) -> Self {
+        radians.map(f32::from)
+    }
+}
+
+impl From<JointArray<Degrees>> for JointArray<f32> {
+    /// Converts to radians, which is the unit of all `f32` angles in nidhogg.
+    fn from(degrees: JointArray<Degrees>) -> Self {
+        degrees.map(f32::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString};
+    use core::f32::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+    use crate::types::FillExt;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Degrees(180.0).to_radians(), Radians(PI));
+        assert_eq!(Radians(FRAC_PI_2).to_degrees(), Degrees(90.0));
+        assert_eq!(Degrees::from(Radians(PI)), Degrees(180.0));
+        assert_eq!(f32::from(Degrees(90.0)), FRAC_PI_2);
+        assert_eq!(f32::from(Radians(0.5)), 0.5);
+
+        let roundtrip = Degrees(37.5).to_radians().to_degrees();
+        assert!((roundtrip.0 - 37.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut angle = Degrees(90.0) + Degrees(45.0) - Degrees(15.0);
+        assert_eq!(angle, Degrees(120.0));
+
+        angle -= Degrees(20.0);
+        angle += -Degrees(10.0);
+        assert_eq!(angle * 2.0 / 3.0, Degrees(60.0));
+        assert!(Radians(0.1) < Radians(0.2));
+    }
+
+    #[test]
+    fn test_joint_array_conversions() {
+        let mut degrees = JointArray::fill(Degrees(0.0));
+        degrees.left_knee_pitch = Degrees(90.0);
+
+        let radians = degrees.clone().to_radians();
+        assert_eq!(radians.left_knee_pitch, Radians(FRAC_PI_2));
+        assert_eq!(radians.clone().to_degrees(), degrees);
+
+        let raw = JointArray::<f32>::from(degrees);
+        assert_eq!(raw, JointArray::<f32>::from(radians.clone()));
+        assert_eq!(JointArray::<Radians>::from(raw), radians);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_builder_uses_radians() {
+        let msg = crate::NaoControlMessage::builder()
+            .position_radians(JointArray::fill(Radians(0.25)))
+            .stiffness(JointArray::fill(1.0))
+            .build();
+        assert_eq!(msg.position, JointArray::fill(0.25));
+
+        let msg = crate::NaoControlMessage::builder()
+            .position_degrees(JointArray::fill(Degrees(180.0)))
+            .stiffness(JointArray::fill(1.0))
+            .build();
+        assert_eq!(msg.position, JointArray::fill(PI));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Radians(1.5).to_string(), "1.5 rad");
+        assert_eq!(format!("{:.1}", Degrees(90.0)), "90.0°");
+    }
+}
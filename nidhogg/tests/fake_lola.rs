@@ -0,0 +1,304 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use nidhogg::{
+    backend::LolaBackend,
+    backend::{ConnectWithRetry, FrameTracker, ReadHardwareInfo, RetryPolicy},
+    testing::{FakeLola, FakeLolaConfig},
+    types::{color, Battery, FillExt, JointArray},
+    DisconnectExt, Error, HardwareInfo, NaoBackend, NaoControlMessage, NaoState,
+};
+
+fn template() -> NaoState {
+    NaoState {
+        position: JointArray {
+            head_yaw: 0.5,
+            left_knee_pitch: 1.2,
+            ..Default::default()
+        },
+        battery: Battery {
+            charge: 0.75,
+            temperature: 30.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_connect_with_retry_to_late_server() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("robocup");
+
+    let server = {
+        let path = path.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            FakeLola::bind(path, FakeLolaConfig::default()).unwrap()
+        })
+    };
+
+    let mut nao =
+        LolaBackend::connect_with_path_with_retry(50, Duration::from_millis(10), &path).unwrap();
+    let lola = server.join().unwrap();
+
+    assert_eq!(nao.read_nao_state().unwrap(), lola.state());
+}
+
+#[test]
+fn test_connect_with_policy_reports_attempts() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("robocup");
+
+    let server = {
+        let path = path.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            FakeLola::bind(path, FakeLolaConfig::default()).unwrap()
+        })
+    };
+
+    let attempts = Arc::new(AtomicU32::new(0));
+    let policy = RetryPolicy::exponential(20, Duration::from_millis(5), 1.5)
+        .with_max_interval(Duration::from_millis(40))
+        .on_attempt({
+            let attempts = attempts.clone();
+            move |progress| {
+                assert!(progress.target.contains("robocup"));
+                attempts.store(progress.attempt, Ordering::SeqCst);
+            }
+        });
+
+    let mut nao = LolaBackend::connect_with_policy_path(&policy, &path).unwrap();
+    let lola = server.join().unwrap();
+
+    assert!(attempts.load(Ordering::SeqCst) > 1);
+    assert_eq!(nao.read_nao_state().unwrap(), lola.state());
+}
+
+#[test]
+fn test_read_write_cycle() {
+    let lola = FakeLola::new(FakeLolaConfig {
+        state: template(),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut nao = lola.connect().unwrap();
+
+    assert_eq!(nao.read_nao_state().unwrap(), template());
+
+    let msg = NaoControlMessage::builder()
+        .position(JointArray {
+            head_pitch: 0.3,
+            ..Default::default()
+        })
+        .chest(color::f32::GREEN)
+        .build();
+    nao.send_control_msg(msg.clone()).unwrap();
+
+    lola.expect_chest_color(color::f32::GREEN);
+    assert_eq!(lola.last_message(), Some(msg));
+
+    // the next frames contain the new state
+    let mut state = template();
+    state.position.head_yaw = -0.5;
+    lola.set_state(state.clone());
+
+    let read = (0..10)
+        .map(|_| nao.read_nao_state().unwrap())
+        .find(|read| *read == state);
+    assert!(read.is_some(), "the updated state was never read");
+}
+
+#[test]
+fn test_disconnect_and_reconnect() {
+    let lola = FakeLola::start().unwrap();
+
+    let mut nao = lola.connect().unwrap();
+    nao.read_nao_state().unwrap();
+    nao.disconnect().unwrap();
+
+    // the server serves the next connection
+    let mut nao = lola.connect().unwrap();
+    nao.read_nao_state().unwrap();
+    nao.send_control_msg(NaoControlMessage::default()).unwrap();
+    assert!(lola.wait_for_messages(1, Duration::from_secs(1)));
+}
+
+#[test]
+fn test_read_timeout() {
+    let lola = FakeLola::start().unwrap();
+    lola.pause();
+
+    let mut nao = lola.connect().unwrap();
+    nao.set_read_timeout(Some(Duration::from_millis(50)))
+        .unwrap();
+
+    assert!(matches!(nao.read_nao_state(), Err(Error::ReadTimeout)));
+
+    lola.resume();
+    nao.set_read_timeout(None).unwrap();
+    nao.read_nao_state().unwrap();
+}
+
+#[test]
+fn test_hardware_info_does_not_consume_a_frame() {
+    let lola = FakeLola::start().unwrap();
+    lola.queue_states((0..6).map(|i| NaoState {
+        position: JointArray {
+            head_yaw: i as f32,
+            ..Default::default()
+        },
+        ..Default::default()
+    }));
+
+    let mut nao = lola.connect().unwrap();
+    let head_yaw = |state: NaoState| state.position.head_yaw;
+
+    // peeks the first frame, which is then returned as state
+    assert_eq!(nao.read_hardware_info().unwrap().body_id, "fake-body");
+    assert_eq!(head_yaw(nao.read_nao_state().unwrap()), 0.0);
+
+    // the info is cached, so no frame is read at all
+    nao.read_hardware_info().unwrap();
+    assert_eq!(head_yaw(nao.read_nao_state().unwrap()), 1.0);
+
+    let (state, info) = nao.read_state_and_hardware_info().unwrap();
+    assert_eq!(head_yaw(state), 2.0);
+    assert_eq!(info.head_id, "fake-head");
+
+    nao.read_hardware_info().unwrap();
+    assert_eq!(head_yaw(nao.read_nao_state().unwrap()), 3.0);
+}
+
+/// Returns a server that sends frames padded to `frame_size`, and the states it sends in order.
+fn server_with_frame_size(frame_size: usize, body_id: &str) -> (FakeLola, Vec<NaoState>) {
+    let lola = FakeLola::new(FakeLolaConfig {
+        frame_size,
+        hardware_info: HardwareInfo {
+            body_id: body_id.to_string(),
+            ..FakeLolaConfig::default().hardware_info
+        },
+        ..Default::default()
+    })
+    .unwrap();
+
+    let states: Vec<_> = (0..5)
+        .map(|i| NaoState {
+            position: JointArray {
+                head_yaw: i as f32 / 10.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .collect();
+    lola.queue_states(states.clone());
+
+    (lola, states)
+}
+
+#[test]
+fn test_frames_of_other_sizes() {
+    let long_id = "B".repeat(200);
+
+    // unpadded frames, frames with more padding, and frames larger than a `LoLA` frame
+    for (frame_size, body_id) in [(0, "fake-body"), (1024, "fake-body"), (0, long_id.as_str())] {
+        let (lola, states) = server_with_frame_size(frame_size, body_id);
+        let mut nao = lola.connect().unwrap();
+
+        for expected in states {
+            let (state, info) = nao.read_state_and_hardware_info().unwrap();
+            assert_eq!(state, expected, "frame size {frame_size}");
+            assert_eq!(info.body_id, body_id);
+        }
+        assert_eq!(nao.read_nao_state().unwrap(), lola.state());
+    }
+}
+
+#[test]
+fn test_strict_frame_size() {
+    let (lola, states) = server_with_frame_size(896, "fake-body");
+    let mut nao = lola.connect().unwrap().with_strict_frame_size();
+    assert_eq!(nao.read_nao_state().unwrap(), states[0]);
+
+    let (lola, _) = server_with_frame_size(0, "fake-body");
+    let mut nao = lola.connect().unwrap().with_strict_frame_size();
+    match nao.read_nao_state() {
+        Err(Error::UnexpectedFrameSize { expected, got }) => {
+            assert_eq!(expected, 896);
+            assert!(got < 896, "{got}");
+        }
+        result => panic!("expected an unexpected frame size, got {result:?}"),
+    }
+}
+
+#[test]
+fn test_missed_frames_are_tracked() {
+    let lola = FakeLola::new(FakeLolaConfig {
+        frame_interval: Duration::from_millis(10),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut nao = lola
+        .connect()
+        .unwrap()
+        .with_stats()
+        .with_frame_tracker(FrameTracker::new(5));
+
+    let mut seq = Vec::new();
+    for _ in 0..8 {
+        seq.push(nao.read_nao_state_tracked().unwrap().1.seq);
+    }
+
+    // the server stops sending frames for a while, which looks like a slow cycle to the client
+    lola.pause();
+    thread::sleep(Duration::from_millis(60));
+    lola.resume();
+
+    let delayed = (0..3)
+        .map(|_| nao.read_nao_state_tracked().unwrap().1)
+        .inspect(|meta| seq.push(meta.seq))
+        .find(|meta| meta.gap >= Duration::from_millis(40))
+        .expect("the delayed frame was never read");
+    assert!((3..=7).contains(&delayed.missed), "{delayed:?}");
+
+    assert!(seq.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    assert!(nao.stats().unwrap().missed_frames >= u64::from(delayed.missed));
+}
+
+#[test]
+fn test_dedup_skips_identical_messages() {
+    let lola = FakeLola::start().unwrap();
+    let mut nao = lola.connect().unwrap().with_dedup(0.0);
+    nao.read_nao_state().unwrap();
+
+    let msg = NaoControlMessage::builder()
+        .position(JointArray::fill(0.1))
+        .stiffness(JointArray::fill(1.0))
+        .chest(color::f32::CYAN)
+        .build();
+    for _ in 0..5 {
+        nao.send_control_msg(msg.clone()).unwrap();
+    }
+
+    // noise below one LED level is rounded away
+    let mut noisy = msg.clone();
+    noisy.chest.blue -= 0.001;
+    nao.send_control_msg(noisy).unwrap();
+
+    // the smallest joint change is always written
+    let mut moved = msg.clone();
+    moved.position.head_yaw += 1e-6;
+    nao.send_control_msg(moved.clone()).unwrap();
+
+    assert!(lola.wait_for_messages(2, Duration::from_secs(1)));
+    assert_eq!(lola.received().len(), 2);
+    assert_eq!(lola.last_message(), Some(moved));
+    assert_eq!(nao.skipped_writes(), 5);
+}
@@ -0,0 +1,133 @@
+//! Snapshot of the items exported by the crate root, the backend module and the prelude.
+//!
+//! Removing or renaming any of these items breaks downstream code. If a change to the public API is
+//! intended, update the snapshot using `UPDATE_SNAPSHOTS=1 cargo test --test public_api`.
+
+use std::{env, fs, path::Path};
+
+/// The modules in the snapshot, with the source file that declares their items.
+const MODULES: [(&str, &str); 3] = [
+    ("nidhogg", "src/lib.rs"),
+    ("nidhogg::backend", "src/backend/mod.rs"),
+    ("nidhogg::prelude", "src/prelude.rs"),
+];
+
+const SNAPSHOT: &str = "tests/public_api.txt";
+
+/// Returns the public items declared at the top level of `source`, including the `cfg` attribute they are declared with.
+///
+/// Re-exports are listed with one line per exported path.
+fn public_items(source: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut cfg = None;
+    let mut lines = source.lines();
+
+    while let Some(line) = lines.next() {
+        if line.starts_with("#[cfg(") {
+            cfg = Some(line);
+            continue;
+        }
+
+        if let Some(item) = line.strip_prefix("pub ") {
+            let prefix = cfg.map(|cfg| format!("{cfg} ")).unwrap_or_default();
+
+            if let Some(tree) = item.strip_prefix("use ") {
+                let mut tree = tree.to_string();
+                while !tree.ends_with(';') {
+                    tree.push_str(lines.next().expect("unterminated use").trim());
+                }
+                for path in flatten(tree.trim_end_matches(';')) {
+                    items.push(format!("{prefix}use {path}"));
+                }
+            } else {
+                let (kind, rest) = item.split_once(' ').expect("item without a name");
+                let name: String = rest
+                    .chars()
+                    .take_while(|c| c.is_alphanumeric() || *c == '_')
+                    .collect();
+                items.push(format!("{prefix}{kind} {name}"));
+            }
+        }
+
+        // other attributes, such as derives, are declared between the `cfg` and the item
+        if !line.starts_with("#[") {
+            cfg = None;
+        }
+    }
+
+    items
+}
+
+/// Flattens a use tree such as `a::{b, c::{d, e}}` into `a::b`, `a::c::d` and `a::c::e`.
+fn flatten(tree: &str) -> Vec<String> {
+    let Some(open) = tree.find('{') else {
+        return vec![tree.trim_end_matches("::self").to_string()];
+    };
+    let (prefix, inner) = (&tree[..open], &tree[open + 1..tree.len() - 1]);
+
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&inner[start..]);
+
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .flat_map(|part| flatten(&format!("{prefix}{part}")))
+        .collect()
+}
+
+#[test]
+fn test_flatten_use_tree() {
+    assert_eq!(
+        flatten("a::{b, c::{self, d}, e}"),
+        ["a::b", "a::c", "a::c::d", "a::e"]
+    );
+}
+
+#[test]
+fn test_public_api_snapshot() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    let mut snapshot = String::new();
+    for (module, path) in MODULES {
+        snapshot.push_str(module);
+        snapshot.push('\n');
+        for item in public_items(&fs::read_to_string(root.join(path)).unwrap()) {
+            snapshot.push_str("    ");
+            snapshot.push_str(&item);
+            snapshot.push('\n');
+        }
+    }
+
+    let path = root.join(SNAPSHOT);
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, snapshot).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap();
+    let removed: Vec<_> = expected
+        .lines()
+        .filter(|line| !snapshot.lines().any(|item| item == *line))
+        .collect();
+    let added: Vec<_> = snapshot
+        .lines()
+        .filter(|line| !expected.lines().any(|item| item == *line))
+        .collect();
+    assert!(
+        snapshot == expected,
+        "the public API changed, run with `UPDATE_SNAPSHOTS=1` if this is intended\nremoved: {removed:#?}\nadded: {added:#?}"
+    );
+}
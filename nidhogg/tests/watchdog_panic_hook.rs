@@ -0,0 +1,65 @@
+//! The panic hook is installed for the whole process, so it is tested in its own test binary.
+
+use std::{thread, time::Duration};
+
+use nidhogg::{
+    safety::{self, SafetyWatchdog},
+    types::{FillExt, JointArray},
+    NaoBackend, NaoControlMessage, NaoState, Result,
+};
+
+/// Backend that records the stiffness of every sent message.
+#[derive(Debug, Default)]
+struct StiffnessLog(Vec<f32>);
+
+impl NaoBackend for StiffnessLog {
+    fn connect() -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    fn send_control_msg(&mut self, update: NaoControlMessage) -> Result<()> {
+        self.0.push(update.stiffness.head_yaw);
+        Ok(())
+    }
+
+    fn read_nao_state(&mut self) -> Result<NaoState> {
+        Ok(NaoState::default())
+    }
+}
+
+fn stiff() -> NaoControlMessage {
+    NaoControlMessage {
+        stiffness: JointArray::fill(1.0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_panic_hook_trips_watchdog() {
+    safety::install_panic_hook();
+    // installing twice does not count panics twice
+    safety::install_panic_hook();
+
+    let mut watchdog = SafetyWatchdog::new(StiffnessLog::default(), Duration::from_secs(10));
+    watchdog.send_control_msg(stiff()).unwrap();
+    assert!(!watchdog.is_tripped());
+
+    // a panic in another thread trips the watchdog, even though it was caught
+    assert!(thread::spawn(|| panic!("control thread panicked"))
+        .join()
+        .is_err());
+    watchdog.send_control_msg(stiff()).unwrap();
+    assert!(watchdog.is_tripped());
+
+    // watchdogs created after the panic are not tripped by it
+    let mut fresh = SafetyWatchdog::new(StiffnessLog::default(), Duration::from_secs(10));
+    fresh.send_control_msg(stiff()).unwrap();
+    assert!(!fresh.is_tripped());
+
+    // resetting clears the trip caused by the panic
+    watchdog.reset();
+    watchdog.send_control_msg(stiff()).unwrap();
+    assert!(!watchdog.is_tripped());
+
+    assert_eq!(watchdog.inner().0, [1.0, 0.0, 1.0]);
+}
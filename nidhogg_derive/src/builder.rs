@@ -1,45 +1,47 @@
 use itertools::MultiUnzip;
-use proc_macro2::TokenStream;
+use proc_macro2::{TokenStream, TokenTree};
 use quote::{format_ident, quote};
-use syn::{
-    parse_macro_input, Data, DataStruct, DeriveInput, Fields, FieldsNamed, Generics, Ident, Type,
-    Visibility,
-};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Generics, Ident, Type, Visibility};
 
-fn error(loc: &impl syn::spanned::Spanned, msg: &'static str) -> proc_macro::TokenStream {
-    syn::Error::new(loc.span(), msg).to_compile_error().into()
-}
+use crate::shape;
 
 /// [`Builder`] derive macro implementation
 pub fn derive(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand(parse_macro_input!(tokens)).into()
+}
+
+fn expand(input: DeriveInput) -> TokenStream {
     let DeriveInput {
         ident,
         vis,
         generics,
         data,
         ..
-    } = parse_macro_input!(tokens);
+    } = input;
     let builder_name = format_ident!("{}Builder", ident);
 
-    parse_field_data(data).map_or(
-        error(&ident, "Builder only supports structs with named fields!"),
-        |field_data| {
-            let builder_struct =
-                builder_struct(&ident, &builder_name, &vis, &generics, &field_data);
-            let impl_builder_struct =
-                impl_builder_struct(&ident, &builder_name, &field_data, &generics);
-            let impl_builder_fn = impl_builder_fn(&ident, &builder_name, &generics);
+    let field_data = match parse_field_data(data, &ident) {
+        Ok(field_data) => field_data,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let builder_struct = builder_struct(&ident, &builder_name, &vis, &generics, &field_data);
+    let impl_builder_struct = impl_builder_struct(&ident, &builder_name, &field_data, &generics);
+    let impl_builder_fn = impl_builder_fn(&ident, &builder_name, &generics);
+    let impl_into_builder_fn = impl_into_builder_fn(&ident, &builder_name, &generics, &field_data);
+    let impl_try_build_fn = impl_try_build_fn(&ident, &builder_name, &vis, &field_data, &generics);
 
-            quote! {
-                 #builder_struct
+    quote! {
+         #builder_struct
 
-                 #impl_builder_struct
+         #impl_builder_struct
 
-                 #impl_builder_fn
-            }
-            .into()
-        },
-    )
+         #impl_builder_fn
+
+         #impl_into_builder_fn
+
+         #impl_try_build_fn
+    }
 }
 
 fn builder_struct(
@@ -51,13 +53,34 @@ fn builder_struct(
 ) -> TokenStream {
     let docs = format!("Builder struct for [`{ident}`].");
     let data_name = &field_data.field_names;
-    let data_type = &field_data.field_types;
+    let data_type: Vec<_> = field_data
+        .field_types
+        .iter()
+        .zip(&field_data.field_phantom)
+        .map(|(ty, phantom)| {
+            if *phantom {
+                quote!(#ty)
+            } else {
+                quote!(Option<#ty>)
+            }
+        })
+        .collect();
+    let empty_values = field_values(field_data, |_| quote!(::core::option::Option::None));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     quote!(
         #[doc = #docs]
         #[must_use]
-        #[derive(Default)]
         #vis struct #builder_name #generics {
-            #(#data_name: Option<#data_type>),*
+            #(#data_name: #data_type),*
+        }
+
+        impl #impl_generics ::core::default::Default for #builder_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#empty_values),*
+                }
+            }
         }
     )
 }
@@ -68,91 +91,486 @@ fn impl_builder_struct(
     field_data: &ParsedFieldData,
     generics: &Generics,
 ) -> TokenStream {
-    let data_name = field_data.field_names.as_slice();
-    let data_vis = field_data.field_visibilities.as_slice();
-    let data_type = field_data.field_types.as_slice();
+    let (data_name, data_vis, data_type, data_docs): (Vec<_>, Vec<_>, Vec<_>, Vec<_>) = field_data
+        .setter_fields()
+        .filter(|&i| !field_data.field_skip_setter[i])
+        .map(|i| {
+            (
+                &field_data.field_names[i],
+                &field_data.field_visibilities[i],
+                &field_data.field_types[i],
+                &field_data.field_docs[i],
+            )
+        })
+        .multiunzip();
     let data_doc: Vec<_> = data_name
         .iter()
-        .map(|ident| format!("Set the `{ident}` value to the provided value."))
+        .zip(&data_docs)
+        .map(|(ident, docs)| {
+            let setter_doc = format!("Set the `{ident}` value to the provided value.");
+            if docs.is_empty() {
+                quote!(#[doc = #setter_doc])
+            } else {
+                quote!(#(#docs)* #[doc = ""] #[doc = #setter_doc])
+            }
+        })
         .collect();
+    let build_values = field_values(field_data, |ident| quote!(self.#ident.unwrap_or_default()));
     let build_fn_doc = format!("Use the provided values to build a new instance of [`{ident}`].\n\nNot explicitly defined fields will use their [`Default`] value. ");
+    let build_where_clause = where_clause_with_default(generics, field_data, |_| true);
 
-    let ty_generics_with_default = generic_type_params_with_default(generics);
-    let (_impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let build_with_fn = build_with_fn(ident, field_data, generics);
 
     quote!(
-        impl <#(#ty_generics_with_default)*> #builder_name #ty_generics #where_clause {
-            #(#[doc = #data_doc]
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            #(#data_doc
+            #[must_use = "builder methods take the builder by value, use the returned builder"]
             #data_vis fn #data_name(mut self, #data_name: #data_type) -> Self {
                 self.#data_name = Some(#data_name);
                 self
             })*
 
+            #build_with_fn
+        }
+
+        impl #impl_generics #builder_name #ty_generics #build_where_clause {
             #[doc = #build_fn_doc]
+            #[must_use = "building has no side effects, use the returned value"]
             pub fn build(self) -> #ident #ty_generics {
                 #ident {
-                    #(#data_name: self.#data_name.unwrap_or_default()),*
+                    #(#build_values),*
                 }
             }
         }
     )
 }
 
-fn impl_builder_fn(ident: &Ident, builder_name: &Ident, generics: &Generics) -> TokenStream {
-    let ty_generics_with_default = generic_type_params_with_default(generics);
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+/// Generates `build_with`, if the struct has any type parameters.
+///
+/// Every type parameter gets a fallback argument, which is called to fill the fields of exactly that type that
+/// were not set, so the type parameters do not need to implement [`Default`]. Fields of other types that were
+/// not set still use their [`Default`] value. With a single type parameter the argument is named `fallback`,
+/// otherwise the arguments are named after the parameters, such as `fallback_t` for `T`.
+fn build_with_fn(ident: &Ident, field_data: &ParsedFieldData, generics: &Generics) -> TokenStream {
+    let params: Vec<_> = generics.type_params().map(|param| &param.ident).collect();
+    let fallbacks: Vec<_> = match params.as_slice() {
+        [] => return TokenStream::new(),
+        [_] => vec![format_ident!("fallback")],
+        params => params
+            .iter()
+            .map(|param| format_ident!("fallback_{}", param.to_string().to_lowercase()))
+            .collect(),
+    };
 
-    let builder_type = if generics.gt_token.is_some() {
-        quote! { #builder_name::#impl_generics }
-    } else {
-        quote! { #builder_name }
+    // the index of the type parameter that is exactly the type of the field
+    let param_of = |ty: &Type| match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            params.iter().position(|param| path.path.is_ident(*param))
+        }
+        _ => None,
     };
+    let values = field_values(field_data, |field| {
+        let i = field_data
+            .field_names
+            .iter()
+            .position(|name| name == field)
+            .unwrap();
+        match param_of(&field_data.field_types[i]) {
+            Some(param) => {
+                let fallback = &fallbacks[param];
+                quote!(self.#field.unwrap_or_else(&#fallback))
+            }
+            None => quote!(self.#field.unwrap_or_default()),
+        }
+    });
+    let where_clause = where_clause_with_default(generics, field_data, |i| {
+        param_of(&field_data.field_types[i]).is_none()
+    });
+    let (_impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+    let fallback_docs = params
+        .iter()
+        .zip(&fallbacks)
+        .map(|(param, fallback)| format!("`{param}` are set by calling `{fallback}`"))
+        .collect::<Vec<_>>()
+        .join(", and fields of type ");
+    let docs = format!("Use the provided values to build a new instance of [`{ident}`].\n\nNot explicitly defined fields of type {fallback_docs}, so the type parameters do not need to implement [`Default`]. ");
+
+    quote! {
+        #[doc = #docs]
+        #[must_use = "building has no side effects, use the returned value"]
+        pub fn build_with(self, #(#fallbacks: impl Fn() -> #params),*) -> #ident #ty_generics #where_clause {
+            #ident {
+                #(#values),*
+            }
+        }
+    }
+}
+
+/// Generates `try_build` and its error type, if the struct has any `#[builder(required)]` fields.
+fn impl_try_build_fn(
+    ident: &Ident,
+    builder_name: &Ident,
+    vis: &Visibility,
+    field_data: &ParsedFieldData,
+    generics: &Generics,
+) -> TokenStream {
+    if !field_data.field_required.contains(&true) {
+        return TokenStream::new();
+    }
+
+    let error_name = format_ident!("{}Error", builder_name);
+    let error_doc = format!("Error returned by [`{builder_name}::try_build`] when required fields of [`{ident}`] are not set.");
+    let try_build_fn_doc = format!("Use the provided values to build a new instance of [`{ident}`].\n\nNot explicitly defined optional fields will use their [`Default`] value.\n\n# Errors\n\nReturns a [`{error_name}`] naming all required fields that were not set.");
+
+    let data_name = field_data.field_names.as_slice();
+    let (required_name, required_str): (Vec<_>, Vec<_>) = data_name
+        .iter()
+        .zip(&field_data.field_required)
+        .filter(|(_, required)| **required)
+        .map(|(name, _)| (name, name.to_string()))
+        .unzip();
+
+    let values = field_values(field_data, |field| {
+        if required_name.contains(&field) {
+            quote!(#field)
+        } else {
+            quote!(self.#field.unwrap_or_default())
+        }
+    });
+    let where_clause =
+        where_clause_with_default(generics, field_data, |i| !field_data.field_required[i]);
+    let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
+
+    let required_count = required_name.len();
 
     quote! {
-        impl <#(#ty_generics_with_default)*> #ident #ty_generics #where_clause {
-            pub fn builder() -> #builder_type {
-                #builder_type::default()
+        #[doc = #error_doc]
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        #vis struct #error_name {
+            missing_fields: [&'static str; #required_count],
+            missing_count: usize,
+        }
+
+        impl #error_name {
+            /// The names of the required fields that were not set, in the order in which they are declared.
+            pub fn missing_fields(&self) -> &[&'static str] {
+                &self.missing_fields[..self.missing_count]
+            }
+        }
+
+        impl ::core::fmt::Debug for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(stringify!(#error_name))
+                    .field("missing_fields", &self.missing_fields())
+                    .finish()
+            }
+        }
+
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, "missing required fields for `{}`: ", stringify!(#ident))?;
+                for (i, field) in self.missing_fields().iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "`{field}`")?;
+                }
+                Ok(())
+            }
+        }
+
+        impl ::core::error::Error for #error_name {}
+
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            #[doc = #try_build_fn_doc]
+            #[must_use = "building has no side effects, use the returned value"]
+            pub fn try_build(self) -> ::core::result::Result<#ident #ty_generics, #error_name> {
+                match (#(self.#required_name,)*) {
+                    (#(::core::option::Option::Some(#required_name),)*) => Ok(#ident {
+                        #(#values),*
+                    }),
+                    (#(#required_name,)*) => {
+                        let mut error = #error_name {
+                            missing_fields: [""; #required_count],
+                            missing_count: 0,
+                        };
+                        #(if #required_name.is_none() {
+                            error.missing_fields[error.missing_count] = #required_str;
+                            error.missing_count += 1;
+                        })*
+
+                        Err(error)
+                    }
+                }
             }
         }
     }
 }
 
-fn generic_type_params_with_default(generics: &Generics) -> Vec<TokenStream> {
-    generics
-        .type_params()
-        .map(|x| {
-            let id = &x.ident;
-            let bounds = x.bounds.iter();
+fn impl_builder_fn(ident: &Ident, builder_name: &Ident, generics: &Generics) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let builder_type = ty_generics.as_turbofish();
+
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn builder() -> #builder_name #ty_generics {
+                #builder_name #builder_type::default()
+            }
+        }
+    }
+}
 
-            quote! { #id: #(#bounds +)* Default }
+fn impl_into_builder_fn(
+    ident: &Ident,
+    builder_name: &Ident,
+    generics: &Generics,
+    field_data: &ParsedFieldData,
+) -> TokenStream {
+    let values = field_values(field_data, |ident| quote!(Some(self.#ident)));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let docs = format!("Convert this [`{ident}`] into a [`{builder_name}`], with all values set to the current values.");
+
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #docs]
+            pub fn into_builder(self) -> #builder_name #ty_generics {
+                #builder_name {
+                    #(#values),*
+                }
+            }
+        }
+    }
+}
+
+/// Returns the field initializers of `ident`, using `value` for every field except `PhantomData` markers.
+fn field_values(
+    field_data: &ParsedFieldData,
+    value: impl Fn(&Ident) -> TokenStream,
+) -> Vec<TokenStream> {
+    field_data
+        .field_names
+        .iter()
+        .zip(&field_data.field_phantom)
+        .map(|(ident, phantom)| {
+            if *phantom {
+                quote!(#ident: ::core::marker::PhantomData)
+            } else {
+                let value = value(ident);
+                quote!(#ident: #value)
+            }
         })
         .collect()
 }
 
+/// Returns the where clause of `generics`, extended with a [`Default`] bound for the types of the selected fields.
+///
+/// Only the types that refer to a type parameter get a bound. The other types are either [`Default`],
+/// or the generated code fails to compile anyway, and bounding them could expose private types.
+fn where_clause_with_default(
+    generics: &Generics,
+    field_data: &ParsedFieldData,
+    selected: impl Fn(usize) -> bool,
+) -> TokenStream {
+    let type_params: Vec<_> = generics.type_params().map(|param| &param.ident).collect();
+    let existing = generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter());
+    let bounds: Vec<_> = field_data
+        .setter_fields()
+        .filter(|&i| selected(i))
+        .map(|i| &field_data.field_types[i])
+        .filter(|ty| refers_to_any(quote!(#ty), &type_params))
+        .map(|ty| quote!(#ty: ::core::default::Default))
+        .collect();
+
+    if generics.where_clause.is_none() && bounds.is_empty() {
+        return TokenStream::new();
+    }
+
+    quote!(where #(#existing,)* #(#bounds,)*)
+}
+
+/// Returns `true` if `tokens` contain any of the identifiers in `idents`.
+fn refers_to_any(tokens: TokenStream, idents: &[&Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => idents.contains(&&ident),
+        TokenTree::Group(group) => refers_to_any(group.stream(), idents),
+        _ => false,
+    })
+}
+
 struct ParsedFieldData {
     field_names: Vec<Ident>,
     field_visibilities: Vec<Visibility>,
     field_types: Vec<Type>,
+    field_required: Vec<bool>,
+    /// Fields marked with `#[builder(skip_setter)]`, whose setter is written by hand.
+    field_skip_setter: Vec<bool>,
+    field_docs: Vec<Vec<Attribute>>,
+    field_phantom: Vec<bool>,
 }
 
-/// Extract the field names, types and visibilities from a [`Data`] struct.
-fn parse_field_data(input: Data) -> Option<ParsedFieldData> {
-    let Data::Struct(DataStruct {
-        fields: Fields::Named(FieldsNamed { named, .. }),
-        ..
-    }) = input
-    else {
-        return None;
-    };
+impl ParsedFieldData {
+    /// Indices of the fields that get a setter, which excludes `PhantomData` markers.
+    fn setter_fields(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.field_names.len()).filter(|&i| !self.field_phantom[i])
+    }
+}
 
-    let (field_names, field_visibilities, field_types) = named
+/// Extract the field names, types, visibilities and `#[builder]` attributes from a [`Data`] struct.
+fn parse_field_data(input: Data, ident: &Ident) -> syn::Result<ParsedFieldData> {
+    let named = shape::named_fields(input, ident, true)?;
+
+    // `PhantomData` markers have no setter, so they can't be required either
+    let (field_required, field_skip_setter): (Vec<_>, Vec<_>) = named
+        .iter()
+        .map(|x| {
+            let (required, skip_setter) = builder_attributes(&x.attrs)?;
+            Ok((required && !shape::is_phantom_data(&x.ty), skip_setter))
+        })
+        .collect::<syn::Result<Vec<_>>>()?
         .into_iter()
-        .map(|x| (x.ident.unwrap(), x.vis, x.ty))
+        .unzip();
+
+    let (field_names, field_visibilities, field_types, field_docs, field_phantom) = named
+        .into_iter()
+        .map(|x| {
+            let docs = x
+                .attrs
+                .into_iter()
+                .filter(|attr| attr.path().is_ident("doc"))
+                .collect::<Vec<_>>();
+            let phantom = shape::is_phantom_data(&x.ty);
+            (x.ident.unwrap(), x.vis, x.ty, docs, phantom)
+        })
         .multiunzip();
 
-    Some(ParsedFieldData {
+    Ok(ParsedFieldData {
         field_names,
         field_visibilities,
         field_types,
+        field_required,
+        field_skip_setter,
+        field_docs,
+        field_phantom,
     })
 }
+
+/// Returns whether the field is marked with `#[builder(required)]` and `#[builder(skip_setter)]`.
+fn builder_attributes(attrs: &[Attribute]) -> syn::Result<(bool, bool)> {
+    let mut required = false;
+    let mut skip_setter = false;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("builder")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("required") {
+                required = true;
+                Ok(())
+            } else if meta.path.is_ident("skip_setter") {
+                skip_setter = true;
+                Ok(())
+            } else {
+                Err(meta
+                    .error("unsupported builder attribute, expected `required` or `skip_setter`"))
+            }
+        })?;
+    }
+
+    Ok((required, skip_setter))
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, Expr, ExprLit, ImplItem, Item, Lit, Meta};
+
+    use super::*;
+
+    /// Returns the doc lines and whether the method is `#[must_use]`, for every inherent method generated for `FooBuilder`.
+    fn builder_methods(input: DeriveInput) -> Vec<(String, Vec<String>, bool)> {
+        let file: syn::File = syn::parse2(expand(input)).unwrap();
+
+        file.items
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::Impl(item) if item.trait_.is_none() => {
+                    let self_ty = &item.self_ty;
+                    (quote!(#self_ty).to_string() == "FooBuilder").then_some(item.items)
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|item| match item {
+                ImplItem::Fn(method) => Some(method),
+                _ => None,
+            })
+            .map(|method| {
+                let docs = method
+                    .attrs
+                    .iter()
+                    .filter_map(|attr| match &attr.meta {
+                        Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                            Expr::Lit(ExprLit {
+                                lit: Lit::Str(doc), ..
+                            }) => Some(doc.value()),
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .collect();
+                let must_use = method
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("must_use"));
+
+                (method.sig.ident.to_string(), docs, must_use)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_setters_preserve_docs_and_are_must_use() {
+        let methods = builder_methods(parse_quote! {
+            struct Foo {
+                /// The bar value.
+                ///
+                /// In meters.
+                bar: f32,
+                baz: u8,
+            }
+        });
+
+        let (_, bar_docs, bar_must_use) = methods.iter().find(|(name, ..)| name == "bar").unwrap();
+        assert_eq!(
+            bar_docs,
+            &[
+                " The bar value.",
+                "",
+                " In meters.",
+                "",
+                "Set the `bar` value to the provided value.",
+            ]
+        );
+        assert!(bar_must_use);
+
+        let (_, baz_docs, _) = methods.iter().find(|(name, ..)| name == "baz").unwrap();
+        assert_eq!(baz_docs, &["Set the `baz` value to the provided value."]);
+
+        assert!(methods.iter().all(|(_, _, must_use)| *must_use));
+    }
+
+    #[test]
+    fn test_skip_setter() {
+        let methods = builder_methods(parse_quote! {
+            struct Foo {
+                #[builder(skip_setter)]
+                bar: f32,
+                baz: u8,
+            }
+        });
+
+        let names: Vec<_> = methods.iter().map(|(name, ..)| name.as_str()).collect();
+        assert_eq!(names, ["baz", "build"]);
+    }
+}
@@ -1,6 +1,10 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, Generics, Ident, Type};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Field, Generics, Ident, Type,
+};
+
+use crate::shape;
 
 /// Derive implementation for function that fills struct with one fixed value.
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -20,19 +24,26 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 fn gen_filler_impl(
     generics: &Generics,
     struct_name: &Ident,
-    fields: &Vec<TokenStream>,
+    fields: &[TokenStream],
     field_type: &Type,
 ) -> TokenStream {
+    let fields = fields.iter();
     match generics.params.first() {
         Some(_) => {
-            let (_, ty_generics, where_clause) = generics.split_for_impl();
-            let impl_generics_test = generic_type_params_with_clone(generics);
+            // only the filled type needs to be `Clone`, not every type parameter,
+            // so marker types used in `PhantomData` fields don't need to implement it
+            let mut generics = generics.clone();
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(#field_type: ::core::clone::Clone));
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
             quote! {
-                impl<#(#impl_generics_test)*> crate::types::FillExt<#field_type> for #struct_name #ty_generics #where_clause {
+                impl #impl_generics crate::types::FillExt<#field_type> for #struct_name #ty_generics #where_clause {
                     fn fill(value: #field_type) -> Self {
                         #struct_name {
-                            #( #fields: value.clone() ), *
+                            #( #fields ), *
                         }
                     }
                 }
@@ -43,7 +54,7 @@ fn gen_filler_impl(
                 impl crate::types::FillExt<#field_type> for #struct_name {
                     fn fill(value: #field_type) -> Self {
                         #struct_name {
-                            #( #fields: value.clone() ), *
+                            #( #fields ), *
                         }
                     }
                 }
@@ -56,51 +67,76 @@ fn parse_fields(
     data: Data,
     struct_name: &Ident,
 ) -> Result<(Vec<TokenStream>, syn::Type), TokenStream> {
-    let fields = match data {
-        Data::Struct(data_struct) => match data_struct.fields {
-            Fields::Named(fields_named) => fields_named.named,
-            other => {
-                return Err(syn::Error::new_spanned(
-                    other,
-                    "Only structs with named fields are supported",
-                )
-                .to_compile_error());
-            }
-        },
-        _ => {
-            return Err(
-                syn::Error::new_spanned(struct_name, "Only supports structs").to_compile_error(),
-            );
-        }
+    let fields =
+        shape::named_fields(data, struct_name, false).map_err(|err| err.to_compile_error())?;
+
+    // `PhantomData` markers carry no value, so they are always skipped
+    let skipped = fields
+        .iter()
+        .map(|field| Ok(is_skipped(&field.attrs)? || shape::is_phantom_data(&field.ty)))
+        .collect::<syn::Result<Vec<_>>>()
+        .map_err(|err| err.to_compile_error())?;
+
+    let Some(field_type) = fields
+        .iter()
+        .zip(&skipped)
+        .find_map(|(field, skipped)| (!skipped).then_some(&field.ty))
+    else {
+        return Err(syn::Error::new_spanned(
+            struct_name,
+            "Filler requires at least one field that is not skipped",
+        )
+        .to_compile_error());
     };
 
-    if fields.is_empty() {
-        return Err(
-            syn::Error::new_spanned(struct_name, "Only supports structs").to_compile_error(),
-        );
+    let expected_type = quote!(#field_type).to_string();
+    for (field, _) in fields
+        .iter()
+        .zip(&skipped)
+        .filter(|(_, skipped)| !**skipped)
+    {
+        let ty = &field.ty;
+        if quote!(#ty).to_string() != expected_type {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!(
+                    "all fields must have the same type `{expected_type}`, use `#[filler(skip)]` to exclude this field"
+                ),
+            )
+            .to_compile_error());
+        }
     }
 
-    let field_type = &fields[0].ty;
-
     Ok((
         fields
             .iter()
-            .map(|Field { ident, .. }| {
-                quote! { #ident }
+            .zip(skipped)
+            .map(|(Field { ident, .. }, skipped)| {
+                if skipped {
+                    quote! { #ident: ::core::default::Default::default() }
+                } else {
+                    quote! { #ident: value.clone() }
+                }
             })
             .collect(),
         field_type.clone(),
     ))
 }
 
-fn generic_type_params_with_clone(generics: &Generics) -> Vec<TokenStream> {
-    generics
-        .type_params()
-        .map(|x| {
-            let id = &x.ident;
-            let bounds = x.bounds.iter();
+/// Returns `true` if the field is marked with `#[filler(skip)]`.
+fn is_skipped(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut skip = false;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("filler")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported filler attribute, expected `skip`"))
+            }
+        })?;
+    }
 
-            quote! { #id: #(#bounds +)* Clone }
-        })
-        .collect()
+    Ok(skip)
 }
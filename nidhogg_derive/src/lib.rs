@@ -1,13 +1,22 @@
 //! This crate provides the [`Builder`] macro used in nidhogg.
+#![deny(clippy::print_stdout, clippy::print_stderr)]
 use proc_macro::TokenStream;
 
 mod builder;
 mod filler;
+mod shape;
 
 /// Derive macro to implement the [builder pattern](https://refactoring.guru/design-patterns/builder)
 /// for an arbitrary struct with named fields.
 ///
-/// These fields in this struct need to implement [`Default`]
+/// `build()` fills the fields that were not set with their [`Default`] value, so it is only available
+/// if the types of the fields implement [`Default`]. The setters have no such bound, so builders can
+/// also be used with types that do not implement [`Default`], using `try_build()` or `build_with()`.
+///
+/// The generated code only refers to `core`, so it can be used in `no_std` crates.
+///
+/// Fields of type `PhantomData`, such as the marker of a generic struct, don't get a setter
+/// and are always initialized with `PhantomData`.
 ///
 /// ## Examples
 /// ```no_run
@@ -22,12 +31,87 @@ mod filler;
 /// let foo = Foo::builder().bar(42).baz(vec![4, 2]).build();
 /// assert_eq!(foo, Foo { bar: 42, baz: vec![4, 2]})
 /// ```
-#[proc_macro_derive(Builder)]
+///
+/// ## Required fields
+/// Fields marked with `#[builder(required)]` are still filled with their [`Default`] value by `build()`,
+/// but the builder additionally gets a `try_build()` method. It returns a `{Name}BuilderError` listing
+/// every required field that was not set, which are returned by its `missing_fields()` method.
+/// The error does not allocate, so it can also be used in `no_std` crates without `alloc`.
+///
+/// ```no_run
+/// use nidhogg_derive::Builder;
+///
+/// #[derive(Builder, Debug, Default, PartialEq)]
+/// struct Foo {
+///     #[builder(required)]
+///     bar: i32,
+///     baz: Vec<u8>
+/// }
+///
+/// let err = Foo::builder().baz(vec![4, 2]).try_build().unwrap_err();
+/// assert_eq!(err.missing_fields(), ["bar"]);
+/// ```
+///
+/// `try_build()` only requires the types of the fields that are not required to implement [`Default`].
+/// If all fields are required, it can be used with any type.
+///
+/// ## Fallback values
+/// Generic structs additionally get `build_with`, which takes a fallback for every type parameter.
+/// Fields whose type is a type parameter and that were not set are filled by calling its fallback, so the
+/// type parameters do not need to implement [`Default`]. A struct with a single type parameter `T` gets
+/// `build_with(fallback)`, a struct with several gets one argument per parameter, such as
+/// `build_with(fallback_k, fallback_v)` for `<K, V>`. Structs without type parameters have no `build_with`.
+///
+/// ```no_run
+/// use nidhogg_derive::Builder;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct ItemId(u32);
+///
+/// #[derive(Builder, Debug, PartialEq)]
+/// struct Pair<T> {
+///     left: T,
+///     right: T,
+/// }
+///
+/// let pair = Pair::builder().left(ItemId(1)).build_with(|| ItemId(0));
+/// assert_eq!(pair, Pair { left: ItemId(1), right: ItemId(0) });
+/// ```
+///
+/// ## Custom setters
+/// Fields marked with `#[builder(skip_setter)]` don't get a generated setter, so it can be written by hand,
+/// for instance to accept anything that converts into the type of the field.
+/// The hand-written setter has to be in the same module, as the fields of the builder are private.
+///
+/// ```no_run
+/// use nidhogg_derive::Builder;
+///
+/// #[derive(Builder, Debug, Default, PartialEq)]
+/// struct Foo {
+///     #[builder(skip_setter)]
+///     bar: u64,
+/// }
+///
+/// impl FooBuilder {
+///     fn bar(mut self, bar: impl Into<u64>) -> Self {
+///         self.bar = Some(bar.into());
+///         self
+///     }
+/// }
+///
+/// let foo = Foo::builder().bar(42u8).build();
+/// assert_eq!(foo, Foo { bar: 42 });
+/// ```
+#[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive_builder(input: TokenStream) -> TokenStream {
     builder::derive(input)
 }
 
-#[proc_macro_derive(Filler)]
+/// Derive macro to implement `FillExt` for a struct with named fields, which sets every field to the same value.
+///
+/// All fields must have the same type. Fields marked with `#[filler(skip)]` are excluded
+/// and set to their [`Default`] value instead, as are `PhantomData` marker fields.
+#[proc_macro_derive(Filler, attributes(filler))]
 pub fn derive_filler(input: TokenStream) -> TokenStream {
     filler::derive(input)
 }
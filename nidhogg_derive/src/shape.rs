@@ -0,0 +1,55 @@
+use syn::{punctuated::Punctuated, Data, Field, Fields, Ident, Token, Type};
+
+/// Returns the named fields of a struct, or an error pointing at the part of the input that is not supported.
+///
+/// Structs with named fields but without any fields are only accepted if `allow_empty` is `true`.
+pub(crate) fn named_fields(
+    data: Data,
+    ident: &Ident,
+    allow_empty: bool,
+) -> syn::Result<Punctuated<Field, Token![,]>> {
+    let data = match data {
+        Data::Struct(data) => data,
+        Data::Enum(data) => {
+            return Err(match data.variants.first() {
+                Some(variant) => syn::Error::new_spanned(variant, "enums are not supported"),
+                None => syn::Error::new_spanned(data.enum_token, "enums are not supported"),
+            });
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "unions are not supported",
+            ));
+        }
+    };
+
+    match data.fields {
+        Fields::Named(fields) if fields.named.is_empty() && !allow_empty => Err(
+            syn::Error::new_spanned(fields, "struct must have at least one field"),
+        ),
+        Fields::Named(fields) => Ok(fields.named),
+        Fields::Unnamed(fields) => Err(match fields.unnamed.first() {
+            Some(field) => syn::Error::new_spanned(field, "tuple structs are not supported"),
+            None => syn::Error::new_spanned(fields, "tuple structs are not supported"),
+        }),
+        Fields::Unit => Err(syn::Error::new_spanned(
+            ident,
+            "struct must have at least one field",
+        )),
+    }
+}
+
+/// Returns `true` if the type is a `PhantomData`, such as the marker field of a generic struct.
+///
+/// These fields carry no data, so the derives skip them and initialize them with `PhantomData`.
+pub(crate) fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData"),
+        _ => false,
+    }
+}
@@ -0,0 +1,142 @@
+use nidhogg_derive::Builder;
+
+#[derive(Builder, Debug, Default, PartialEq)]
+struct Point {
+    x: i32,
+    values: Vec<u8>,
+}
+
+#[derive(Builder, Clone, Debug, Default, PartialEq)]
+struct Generic<T> {
+    first: T,
+    second: T,
+}
+
+/// A handle that has no sensible default value.
+#[derive(Clone, Debug, PartialEq)]
+struct ItemId(u32);
+
+#[derive(Builder, Debug, PartialEq)]
+struct Handles<T> {
+    #[builder(required)]
+    body: T,
+    #[builder(required)]
+    head: T,
+    label: Option<String>,
+}
+
+#[derive(Builder, Debug, PartialEq)]
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    values: Vec<V>,
+    count: u32,
+}
+
+#[test]
+fn test_build_uses_defaults() {
+    let point = Point::builder().x(42).build();
+
+    assert_eq!(
+        point,
+        Point {
+            x: 42,
+            values: Vec::new()
+        }
+    );
+}
+
+#[test]
+fn test_into_builder_preserves_values() {
+    let point = Point {
+        x: 42,
+        values: vec![4, 2],
+    };
+
+    let updated = point.into_builder().x(1337).build();
+
+    assert_eq!(
+        updated,
+        Point {
+            x: 1337,
+            values: vec![4, 2]
+        }
+    );
+}
+
+#[test]
+fn test_into_builder_generic() {
+    let value = Generic {
+        first: 1_u8,
+        second: 2,
+    };
+
+    assert_eq!(value.clone().into_builder().build(), value);
+    assert_eq!(
+        value.into_builder().second(3).build(),
+        Generic {
+            first: 1,
+            second: 3
+        }
+    );
+}
+
+#[test]
+fn test_build_with_non_default_type() {
+    let value = Generic::builder()
+        .second(ItemId(2))
+        .build_with(|| ItemId(0));
+    assert_eq!(
+        value,
+        Generic {
+            first: ItemId(0),
+            second: ItemId(2)
+        }
+    );
+
+    let value = value
+        .into_builder()
+        .first(ItemId(1))
+        .build_with(|| unreachable!());
+    assert_eq!(value.first, ItemId(1));
+}
+
+#[test]
+fn test_build_with_multiple_type_params() {
+    let entry = Entry::<ItemId, ItemId>::builder()
+        .value(ItemId(2))
+        .build_with(|| ItemId(0), || unreachable!());
+    assert_eq!(
+        entry,
+        Entry {
+            key: ItemId(0),
+            value: ItemId(2),
+            values: Vec::new(),
+            count: 0
+        }
+    );
+}
+
+#[test]
+fn test_try_build_non_default_type() {
+    let handles = Handles::builder()
+        .body(ItemId(1))
+        .head(ItemId(2))
+        .try_build()
+        .unwrap();
+    assert_eq!(
+        handles,
+        Handles {
+            body: ItemId(1),
+            head: ItemId(2),
+            label: None
+        }
+    );
+
+    let err = Handles::builder()
+        .head(ItemId(2))
+        .label(Some("nao".to_owned()))
+        .try_build()
+        .unwrap_err();
+    assert_eq!(err.missing_fields(), ["body"]);
+}
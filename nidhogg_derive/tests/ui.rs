@@ -0,0 +1,8 @@
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/builder/*-pass.rs");
+    t.compile_fail("tests/ui/builder/*-fail.rs");
+    t.pass("tests/ui/filler/*-pass.rs");
+    t.compile_fail("tests/ui/filler/*-fail.rs");
+}
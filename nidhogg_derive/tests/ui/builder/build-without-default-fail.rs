@@ -0,0 +1,15 @@
+use nidhogg_derive::Builder;
+
+#[derive(Debug)]
+struct ItemId(u32);
+
+#[derive(Builder, Debug)]
+struct Pair<T> {
+    left: T,
+    right: T,
+}
+
+fn main() {
+    // the setters work without `Default`, but `build` does not
+    let _ = Pair::builder().left(ItemId(1)).right(ItemId(2)).build();
+}
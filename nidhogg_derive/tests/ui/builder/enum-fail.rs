@@ -0,0 +1,9 @@
+use nidhogg_derive::Builder;
+
+#[derive(Builder)]
+pub enum Command {
+    Stand { height: f32 },
+    Sit,
+}
+
+fn main() {}
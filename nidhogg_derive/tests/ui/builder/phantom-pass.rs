@@ -0,0 +1,32 @@
+use std::marker::PhantomData;
+
+use nidhogg_derive::Builder;
+
+pub trait Side: Default {}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Left;
+
+impl Side for Left {}
+
+#[derive(Builder, Debug, Default, PartialEq)]
+pub struct Ear<S: Side> {
+    first: f32,
+    second: f32,
+    _marker: PhantomData<S>,
+}
+
+fn main() {
+    let ear = Ear::<Left>::builder().first(0.5).build();
+    assert_eq!(
+        ear,
+        Ear {
+            first: 0.5,
+            second: 0.0,
+            _marker: PhantomData,
+        }
+    );
+
+    let ear = ear.into_builder().second(1.0).build();
+    assert_eq!(ear.second, 1.0);
+}
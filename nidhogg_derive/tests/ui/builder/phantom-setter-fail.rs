@@ -0,0 +1,13 @@
+use std::marker::PhantomData;
+
+use nidhogg_derive::Builder;
+
+#[derive(Builder, Debug, Default)]
+pub struct Ear<S> {
+    first: f32,
+    _marker: PhantomData<S>,
+}
+
+fn main() {
+    let _ = Ear::<u8>::builder()._marker(PhantomData).build();
+}
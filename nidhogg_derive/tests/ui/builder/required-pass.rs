@@ -0,0 +1,43 @@
+use nidhogg_derive::Builder;
+
+#[derive(Builder, Debug, Default, PartialEq)]
+pub struct Command {
+    #[builder(required)]
+    position: f32,
+    #[builder(required)]
+    stiffness: f32,
+    name: String,
+}
+
+fn main() {
+    let command = Command::builder()
+        .position(0.5)
+        .stiffness(1.0)
+        .try_build()
+        .unwrap();
+    assert_eq!(
+        command,
+        Command {
+            position: 0.5,
+            stiffness: 1.0,
+            name: String::new()
+        }
+    );
+
+    let err = Command::builder().position(0.5).try_build().unwrap_err();
+    assert_eq!(err.missing_fields(), ["stiffness"]);
+    assert_eq!(
+        format!("{err:?}"),
+        r#"CommandBuilderError { missing_fields: ["stiffness"] }"#
+    );
+
+    let err = Command::builder().name("wave".to_owned()).try_build().unwrap_err();
+    assert_eq!(err.missing_fields(), ["position", "stiffness"]);
+    assert_eq!(
+        err.to_string(),
+        "missing required fields for `Command`: `position`, `stiffness`"
+    );
+
+    // `build` keeps filling in defaults, including for required fields.
+    assert_eq!(Command::builder().build(), Command::default());
+}
@@ -0,0 +1,29 @@
+use nidhogg_derive::Builder;
+
+#[derive(Builder, Debug, Default, PartialEq)]
+pub struct Command {
+    #[builder(required, skip_setter)]
+    position: f64,
+    stiffness: f32,
+}
+
+impl CommandBuilder {
+    pub fn position(mut self, position: impl Into<f64>) -> Self {
+        self.position = Some(position.into());
+        self
+    }
+}
+
+fn main() {
+    let command = Command::builder().position(0.5f32).stiffness(1.0).build();
+    assert_eq!(
+        command,
+        Command {
+            position: 0.5,
+            stiffness: 1.0,
+        }
+    );
+
+    let err = Command::builder().stiffness(1.0).try_build().unwrap_err();
+    assert_eq!(err.missing_fields(), ["position"]);
+}
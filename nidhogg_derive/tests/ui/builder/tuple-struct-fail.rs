@@ -0,0 +1,6 @@
+use nidhogg_derive::Builder;
+
+#[derive(Builder, Default)]
+pub struct Position(f32, f32);
+
+fn main() {}
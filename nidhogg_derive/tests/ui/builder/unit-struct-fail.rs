@@ -0,0 +1,6 @@
+use nidhogg_derive::Builder;
+
+#[derive(Builder, Default)]
+pub struct Command;
+
+fn main() {}
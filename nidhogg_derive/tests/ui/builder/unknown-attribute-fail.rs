@@ -0,0 +1,9 @@
+use nidhogg_derive::Builder;
+
+#[derive(Builder, Default)]
+pub struct Command {
+    #[builder(optional)]
+    position: f32,
+}
+
+fn main() {}
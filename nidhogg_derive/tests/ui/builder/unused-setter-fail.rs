@@ -0,0 +1,13 @@
+#![deny(unused_must_use)]
+
+use nidhogg_derive::Builder;
+
+#[derive(Builder, Default)]
+pub struct Command {
+    position: f32,
+}
+
+fn main() {
+    let builder = Command::builder();
+    builder.position(1.0);
+}
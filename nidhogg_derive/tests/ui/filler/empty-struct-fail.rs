@@ -0,0 +1,12 @@
+use nidhogg_derive::Filler;
+
+mod types {
+    pub trait FillExt<T> {
+        fn fill(value: T) -> Self;
+    }
+}
+
+#[derive(Filler)]
+pub struct Battery {}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use nidhogg_derive::Filler;
+
+mod types {
+    pub trait FillExt<T> {
+        fn fill(value: T) -> Self;
+    }
+}
+
+#[derive(Filler)]
+pub enum Battery {
+    Charging { charge: f32 },
+    Full,
+}
+
+fn main() {}
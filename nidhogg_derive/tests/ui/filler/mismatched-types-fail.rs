@@ -0,0 +1,16 @@
+use nidhogg_derive::Filler;
+
+mod types {
+    pub trait FillExt<T> {
+        fn fill(value: T) -> Self;
+    }
+}
+
+#[derive(Filler)]
+pub struct Battery {
+    charge: f32,
+    current: f32,
+    status: u8,
+}
+
+fn main() {}
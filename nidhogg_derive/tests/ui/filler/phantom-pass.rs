@@ -0,0 +1,32 @@
+use std::marker::PhantomData;
+
+use nidhogg_derive::Filler;
+
+mod types {
+    pub trait FillExt<T> {
+        fn fill(value: T) -> Self;
+    }
+}
+
+use types::FillExt;
+
+#[derive(Debug, PartialEq)]
+pub struct Left;
+
+#[derive(Debug, Filler, PartialEq)]
+pub struct Ear<S> {
+    first: f32,
+    second: f32,
+    _marker: PhantomData<S>,
+}
+
+fn main() {
+    assert_eq!(
+        Ear::<Left>::fill(0.5),
+        Ear {
+            first: 0.5,
+            second: 0.5,
+            _marker: PhantomData,
+        }
+    );
+}
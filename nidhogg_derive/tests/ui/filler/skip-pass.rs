@@ -0,0 +1,48 @@
+use nidhogg_derive::Filler;
+
+mod types {
+    pub trait FillExt<T> {
+        fn fill(value: T) -> Self;
+    }
+}
+
+use types::FillExt;
+
+#[derive(Debug, Filler, PartialEq)]
+pub struct Battery {
+    charge: f32,
+    current: f32,
+    #[filler(skip)]
+    status: u8,
+    #[filler(skip)]
+    label: String,
+}
+
+#[derive(Debug, Filler, PartialEq)]
+pub struct Pair<T> {
+    first: T,
+    second: T,
+    #[filler(skip)]
+    count: usize,
+}
+
+fn main() {
+    assert_eq!(
+        Battery::fill(0.5),
+        Battery {
+            charge: 0.5,
+            current: 0.5,
+            status: 0,
+            label: String::new(),
+        }
+    );
+
+    assert_eq!(
+        Pair::fill('a'),
+        Pair {
+            first: 'a',
+            second: 'a',
+            count: 0
+        }
+    );
+}
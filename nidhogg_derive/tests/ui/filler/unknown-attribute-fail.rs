@@ -0,0 +1,16 @@
+use nidhogg_derive::Filler;
+
+mod types {
+    pub trait FillExt<T> {
+        fn fill(value: T) -> Self;
+    }
+}
+
+#[derive(Filler)]
+pub struct Battery {
+    charge: f32,
+    #[filler(ignore)]
+    status: u8,
+}
+
+fn main() {}
@@ -0,0 +1,88 @@
+//! Uses the [`nidhogg::types`] from a `no_std` crate, to make sure they keep working without `std`.
+//!
+//! Within the workspace, cargo enables the features nidhogg is built with by the other members,
+//! so test this crate on its own to build nidhogg without its default features:
+//!
+//! ```sh
+//! cargo test -p nidhogg_no_std
+//! ```
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use nidhogg::types::{JointArray, JointName, JointRegion};
+
+/// The mean absolute difference between the measured and the requested joint positions.
+pub fn tracking_error(measured: &JointArray<f32>, requested: &JointArray<f32>) -> f32 {
+    measured.diff(requested.clone()).mean()
+}
+
+/// The joints of `region` whose position differs from `requested` by more than `tolerance`.
+pub fn lagging_joints(
+    measured: &JointArray<f32>,
+    requested: &JointArray<f32>,
+    region: JointRegion,
+    tolerance: f32,
+) -> Vec<JointName> {
+    let selected = JointArray::from_region(region);
+
+    measured
+        .diff(requested.clone())
+        .zip(selected)
+        .map(|(error, selected)| selected && error > tolerance)
+        .iter_named()
+        .filter(|(_, lagging)| **lagging)
+        .map(|(name, _)| name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString, vec};
+
+    use nidhogg::types::{color, FillExt, RgbU8};
+
+    use super::*;
+
+    #[test]
+    fn test_joint_array_math() {
+        let requested = JointArray::fill(0.5);
+        let measured = JointArray {
+            head_yaw: 1.5,
+            left_knee_pitch: 0.25,
+            ..JointArray::fill(0.5)
+        };
+
+        assert_eq!(tracking_error(&measured, &requested), 1.25 / 25.0);
+        assert_eq!(
+            lagging_joints(&measured, &requested, JointRegion::Legs, 0.1),
+            vec![JointName::LeftKneePitch]
+        );
+        assert_eq!(
+            lagging_joints(&measured, &requested, JointRegion::All, 0.5),
+            vec![JointName::HeadYaw]
+        );
+
+        let doubled = measured.clone().zip(requested).map(|(a, b)| a + b);
+        assert_eq!(doubled.head_yaw, 2.0);
+        assert_eq!(doubled.sum(), 2.0 + 0.75 + 23.0);
+        assert_eq!(
+            JointArray::try_from_iter(doubled.clone().to_vec()),
+            Ok(doubled)
+        );
+    }
+
+    #[test]
+    fn test_formatting_and_parsing() {
+        assert_eq!("HeadYaw".parse(), Ok(JointName::HeadYaw));
+        assert_eq!(
+            "Tail".parse::<JointName>().unwrap_err().to_string(),
+            "unknown joint name `Tail`"
+        );
+
+        assert_eq!("#00ffff".parse::<RgbU8>(), Ok(color::u8::CYAN));
+        assert!(format!("{}", JointArray::fill(0.5)).contains("head_yaw"));
+    }
+}